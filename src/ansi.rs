@@ -0,0 +1,91 @@
+//! # Module `ansi`
+//!
+//! Ce module supprime les séquences d'échappement ANSI (couleurs, déplacements du curseur...)
+//! d'un texte. Il est partagé par les commandes qui proposent `--strip-ansi`.
+//!
+//! Les séquences reconnues sont :
+//!
+//! - CSI : `ESC [` paramètres puis un octet final entre `@` et `~` (ex. `\x1b[31m`).
+//! - OSC : `ESC ]` ... terminée par `BEL` ou `ESC \` (ex. titre de fenêtre, liens).
+//! - les séquences courtes `ESC` suivi d'un seul caractère (ex. `\x1bc`).
+
+/// État de l'automate de [`strip`].
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    /// Texte ordinaire.
+    Text,
+    /// Juste après `ESC`.
+    Escape,
+    /// Dans une séquence `ESC [`, jusqu'à l'octet final.
+    Csi,
+    /// Dans une séquence `ESC ]`, jusqu'à `BEL` ou `ESC \`.
+    Osc,
+    /// `ESC` rencontré dans une séquence OSC (début possible de `ESC \`).
+    OscEscape,
+}
+
+/// # Fonction : `strip`
+///
+/// Retourne le texte sans ses séquences d'échappement ANSI.
+///
+/// ## Fonctionnement :
+/// Parcourt le texte caractère par caractère avec un automate à états :
+/// les caractères ne sont recopiés que dans l'état [`State::Text`].
+/// Une séquence non terminée en fin de texte est supprimée.
+///
+/// ## Exemple :
+/// ```rust
+/// assert_eq!(ansi::strip("\x1b[1;31mErreur\x1b[0m : fichier"), "Erreur : fichier");
+/// ```
+pub fn strip(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut state = State::Text;
+
+    for c in text.chars() {
+        state = match (state, c) {
+            (State::Text, '\x1b') => State::Escape,
+            (State::Text, _) => {
+                output.push(c);
+                State::Text
+            }
+            (State::Escape, '[') => State::Csi,
+            (State::Escape, ']') => State::Osc,
+            // Séquence courte : ESC suivi d'un seul caractère
+            (State::Escape, _) => State::Text,
+            (State::Csi, '@'..='~') => State::Text,
+            (State::Csi, _) => State::Csi,
+            (State::Osc, '\x07') => State::Text,
+            (State::Osc, '\x1b') => State::OscEscape,
+            (State::Osc, _) => State::Osc,
+            (State::OscEscape, '\\') => State::Text,
+            (State::OscEscape, _) => State::Osc,
+        };
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csi_sequences_are_removed() {
+        assert_eq!(strip("\x1b[1;31mErreur\x1b[0m : fichier"), "Erreur : fichier");
+        assert_eq!(strip("a\x1b[2Kb\x1b[10;20Hc"), "abc");
+        assert_eq!(strip("sans couleur"), "sans couleur");
+    }
+
+    #[test]
+    fn osc_sequences_end_with_bel_or_st() {
+        assert_eq!(strip("\x1b]0;titre\x07texte"), "texte");
+        assert_eq!(strip("\x1b]8;;https://exemple.fr\x1b\\lien\x1b]8;;\x1b\\"), "lien");
+    }
+
+    #[test]
+    fn short_and_unfinished_sequences_are_removed() {
+        assert_eq!(strip("\x1bcécran"), "écran");
+        assert_eq!(strip("fin\x1b[31"), "fin");
+        assert_eq!(strip("fin\x1b"), "fin");
+    }
+}
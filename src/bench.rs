@@ -0,0 +1,190 @@
+//! # Module `bench`
+//!
+//! Ce module mesure les performances d'une commande (`--bench`, option cachée du
+//! lancement direct) : la commande est exécutée plusieurs fois, sa sortie est
+//! masquée, et les durées sont résumées par leur minimum, médiane, moyenne et maximum.
+//!
+//! La boucle de mesure se trouve dans `main` ; ce module fournit le calcul des
+//! statistiques ([`Stats`]), le tableau du rapport et le masquage de la sortie standard.
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// Statistiques d'une série de durées.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// # Fonction : `compute`
+    ///
+    /// Calcule les statistiques de `durations`, dans n'importe quel ordre.
+    /// Pour un nombre pair de mesures, la médiane est la moyenne des deux du milieu.
+    /// Retourne `None` s'il n'y a aucune mesure.
+    ///
+    /// ## Exemple :
+    /// ```rust
+    /// let ms = Duration::from_millis;
+    /// let stats = Stats::compute(&[ms(30), ms(10), ms(20), ms(60)]).unwrap();
+    /// assert_eq!((stats.min, stats.median, stats.mean, stats.max), (ms(10), ms(25), ms(30), ms(60)));
+    /// assert_eq!(Stats::compute(&[ms(7), ms(3), ms(5)]).unwrap().median, ms(5));
+    /// assert_eq!(Stats::compute(&[]), None);
+    /// ```
+    pub fn compute(durations: &[Duration]) -> Option<Stats> {
+        if durations.is_empty() {
+            return None;
+        }
+
+        let mut sorted = durations.to_vec();
+        sorted.sort();
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2
+        } else {
+            sorted[middle]
+        };
+
+        Some(Stats {
+            runs: sorted.len(),
+            min: sorted[0],
+            median,
+            mean: sorted.iter().sum::<Duration>() / sorted.len() as u32,
+            max: sorted[sorted.len() - 1],
+        })
+    }
+}
+
+/// # Fonction : `write_report`
+///
+/// Écrit le tableau des statistiques de la commande `command`.
+///
+/// ## Exemple de sortie :
+/// ```text
+/// Commande : cat gros.txt (20 exécution(s))
+///   min         médiane     moyenne     max
+///   1.204 ms    1.310 ms    1.352 ms    2.018 ms
+/// ```
+pub fn write_report(command: &str, stats: &Stats, out: &mut impl Write) -> io::Result<()> {
+    let cells = [stats.min, stats.median, stats.mean, stats.max].map(format_duration);
+
+    writeln!(out, "Commande : {} ({} exécution(s))", command, stats.runs)?;
+    writeln!(out, "  {:<11} {:<11} {:<11} max", "min", "médiane", "moyenne")?;
+    writeln!(out, "  {:<11} {:<11} {:<11} {}", cells[0], cells[1], cells[2], cells[3])
+}
+
+/// Affiche une durée avec l'unité la plus lisible (µs, ms ou s), trois décimales.
+///
+/// ## Exemple :
+/// ```rust
+/// assert_eq!(format_duration(Duration::from_micros(1500)), "1.500 ms");
+/// assert_eq!(format_duration(Duration::from_secs(2)), "2.000 s");
+/// ```
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs_f64();
+    if seconds >= 1.0 {
+        format!("{:.3} s", seconds)
+    } else if seconds >= 0.001 {
+        format!("{:.3} ms", seconds * 1e3)
+    } else {
+        format!("{:.3} µs", seconds * 1e6)
+    }
+}
+
+/// Masque la sortie standard tant qu'il existe : elle est redirigée vers
+/// `/dev/null`, puis rétablie à la destruction. Les erreurs (stderr) restent visibles.
+///
+/// Hors Unix, la sortie n'est pas masquée.
+pub struct Silence {
+    #[cfg(unix)]
+    saved: std::os::fd::OwnedFd,
+}
+
+#[cfg(unix)]
+unsafe extern "C" {
+    fn dup2(old: std::ffi::c_int, new: std::ffi::c_int) -> std::ffi::c_int;
+}
+
+impl Silence {
+    /// # Fonction : `new`
+    ///
+    /// Redirige la sortie standard vers `/dev/null` en gardant une copie de
+    /// l'ancienne, pour la rétablir ensuite.
+    #[cfg(unix)]
+    pub fn new() -> io::Result<Silence> {
+        use std::os::fd::{AsFd, AsRawFd};
+
+        io::stdout().flush()?;
+        let saved = io::stdout().as_fd().try_clone_to_owned()?;
+        let null = std::fs::OpenOptions::new().write(true).open("/dev/null")?;
+        // SAFETY : les deux descripteurs sont ouverts pendant tout l'appel.
+        if unsafe { dup2(null.as_raw_fd(), io::stdout().as_raw_fd()) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Silence { saved })
+    }
+
+    #[cfg(not(unix))]
+    pub fn new() -> io::Result<Silence> {
+        Ok(Silence {})
+    }
+}
+
+impl Drop for Silence {
+    fn drop(&mut self) {
+        // Ce qui reste dans le tampon appartient encore à la sortie masquée
+        let _ = io::stdout().flush();
+
+        #[cfg(unix)]
+        {
+            use std::os::fd::AsRawFd;
+            // SAFETY : `saved` est une copie ouverte de l'ancienne sortie standard.
+            unsafe {
+                dup2(self.saved.as_raw_fd(), io::stdout().as_raw_fd());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: fn(u64) -> Duration = Duration::from_millis;
+
+    #[test]
+    fn stats_over_fixed_durations() {
+        let stats = Stats::compute(&[MS(30), MS(10), MS(20), MS(60)]).unwrap();
+        assert_eq!(stats, Stats { runs: 4, min: MS(10), median: MS(25), mean: MS(30), max: MS(60) });
+
+        let odd = Stats::compute(&[MS(7), MS(3), MS(5)]).unwrap();
+        assert_eq!((odd.median, odd.mean), (MS(5), MS(5)));
+
+        let single = Stats::compute(&[MS(4)]).unwrap();
+        assert_eq!((single.min, single.median, single.max), (MS(4), MS(4), MS(4)));
+        assert_eq!(Stats::compute(&[]), None);
+    }
+
+    #[test]
+    fn durations_use_the_most_readable_unit() {
+        assert_eq!(format_duration(Duration::from_micros(250)), "250.000 µs");
+        assert_eq!(format_duration(Duration::from_micros(1500)), "1.500 ms");
+        assert_eq!(format_duration(Duration::from_secs(2)), "2.000 s");
+    }
+
+    #[test]
+    fn report_lists_the_four_statistics() {
+        let stats = Stats::compute(&[MS(1), MS(3)]).unwrap();
+        let mut report = Vec::new();
+        write_report("cat gros.txt", &stats, &mut report).unwrap();
+
+        let report = String::from_utf8(report).unwrap();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[0], "Commande : cat gros.txt (2 exécution(s))");
+        assert_eq!(lines[2].split_whitespace().collect::<Vec<_>>(), ["1.000", "ms", "2.000", "ms", "2.000", "ms", "3.000", "ms"]);
+    }
+}
@@ -1,19 +1,26 @@
-use std::fs::File;
-use std::io::{self, Read, Write, BufReader};
-use std::path::Path;
+//! Implémentation Rust de la commande `cat`.
+//!
+//! Ce module implémente la commande `cat` avec Rust. Elle permet de traiter
+//! des fichiers et des flux stdin, et de gérer les multiples options de la commande.
 
-const VERSION: &str = "1.0.0";
-const BUFFER_SIZE: usize = 8192; // Taille du bloc pour la lecture
+use std::fs::{self, File};
+use std::io::{self, IsTerminal, Read, Write, BufReader};
+use std::path::{Path, PathBuf};
+use std::process;
 
-/// Implémentation Rust de la commande `cat`.
-///
-/// Ce module implémente la commande `cat` avec Rust. Elle permet de traiter
-/// des fichiers et des flux stdin, et de gérer les multiples options de la commande.
+use crate::syntax::{self, Language};
+use crate::{ansi, date, escape, file_list, lines, menu, path, pipeline, size};
+
+const VERSION: &str = "1.0.0";
+const BUFFER_SIZE: usize = 8192; // Taille du bloc pour la lecture, sauf avec --buffer-size
+const HEX_WIDTH: usize = 16; // Nombre d'octets par ligne de --hex
+const INDENT_WIDTH: usize = 4; // Nombre d'espaces par niveau d'indentation de --fold-level
+const TEMP_FILE_ATTEMPTS: usize = 100; // Noms essayés pour le fichier temporaire de --in-place
 
 /// Structure représentant les options de traitement pour la commande `cat`.
 ///
 /// Chaque champ correspond à une option possible de `cat`.
-#[derive(Default, Clone)]
+#[derive(Default, Clone, PartialEq)]
 struct Options {
     /// Affiche tous les caractères non imprimables (équivalent à `-vET` ou `-A`).
     show_all: bool,
@@ -29,12 +36,144 @@ struct Options {
     show_tabs: bool,
     /// Affiche les caractères non imprimables sauf les tabulations et fins de ligne (`-v`).
     show_nonprinting: bool,
+    /// Réécrit le résultat dans le fichier source au lieu de l'afficher (`--in-place`).
+    in_place: bool,
+    /// Numérote uniquement les lignes de l'intervalle (`--number-range=START:END`).
+    number_range: Option<LineRange>,
+    /// Affiche uniquement les lignes de l'intervalle (`--lines-range=START:END`).
+    lines_range: Option<LineRange>,
+    /// Rend visibles les espaces et tabulations en fin de ligne (`--show-trailing-whitespace`).
+    show_trailing_whitespace: bool,
+    /// Coupe les lignes à `N` colonnes sur les blancs, comme `fold -s` (`--wrap=N`).
+    wrap: Option<usize>,
+    /// Juxtapose les lignes correspondantes de plusieurs fichiers, comme `paste` (`--paste`).
+    paste: bool,
+    /// Séparateur des colonnes de `--paste` (`-d SEP`, tabulation par défaut).
+    delimiter: Option<String>,
+    /// Supprime les séquences d'échappement ANSI (`--strip-ansi`).
+    strip_ansi: bool,
+    /// Préfixe chaque ligne par `fichier:numéro:`, comme `grep -n` (`--grep-format`).
+    grep_format: bool,
+    /// Affiche un en-tête avec nom, taille et date de modification avant chaque fichier (`--with-header`).
+    with_header: bool,
+    /// Fichier de référence : les lignes qui en diffèrent sont marquées (`--diff-mark REF`).
+    diff_mark: Option<String>,
+    /// Signale sur stderr les fins de ligne utilisées (LF, CRLF, CR ou mélange) (`--check-endings`).
+    check_endings: bool,
+    /// Affiche le fichier en hexadécimal, comme `hexdump -C` (`-x`, `--hex`).
+    hex: bool,
+    /// Nombre d'octets par ligne de `--hex` (`--width=N`, 16 par défaut).
+    hex_width: Option<usize>,
+    /// N'affiche que les `N` premières lignes (`--head N`).
+    head: Option<usize>,
+    /// N'affiche que les `N` dernières lignes (`--tail N`).
+    tail: Option<usize>,
+    /// Remplace les suites d'espaces par un seul espace, comme `tr -s ' '` (`--squeeze-spaces`).
+    squeeze_spaces: bool,
+    /// Traite aussi les tabulations comme des espaces à réduire (`--squeeze-spaces=tabs`).
+    squeeze_tabs: bool,
+    /// Avec `--squeeze-spaces`, laisse intacte l'indentation en début de ligne (`--keep-indent`).
+    keep_indent: bool,
+    /// Décale chaque lettre ASCII de 13 positions dans l'alphabet (`--rot13`).
+    rot13: bool,
+    /// Met le texte en majuscules (`--upper`).
+    upper: bool,
+    /// Met le texte en minuscules (`--lower`).
+    lower: bool,
+    /// Avec `-n`, écrit les numéros de ligne et leur position dans ce fichier
+    /// au lieu de les afficher (`--sidecar FICHIER`).
+    sidecar: Option<String>,
+    /// Masque les lignes indentées de plus de `N` niveaux (`--fold-level N`).
+    fold_level: Option<usize>,
+    /// Nombre d'espaces formant un niveau d'indentation (`--indent-width N`, 4 par défaut).
+    indent_width: Option<usize>,
+    /// Affiche titres, gras et italique Markdown avec des styles du terminal (`--md`).
+    markdown: bool,
+    /// Colore les lignes selon leur format : JSON, diff ou journal (`--syntax LANG`).
+    syntax: Option<Language>,
+    /// Réduit les lignes identiques consécutives à une seule, comme `uniq` (`--dedup`).
+    dedup: bool,
+    /// Avec `--dedup`, préfixe chaque ligne par son nombre de répétitions, comme `uniq -c`
+    /// (`--dedup-count`).
+    dedup_count: bool,
+    /// N'affiche que la ligne `N` et son contexte, mise en évidence (`--goto N`).
+    goto: Option<usize>,
+    /// Nombre de lignes affichées avant et après la ligne de `--goto` (`--context C`, 3 par défaut).
+    context: Option<usize>,
+    /// Taille des blocs lus dans les fichiers (`--buffer-size N` ou variable
+    /// `UTILS_BUFFER_SIZE`, `BUFFER_SIZE` par défaut).
+    buffer_size: Option<usize>,
+    /// Texte ajouté au début de chaque ligne (`--prefix TEXTE`), après le numéro de `-n`.
+    prefix: Option<String>,
+    /// Texte ajouté à la fin de chaque ligne (`--suffix TEXTE`).
+    suffix: Option<String>,
+    /// Place le préfixe avant le numéro de `-n` au lieu d'après (`--prefix-before-number`).
+    prefix_before_number: bool,
+    /// Listes de fichiers à afficher (`--files-from F`, `--files0-from F`).
+    lists: file_list::Sources,
+    /// La sortie est directement un terminal (pas un fichier ni la commande suivante
+    /// d'un pipeline) : seules ces sorties reçoivent couleurs et surlignage.
+    /// Fixé par l'appelant de [`process_command`], pas par une option.
+    terminal: bool,
+}
+
+/// Intervalle de lignes (numérotées à partir de 1, bornes incluses).
+///
+/// Une borne absente signifie que l'intervalle est ouvert de ce côté
+/// (`100:` jusqu'à la fin, `:50` depuis le début).
+#[derive(Default, Clone, Copy, PartialEq)]
+struct LineRange {
+    /// Première ligne de l'intervalle.
+    start: Option<usize>,
+    /// Dernière ligne de l'intervalle.
+    end: Option<usize>,
+}
+
+impl LineRange {
+    /// Parse un intervalle au format `START:END`.
+    ///
+    /// # Exemple
+    /// ```rust
+    /// let range = LineRange::parse("10:").unwrap();
+    /// assert!(range.contains(10) && !range.contains(9));
+    /// ```
+    fn parse(spec: &str) -> io::Result<LineRange> {
+        let invalid = || io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Intervalle invalide : '{}' (format attendu START:END)", spec)
+        );
+
+        let (start, end) = spec.split_once(':').ok_or_else(invalid)?;
+        let parse_bound = |bound: &str| -> io::Result<Option<usize>> {
+            if bound.is_empty() {
+                return Ok(None);
+            }
+            match bound.parse::<usize>() {
+                Ok(n) if n > 0 => Ok(Some(n)),
+                _ => Err(invalid()),
+            }
+        };
+
+        let range = LineRange { start: parse_bound(start)?, end: parse_bound(end)? };
+        if let (Some(s), Some(e)) = (range.start, range.end)
+            && s > e
+        {
+            return Err(invalid());
+        }
+
+        Ok(range)
+    }
+
+    /// Indique si la ligne `line` (à partir de 1) appartient à l'intervalle.
+    fn contains(&self, line: usize) -> bool {
+        self.start.is_none_or(|s| line >= s) && self.end.is_none_or(|e| line <= e)
+    }
 }
 
 /// Fonction principale du programme `cat`.
 ///
 /// Démarre un shell interactif permettant de saisir des commandes `cat`. Si l'utilisateur
-/// quitte l'utilitaire en tapant `quit`, il reviendra sur le menu principal.
+/// quitte l'utilitaire en tapant `menu` (ou `b`, `0`, `quit`), il reviendra sur le menu principal.
 ///
 /// # Exemple
 /// ```no_run
@@ -43,7 +182,7 @@ struct Options {
 pub fn cat() {
     loop {
         println!("\n=== Programme utilitaire cat ===");
-        println!("Entrez votre commande (ou 'quit' pour quitter) :");
+        println!("Entrez votre commande (ou 'menu' pour revenir au menu principal) :");
         print!("> ");
         io::stdout().flush().unwrap();
 
@@ -52,7 +191,7 @@ pub fn cat() {
         
         let input = input.trim();
         
-        if input == "quit" {
+        if input == "quit" || menu::is_back(input) {
             break;
         }
 
@@ -62,8 +201,8 @@ pub fn cat() {
 
         // Vérifier si la commande contient un pipe
         if input.contains('|') {
-            // Traiter la commande avec pipe (echo ... | cat ...)
-            match process_piped_command(input) {
+            // Enchaîner les commandes (echo ... | cat ..., cat ... | wc ...)
+            match pipeline::run_line(input) {
                 Ok(_) => {},
                 Err(e) => {
                     eprintln!("Erreur : {}", e);
@@ -86,7 +225,7 @@ pub fn cat() {
 
             // Traiter la commande
             let args: Vec<&str> = parts[1..].iter().map(|s| s.as_str()).collect();
-            match process_command(&args) {
+            match process_command(&args, None, io::stdout().is_terminal(), &mut io::stdout()) {
                 Ok(_) => {},
                 Err(e) => {
                     eprintln!("Erreur : {}", e);
@@ -97,6 +236,21 @@ pub fn cat() {
     }
 }
 
+/// Exécute une commande `cat` en mode non interactif.
+///
+/// Les arguments sont ceux passés au programme après le nom de la commande
+/// (ex. `projet cat fichier.txt`). Retourne le code de sortie : 1 si un fichier
+/// n'a pas pu être affiché, 0 sinon.
+pub fn run(args: &[String]) -> i32 {
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    if let Err(e) = process_command(&args, None, io::stdout().is_terminal(), &mut io::stdout()) {
+        pipeline::exit_on_broken_pipe(&e);
+        eprintln!("Erreur : {}", e);
+        return 1;
+    }
+    0
+}
+
 /// Parse une ligne de commande en respectant les guillemets et échappements.
 ///
 /// # Algorithme
@@ -162,203 +316,560 @@ fn parse_command_line(input: &str) -> Vec<String> {
     result
 }
 
-/// Interprète les séquences d’échappement dans une chaîne.
-///
-/// # Algorithme
-/// - Parcours de la chaîne caractère par caractère.
-/// - Si `\`, détermine la séquence (`n`, `t`, `r`, `xFF`, etc...).
-/// - Remplace par le caractère correspondant.
-///
-/// # Arguments
-/// * `input` - Chaîne avec séquences d’échappement.
-///
-/// # Retour
-/// Chaîne transformée.
+/// Commande `cat` utilisable dans un pipeline (`cat fichier | wc -l`).
 ///
-/// # Exemple
-/// ```rust
-/// let text = cat_rs::unescape("Hello\\nWorld");
-/// assert_eq!(text, "Hello\nWorld");
-/// // Affiche :
-/// // Hello
-/// // World
-/// ```
-fn unescape(input: &str) -> String {
-    let mut output = String::new();
-    let mut chars = input.chars().peekable();
+/// Sans fichier, le texte reçu de la commande précédente est traité comme stdin.
+pub struct CatCommand {
+    args: Vec<String>,
+}
 
-    while let Some(c) = chars.next() {
-        if c == '\\' {
-            match chars.peek() {
-                Some('n') => { output.push('\n'); chars.next(); }
-                Some('t') => { output.push('\t'); chars.next(); }
-                Some('r') => { output.push('\r'); chars.next(); }
-                Some('v') => { output.push('\u{000B}'); chars.next(); }
-                Some('a') => { output.push('\u{0007}'); chars.next(); }
-                Some('x') => {
-                    chars.next();
-                    let hex: String = chars.by_ref().take(2).collect();
-                    if let Ok(val) = u8::from_str_radix(&hex, 16) {
-                        output.push(val as char);
-                    }
-                }
-                Some('\\') => { output.push('\\'); chars.next(); }
-                _ => output.push(c),
-            }
-        } else {
-            output.push(c);
-        }
+impl CatCommand {
+    pub fn new(args: &[String]) -> Self {
+        CatCommand { args: args.to_vec() }
     }
+}
 
-    output
+impl pipeline::Command for CatCommand {
+    fn run(&self, input: Option<&[u8]>, terminal: bool) -> io::Result<Vec<u8>> {
+        let args: Vec<&str> = self.args.iter().map(|s| s.as_str()).collect();
+        let mut output = Vec::new();
+        process_command(&args, input, terminal, &mut output)?;
+        Ok(output)
+    }
 }
 
-/// Traite une commande contenant un pipe (`echo ... | cat ...`).
+/// Traite une commande `cat` avec ses arguments.
 ///
 /// # Algorithme
-/// 1. Sépare par `|`.
-/// 2. Vérifie la partie echo.
-/// 3. Extrait le texte après echo.
-/// 4. Vérifie la partie cat.
-/// 5. Parse les options.
-/// 6. Applique les options sur le texte.
+/// - Gère `--help` et `--version`.
+/// - Parse les options et les fichiers.
+/// - Sans fichier, traite `input` (sortie de la commande précédente d'un pipeline).
+/// - Traite chaque fichier à la suite (ou les juxtapose avec `--paste`).
 ///
 /// # Arguments
-/// * `input` - Ligne de commande avec pipe.
+/// * `args` - Arguments de la commande.
+/// * `input` - Texte reçu d'un pipeline, ou `None`.
+/// * `terminal` - `out` est un terminal (voir `Options::terminal`).
+/// * `out` - Destination de l'affichage (sortie standard ou tampon du pipeline).
 ///
 /// # Retour
 /// `io::Result<()>` indiquant succès ou erreur.
 ///
 /// # Exemple
 /// ```no_run
-/// process_piped_command(r#"echo "Hello" | cat -n"#)?;
-/// // Affiche :
-/// //      1  Hello
+/// process_command(&["-n", "fichier.txt"], None, false, &mut io::stdout())?;
+/// // Affiche (exemple) :
+/// //      1  Contenu ligne 1
+/// //      2  Contenu ligne 2
 /// ```
-fn process_piped_command(input: &str) -> io::Result<()> {
-    let pipe_parts: Vec<&str> = input.split('|').map(|s| s.trim()).collect();
-    
-    if pipe_parts.len() != 2 {
+fn process_command(args: &[&str], input: Option<&[u8]>, terminal: bool, out: &mut impl Write) -> io::Result<()> {
+    // Gérer --help
+    if args.contains(&"--help") {
+        display_help();
+        return Ok(());
+    }
+
+    // Gérer --version
+    if args.contains(&"--version") {
+        display_version();
+        return Ok(());
+    }
+
+    // Parser les options et les fichiers
+    let (mut options, mut files) = parse_arguments(args)?;
+    options.terminal = terminal;
+
+    // Ajouter les fichiers listés dans --files0-from (séparés par \0) et --files-from (un par ligne)
+    if !options.lists.is_empty() {
+        files = file_list::collect(&files, &options.lists, input)?
+            .iter()
+            .map(|file| file.to_string_lossy().into_owned())
+            .collect();
+    }
+    // Une liste lue sur stdin n'est pas un texte à afficher
+    let input = if options.lists.reads_stdin() { None } else { input };
+
+    // --context n'a de sens qu'avec --goto
+    if options.context.is_some() && options.goto.is_none() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "L'option --context nécessite --goto"
+        ));
+    }
+
+    // --goto lit seulement la fenêtre autour de la ligne : les autres options,
+    // qui transforment le texte entier, ne s'y appliquent pas
+    if let Some(target) = options.goto {
+        if !is_plain_goto(&options) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "L'option --goto ne peut être combinée qu'avec --context, --files-from et --files0-from"
+            ));
+        }
+        let context = options.context.unwrap_or(lines::DEFAULT_CONTEXT);
+        return goto_line(target, context, &files, input, options.terminal, out);
+    }
+
+    // --in-place n'accepte qu'un seul fichier
+    if options.in_place && (files.len() > 1 || options.paste) {
         return Err(io::Error::new(
             io::ErrorKind::InvalidInput,
-            "Format invalide : utilisez 'echo [texte] | cat.rs [options]'"
+            "L'option --in-place n'accepte qu'un seul fichier"
         ));
     }
 
-    let echo_part = pipe_parts[0];
-    let cat_part = pipe_parts[1];
+    // --diff-mark compare les lignes une à une : les options qui suppriment,
+    // ajoutent ou regroupent des lignes fausseraient la correspondance
+    if options.diff_mark.is_some()
+        && (options.squeeze_blank || options.lines_range.is_some() || options.wrap.is_some()
+            || options.paste || options.in_place || options.head.is_some() || options.tail.is_some()
+            || options.fold_level.is_some() || options.dedup)
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "L'option --diff-mark ne peut pas être combinée avec -s, --lines-range, --wrap, --paste, --head, --tail, --fold-level, --dedup ou --in-place"
+        ));
+    }
 
-    // Parser la partie echo avec gestion des guillemets
-    let echo_parsed = parse_command_line(echo_part);
-    if echo_parsed.is_empty() || echo_parsed[0] != "echo" {
+    // --grep-format renvoie aux lignes du fichier : les options qui suppriment ou
+    // regroupent des lignes avant la numérotation décaleraient les numéros
+    if options.grep_format && (options.squeeze_blank || options.dedup || options.fold_level.is_some()) {
         return Err(io::Error::new(
             io::ErrorKind::InvalidInput,
-            "La commande doit commencer par 'echo'"
+            "L'option --grep-format ne peut pas être combinée avec -s, --dedup ou --fold-level"
         ));
     }
 
-    // Extraire le texte après echo (tout sauf le premier mot "echo")
-    let stdin_text = echo_parsed[1..].join(" ");
+    // --fold-level masque des lignes : le fichier ne doit pas être réécrit sans elles
+    if options.fold_level.is_some() && options.in_place {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "L'option --fold-level ne peut pas être combinée avec --in-place"
+        ));
+    }
 
-    // Parser la partie cat avec gestion des guillemets
-    let cat_parsed = parse_command_line(cat_part);
-    if cat_parsed.is_empty() || cat_parsed[0] != "cat" {
+    // --indent-width n'a de sens qu'avec --fold-level
+    if options.indent_width.is_some() && options.fold_level.is_none() {
         return Err(io::Error::new(
             io::ErrorKind::InvalidInput,
-            "Après le pipe, la commande doit être 'cat [options]'"
+            "L'option --indent-width nécessite --fold-level"
         ));
     }
 
-    // Parser les options de cat (tout sauf le premier mot "cat.rs")
-    let cat_args: Vec<&str> = cat_parsed[1..].iter().map(|s| s.as_str()).collect();
-    let (options, _) = parse_arguments(&cat_args)?;
+    // --hex affiche les octets bruts : les options qui transforment le texte ne s'y appliquent pas
+    if options.hex && (options.in_place || options.paste || options.diff_mark.is_some()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "L'option --hex ne peut pas être combinée avec --in-place, --paste ou --diff-mark"
+        ));
+    }
 
-    // Traiter le stdin avec les options
-    let text_unescape = unescape(&stdin_text);
-    process_stdin(&text_unescape, &options)?;
+    // --prefix-before-number n'a de sens qu'avec --prefix
+    if options.prefix_before_number && options.prefix.is_none() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "L'option --prefix-before-number nécessite --prefix"
+        ));
+    }
 
-    Ok(())
+    // --hex affiche les octets bruts, sans lignes à décorer
+    if options.hex && (options.prefix.is_some() || options.suffix.is_some()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Les options --prefix et --suffix ne peuvent pas être combinées avec --hex"
+        ));
+    }
+
+    // --syntax et --md colorent tous deux le texte, de façons incompatibles
+    if options.syntax.is_some() && (options.markdown || options.hex) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "L'option --syntax ne peut pas être combinée avec --md ou --hex"
+        ));
+    }
+
+    // --width n'a de sens qu'avec --hex
+    if options.hex_width.is_some() && !options.hex {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "L'option --width nécessite --hex"
+        ));
+    }
+
+    // --upper et --lower sont contradictoires
+    if options.upper && options.lower {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Les options --upper et --lower ne peuvent pas être combinées"
+        ));
+    }
+
+    // --keep-indent n'a de sens qu'avec --squeeze-spaces
+    if options.keep_indent && !options.squeeze_spaces {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "L'option --keep-indent nécessite --squeeze-spaces"
+        ));
+    }
+
+    // -d n'a de sens qu'avec --paste
+    if options.delimiter.is_some() && !options.paste {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "L'option -d nécessite --paste"
+        ));
+    }
+
+    // --sidecar exporte la numérotation de -n au lieu de l'afficher
+    if options.sidecar.is_some() {
+        if !options.number {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "L'option --sidecar nécessite -n"
+            ));
+        }
+        if options.number_nonblank || options.number_range.is_some() || options.in_place || options.hex {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "L'option --sidecar ne peut pas être combinée avec -b, --number-range, --in-place ou --hex"
+            ));
+        }
+        return write_with_sidecar(&options, files, input, out);
+    }
+
+    process_inputs(&options, files, input, out)
 }
 
-/// Traite une commande `cat` avec ses arguments.
-///
-/// # Algorithme
-/// - Gère `--help` et `--version`.
-/// - Parse les options et le fichier.
-/// - Traite le fichier ou affiche une erreur.
+/// Affiche le texte reçu du pipeline, les fichiers juxtaposés (`--paste`)
+/// ou les fichiers les uns à la suite des autres.
 ///
 /// # Arguments
-/// * `args` - Arguments de la commande.
+/// * `options` - Options, déjà vérifiées par [`process_command`].
+/// * `files` - Fichiers à afficher.
+/// * `input` - Texte reçu d'un pipeline, ou `None`.
+/// * `out` - Destination de l'affichage.
+fn process_inputs(options: &Options, files: Vec<String>, input: Option<&[u8]>, out: &mut impl Write) -> io::Result<()> {
+    // Sans fichier, traiter le texte reçu du pipeline
+    if files.is_empty() && !options.paste
+        && let Some(input) = input
+    {
+        // Impossible de réécrire un flux stdin
+        if options.in_place {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "L'option --in-place ne peut pas être utilisée avec stdin"
+            ));
+        }
+        if options.hex {
+            return write!(out, "{}", hexdump(input, 0, options.hex_width.unwrap_or(HEX_WIDTH)));
+        }
+        return process_stdin(&String::from_utf8_lossy(input), options, out);
+    }
+
+    if files.is_empty() {
+        writeln!(out, "Erreur : Aucun fichier spécifié")?;
+        writeln!(out, "Utilisez 'cat --help' pour plus d'informations")?;
+        return Ok(());
+    }
+
+    // Juxtaposer les fichiers ligne par ligne
+    if options.paste {
+        let delimiter = options.delimiter.as_deref().unwrap_or("\t");
+        let pasted = paste_files(&files, delimiter)?;
+        write!(out, "{}", apply_options(&pasted, options, "(paste)"))?;
+        return Ok(());
+    }
+
+    // Traiter les fichiers les uns à la suite des autres ; une erreur sur
+    // un fichier n'empêche pas d'afficher les suivants, sauf si la sortie
+    // elle-même est fermée (BrokenPipe)
+    let mut first_error = None;
+    for path in files {
+        match process_file(path, options, out) {
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => return Err(e),
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+            Ok(()) => {}
+        }
+    }
+
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// Indique que `options` ne contient, en plus de `--goto`, que des options qui s'y
+/// appliquent : `--context` et les listes de fichiers (`--files-from`, `--files0-from`).
+/// La taille des blocs (`--buffer-size`, ou la variable `UTILS_BUFFER_SIZE`) est tolérée.
 ///
-/// # Retour
-/// `io::Result<()>` indiquant succès ou erreur.
+/// Ce sont les options analysées qui sont comparées, pas les arguments : une valeur
+/// commençant par `-` (`--prefix -`) ou un nom de fichier comme `-` ne faussent rien.
+fn is_plain_goto(options: &Options) -> bool {
+    let plain = Options {
+        goto: options.goto,
+        context: options.context,
+        lists: options.lists.clone(),
+        buffer_size: options.buffer_size,
+        terminal: options.terminal,
+        ..Default::default()
+    };
+    *options == plain
+}
+
+/// Affiche la ligne `target` de chaque fichier (ou du texte du pipeline) avec
+/// `context` lignes autour (`--goto`, `--context`).
+///
+/// # Algorithme
+/// - Lit chaque source avec [`lines::write_window`], qui s'arrête après la fenêtre.
+/// - Avec plusieurs fichiers, précède chaque fenêtre de `==> nom <==`.
+/// - La ligne demandée est mise en évidence si `highlight` est vrai (sortie
+///   directement dans un terminal, voir `Options::terminal`).
+/// - Si un fichier compte moins de `target` lignes, le signale par une erreur
+///   (après avoir traité les fichiers suivants).
 ///
 /// # Exemple
-/// ```no_run
-/// process_command(&["-n", "fichier.txt"])?;
-/// // Affiche (exemple) :
-/// //      1  Contenu ligne 1
-/// //      2  Contenu ligne 2
+/// ```text
+/// $ cat --goto 42 --context 1 main.rs
+///   41 |     let total = 0;
+/// > 42 |     total += valeur;
+///   43 | }
 /// ```
-fn process_command(args: &[&str]) -> io::Result<()> {
-    // Gérer --help
-    if args.contains(&"--help") {
-        display_help();
-        return Ok(());
+fn goto_line(
+    target: usize,
+    context: usize,
+    files: &[String],
+    input: Option<&[u8]>,
+    highlight: bool,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let beyond_end = |source: &str, read: usize| {
+        let plural = if read > 1 { "s" } else { "" };
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("La ligne {} n'existe pas : {} ne compte que {} ligne{}", target, source, read, plural)
+        )
+    };
+
+    // Sans fichier, chercher la ligne dans le texte reçu du pipeline
+    if files.is_empty() {
+        let Some(input) = input else {
+            writeln!(out, "Erreur : Aucun fichier spécifié")?;
+            writeln!(out, "Utilisez 'cat --help' pour plus d'informations")?;
+            return Ok(());
+        };
+        let read = lines::write_window(input, target, context, highlight, out)?;
+        return if read < target { Err(beyond_end("l'entrée", read)) } else { Ok(()) };
     }
 
-    // Gérer --version
-    if args.contains(&"--version") {
-        display_version();
-        return Ok(());
+    let mut first_error = None;
+    for (index, file_path) in files.iter().enumerate() {
+        if files.len() > 1 {
+            let separator = if index == 0 { "" } else { "\n" };
+            writeln!(out, "{}==> {} <==", separator, file_path)?;
+        }
+
+        let file = match path::resolve(file_path).and_then(File::open) {
+            Ok(file) => file,
+            Err(e) => {
+                eprintln!("cat: {}: {}", file_path, e);
+                first_error.get_or_insert(e);
+                continue;
+            }
+        };
+        let read = lines::write_window(BufReader::new(file), target, context, highlight, out)?;
+        if read < target {
+            first_error.get_or_insert(beyond_end(&format!("'{}'", file_path), read));
+        }
     }
 
-    // Parser les options et le fichier
-    let (options, file_path) = parse_arguments(args)?;
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
 
-    // Traiter le fichier
-    if let Some(path) = file_path {
-        process_file(path, &options)?;
-    } else {
-        println!("Erreur : Aucun fichier spécifié");
-        println!("Utilisez 'cat --help' pour plus d'informations");
+/// Affiche le texte sans numéros et écrit la numérotation dans un fichier à part (`--sidecar`).
+///
+/// # Algorithme
+/// - Produit la sortie avec [`process_inputs`] comme sans `-n`, dans un tampon.
+/// - Écrit le tampon dans `out`, même si un fichier a échoué (comme sans `--sidecar`).
+/// - Écrit dans le fichier annexe une ligne `numéro\tposition` par ligne affichée,
+///   où `position` est le décalage en octets du début de la ligne dans la sortie.
+///
+/// # Exemple
+/// Pour la sortie `"ab\ncdé\nf"`, le fichier annexe contient les lignes
+/// `1\t0`, `2\t3` et `3\t8` (`é` occupe deux octets).
+fn write_with_sidecar(options: &Options, files: Vec<String>, input: Option<&[u8]>, out: &mut impl Write) -> io::Result<()> {
+    let plain = Options { number: false, sidecar: None, ..options.clone() };
+    let mut content = Vec::new();
+    let result = process_inputs(&plain, files, input, &mut content);
+    out.write_all(&content)?;
+
+    let sidecar = options.sidecar.as_deref().unwrap_or_default();
+    let mut writer = io::BufWriter::new(File::create(path::resolve(sidecar)?)?);
+    let mut offset = 0;
+    for (index, line) in lines::lines_with_terminators(&content[..]).enumerate() {
+        let (text, terminator) = line?;
+        writeln!(writer, "{}\t{}", index + 1, offset)?;
+        offset += text.len() + terminator.len();
     }
+    writer.flush()?;
 
-    Ok(())
+    result
 }
 
-/// Parse les arguments pour extraire options et chemin de fichier.
+/// Parse les arguments pour extraire options et chemins de fichiers.
 ///
 /// # Algorithme
 /// - Pour chaque argument :
+///   - `-d` prend l'argument suivant comme séparateur de `--paste`
+///     (les séquences comme `\t` sont interprétées).
+///   - `--diff-mark` prend l'argument suivant comme fichier de référence.
+///   - `--sidecar` prend l'argument suivant comme fichier annexe.
+///   - `--head` et `--tail` prennent l'argument suivant comme nombre de lignes.
+///   - `--fold-level` et `--indent-width` prennent l'argument suivant comme nombre.
 ///   - S’il commence par `-`, est traité comme option.
-///   - Sinon, est considéré comme le chemin fichier.
+///   - Sinon, est ajouté à la liste des fichiers.
 ///
 /// # Arguments
 /// * `args` - Slice des arguments.
 ///
 /// # Retour
-/// Tuple `(Options, Option<String>)`.
+/// Tuple `(Options, Vec<String>)`.
 ///
 /// # Exemple
 /// ```rust
-/// let (opts, file) = cat_rs::parse_arguments(&["-n", "fichier.txt"]).unwrap();
-/// assert_eq!(file.unwrap(), "fichier.txt");
+/// let (opts, files) = cat_rs::parse_arguments(&["-n", "fichier.txt"]).unwrap();
+/// assert_eq!(files, vec!["fichier.txt"]);
 /// ```
-fn parse_arguments(args: &[&str]) -> io::Result<(Options, Option<String>)> {
+fn parse_arguments(args: &[&str]) -> io::Result<(Options, Vec<String>)> {
     let mut options = Options::default();
-    let mut file_path: Option<String> = None;
+    let mut files: Vec<String> = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        // --files-from / --files0-from : la liste est la valeur après '=' ou l'argument suivant
+        let listed = file_list::parse_option(&mut options.lists, arg, || iter.next().copied())
+            .map_err(|option| io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("L'option {} attend un fichier (ou - pour stdin)", option)
+            ))?;
+        if listed {
+            continue;
+        }
 
-    for arg in args {
-        if arg.starts_with('-') {
-            parse_option(*arg, &mut options)?;
-        } else if file_path.is_none() {
-            // Prendre le premier argument qui n'est pas une option comme fichier
-            file_path = Some(arg.to_string());
+        if *arg == "-d" || *arg == "--delimiter" {
+            let Some(delimiter) = iter.next() else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "L'option -d attend un séparateur"
+                ));
+            };
+            options.delimiter = Some(escape::unescape(delimiter));
+        } else if *arg == "--diff-mark" {
+            let Some(reference) = iter.next() else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "L'option --diff-mark attend un fichier de référence"
+                ));
+            };
+            options.diff_mark = Some(reference.to_string());
+        } else if *arg == "--sidecar" {
+            let Some(sidecar) = iter.next() else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "L'option --sidecar attend un fichier"
+                ));
+            };
+            options.sidecar = Some(sidecar.to_string());
+        } else if *arg == "--head" || *arg == "--tail" {
+            let count = iter.next().and_then(|n| n.parse::<usize>().ok()).ok_or_else(|| io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("L'option {} attend un nombre de lignes", arg)
+            ))?;
+            if *arg == "--head" {
+                options.head = Some(count);
+            } else {
+                options.tail = Some(count);
+            }
+        } else if *arg == "--fold-level" {
+            let level = iter.next().and_then(|n| n.parse::<usize>().ok()).ok_or_else(|| io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "L'option --fold-level attend un nombre de niveaux"
+            ))?;
+            options.fold_level = Some(level);
+        } else if *arg == "--goto" {
+            match iter.next().and_then(|n| n.parse::<usize>().ok()) {
+                Some(line) if line > 0 => options.goto = Some(line),
+                _ => return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "L'option --goto attend un numéro de ligne strictement positif"
+                )),
+            }
+        } else if *arg == "--context" {
+            let context = iter.next().and_then(|n| n.parse::<usize>().ok()).ok_or_else(|| io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "L'option --context attend un nombre de lignes"
+            ))?;
+            options.context = Some(context);
+        } else if *arg == "--buffer-size" {
+            let Some(value) = iter.next() else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "L'option --buffer-size attend une taille en octets (ex. 4096, 64K, 1M)"
+                ));
+            };
+            let size = size::parse_buffer_size(value)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("--buffer-size : {}", e)))?;
+            options.buffer_size = Some(size);
+        } else if *arg == "--prefix" || *arg == "--suffix" {
+            let Some(text) = iter.next() else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("L'option {} attend un texte", arg)
+                ));
+            };
+            if *arg == "--prefix" {
+                options.prefix = Some(escape::unescape(text));
+            } else {
+                options.suffix = Some(escape::unescape(text));
+            }
+        } else if *arg == "--syntax" {
+            let Some(name) = iter.next() else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "L'option --syntax attend un format (json, diff ou log)"
+                ));
+            };
+            let language = Language::parse(name).ok_or_else(|| io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Format inconnu pour --syntax : '{}' (json, diff ou log)", name)
+            ))?;
+            options.syntax = Some(language);
+        } else if *arg == "--indent-width" {
+            match iter.next().and_then(|n| n.parse::<usize>().ok()) {
+                Some(width) if width > 0 => options.indent_width = Some(width),
+                _ => return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "L'option --indent-width attend un nombre d'espaces strictement positif"
+                )),
+            }
+        } else if arg.starts_with('-') {
+            parse_option(arg, &mut options)?;
+        } else {
+            files.push(arg.to_string());
         }
     }
 
-    Ok((options, file_path))
+    // Sans --buffer-size, la taille peut venir de l'environnement
+    if options.buffer_size.is_none() {
+        options.buffer_size = size::env_buffer_size()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    }
+
+    Ok((options, files))
 }
 
 /// Parse une option et met à jour la structure `Options`.
@@ -398,6 +909,56 @@ fn parse_option(opt: &str, options: &mut Options) -> io::Result<()> {
         "-s" | "--squeeze-blank" => options.squeeze_blank = true,
         "-T" | "--show-tabs" => options.show_tabs = true,
         "-v" | "--show-nonprinting" => options.show_nonprinting = true,
+        "--in-place" => options.in_place = true,
+        "--show-trailing-whitespace" => options.show_trailing_whitespace = true,
+        "--paste" => options.paste = true,
+        "--strip-ansi" => options.strip_ansi = true,
+        "--grep-format" => options.grep_format = true,
+        "--with-header" => options.with_header = true,
+        "--check-endings" => options.check_endings = true,
+        "-x" | "--hex" => options.hex = true,
+        "--squeeze-spaces" => options.squeeze_spaces = true,
+        "--squeeze-spaces=tabs" => {
+            options.squeeze_spaces = true;
+            options.squeeze_tabs = true;
+        }
+        "--keep-indent" => options.keep_indent = true,
+        "--rot13" => options.rot13 = true,
+        "--md" => options.markdown = true,
+        "--dedup" => options.dedup = true,
+        "--prefix-before-number" => options.prefix_before_number = true,
+        "--dedup-count" => {
+            options.dedup = true;
+            options.dedup_count = true;
+        }
+        "--upper" => options.upper = true,
+        "--lower" => options.lower = true,
+        _ if opt.starts_with("--number-range=") => {
+            options.number_range = Some(LineRange::parse(&opt["--number-range=".len()..])?);
+        },
+        _ if opt.starts_with("--lines-range=") => {
+            options.lines_range = Some(LineRange::parse(&opt["--lines-range=".len()..])?);
+        },
+        _ if opt.starts_with("--width=") => {
+            let value = &opt["--width=".len()..];
+            match value.parse::<usize>() {
+                Ok(width) if width > 0 => options.hex_width = Some(width),
+                _ => return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Largeur invalide : '{}' (entier positif attendu)", value)
+                )),
+            }
+        },
+        _ if opt.starts_with("--wrap=") => {
+            let value = &opt["--wrap=".len()..];
+            match value.parse::<usize>() {
+                Ok(width) if width > 0 => options.wrap = Some(width),
+                _ => return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Largeur invalide : '{}' (entier positif attendu)", value)
+                )),
+            }
+        },
         _ => {
             // Gérer les options combinées (ex: -vET)
             if opt.starts_with('-') && opt.len() > 2 && !opt.starts_with("--") {
@@ -415,25 +976,33 @@ fn parse_option(opt: &str, options: &mut Options) -> io::Result<()> {
 ///
 /// # Algorithme
 /// - Vérifie l’existence du fichier.
-/// - Lit le fichier par blocs de taille `BUFFER_SIZE`.
-/// - Convertit les octets en `String`.
+/// - Avec `--hex`, affiche les octets du fichier avec [`write_hex_dump`].
+/// - Lit le fichier par blocs de taille `BUFFER_SIZE` (ou `--buffer-size`).
+/// - Convertit les octets en `String` une fois le fichier entièrement lu, pour
+///   qu'un caractère UTF-8 à cheval sur deux blocs reste valide.
 /// - Applique les options sur le texte.
+/// - Marque les lignes qui diffèrent du fichier de référence si `--diff-mark`.
+/// - Affiche l'en-tête du fichier si `--with-header` (voir [`file_header`]).
 /// - Affiche le résultat.
 ///
 /// # Arguments
 /// * `file_path` - Chemin vers le fichier.
 /// * `options` - Options de traitement.
+/// * `out` - Destination de l'affichage.
 ///
 /// # Retour
 /// `io::Result<()>`.
 ///
 /// # Exemple
 /// ```no_run
-/// process_file("fichier.txt".to_string(), &Options::default())?;
+/// process_file("fichier.txt".to_string(), &Options::default(), &mut io::stdout())?;
 /// ```
-fn process_file(file_path: String, options: &Options) -> io::Result<()> {
+fn process_file(file_path: String, options: &Options, out: &mut impl Write) -> io::Result<()> {
+    // Résoudre le chemin (gérer ., .. et ~)
+    let resolved = path::resolve(&file_path)?;
+
     // Vérifier si le fichier existe
-    if !Path::new(&file_path).exists() {
+    if !resolved.exists() {
         eprintln!("cat: {}: Aucun fichier ou dossier de ce type", file_path);
         return Err(io::Error::new(
             io::ErrorKind::NotFound,
@@ -442,7 +1011,7 @@ fn process_file(file_path: String, options: &Options) -> io::Result<()> {
     }
 
     // Tenter d'ouvrir le fichier
-    let file = match File::open(&file_path) {
+    let file = match File::open(&resolved) {
         Ok(f) => f,
         Err(e) => {
             eprintln!("cat: {}: {}", file_path, e);
@@ -450,25 +1019,25 @@ fn process_file(file_path: String, options: &Options) -> io::Result<()> {
         }
     };
 
-    let mut reader = BufReader::new(file);
-    let mut content = String::new();
+    let buffer_size = options.buffer_size.unwrap_or(BUFFER_SIZE);
+    let mut reader = BufReader::with_capacity(buffer_size, file);
+
+    // Les fichiers binaires ne sont pas convertis en texte
+    if options.hex {
+        if options.with_header {
+            writeln!(out, "{}", file_header(&file_path, &fs::metadata(&resolved)?))?;
+        }
+        return write_hex_dump(&mut reader, options.hex_width.unwrap_or(HEX_WIDTH), out);
+    }
+
+    let mut bytes = Vec::new();
 
     // Lire le fichier bloc par bloc
-    let mut buffer = vec![0u8; BUFFER_SIZE];
+    let mut buffer = vec![0u8; buffer_size];
     loop {
         match reader.read(&mut buffer) {
             Ok(0) => break, // Fin du fichier
-            Ok(n) => {
-                // Convertir les octets lus en String
-                match String::from_utf8(buffer[..n].to_vec()) {
-                    Ok(text) => content.push_str(&text),
-                    Err(e) => {
-                        eprintln!("Erreur : Impossible de lire le contenu du fichier (encodage invalide)");
-                        eprintln!("Détails : {}", e);
-                        return Err(io::Error::new(io::ErrorKind::InvalidData, e));
-                    }
-                }
-            },
+            Ok(n) => bytes.extend_from_slice(&buffer[..n]),
             Err(e) => {
                 eprintln!("Erreur : Erreur lors de la lecture du fichier");
                 eprintln!("Détails : {}", e);
@@ -477,65 +1046,465 @@ fn process_file(file_path: String, options: &Options) -> io::Result<()> {
         }
     }
 
+    // Convertir les octets lus en String
+    let content = match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("Erreur : Impossible de lire le contenu du fichier (encodage invalide)");
+            eprintln!("Détails : {}", e);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, e));
+        }
+    };
+
+    // Signaler les fins de ligne avant l'affichage normal
+    if options.check_endings {
+        eprintln!("{}", line_endings_summary(&content, &file_path));
+    }
+
     // Traiter le contenu avec les options
-    let result = apply_options(&content, options);
-    print!("{}", result);
+    let mut result = apply_options(&content, options, &file_path);
+
+    // Marquer les différences avec le fichier de référence
+    if let Some(reference) = &options.diff_mark {
+        let reference_content = match path::resolve(reference).and_then(fs::read_to_string) {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("cat: {}: {}", reference, e);
+                return Err(e);
+            }
+        };
+        result = diff_mark_lines(&result, &content, &reference_content);
+    }
+
+    // Conserver le retour à la ligne final du fichier d'origine
+    // (indispensable pour enchaîner plusieurs fichiers)
+    if content.ends_with('\n') && !result.ends_with('\n') {
+        result.push('\n');
+    }
+
+    if options.in_place {
+        write_in_place(&resolved, &result)?;
+    } else {
+        if options.with_header {
+            writeln!(out, "{}", file_header(&file_path, &fs::metadata(&resolved)?))?;
+        }
+        write!(out, "{}", result)?;
+    }
 
     Ok(())
 }
 
-/// Traite le texte provenant de stdin.
+/// Affiche le contenu d'un lecteur en hexadécimal (`--hex`).
 ///
 /// # Algorithme
-/// - Applique les options sur le texte entré par l'utilisateur.
-/// - Affiche le résultat.
+/// - Lit des blocs de `width * 512` octets, pour ne jamais charger un gros fichier en entier.
+/// - Formate chaque bloc avec [`hexdump`] en poursuivant les positions du bloc précédent.
 ///
 /// # Arguments
-/// * `text` - Texte à afficher.
-/// * `options` - Options.
-///
-/// # Retour
-/// `io::Result<()>`.
-fn process_stdin(text: &str, options: &Options) -> io::Result<()> {
-    let result = apply_options(text, options);
-    print!("{}", result);
-    Ok(())
+/// * `reader` - Source des octets.
+/// * `width` - Nombre d'octets par ligne.
+/// * `out` - Destination de l'affichage.
+fn write_hex_dump(reader: &mut impl Read, width: usize, out: &mut impl Write) -> io::Result<()> {
+    let mut offset = 0;
+    let mut block = Vec::with_capacity(width * 512);
+
+    loop {
+        block.clear();
+        reader.take((width * 512) as u64).read_to_end(&mut block)?;
+        if block.is_empty() {
+            return Ok(());
+        }
+        write!(out, "{}", hexdump(&block, offset, width))?;
+        offset += block.len();
+    }
 }
 
-/// Applique toutes les options au contenu.
+/// Formate des octets en vidage hexadécimal, comme `hexdump -C`.
 ///
 /// # Algorithme
-/// 1. Réduit les lignes vides si `squeeze_blank`.
-/// 2. Affiche caractères non imprimables si `show_nonprinting`.
-/// 3. Affiche tabulations et fins de ligne si demandées.
-/// 4. Numérote les lignes selon `number_nonblank` ou `number`.
+/// - Une ligne par groupe de `width` octets.
+/// - Colonne de position (8 chiffres hexadécimaux, à partir de `offset`).
+/// - Octets en hexadécimal, avec une espace supplémentaire tous les 8 octets ;
+///   la dernière ligne est complétée pour aligner la gouttière.
+/// - Gouttière ASCII entre `|`, où les caractères non imprimables deviennent `.`.
 ///
 /// # Arguments
-/// * `content` - Texte à traiter.
-/// * `options` - Options.
+/// * `bytes` - Octets à afficher.
+/// * `offset` - Position du premier octet dans le fichier.
+/// * `width` - Nombre d'octets par ligne.
 ///
 /// # Retour
-/// Texte formaté.
+/// Le vidage, chaque ligne terminée par un retour à la ligne.
 ///
 /// # Exemple
 /// ```rust
-/// use cat_rs::{apply_options, Options};
-/// let opts = Options { number: true, ..Default::default() };
-/// let text = "Ligne1\nLigne2";
-/// let result = apply_options(text, &opts);
-/// println!("{}", result);
-/// // Affiche :
+/// assert_eq!(
+///     hexdump(b"Hello\n\x00", 16, 16),
+///     "00000010  48 65 6c 6c 6f 0a 00                              |Hello..|\n"
+/// );
+/// ```
+fn hexdump(bytes: &[u8], offset: usize, width: usize) -> String {
+    let mut output = String::new();
+
+    for (row, chunk) in bytes.chunks(width).enumerate() {
+        output.push_str(&format!("{:08x} ", offset + row * width));
+
+        for index in 0..width {
+            if index % 8 == 0 {
+                output.push(' ');
+            }
+            match chunk.get(index) {
+                Some(byte) => output.push_str(&format!("{:02x} ", byte)),
+                None => output.push_str("   "),
+            }
+        }
+
+        let gutter: String = chunk
+            .iter()
+            .map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' })
+            .collect();
+        output.push_str(&format!(" |{}|\n", gutter));
+    }
+
+    output
+}
+
+/// Construit l'en-tête affiché avant un fichier avec `--with-header`.
+///
+/// Contrairement au `==> nom <==` de `head -v`, il indique aussi la taille
+/// et la date de dernière modification du fichier.
+///
+/// # Arguments
+/// * `file_path` - Nom du fichier tel que saisi.
+/// * `metadata` - Métadonnées du fichier (`fs::metadata`).
+///
+/// # Retour
+/// La ligne d'en-tête, sans retour à la ligne.
+///
+/// # Exemple
+/// ```no_run
+/// let header = file_header("notes.txt", &fs::metadata("notes.txt")?);
+/// // "==> notes.txt (1.2 Ko, modifié le 2026-10-16 09:30:00 UTC) <=="
+/// ```
+fn file_header(file_path: &str, metadata: &fs::Metadata) -> String {
+    let modified = metadata
+        .modified()
+        .map(date::format)
+        .unwrap_or_else(|_| "date inconnue".to_string());
+
+    format!(
+        "==> {} ({}, modifié le {}) <==",
+        file_path,
+        size::human_readable(metadata.len()),
+        modified
+    )
+}
+
+/// Décrit les fins de ligne d'un contenu (`--check-endings`).
+///
+/// # Algorithme
+/// - Parcourt les lignes avec [`lines::lines_with_terminators`] en comptant leurs
+///   terminateurs `\r\n` (CRLF) et `\n` (LF), puis les `\r` seuls (CR) restés dans le contenu.
+/// - Un seul type : `fichier: LF (40)`.
+/// - Plusieurs types : `fichier: mixed (LF: 40, CRLF: 3)`.
+/// - Aucun : `fichier: no line endings`.
+///
+/// # Arguments
+/// * `content` - Contenu du fichier.
+/// * `source` - Nom affiché en tête du résumé.
+///
+/// # Exemple
+/// ```rust
+/// assert_eq!(line_endings_summary("a\nb\r\nc\n", "f.txt"), "f.txt: mixed (LF: 2, CRLF: 1)");
+/// assert_eq!(line_endings_summary("a\r\n", "f.txt"), "f.txt: CRLF (1)");
+/// ```
+fn line_endings_summary(content: &str, source: &str) -> String {
+    let (mut lf, mut crlf, mut cr) = (0, 0, 0);
+
+    // La lecture d'un tampon en mémoire ne peut pas échouer
+    for (line, terminator) in lines::lines_with_terminators(content.as_bytes()).flatten() {
+        match terminator {
+            "\r\n" => crlf += 1,
+            "\n" => lf += 1,
+            _ => {}
+        }
+        cr += line.iter().filter(|&&byte| byte == b'\r').count();
+    }
+
+    let counts: Vec<(&str, usize)> = [("LF", lf), ("CRLF", crlf), ("CR", cr)]
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .collect();
+
+    match counts.as_slice() {
+        [] => format!("{}: no line endings", source),
+        [(name, count)] => format!("{}: {} ({})", source, name, count),
+        _ => {
+            let details: Vec<String> = counts.iter().map(|(name, count)| format!("{}: {}", name, count)).collect();
+            format!("{}: mixed ({})", source, details.join(", "))
+        }
+    }
+}
+
+/// Ajoute une marge indiquant, ligne par ligne, les différences avec un fichier de référence.
+///
+/// # Algorithme
+/// - Compare chaque ligne d'origine à la ligne de même numéro de la référence.
+/// - Préfixe la ligne affichée correspondante par :
+///   - `"  "` si elle est identique,
+///   - `"* "` si elle a été modifiée,
+///   - `"+ "` si la référence n'a pas de ligne à ce numéro (ligne en plus).
+/// - Ajoute à la fin les lignes de la référence absentes du fichier, préfixées par `"- "`.
+///
+/// La comparaison est positionnelle : une ligne insérée marque aussi les suivantes.
+///
+/// # Arguments
+/// * `result` - Texte affiché (après les options), une ligne par ligne d'origine.
+/// * `original` - Contenu d'origine du fichier.
+/// * `reference` - Contenu du fichier de référence.
+///
+/// # Exemple
+/// ```rust
+/// assert_eq!(diff_mark_lines("a\nB\nc", "a\nB\nc", "a\nb"), "  a\n* B\n+ c");
+/// assert_eq!(diff_mark_lines("a", "a", "a\nb"), "  a\n- b");
+/// ```
+fn diff_mark_lines(result: &str, original: &str, reference: &str) -> String {
+    let original_lines: Vec<&str> = original.lines().collect();
+    let reference_lines: Vec<&str> = reference.lines().collect();
+
+    let mut marked: Vec<String> = result
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let mark = match (original_lines.get(i), reference_lines.get(i)) {
+                (Some(current), Some(expected)) if current == expected => "  ",
+                (_, Some(_)) => "* ",
+                (_, None) => "+ ",
+            };
+            format!("{}{}", mark, line)
+        })
+        .collect();
+
+    // Lignes de la référence qui n'existent plus dans le fichier
+    for missing in reference_lines.iter().skip(original_lines.len()) {
+        marked.push(format!("- {}", missing));
+    }
+
+    marked.join("\n")
+}
+
+/// Juxtapose les lignes correspondantes de plusieurs fichiers, comme `paste`.
+///
+/// # Algorithme
+/// - Lit toutes les lignes de chaque fichier.
+/// - Pour chaque numéro de ligne, joint la ligne de chaque fichier avec `delimiter`.
+/// - Un fichier plus court que les autres fournit des champs vides.
+///
+/// # Arguments
+/// * `files` - Chemins des fichiers, dans l'ordre des colonnes.
+/// * `delimiter` - Séparateur entre les colonnes.
+///
+/// # Retour
+/// `io::Result<String>` : le texte juxtaposé, terminé par un retour à la ligne.
+///
+/// # Exemple
+/// ```no_run
+/// // a.txt : "1\n2\n3"   b.txt : "un\ndeux"
+/// let text = paste_files(&["a.txt".into(), "b.txt".into()], "\t")?;
+/// assert_eq!(text, "1\tun\n2\tdeux\n3\t\n");
+/// ```
+fn paste_files(files: &[String], delimiter: &str) -> io::Result<String> {
+    let mut contents = Vec::with_capacity(files.len());
+    for file in files {
+        match path::resolve(file).and_then(fs::read_to_string) {
+            Ok(content) => contents.push(content),
+            Err(e) => {
+                eprintln!("cat: {}: {}", file, e);
+                return Err(e);
+            }
+        }
+    }
+
+    let columns: Vec<Vec<&str>> = contents.iter().map(|c| c.lines().collect()).collect();
+    let row_count = columns.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut result = String::new();
+    for row in 0..row_count {
+        let fields: Vec<&str> = columns
+            .iter()
+            .map(|lines| lines.get(row).copied().unwrap_or(""))
+            .collect();
+        result.push_str(&fields.join(delimiter));
+        result.push('\n');
+    }
+
+    Ok(result)
+}
+
+/// Réécrit le contenu d'un fichier de manière atomique.
+///
+/// # Algorithme
+/// - Crée un fichier temporaire au nom unique dans le même dossier (voir
+///   [`create_temp_file`]) : un fichier existant n'est jamais écrasé.
+/// - Y écrit le contenu, lui donne les permissions du fichier d'origine puis
+///   remplace le fichier d'origine avec `fs::rename`.
+/// - Supprime le fichier temporaire en cas d'échec.
+///
+/// Le fichier d'origine n'est donc jamais laissé à moitié écrit, et garde ses
+/// permissions (un script reste exécutable).
+///
+/// # Arguments
+/// * `file_path` - Chemin du fichier à réécrire.
+/// * `content` - Nouveau contenu.
+///
+/// # Retour
+/// `io::Result<()>`.
+fn write_in_place(file_path: &Path, content: &str) -> io::Result<()> {
+    let permissions = fs::metadata(file_path)?.permissions();
+    let (mut temp_file, temp_path) = create_temp_file(file_path)?;
+
+    let result = temp_file
+        .write_all(content.as_bytes())
+        .and_then(|_| temp_file.sync_all())
+        .and_then(|_| fs::set_permissions(&temp_path, permissions))
+        .and_then(|_| fs::rename(&temp_path, file_path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+
+    result
+}
+
+/// Crée le fichier temporaire de [`write_in_place`] à côté de `file_path`, sous
+/// le nom `.<nom>.<pid>.<n>.cat-tmp`. La création échoue plutôt que d'ouvrir un
+/// fichier existant (`create_new`) : `n` est alors augmenté jusqu'à trouver un nom libre.
+fn create_temp_file(file_path: &Path) -> io::Result<(File, PathBuf)> {
+    let file_name = file_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    for attempt in 0..TEMP_FILE_ATTEMPTS {
+        let temp_path = file_path.with_file_name(format!(".{}.{}.{}.cat-tmp", file_name, process::id(), attempt));
+        match fs::OpenOptions::new().write(true).create_new(true).open(&temp_path) {
+            Ok(file) => return Ok((file, temp_path)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!("Impossible de créer un fichier temporaire à côté de '{}'", file_path.display())
+    ))
+}
+
+/// Traite le texte provenant de stdin.
+///
+/// # Algorithme
+/// - Applique les options sur le texte reçu de la commande précédente.
+/// - Affiche le résultat.
+///
+/// # Arguments
+/// * `text` - Texte à afficher.
+/// * `options` - Options.
+/// * `out` - Destination de l'affichage.
+///
+/// # Retour
+/// `io::Result<()>`.
+fn process_stdin(text: &str, options: &Options, out: &mut impl Write) -> io::Result<()> {
+    if options.check_endings {
+        eprintln!("{}", line_endings_summary(text, "(standard input)"));
+    }
+    let mut result = apply_options(text, options, "(standard input)");
+    // Conserver le retour à la ligne final, comme pour un fichier
+    if text.ends_with('\n') && !result.ends_with('\n') {
+        result.push('\n');
+    }
+    write!(out, "{}", result)
+}
+
+/// Applique toutes les options au contenu.
+///
+/// # Algorithme
+/// 0. Supprime les séquences ANSI si `strip_ansi`, transforme les lettres
+///    et masque les lignes trop indentées (`--fold-level`).
+/// 1. Réduit les lignes vides si `squeeze_blank`, puis les lignes identiques
+///    consécutives si `dedup`.
+/// 2. Affiche caractères non imprimables si `show_nonprinting`.
+/// 3. Affiche tabulations et fins de ligne si demandées, puis met en forme
+///    le Markdown si `markdown` (ou colore selon le format de `syntax`) si la
+///    sortie est un terminal.
+/// 4. Numérote les lignes selon `number_nonblank` ou `number`.
+/// 5. Préfixe les lignes par `source:numéro:` si `grep_format`.
+/// 6. Filtre l'intervalle de lignes, garde les premières/dernières lignes
+///    (`--head`, `--tail`) puis coupe les lignes longues.
+///
+/// # Arguments
+/// * `content` - Texte à traiter.
+/// * `options` - Options.
+/// * `source` - Nom du fichier affiché par `--grep-format`.
+///
+/// # Retour
+/// Texte formaté.
+///
+/// # Exemple
+/// ```rust
+/// use cat_rs::{apply_options, Options};
+/// let opts = Options { number: true, ..Default::default() };
+/// let text = "Ligne1\nLigne2";
+/// let result = apply_options(text, &opts, "fichier.txt");
+/// println!("{}", result);
+/// // Affiche :
 /// //      1  Ligne1
 /// //      2  Ligne2
 /// ```
-fn apply_options(content: &str, options: &Options) -> String {
-    let mut result = content.to_string();
+fn apply_options(content: &str, options: &Options, source: &str) -> String {
+    // 0. Supprimer les codes ANSI avant tout (sinon -v les rendrait visibles)
+    let mut result = if options.strip_ansi {
+        ansi::strip(content)
+    } else {
+        content.to_string()
+    };
+
+    // 0 bis. Transformer les lettres (avant -v et -T, dont les notations ^I ou M-
+    //        ne doivent pas être transformées)
+    if options.rot13 || options.upper || options.lower {
+        result = transform_letters(&result, options);
+    }
+
+    // 0 ter. Vue d'ensemble : masquer les lignes trop indentées (avant la numérotation,
+    //        qui porte alors sur les lignes affichées)
+    if let Some(level) = options.fold_level {
+        result = fold_indented_lines(&result, level, options.indent_width.unwrap_or(INDENT_WIDTH));
+    }
 
     // 1. D'abord, squeeze blank (réduire les lignes vides)
     if options.squeeze_blank {
         result = squeeze_blank_lines(&result);
     }
 
+    // 1 bis. Réduire les lignes répétées (avant la numérotation, qui porte alors
+    //        sur les lignes affichées)
+    if options.dedup {
+        result = dedup_lines(&result, options.dedup_count);
+    }
+
+    // 1 ter. Réduire les suites d'espaces (avant de rendre visibles les blancs restants)
+    if options.squeeze_spaces {
+        result = squeeze_spaces(&result, options.squeeze_tabs, options.keep_indent);
+    }
+
+    // 1 quater. Rendre visibles les blancs de fin de ligne (avant -T qui remplace les
+    //          tabulations) ; leur couleur attend la fin de -v, -T et -E
+    let mut trailing_marks = None;
+    if options.show_trailing_whitespace {
+        let (marked, counts) = show_trailing_whitespace(&result);
+        result = marked;
+        if options.terminal && !options.in_place {
+            trailing_marks = Some(counts);
+        }
+    }
+
     // 2. Ensuite, traiter les caractères spéciaux
     if options.show_nonprinting {
         result = show_nonprinting_chars(&result, options.show_tabs, options.show_ends);
@@ -551,11 +1520,62 @@ fn apply_options(content: &str, options: &Options) -> String {
         result = show_ends(&result);
     }
 
+    // 4 bis. Couleur des blancs de fin de ligne (après -v, qui rendrait visible son code ANSI)
+    if let Some(counts) = &trailing_marks {
+        result = highlight_trailing_marks(&result, counts, options.show_ends);
+    }
+
+    // 4 ter. Styles Markdown (après -v, qui rendrait visibles leurs codes ANSI, et
+    //        avant la numérotation, pour reconnaître les titres en début de ligne)
+    if options.markdown && options.terminal && !options.in_place {
+        result = render_markdown(&result);
+    }
+
+    // 4 quater. Couleurs du format (même place que --md : les débuts de ligne,
+    //        comme le + d'un diff, sont encore intacts avant la numérotation)
+    if let Some(language) = options.syntax
+        && options.terminal
+        && !options.in_place
+    {
+        result = syntax::colorize(&result, language);
+    }
+
     // 5. Numéroter les lignes (à la fin pour avoir les bons numéros)
     if options.number_nonblank {
         result = number_nonblank_lines(&result);
-    } else if options.number {
-        result = number_lines(&result);
+    } else if options.number || options.number_range.is_some() {
+        result = number_lines(&result, options.number_range.as_ref());
+    }
+
+    // 5 bis. Préfixe et suffixe de chaque ligne (après la numérotation : le préfixe
+    //        suit le numéro, sauf avec --prefix-before-number)
+    if options.prefix.is_some() || options.suffix.is_some() {
+        let numbered = options.number || options.number_nonblank || options.number_range.is_some();
+        result = decorate_lines(&result, options, numbered);
+    }
+
+    // 5 ter. Préfixe pour les éditeurs (avant le filtrage, pour garder
+    //        les numéros de ligne du fichier)
+    if options.grep_format {
+        result = grep_format_lines(&result, source);
+    }
+
+    // 6. Ne garder que les lignes de l'intervalle demandé
+    if let Some(range) = &options.lines_range {
+        result = filter_lines(&result, range);
+    }
+
+    // 6 bis. Garder les premières ou dernières lignes (après la numérotation :
+    //        avec --tail, les numéros restent ceux du fichier)
+    if options.head.is_some() || options.tail.is_some() {
+        result = slice_lines(&result, options.head, options.tail);
+    }
+
+    // 7. Couper les lignes trop longues (après la numérotation : seules les
+    //    lignes logiques d'origine sont numérotées, pas les suites)
+    if let Some(width) = options.wrap {
+        let numbered = options.number || options.number_nonblank || options.number_range.is_some();
+        result = wrap_lines(&result, width, numbered);
     }
 
     result
@@ -565,21 +1585,196 @@ fn apply_options(content: &str, options: &Options) -> String {
 ///
 /// # Algorithme
 /// - Itère sur toutes les lignes.
-/// - Ajoute un numéro à gauche.
+/// - Ajoute un numéro à gauche (seulement dans l'intervalle `range` s'il est fourni).
 ///
 /// # Arguments
 /// * `content` - Texte.
+/// * `range` - Intervalle optionnel des lignes à numéroter.
 ///
 /// # Retour
 /// Texte avec lignes numérotées.
-fn number_lines(content: &str) -> String {
+fn number_lines(content: &str, range: Option<&LineRange>) -> String {
+    content.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            if range.is_none_or(|r| r.contains(i + 1)) {
+                format!("{:6}\t{}", i + 1, line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Préfixe chaque ligne par `source:numéro:`, comme la sortie de `grep -n`.
+///
+/// Les éditeurs (liste *quickfix* de Vim, VS Code...) peuvent ainsi ouvrir
+/// directement le fichier à la bonne ligne.
+///
+/// # Arguments
+/// * `content` - Texte.
+/// * `source` - Nom du fichier.
+///
+/// # Retour
+/// Texte préfixé.
+///
+/// # Exemple
+/// ```rust
+/// assert_eq!(grep_format_lines("a\nb", "f.txt"), "f.txt:1:a\nf.txt:2:b");
+/// ```
+fn grep_format_lines(content: &str, source: &str) -> String {
     content.lines()
         .enumerate()
-        .map(|(i, line)| format!("{:6}\t{}", i + 1, line))
+        .map(|(i, line)| format!("{}:{}:{}", source, i + 1, line))
         .collect::<Vec<String>>()
         .join("\n")
 }
 
+/// Garde uniquement les lignes appartenant à un intervalle.
+///
+/// # Arguments
+/// * `content` - Texte.
+/// * `range` - Intervalle des lignes à conserver.
+///
+/// # Retour
+/// Texte filtré.
+fn filter_lines(content: &str, range: &LineRange) -> String {
+    content.lines()
+        .enumerate()
+        .filter(|(i, _)| range.contains(i + 1))
+        .map(|(_, line)| line)
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+/// Garde les `head` premières lignes puis, parmi elles, les `tail` dernières,
+/// comme `head -n HEAD | tail -n TAIL`.
+///
+/// # Exemple
+/// ```rust
+/// assert_eq!(slice_lines("a\nb\nc\nd", Some(3), None), "a\nb\nc");
+/// assert_eq!(slice_lines("a\nb\nc\nd", None, Some(2)), "c\nd");
+/// assert_eq!(slice_lines("a\nb\nc\nd", Some(3), Some(1)), "c");
+/// ```
+fn slice_lines(content: &str, head: Option<usize>, tail: Option<usize>) -> String {
+    let mut lines: Vec<&str> = content.lines().collect();
+    if let Some(head) = head {
+        lines.truncate(head);
+    }
+    if let Some(tail) = tail {
+        lines.drain(..lines.len().saturating_sub(tail));
+    }
+    lines.join("\n")
+}
+
+/// Coupe chaque ligne à `width` colonnes, comme `fold -s`.
+///
+/// # Algorithme
+/// - Sans numérotation, coupe chaque ligne avec [`wrap_line`].
+/// - Avec numérotation, le préfixe `     N\t` reste sur la première ligne et
+///   seul le texte est coupé ; les suites sont alignées sous le texte.
+///
+/// # Arguments
+/// * `content` - Texte.
+/// * `width` - Nombre maximal de colonnes par ligne.
+/// * `numbered` - Les lignes peuvent commencer par un numéro (`-n`, `-b`).
+///
+/// # Retour
+/// Texte dont aucune ligne ne dépasse `width` colonnes.
+fn wrap_lines(content: &str, width: usize, numbered: bool) -> String {
+    let mut rows = Vec::new();
+
+    for line in content.lines() {
+        let (number, text) = match line.split_once('\t') {
+            Some((number, text)) if numbered && is_line_number(number) => (number, text),
+            _ => {
+                rows.extend(wrap_line(line, width));
+                continue;
+            }
+        };
+
+        let indent = advance_column(number.len(), '\t');
+        let mut wrapped = wrap_line(text, width.saturating_sub(indent).max(1)).into_iter();
+        rows.push(format!("{}\t{}", number, wrapped.next().unwrap_or_default()));
+        rows.extend(wrapped.map(|row| format!("{}{}", " ".repeat(indent), row)));
+    }
+
+    rows.join("\n")
+}
+
+/// Indique si `prefix` est un numéro de ligne produit par [`number_lines`].
+fn is_line_number(prefix: &str) -> bool {
+    prefix.len() >= 6 && prefix.trim_start().parse::<usize>().is_ok()
+}
+
+/// Coupe une ligne en plusieurs lignes d'au plus `width` colonnes.
+///
+/// # Algorithme
+/// - Parcourt les caractères en suivant la colonne courante (une tabulation
+///   avance jusqu'au prochain multiple de 8).
+/// - Quand un caractère dépasserait `width` :
+///   - si c'est un blanc, coupe à cet endroit ;
+///   - sinon, coupe après le dernier blanc de la ligne en cours (hors indentation) ;
+///   - sans blanc (mot plus long que `width`), coupe au milieu du mot.
+/// - Les blancs autour d'une coupure sont supprimés.
+///
+/// # Exemple
+/// ```rust
+/// assert_eq!(wrap_line("un deux trois", 8), vec!["un deux", "trois"]);
+/// assert_eq!(wrap_line("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+/// ```
+fn wrap_line(line: &str, width: usize) -> Vec<String> {
+    let mut rows = Vec::new();
+    let mut current = String::new();
+    let mut column = 0;
+    // Position (en octets) juste après le dernier blanc de `current`
+    let mut last_blank: Option<usize> = None;
+
+    for c in line.chars() {
+        // Les blancs au début d'une ligne coupée sont omis
+        if c.is_whitespace() && current.is_empty() && !rows.is_empty() {
+            continue;
+        }
+
+        if !current.is_empty() && advance_column(column, c) > width {
+            if c.is_whitespace() {
+                rows.push(current.trim_end().to_string());
+                current.clear();
+                last_blank = None;
+                column = 0;
+                continue;
+            } else if let Some(pos) = last_blank.filter(|&pos| !current[..pos].trim().is_empty()) {
+                let rest = current.split_off(pos);
+                rows.push(current.trim_end().to_string());
+                current = rest;
+            } else {
+                rows.push(std::mem::take(&mut current));
+            }
+            last_blank = None;
+            column = current.chars().fold(0, advance_column);
+        }
+
+        current.push(c);
+        column = advance_column(column, c);
+        if c.is_whitespace() {
+            last_blank = Some(current.len());
+        }
+    }
+
+    rows.push(current);
+    rows
+}
+
+/// Retourne la colonne atteinte après avoir affiché `c` depuis la colonne `column`.
+fn advance_column(column: usize, c: char) -> usize {
+    if c == '\t' {
+        (column / 8 + 1) * 8
+    } else {
+        column + 1
+    }
+}
+
 /// Numérote uniquement les lignes non vides.
 ///
 /// # Algorithme
@@ -621,6 +1816,71 @@ fn show_ends(content: &str) -> String {
         .join("\n")
 }
 
+/// Rend visibles les espaces et tabulations en fin de ligne.
+///
+/// # Algorithme
+/// - Pour chaque ligne, repère la suite d'espaces et de tabulations finale.
+/// - Remplace chaque espace par `·` et chaque tabulation par `»`.
+/// - Retient le nombre de marques de chaque ligne, pour que
+///   [`highlight_trailing_marks`] les colore une fois `-v`, `-T` et `-E` appliqués.
+///
+/// # Arguments
+/// * `content` - Texte à traiter.
+///
+/// # Retour
+/// Texte avec les blancs de fin de ligne visibles, et le nombre de marques par ligne.
+///
+/// # Exemple
+/// ```rust
+/// assert_eq!(show_trailing_whitespace("a \t\nb"), ("a·»\nb".to_string(), vec![2, 0]));
+/// ```
+fn show_trailing_whitespace(content: &str) -> (String, Vec<usize>) {
+    let mut counts = Vec::new();
+    let marked = content.lines()
+        .map(|line| {
+            let text = line.trim_end_matches([' ', '\t']);
+            let trailing = &line[text.len()..];
+            counts.push(trailing.len());
+
+            let marks: String = trailing
+                .chars()
+                .map(|c| if c == '\t' { '»' } else { '·' })
+                .collect();
+            format!("{}{}", text, marks)
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    (marked, counts)
+}
+
+/// Colore sur fond rouge les marques de [`show_trailing_whitespace`] (sur un terminal).
+///
+/// Appliqué après `-v`, qui rendrait visible le code ANSI (`^[[41m`) : les lignes
+/// sont alors les mêmes, et les marques de chacune sont ses `counts[i]` derniers
+/// caractères, avant le `$` ajouté par `-E` (`ends`).
+///
+/// # Exemple
+/// ```rust
+/// assert_eq!(highlight_trailing_marks("a··$\nb$", &[2, 0], true), "a\x1b[41m··\x1b[0m$\nb$");
+/// ```
+fn highlight_trailing_marks(content: &str, counts: &[usize], ends: bool) -> String {
+    content.lines()
+        .zip(counts.iter().copied().chain(std::iter::repeat(0)))
+        .map(|(line, count)| {
+            let body = if ends { line.strip_suffix('$').unwrap_or(line) } else { line };
+            // Début des marques : le `count`-ième caractère en partant de la fin
+            let start = count.checked_sub(1).and_then(|last| body.char_indices().rev().nth(last));
+            match start {
+                Some((index, _)) => {
+                    format!("{}\x1b[41m{}\x1b[0m{}", &body[..index], &body[index..], &line[body.len()..])
+                }
+                None => line.to_string(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 /// Remplace les tabulations par `^I`.
 ///
 /// # Arguments
@@ -666,6 +1926,332 @@ fn squeeze_blank_lines(content: &str) -> String {
     result.join("\n")
 }
 
+/// Ajoute le préfixe (`--prefix`) et le suffixe (`--suffix`) à chaque ligne.
+///
+/// Une ligne numérotée par `-n` ou `-b` garde son numéro en tête : le préfixe est placé
+/// entre le numéro et le texte, ou avant le numéro avec `--prefix-before-number`.
+///
+/// # Arguments
+/// * `content` - Texte.
+/// * `options` - Options (`--prefix`, `--suffix`, `--prefix-before-number`).
+/// * `numbered` - Les lignes peuvent commencer par un numéro (`-n`, `-b`).
+///
+/// # Exemple
+/// ```rust
+/// // cat -n --prefix "> " --suffix " ;"
+/// assert_eq!(decorate_lines("     1\tbonjour\n     2\tau revoir", &options, true),
+///            "     1\t> bonjour ;\n     2\t> au revoir ;");
+/// ```
+fn decorate_lines(content: &str, options: &Options, numbered: bool) -> String {
+    let prefix = options.prefix.as_deref().unwrap_or_default();
+    let suffix = options.suffix.as_deref().unwrap_or_default();
+
+    content.lines()
+        .map(|line| match line.split_once('\t') {
+            Some((number, text)) if numbered && !options.prefix_before_number && is_line_number(number) => {
+                format!("{}\t{}{}{}", number, prefix, text, suffix)
+            }
+            _ => format!("{}{}{}", prefix, line, suffix),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Réduit chaque suite de lignes identiques consécutives à une seule ligne, comme `uniq`.
+///
+/// # Algorithme
+/// - Parcours les lignes en gardant seulement la précédente et son nombre de répétitions.
+/// - Quand une ligne différente arrive (ou à la fin), écrit la ligne retenue,
+///   précédée de son nombre de répétitions si `with_count` (`--dedup-count`).
+///
+/// Des lignes identiques mais séparées par une autre ligne sont toutes conservées.
+///
+/// # Exemple
+/// ```rust
+/// assert_eq!(dedup_lines("a\na\nb\na\na\na", false), "a\nb\na");
+/// assert_eq!(dedup_lines("a\na\nb", true), "      2 a\n      1 b");
+/// ```
+fn dedup_lines(content: &str, with_count: bool) -> String {
+    let mut result = Vec::new();
+    let mut previous: Option<(&str, usize)> = None;
+
+    let mut flush = |line: &str, count: usize| {
+        if with_count {
+            result.push(format!("{:7} {}", count, line));
+        } else {
+            result.push(line.to_string());
+        }
+    };
+
+    for line in content.lines() {
+        match previous {
+            Some((last, count)) if last == line => previous = Some((last, count + 1)),
+            Some((last, count)) => {
+                flush(last, count);
+                previous = Some((line, 1));
+            }
+            None => previous = Some((line, 1)),
+        }
+    }
+    if let Some((last, count)) = previous {
+        flush(last, count);
+    }
+
+    result.join("\n")
+}
+
+/// Applique `--rot13` puis `--upper` ou `--lower` au texte.
+///
+/// Les caractères qui ne sont pas des lettres (chiffres, ponctuation, fins de ligne)
+/// restent inchangés. `--rot13` ne décale que les lettres ASCII (voir [`rot13`]),
+/// tandis que les changements de casse s'appliquent aussi aux lettres accentuées.
+///
+/// # Arguments
+/// * `content` - Texte.
+/// * `options` - Options indiquant les transformations.
+///
+/// # Retour
+/// Texte transformé.
+fn transform_letters(content: &str, options: &Options) -> String {
+    let text: String = if options.rot13 {
+        content.chars().map(rot13).collect()
+    } else {
+        content.to_string()
+    };
+
+    if options.upper {
+        text.to_uppercase()
+    } else if options.lower {
+        text.to_lowercase()
+    } else {
+        text
+    }
+}
+
+/// Masque les lignes indentées de plus de `max_level` niveaux (`--fold-level`).
+///
+/// # Algorithme
+/// - Le niveau d'une ligne est calculé sur son indentation : une tabulation vaut
+///   un niveau, et chaque groupe de `indent_width` espaces aussi.
+/// - Une suite de lignes masquées est remplacée par une seule ligne `...`, précédée
+///   de l'indentation de la première ligne masquée.
+/// - Une ligne vide prend le sort des lignes qui l'entourent : elle est masquée
+///   si elle se trouve au milieu d'une suite de lignes masquées.
+///
+/// # Arguments
+/// * `content` - Texte.
+/// * `max_level` - Niveau le plus profond encore affiché (0 : lignes non indentées).
+/// * `indent_width` - Nombre d'espaces par niveau.
+///
+/// # Retour
+/// Texte replié.
+///
+/// # Exemple
+/// ```rust
+/// let text = "a:\n  b:\n    c: 1\n    d: 2\n  e: 3\nf: 4";
+/// assert_eq!(fold_indented_lines(text, 0, 2), "a:\n  ...\nf: 4");
+/// assert_eq!(fold_indented_lines(text, 1, 2), "a:\n  b:\n    ...\n  e: 3\nf: 4");
+/// ```
+fn fold_indented_lines(content: &str, max_level: usize, indent_width: usize) -> String {
+    let level = |line: &str| {
+        let indent = &line[..line.len() - line.trim_start_matches([' ', '\t']).len()];
+        let tabs = indent.chars().filter(|&c| c == '\t').count();
+        tabs + (indent.len() - tabs) / indent_width
+    };
+
+    let mut result: Vec<String> = Vec::new();
+    let mut blanks: Vec<&str> = Vec::new();
+    let mut folding = false;
+
+    for line in content.lines() {
+        if line.trim().is_empty() {
+            blanks.push(line);
+            continue;
+        }
+
+        if level(line) > max_level {
+            if !folding {
+                result.extend(blanks.drain(..).map(String::from));
+                let indent = &line[..line.len() - line.trim_start_matches([' ', '\t']).len()];
+                result.push(format!("{}...", indent));
+                folding = true;
+            }
+            // Les lignes vides au milieu du bloc replié sont masquées avec lui
+            blanks.clear();
+        } else {
+            result.extend(blanks.drain(..).map(String::from));
+            result.push(line.to_string());
+            folding = false;
+        }
+    }
+    result.extend(blanks.drain(..).map(String::from));
+
+    result.join("\n")
+}
+
+/// Met en forme un texte Markdown pour le terminal (`--md`).
+///
+/// # Algorithme
+/// - Un titre (`#` à `######` suivis d'une espace) est affiché sans ses `#`,
+///   en gras souligné.
+/// - Dans les autres lignes, `**texte**` est mis en gras et `*texte*` en italique
+///   (voir [`render_emphasis`]).
+/// - Les blocs de code délimités par ```` ``` ```` sont laissés tels quels.
+///
+/// # Exemple
+/// ```rust
+/// assert_eq!(render_markdown("# Titre"), "\x1b[1;4mTitre\x1b[0m");
+/// assert_eq!(render_markdown("un **mot**"), "un \x1b[1mmot\x1b[22m");
+/// ```
+fn render_markdown(content: &str) -> String {
+    let mut in_code_block = false;
+
+    content
+        .lines()
+        .map(|line| {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+                return line.to_string();
+            }
+            if in_code_block {
+                return line.to_string();
+            }
+
+            let hashes = line.len() - line.trim_start_matches('#').len();
+            let title = &line[hashes..];
+            if (1..=6).contains(&hashes) && (title.is_empty() || title.starts_with(' ')) {
+                format!("\x1b[1;4m{}\x1b[0m", render_emphasis(title.trim()))
+            } else {
+                render_emphasis(line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Met en gras `**texte**` et en italique `*texte*` dans une ligne (`--md`).
+///
+/// Un `*` sans partenaire sur la ligne, ou collé à une espace du côté intérieur
+/// (puce `* élément`, multiplication `a * b`), est laissé tel quel.
+///
+/// # Exemple
+/// ```rust
+/// assert_eq!(render_emphasis("*vite*"), "\x1b[3mvite\x1b[23m");
+/// assert_eq!(render_emphasis("2 * 3 * 4"), "2 * 3 * 4");
+/// ```
+fn render_emphasis(line: &str) -> String {
+    let mut result = String::new();
+    let mut rest = line;
+
+    while let Some(start) = rest.find('*') {
+        result.push_str(&rest[..start]);
+        let marked = &rest[start..];
+
+        if let Some(inner) = marked.strip_prefix("**")
+            && let Some(end) = inner.find("**")
+            && is_emphasized(&inner[..end])
+        {
+            result.push_str(&format!("\x1b[1m{}\x1b[22m", render_emphasis(&inner[..end])));
+            rest = &inner[end + 2..];
+            continue;
+        }
+
+        let inner = &marked[1..];
+        if let Some(end) = inner.find('*')
+            && is_emphasized(&inner[..end])
+        {
+            result.push_str(&format!("\x1b[3m{}\x1b[23m", &inner[..end]));
+            rest = &inner[end + 1..];
+            continue;
+        }
+
+        result.push('*');
+        rest = inner;
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Indique si le texte entre deux marqueurs `*` forme une emphase : non vide
+/// et sans espace au début ni à la fin.
+fn is_emphasized(text: &str) -> bool {
+    !text.is_empty() && !text.starts_with(char::is_whitespace) && !text.ends_with(char::is_whitespace)
+}
+
+/// Décale une lettre ASCII de 13 positions en restant dans l'alphabet (ROT13).
+///
+/// Appliquer deux fois ROT13 redonne le caractère d'origine.
+///
+/// # Exemple
+/// ```rust
+/// let text = "Bonjour, le Monde ! 42";
+/// let encoded: String = text.chars().map(rot13).collect();
+/// assert_eq!(encoded, "Obawbhe, yr Zbaqr ! 42");
+/// assert_eq!(encoded.chars().map(rot13).collect::<String>(), text);
+/// ```
+fn rot13(c: char) -> char {
+    match c {
+        'a'..='z' => ((c as u8 - b'a' + 13) % 26 + b'a') as char,
+        'A'..='Z' => ((c as u8 - b'A' + 13) % 26 + b'A') as char,
+        _ => c,
+    }
+}
+
+/// Remplace chaque suite d'espaces d'une ligne par un seul espace, comme `tr -s ' '`.
+///
+/// # Algorithme
+/// - Parcourt les lignes une à une, avec leur fin de ligne (`\n` ou `\r\n`),
+///   recopiée telle quelle : la dernière ligne reste sans fin si elle n'en avait pas.
+/// - Avec `keep_indent`, recopie tels quels les blancs du début de la ligne.
+/// - Pour le reste, n'écrit qu'un espace par suite d'espaces (ou, avec `tabs`,
+///   par suite d'espaces et de tabulations mélangés).
+///
+/// # Arguments
+/// * `content` - Texte.
+/// * `tabs` - Réduit aussi les tabulations (`--squeeze-spaces=tabs`).
+/// * `keep_indent` - Conserve l'indentation (`--keep-indent`).
+///
+/// # Retour
+/// Texte sans suite d'espaces.
+///
+/// # Exemple
+/// ```rust
+/// assert_eq!(squeeze_spaces("  a   b\tc", false, false), " a b\tc");
+/// assert_eq!(squeeze_spaces("  a \t b", true, true), "  a b");
+/// assert_eq!(squeeze_spaces("a  b\r\nc  d\n", false, false), "a b\r\nc d\n");
+/// ```
+fn squeeze_spaces(content: &str, tabs: bool, keep_indent: bool) -> String {
+    let is_blank = |c: char| c == ' ' || (tabs && c == '\t');
+
+    content.split_inclusive('\n')
+        .map(|full_line| {
+            let line = full_line.trim_end_matches(['\n', '\r']);
+            let (indent, rest) = if keep_indent {
+                line.split_at(line.len() - line.trim_start_matches([' ', '\t']).len())
+            } else {
+                ("", line)
+            };
+
+            let mut squeezed = String::from(indent);
+            let mut previous_blank = false;
+            for c in rest.chars() {
+                if is_blank(c) {
+                    if !previous_blank {
+                        squeezed.push(' ');
+                    }
+                    previous_blank = true;
+                } else {
+                    squeezed.push(c);
+                    previous_blank = false;
+                }
+            }
+            squeezed.push_str(&full_line[line.len()..]);
+            squeezed
+        })
+        .collect()
+}
+
 /// Affiche les caractères non imprimables.
 ///
 /// # Algorithme
@@ -742,19 +2328,78 @@ fn display_help() {
     println!("  -E, --show-ends          Affiche $ à la fin de chaque ligne");
     println!("  -n, --number             Numérote toutes les lignes");
     println!("  -s, --squeeze-blank      Remplace plusieurs lignes vides consécutives par une seule");
+    println!("      --dedup              Réduit les lignes identiques consécutives à une seule (comme uniq)");
+    println!("      --dedup-count        Comme --dedup, précédé du nombre de répétitions (comme uniq -c)");
+    println!("      --prefix TEXTE       Ajoute TEXTE au début de chaque ligne (après le numéro de -n)");
+    println!("      --suffix TEXTE       Ajoute TEXTE à la fin de chaque ligne (\\t, \\n... acceptés)");
+    println!("      --prefix-before-number");
+    println!("                           Place le préfixe avant le numéro de -n ou -b");
     println!("  -T, --show-tabs          Affiche les tabulations sous la forme ^I");
     println!("  -v, --show-nonprinting   Affiche les caractères non imprimables sauf les tabulations");
     println!("                           et les fins de ligne");
+    println!("      --in-place           Réécrit le résultat dans le fichier au lieu de l'afficher");
+    println!("      --show-trailing-whitespace");
+    println!("                           Rend visibles les blancs de fin de ligne (· espace, » tabulation)");
+    println!("      --number-range=START:END");
+    println!("                           Numérote uniquement les lignes de l'intervalle (ex: 100:, :50)");
+    println!("      --lines-range=START:END");
+    println!("                           Affiche uniquement les lignes de l'intervalle");
+    println!("      --wrap=N             Coupe les lignes à N colonnes sur les blancs (comme fold -s)");
+    println!("      --head N             N'affiche que les N premières lignes");
+    println!("      --tail N             N'affiche que les N dernières lignes (numéros d'origine avec -n)");
+    println!("      --squeeze-spaces     Remplace les suites d'espaces par un seul (comme tr -s ' ')");
+    println!("      --squeeze-spaces=tabs");
+    println!("                           Réduit aussi les tabulations, remplacées par un espace");
+    println!("      --keep-indent        Avec --squeeze-spaces, conserve l'indentation des lignes");
+    println!("      --fold-level N       Remplace par ... les lignes indentées de plus de N niveaux");
+    println!("      --indent-width N     Espaces par niveau pour --fold-level (4 par défaut, tabulation = 1)");
+    println!("      --goto N             N'affiche que la ligne N, mise en évidence, et son contexte");
+    println!("      --context C          Nombre de lignes autour de la ligne de --goto (3 par défaut)");
+    println!("      --md                 Affiche titres (#), **gras** et *italique* Markdown avec les");
+    println!("                           styles du terminal (texte brut si la sortie est redirigée)");
+    println!("      --syntax LANG        Colore les lignes selon leur format : json, diff ou log");
+    println!("                           (texte brut si la sortie est redirigée)");
+    println!("      --rot13              Décale chaque lettre de 13 positions (ROT13, réversible)");
+    println!("      --buffer-size N      Lit les fichiers par blocs de N octets (ex. 4096, 64K, 1M ;");
+    println!("                           64M au plus), ou selon la variable UTILS_BUFFER_SIZE");
+    println!("      --files-from F       Affiche aussi les fichiers listés dans F (un par ligne, - pour stdin)");
+    println!("      --files0-from F      Affiche aussi les fichiers listés dans F (séparés par \\0, - pour stdin)");
+    println!("      --upper              Met le texte en majuscules");
+    println!("      --lower              Met le texte en minuscules");
+    println!("      --paste              Juxtapose les lignes de plusieurs fichiers (comme paste)");
+    println!("  -d, --delimiter SEP      Séparateur des colonnes de --paste (tabulation par défaut)");
+    println!("      --strip-ansi         Supprime les séquences d'échappement ANSI (couleurs...)");
+    println!("      --grep-format        Préfixe chaque ligne par fichier:numéro: (comme grep -n)");
+    println!("      --with-header        Affiche nom, taille et date de modification avant chaque fichier");
+    println!("      --sidecar FICHIER    Avec -n, affiche le texte sans numéros et écrit dans FICHIER");
+    println!("                           une ligne numéro<TAB>position (en octets) par ligne affichée");
+    println!("      --check-endings      Indique sur stderr les fins de ligne utilisées (LF, CRLF, CR, mixed)");
+    println!("      --diff-mark REF      Marque les lignes qui diffèrent de REF (* modifiée, + en plus,");
+    println!("                           - absente)");
+    println!("  -x, --hex                Affiche le fichier en hexadécimal (position, octets, ASCII)");
+    println!("      --width=N            Nombre d'octets par ligne de --hex (16 par défaut)");
     println!("      --help               Affiche cette aide et quitte");
     println!("      --version            Affiche la version et quitte");
     println!();
     println!("Exemples:");
     println!("  cat fichier.txt");
     println!("  cat -n fichier.txt");
+    println!("  cat fichier1.txt fichier2.txt");
+    println!("  cat --grep-format --lines-range=10:20 *.rs");
+    println!("  cat --with-header notes.txt todo.txt");
+    println!("  cat -n --diff-mark ancien.txt nouveau.txt");
     println!("  cat -vET fichier.txt");
+    println!("  cat -n --wrap=80 fichier.txt");
+    println!("  cat -n --tail 5 journal.log");
+    println!("  cat --goto 120 --context 5 src/main.rs");
+    println!("  cat --md README.md");
+    println!("  cat --syntax diff correctif.patch");
+    println!("  cat --squeeze-spaces --keep-indent tableau.txt");
+    println!("  cat --hex --width=8 image.png");
+    println!("  cat --paste -d \",\" noms.txt ages.txt");
     println!("  cat \"chemin/avec des espaces/fichier.txt\"");
     println!("  echo \"Bonjour le monde\" | cat -n");
-    println!("  echo \"Texte avec \\t tabulation\" | cat -T");
+    println!("  echo -e \"Texte avec \\t tabulation\" | cat -T");
 }
 
 /// Affiche la version du programme.
@@ -766,4 +2411,345 @@ fn display_help() {
 fn display_version() {
     println!("cat version {}", VERSION);
     println!("Implémentation Rust de la commande cat");
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::Command;
+    use crate::testdir::TestDir;
+
+    #[test]
+    fn in_place_replaces_content_without_leftovers() {
+        let dir = TestDir::new("cat-in-place");
+        let file = dir.write("notes.txt", "ancien\n");
+
+        write_in_place(&file, "nouveau\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&file).unwrap(), "nouveau\n");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn in_place_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TestDir::new("cat-in-place-perms");
+        let file = dir.write("script.sh", "echo a\n");
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o750)).unwrap();
+
+        write_in_place(&file, "echo b\n").unwrap();
+
+        assert_eq!(fs::metadata(&file).unwrap().permissions().mode() & 0o777, 0o750);
+    }
+
+    #[test]
+    fn in_place_does_not_clobber_an_existing_temp_name() {
+        let dir = TestDir::new("cat-in-place-temp");
+        let file = dir.write("notes.txt", "ancien\n");
+        let taken = dir.write(&format!(".notes.txt.{}.0.cat-tmp", process::id()), "à garder");
+
+        write_in_place(&file, "nouveau\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&taken).unwrap(), "à garder");
+        assert_eq!(fs::read_to_string(&file).unwrap(), "nouveau\n");
+    }
+
+    #[test]
+    fn squeeze_spaces_keeps_line_terminators() {
+        assert_eq!(squeeze_spaces("a  b\r\nc   d\n", false, false), "a b\r\nc d\n");
+        assert_eq!(squeeze_spaces("a  b\n\nfin  sans retour", false, false), "a b\n\nfin sans retour");
+        assert_eq!(squeeze_spaces("  x \t y\r\n", true, true), "  x y\r\n");
+        assert_eq!(squeeze_spaces("", false, false), "");
+    }
+
+    fn output_of(args: &[&str], input: &str, terminal: bool) -> io::Result<String> {
+        let mut output = Vec::new();
+        process_command(args, Some(input.as_bytes()), terminal, &mut output)?;
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn goto_highlights_only_a_terminal_output() {
+        let text = "un\ndeux\ntrois\n";
+        let plain = output_of(&["--goto", "2", "--context", "0"], text, false).unwrap();
+        assert!(plain.contains("deux") && !plain.contains('\x1b'), "{plain:?}");
+
+        let highlighted = output_of(&["--goto", "2", "--context", "0"], text, true).unwrap();
+        assert!(highlighted.contains('\x1b'), "{highlighted:?}");
+    }
+
+    #[test]
+    fn goto_rejects_options_it_does_not_apply() {
+        assert!(output_of(&["--goto", "1", "--context", "1"], "a\n", false).is_ok());
+        for args in [&["--goto", "1", "-n"][..], &["-s", "--goto", "1"], &["--goto", "1", "--prefix", "x"]] {
+            let error = output_of(args, "a\n", false).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput, "{args:?}");
+        }
+    }
+
+    #[test]
+    fn goto_accepts_a_file_list() {
+        let dir = TestDir::new("cat-goto-list");
+        let file = dir.write("a.txt", "un\ndeux\n");
+        let list = dir.write("liste.txt", format!("{}\n", file.display()));
+
+        let output = output_of(&["--goto", "2", "--files-from", list.to_str().unwrap()], "", false).unwrap();
+        assert!(output.contains("deux"), "{output:?}");
+    }
+
+    #[test]
+    fn trailing_whitespace_is_colored_only_in_a_terminal() {
+        let args = ["--show-trailing-whitespace"];
+        assert!(!output_of(&args, "a  \n", false).unwrap().contains('\x1b'));
+        assert!(output_of(&args, "a  \n", true).unwrap().contains('\x1b'));
+    }
+
+    #[test]
+    fn trailing_whitespace_highlight_survives_show_all() {
+        let args = ["-A", "--show-trailing-whitespace"];
+        assert_eq!(output_of(&args, "a \t\nb\n", true).unwrap(), "a\x1b[41m·»\x1b[0m$\nb$\n");
+        assert_eq!(output_of(&args, "a \t\nb\n", false).unwrap(), "a·»$\nb$\n");
+        assert_eq!(
+            output_of(&["-E", "--show-trailing-whitespace"], "x\x01 \n", true).unwrap(),
+            "x\x01\x1b[41m·\x1b[0m$\n"
+        );
+        assert_eq!(
+            output_of(&["-v", "--show-trailing-whitespace"], "x\x01 \n", true).unwrap(),
+            "x^A\x1b[41m·\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn markdown_is_rendered_only_in_a_terminal() {
+        let text = "# Titre\n**gras**\n";
+        assert_eq!(output_of(&["--md"], text, false).unwrap(), text);
+        assert!(output_of(&["--md"], text, true).unwrap().contains('\x1b'));
+        assert_eq!(CatCommand::new(&["--md".to_string()]).run(Some(text.as_bytes()), false).unwrap(), text.as_bytes());
+    }
+
+    #[test]
+    fn syntax_colors_only_a_terminal_output() {
+        let patch = "+ajout\n-retrait\n";
+        assert_eq!(output_of(&["--syntax", "diff"], patch, false).unwrap(), patch);
+        assert_eq!(
+            output_of(&["--syntax", "diff"], patch, true).unwrap(),
+            "\x1b[32m+ajout\x1b[0m\n\x1b[31m-retrait\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn strip_ansi_removes_colors() {
+        assert_eq!(output_of(&["--strip-ansi"], "\x1b[31mrouge\x1b[0m\n", false).unwrap(), "rouge\n");
+    }
+
+    #[test]
+    fn header_shows_name_size_and_modification_date() {
+        let dir = TestDir::new("cat-header");
+        let file = dir.write("notes.txt", "0123456789");
+        let modified = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        File::options().write(true).open(&file).unwrap().set_modified(modified).unwrap();
+
+        let header = file_header("notes.txt", &fs::metadata(&file).unwrap());
+        assert_eq!(header, "==> notes.txt (10 o, modifié le 2023-11-14 22:13:20 UTC) <==");
+    }
+
+    #[test]
+    fn grep_format_numbers_each_file_from_one() {
+        let dir = TestDir::new("cat-grep-format");
+        let first = dir.write("a.txt", "x\ny\n");
+        let second = dir.write("b.txt", "z\n");
+        let (first, second) = (first.to_str().unwrap(), second.to_str().unwrap());
+
+        let output = output_of(&["--grep-format", first, second], "", false).unwrap();
+        assert_eq!(output, format!("{first}:1:x\n{first}:2:y\n{second}:1:z\n"));
+
+        let output = output_of(&["--grep-format", "--lines-range=2:2", first], "", false).unwrap();
+        assert_eq!(output, format!("{first}:2:y\n"));
+    }
+
+    #[test]
+    fn grep_format_rejects_options_that_drop_lines() {
+        for options in [&["-s"][..], &["--dedup"], &["--fold-level", "1"]] {
+            let args = [&["--grep-format"][..], options].concat();
+            let error = output_of(&args, "a\n\n\nb\n", false).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput, "{options:?}");
+        }
+    }
+
+    #[test]
+    fn line_range_bounds() {
+        let bounds = |spec: &str| LineRange::parse(spec).map(|range| (range.start, range.end));
+        assert_eq!(bounds("10:").unwrap(), (Some(10), None));
+        assert_eq!(bounds(":5").unwrap(), (None, Some(5)));
+        assert_eq!(bounds("3:3").unwrap(), (Some(3), Some(3)));
+        assert_eq!(bounds(":").unwrap(), (None, None));
+        for invalid in ["5:2", "0:3", "a:b", "10", "-1:4", ""] {
+            assert_eq!(bounds(invalid).unwrap_err().kind(), io::ErrorKind::InvalidInput, "{invalid}");
+        }
+
+        let range = LineRange::parse("2:3").unwrap();
+        assert!(!range.contains(1) && range.contains(2) && range.contains(3) && !range.contains(4));
+        assert_eq!(output_of(&["--lines-range=2:3"], "a\nb\nc\nd\n", false).unwrap(), "b\nc\n");
+    }
+
+    #[test]
+    fn wrap_line_breaks_on_blanks_or_inside_long_words() {
+        assert_eq!(wrap_line("un deux trois", 8), ["un deux", "trois"]);
+        assert_eq!(wrap_line("abcdefghij", 4), ["abcd", "efgh", "ij"]);
+        assert_eq!(wrap_line("  indenté mot", 10), ["  indenté", "mot"]);
+        assert_eq!(wrap_line("court", 10), ["court"]);
+        assert_eq!(wrap_line("", 10), [""]);
+    }
+
+    #[test]
+    fn wrap_keeps_line_numbers_on_the_first_row() {
+        let numbered = "     1\tun deux trois quatre\n     2\tcinq";
+        // Le préfixe occupe 8 colonnes : il en reste 8 pour le texte
+        assert_eq!(wrap_lines(numbered, 16, true), "     1\tun deux\n        trois\n        quatre\n     2\tcinq");
+        assert_eq!(output_of(&["-n", "--wrap=16"], "un deux trois\n", false).unwrap(), "     1\tun deux\n        trois\n");
+    }
+
+    #[test]
+    fn paste_fills_missing_lines_with_empty_fields() {
+        let dir = TestDir::new("cat-paste");
+        let long = dir.write("a.txt", "1\n2\n3\n");
+        let short = dir.write("b.txt", "un\ndeux");
+        let empty = dir.write("c.txt", "");
+        let files: Vec<String> = [&long, &short, &empty].iter().map(|file| file.display().to_string()).collect();
+
+        assert_eq!(paste_files(&files[..2], "\t").unwrap(), "1\tun\n2\tdeux\n3\t\n");
+        assert_eq!(paste_files(&[files[1].clone(), files[0].clone()], ",").unwrap(), "un,1\ndeux,2\n,3\n");
+        assert_eq!(paste_files(&files, ";").unwrap(), "1;un;\n2;deux;\n3;;\n");
+        assert!(paste_files(&[files[0].clone(), dir.join("absent").display().to_string()], "\t").is_err());
+    }
+
+    #[test]
+    fn diff_marks_changed_added_and_missing_lines() {
+        // Ligne modifiée
+        assert_eq!(diff_mark_lines("a\nB\nc", "a\nB\nc", "a\nb\nc"), "  a\n* B\n  c");
+        // Lignes en plus à la fin du fichier
+        assert_eq!(diff_mark_lines("a\nb\nc", "a\nb\nc", "a"), "  a\n+ b\n+ c");
+        // Lignes supprimées : celles de la référence sont ajoutées à la fin
+        assert_eq!(diff_mark_lines("a", "a", "a\nb\nc"), "  a\n- b\n- c");
+        // Comparaison positionnelle : une insertion marque aussi les lignes suivantes
+        assert_eq!(diff_mark_lines("a\nx\nb", "a\nx\nb", "a\nb"), "  a\n* x\n+ b");
+        // Le texte affiché peut différer de l'original (ex. -n) : c'est l'original qui est comparé
+        assert_eq!(diff_mark_lines("1 a\n2 b", "a\nb", "a\nc"), "  1 a\n* 2 b");
+    }
+
+    #[test]
+    fn line_endings_are_counted_by_kind() {
+        assert_eq!(line_endings_summary("a\nb\r\nc\n", "f.txt"), "f.txt: mixed (LF: 2, CRLF: 1)");
+        assert_eq!(line_endings_summary("a\r\nb\r\n", "f.txt"), "f.txt: CRLF (2)");
+        assert_eq!(line_endings_summary("a\nb", "f.txt"), "f.txt: LF (1)");
+        assert_eq!(line_endings_summary("a\rb\r\n", "f.txt"), "f.txt: mixed (CRLF: 1, CR: 1)");
+        assert_eq!(line_endings_summary("sans fin", "f.txt"), "f.txt: no line endings");
+        assert_eq!(line_endings_summary("", "f.txt"), "f.txt: no line endings");
+    }
+
+    #[test]
+    fn hexdump_offsets_follow_the_bytes() {
+        assert_eq!(
+            hexdump(b"Hello\n\x00", 16, 16),
+            "00000010  48 65 6c 6c 6f 0a 00                              |Hello..|\n"
+        );
+        assert_eq!(hexdump(b"abcde", 0, 4), "00000000  61 62 63 64  |abcd|\n00000004  65           |e|\n");
+
+        // Les blocs lus successivement poursuivent la colonne de position
+        let bytes: Vec<u8> = (0..=255).cycle().take(16 * 512 + 20).collect();
+        let mut out = Vec::new();
+        write_hex_dump(&mut &bytes[..], 16, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let offsets: Vec<&str> = out.lines().map(|line| &line[..8]).collect();
+        assert_eq!(offsets.len(), 514);
+        assert_eq!(offsets[512], "00002000");
+        assert_eq!(offsets[513], "00002010");
+        assert_eq!(out, hexdump(&bytes, 0, 16));
+    }
+
+    #[test]
+    fn head_and_tail_keep_the_file_line_numbers() {
+        assert_eq!(slice_lines("a\nb\nc\nd", Some(3), Some(1)), "c");
+        assert_eq!(slice_lines("a\nb", Some(5), Some(5)), "a\nb");
+        assert_eq!(slice_lines("a\nb", Some(0), None), "");
+
+        let text = "a\nb\nc\nd\ne\n";
+        assert_eq!(output_of(&["-n", "--tail", "2"], text, false).unwrap(), "     4\td\n     5\te\n");
+        assert_eq!(output_of(&["-n", "--head", "3", "--tail", "1"], text, false).unwrap(), "     3\tc\n");
+    }
+
+    #[test]
+    fn rot13_is_its_own_inverse_and_skips_non_ascii() {
+        let text = "Bonjour, le Monde ! 42 Zz";
+        let encoded: String = text.chars().map(rot13).collect();
+        assert_eq!(encoded, "Obawbhe, yr Zbaqr ! 42 Mm");
+        assert_eq!(encoded.chars().map(rot13).collect::<String>(), text);
+
+        for c in ['é', 'Ç', 'ß', 'œ', '€', '😀'] {
+            assert_eq!(rot13(c), c);
+        }
+        assert_eq!(output_of(&["--rot13"], "Été à Noël\n", false).unwrap(), "Égé à Abëy\n");
+    }
+
+    #[test]
+    fn sidecar_offsets_point_at_each_line() {
+        let dir = TestDir::new("cat-sidecar");
+        let file = dir.write("texte.txt", "ab\r\ncdé\n\nf");
+        let sidecar = dir.join("numeros.tsv");
+
+        let args = ["-n", "--sidecar", sidecar.to_str().unwrap(), file.to_str().unwrap()];
+        let output = output_of(&args, "", false).unwrap();
+        assert_eq!(output, "ab\r\ncdé\n\nf");
+
+        let entries = fs::read_to_string(&sidecar).unwrap();
+        assert_eq!(entries, "1\t0\n2\t4\n3\t9\n4\t10\n");
+        for (line, entry) in output.split_inclusive('\n').zip(entries.lines()) {
+            let offset: usize = entry.split_once('\t').unwrap().1.parse().unwrap();
+            assert!(output[offset..].starts_with(line), "{entry}");
+        }
+    }
+
+    #[test]
+    fn fold_level_hides_deeper_lines() {
+        let text = "a:\n  b:\n    c:\n      d: 1\n    e: 2\n  f: 3\ng: 4";
+        assert_eq!(fold_indented_lines(text, 0, 2), "a:\n  ...\ng: 4");
+        assert_eq!(fold_indented_lines(text, 1, 2), "a:\n  b:\n    ...\n  f: 3\ng: 4");
+        assert_eq!(fold_indented_lines(text, 2, 2), "a:\n  b:\n    c:\n      ...\n    e: 2\n  f: 3\ng: 4");
+        assert_eq!(fold_indented_lines(text, 1, 4), "a:\n  b:\n    c:\n      d: 1\n    e: 2\n  f: 3\ng: 4");
+
+        // Une tabulation vaut un niveau ; les lignes vides du bloc replié disparaissent avec lui
+        assert_eq!(fold_indented_lines("fn a() {\n\tx;\n\n\ty;\n}\n\nfn b() {}", 0, 4), "fn a() {\n\t...\n}\n\nfn b() {}");
+        assert_eq!(output_of(&["--fold-level", "0"], "a\n    b\nc\n", false).unwrap(), "a\n    ...\nc\n");
+    }
+
+    #[test]
+    fn dedup_collapses_only_consecutive_runs() {
+        assert_eq!(dedup_lines("a\na\nb\na\na\na", false), "a\nb\na");
+        assert_eq!(dedup_lines("a\na\nb\na\na\na", true), "      2 a\n      1 b\n      3 a");
+        assert_eq!(dedup_lines("\n\nx", false), "\nx");
+        assert_eq!(dedup_lines("", true), "");
+
+        // La numérotation suit les lignes affichées
+        assert_eq!(output_of(&["--dedup", "-n"], "a\na\nb\n", false).unwrap(), "     1\ta\n     2\tb\n");
+    }
+
+    #[test]
+    fn prefix_and_suffix_wrap_each_line() {
+        let options = Options { prefix: Some("> ".to_string()), suffix: Some(" ;".to_string()), ..Default::default() };
+        assert_eq!(decorate_lines("un\n  deux", &options, false), "> un ;\n>   deux ;");
+        assert_eq!(decorate_lines("     1\tun\n     2\t  deux", &options, true), "     1\t> un ;\n     2\t>   deux ;");
+        let before = Options { prefix_before_number: true, ..options.clone() };
+        assert_eq!(decorate_lines("     1\tun", &before, true), ">      1\tun ;");
+        // Une tabulation dans le texte non numéroté n'est pas prise pour un numéro
+        assert_eq!(decorate_lines("a\tb", &options, true), "> a\tb ;");
+
+        let text = "un\n  deux\n";
+        assert_eq!(output_of(&["-n", "--prefix", "> "], text, false).unwrap(), "     1\t> un\n     2\t>   deux\n");
+        assert_eq!(
+            output_of(&["-n", "--prefix", "# ", "--prefix-before-number", "--suffix", "!"], text, false).unwrap(),
+            "#      1\tun!\n#      2\t  deux!\n"
+        );
+    }
+}
@@ -2,15 +2,156 @@
 //!
 //! Ce module implémente la commande Unix **`cp`** en Rust.
 //!
-//! Il permet de **copier un fichier** d’un emplacement à un autre,
+//! Il permet de **copier un fichier** (ou un dossier avec `-r`) d’un emplacement à un autre,
 //! avec la prise en charge des options suivantes :
 //!
-//! - `-i` : demande confirmation avant d’écraser un fichier existant (*interactive*).  
+//! - `-i` : demande confirmation avant d’écraser un fichier existant (*interactive*).
 //! - `-v` : affiche le nom des fichiers copiés (*verbose*).
+//! - `-r` : copie un dossier et tout son contenu (*recursive*).
+//! - `-L` / `--dereference` : copie le contenu des cibles des liens symboliques.
+//! - `-P` / `--no-dereference` : recrée les liens symboliques tels quels (par défaut).
+//! - `-a` / `--archive` : conserve les permissions, le propriétaire et les dates (implique `-r`).
+//! - `--sparse=WHEN` : recrée les trous des fichiers creux (`auto`, `always` ou `never`).
+//! - `--jobs N` : copie les fichiers d’un dossier en parallèle sur `N` threads.
+//! - `--xattrs` / `--no-xattrs` : copie ou supprime les attributs étendus (Linux).
+//! - `--verify` : relit la source et la copie pour vérifier qu’elles sont identiques.
+//! - `--progress` : affiche l’avancement, le débit et le temps restant de chaque fichier.
+//! - `--print-paths` : affiche les chemins absolus de la source et de la copie (`source\tdestination`).
+//! - `--dirs-only` : recrée seulement l’arborescence des dossiers, sans les fichiers (implique `-r`).
+//! - `-f` / `--force` : écrase aussi les fichiers protégés par un marqueur `<nom>.keep`.
+//! - `--skip-identical` : ne réécrit pas une destination dont le contenu est déjà celui de la source.
+//! - `--as MODELE` : nomme chaque copie d’après un modèle (`{name}`, `{ext}`, `{date}`),
+//!   pour copier plusieurs fichiers dans un dossier (voir [`crate::template`]).
+//! - `--buffer-size N` : copie par tranches de `N` octets (ou selon la variable
+//!   `UTILS_BUFFER_SIZE`) au lieu du tampon interne de `fs::copy`.
 
-use std::fs;
-use std::io::{self, Write};
-use std::path::Path;
+use std::collections::VecDeque;
+use std::fs::{self, File, FileTimes};
+use std::io::{self, IsTerminal, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::template::{Batch, Template};
+use crate::{date, glob, interrupt, path, size, sum, xattr};
+
+const SPARSE_BUFFER_SIZE: usize = 64 * 1024; // Taille des lectures de la copie creuse
+const SPARSE_BLOCK_SIZE: usize = 4096; // Granularité de détection des blocs de zéros
+const VERIFY_RETRIES: usize = 1; // Nouvelles copies tentées quand --verify échoue
+const PROGRESS_BUFFER_SIZE: usize = 64 * 1024; // Taille des lectures de --progress
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200); // Délai minimal entre deux affichages
+const PROGRESS_WINDOW: Duration = Duration::from_secs(3); // Fenêtre de lissage du débit
+
+/// Mode de gestion des fichiers creux (`--sparse=WHEN`).
+#[derive(Default, Clone, Copy, PartialEq)]
+enum Sparse {
+    /// Recrée les trous uniquement si la source est détectée comme creuse (par défaut).
+    #[default]
+    Auto,
+    /// Transforme toute suite de blocs de zéros en trou.
+    Always,
+    /// Copie tous les octets, zéros compris.
+    Never,
+}
+
+/// Traitement des attributs étendus (`--xattrs`, `--no-xattrs`).
+#[derive(Default, Clone, Copy, PartialEq)]
+enum Xattrs {
+    /// Laisse la copie telle que produite par le système (par défaut).
+    #[default]
+    Default,
+    /// Recopie les attributs étendus de la source.
+    Copy,
+    /// Supprime tous les attributs étendus de la copie.
+    Strip,
+}
+
+/// Structure représentant les options de la commande `cp`.
+///
+/// Chaque champ correspond à une option possible de `cp`.
+#[derive(Default, Clone)]
+struct Options {
+    /// Demande confirmation avant d'écraser (`-i`).
+    interactive: bool,
+    /// Affiche les fichiers copiés (`-v`).
+    verbose: bool,
+    /// Copie les dossiers récursivement (`-r`).
+    recursive: bool,
+    /// Suit les liens symboliques rencontrés pendant le parcours (`-L`).
+    /// Sinon, les liens sont recréés (`-P`).
+    dereference: bool,
+    /// Conserve permissions, propriétaire et dates (`-a`).
+    archive: bool,
+    /// Gestion des fichiers creux (`--sparse=WHEN`).
+    sparse: Sparse,
+    /// Nombre de threads de copie pour `-r` (`--jobs N`, 1 par défaut).
+    jobs: usize,
+    /// Traitement des attributs étendus (`--xattrs`, `--no-xattrs`).
+    xattrs: Xattrs,
+    /// Compare la copie à la source après chaque fichier copié (`--verify`).
+    verify: bool,
+    /// Affiche l'avancement, le débit et le temps restant de chaque fichier (`--progress`).
+    progress: bool,
+    /// Affiche les chemins absolus de la source et de la copie après la copie (`--print-paths`).
+    print_paths: bool,
+    /// Recrée seulement les dossiers de la source, sans copier les fichiers (`--dirs-only`).
+    dirs_only: bool,
+    /// Écrase aussi les fichiers protégés par un marqueur `.keep` (`-f`).
+    force: bool,
+    /// Ne copie pas un fichier si la destination a déjà le même contenu (`--skip-identical`).
+    skip_identical: bool,
+    /// Taille des tranches de la copie (`--buffer-size N` ou variable `UTILS_BUFFER_SIZE`).
+    /// Sans valeur, la copie est confiée à `fs::copy`.
+    buffer_size: Option<usize>,
+    /// Modèle du nom de chaque copie (`--as MODELE`).
+    as_template: Option<Template>,
+}
+
+/// Débit d'une copie (`--progress`), lissé sur les dernières `PROGRESS_WINDOW`.
+///
+/// Les instants sont des durées écoulées depuis le début de la copie : le calcul
+/// ne dépend donc pas de l'horloge et peut être rejoué avec des valeurs choisies.
+///
+/// ## Exemple :
+/// ```rust
+/// let mut throughput = Throughput::default();
+/// throughput.record(Duration::ZERO, 0);
+/// throughput.record(Duration::from_secs(2), 20_000_000);
+/// assert_eq!(throughput.rate(), Some(10_000_000.0));
+/// assert_eq!(throughput.eta(30_000_000), Some(Duration::from_secs(3)));
+/// ```
+#[derive(Default)]
+struct Throughput {
+    /// Mesures `(instant, octets copiés)`, de la plus ancienne à la plus récente.
+    samples: VecDeque<(Duration, u64)>,
+}
+
+impl Throughput {
+    /// Enregistre le total d'octets copiés à l'instant `elapsed`, puis oublie les
+    /// mesures sorties de la fenêtre (la plus ancienne gardée sert de point de départ).
+    fn record(&mut self, elapsed: Duration, bytes: u64) {
+        self.samples.push_back((elapsed, bytes));
+        while self.samples.len() > 2 && elapsed.saturating_sub(self.samples[1].0) >= PROGRESS_WINDOW {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Débit en octets par seconde entre la plus ancienne et la plus récente mesure,
+    /// ou `None` tant qu'aucun temps ne s'est écoulé entre elles.
+    fn rate(&self) -> Option<f64> {
+        let (first_time, first_bytes) = self.samples.front()?;
+        let (last_time, last_bytes) = self.samples.back()?;
+        let seconds = last_time.saturating_sub(*first_time).as_secs_f64();
+        (seconds > 0.0).then(|| last_bytes.saturating_sub(*first_bytes) as f64 / seconds)
+    }
+
+    /// Temps restant estimé pour copier `remaining` octets au débit actuel.
+    fn eta(&self, remaining: u64) -> Option<Duration> {
+        let rate = self.rate().filter(|rate| *rate > 0.0)?;
+        Some(Duration::from_secs_f64(remaining as f64 / rate))
+    }
+}
 
 /// # Fonction : `copy_file`
 ///
@@ -19,75 +160,953 @@ use std::path::Path;
 ///
 /// ## Fonctionnement :
 /// 1. Vérifie si le fichier source existe.
-/// 2. Détermine si la destination est un dossier ou un fichier.
-/// 3. Si la destination existe déjà :  
+/// 2. Détermine si la destination est un dossier ou un fichier. Une destination
+///    terminée par `/` doit être un dossier (ou le devenir, pour la copie d’un dossier).
+/// 3. Refuse de copier un fichier sur lui-même ou un dossier dans l’un de ses descendants.
+/// 4. Si la source est un dossier, le copie récursivement avec [`copy_dir`] (flag `-r`).
+/// 5. Si la destination existe déjà :
 ///     - et que le flag `-i` est activé, demande confirmation avant d’écraser.
-/// 4. Copie le fichier vers la destination.
-/// 5. Si le flag `-v` est activé, affiche le nom du fichier copié.
+/// 6. Copie le fichier vers la destination.
+/// 7. Si le flag `-a` est activé, recopie les attributs avec [`preserve_attributes`].
+/// 8. Si le flag `-v` est activé, affiche le nom du fichier copié.
 ///
 /// ## Flags pris en charge :
-/// - `-i` : *interactive* → demande confirmation avant d’écraser un fichier existant.  
+/// - `-i` : *interactive* → demande confirmation avant d’écraser un fichier existant.
 /// - `-v` : *verbose* → affiche les fichiers copiés.
-fn copy_file(flag: Option<&str>, source: &str, destination: &str) {
-    
+/// - `-r` : *recursive* → copie les dossiers.
+/// - `-L` / `-P` : suit ou recrée les liens symboliques pendant la copie récursive.
+/// - `-a` : *archive* → conserve permissions, propriétaire et dates.
+/// - `--print-paths` : affiche les chemins finaux avec [`print_final_paths`].
+fn copy_file(options: &Options, source: &str, destination: &str) {
+
+    // Résout les chemins (gère ., .. et ~)
+    let (source_path, destination_path) = match (path::resolve(source), path::resolve(destination)) {
+        (Ok(s), Ok(d)) => (s, d),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("cp: cannot resolve path: {e}");
+            return;
+        }
+    };
+
     // Vérifie si le fichier source existe
-    if !Path::new(source).exists() {
+    if !source_path.exists() {
         eprintln!("cp: cannot stat '{source}': No such file or directory");
         return;
     }
 
-    
+
+    //    Une destination terminée par un séparateur désigne forcément un dossier :
+    //    - s'il existe mais n'est pas un dossier, erreur ;
+    //    - s'il n'existe pas, seule la copie d'un dossier peut le créer.
+    if destination.ends_with(['/', '\\']) && !destination_path.is_dir() {
+        if destination_path.exists() {
+            eprintln!("cp: failed to access '{destination}': Not a directory");
+            return;
+        }
+        if !source_path.is_dir() {
+            eprintln!("cp: cannot create regular file '{destination}': Not a directory");
+            return;
+        }
+    }
+
+
     //    Vérifie si la destination est un fichier ou un répertoire :
     //    - Si c’est un répertoire, on ajoute le nom du fichier source à la fin.
     //    - Sinon, on considère que la destination est un fichier et on garde son nom tel quel.
-    let final_destination = if Path::new(destination).is_dir() {
-        let file_name = Path::new(source)
+    let (final_destination, final_dest_path) = if destination_path.is_dir() {
+        let file_name = source_path
             .file_name()
             .unwrap()
             .to_str()
             .unwrap();
-        format!("{}/{}", destination.trim_end_matches('/'), file_name)
+        (
+            format!("{}/{}", destination.trim_end_matches('/'), file_name),
+            destination_path.join(file_name),
+        )
     } else {
-        destination.to_string()
+        (destination.to_string(), destination_path)
     };
 
-    
+
+    //    Refuse une destination identique à la source, ou située dans la source
+    //    quand c'est un dossier (ce qui provoquerait une récursion infinie).
+    if let (Ok(from), Ok(to)) = (path::canonicalize(&source_path), path::canonicalize(&final_dest_path)) {
+        if from == to {
+            eprintln!("cp: '{source}' and '{final_destination}' are the same file");
+            return;
+        }
+        if source_path.is_dir() && to.starts_with(&from) {
+            eprintln!("cp: cannot copy '{source}' to a subdirectory of itself, '{final_destination}'");
+            return;
+        }
+    }
+
+
+    //    Avec -P (par défaut), une source qui est un lien vers un dossier est recréée
+    //    comme un lien, comme ceux rencontrés pendant le parcours. Un '/' final
+    //    (`lien/`) désigne le dossier pointé, qui est alors copié.
+    let is_symlink = fs::symlink_metadata(&source_path).is_ok_and(|m| m.file_type().is_symlink());
+    if is_symlink && source_path.is_dir() && options.recursive && !options.dereference && !source.ends_with(['/', '\\']) {
+        match copy_symlink(&source_path, &final_dest_path) {
+            Ok(()) if options.verbose => println!("'{source}' -> '{final_destination}'"),
+            Ok(()) => {}
+            Err(e) => eprintln!("cp: cannot copy '{source}' to '{final_destination}': {e}"),
+        }
+        return;
+    }
+
+
+    //    --dirs-only ne recopie que des dossiers : la source doit en être un.
+    if options.dirs_only && !source_path.is_dir() {
+        eprintln!("cp: --dirs-only: '{source}' is not a directory");
+        return;
+    }
+
+
+    //    Si la source est un dossier :
+    //    - sans -r, on refuse la copie comme la commande Unix ;
+    //    - avec -r, on copie tout son contenu (ou seulement ses dossiers avec --dirs-only).
+    if source_path.is_dir() {
+        if !options.recursive {
+            eprintln!("cp: -r not specified; omitting directory '{source}'");
+            return;
+        }
+        let result = if options.dirs_only {
+            copy_dir_structure(options, &source_path, &final_dest_path, &mut Vec::new())
+        } else {
+            copy_dir(options, &source_path, &final_dest_path, &mut Vec::new())
+        };
+        match result {
+            Ok(()) if options.print_paths => print_final_paths(&source_path, &final_dest_path),
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {
+                eprintln!("cp: interrupted; '{final_destination}' is incomplete");
+            }
+            Err(e) => eprintln!("cp: cannot copy '{source}' to '{final_destination}': {e}"),
+        }
+        return;
+    }
+
+
     //    Vérifie si le fichier de destination existe déjà :
     //    - Si oui, et que l’utilisateur a passé le flag -i (interactive),
     //      on lui demande s’il veut écraser le fichier existant.
     //    - Si l’utilisateur tape 'y', le programme continue
     //      et effectuera la copie juste après.
     //   - Sinon, la copie est annulée.
-    if Path::new(&final_destination).exists() {
-        if let Some(f) = flag {
-            if f == "-i" {
-                print!("cp: overwrite '{final_destination}'? ");
-                io::stdout().flush().unwrap();
-                let mut answer = String::new();
-                io::stdin().read_line(&mut answer).unwrap();
-
-                // Si l'utilisateur ne confirme pas, on annule la copie
-                if !answer.trim().eq_ignore_ascii_case("y") {
-                    println!("cp: not overwritten.");
-                    return;
-                }
-            }
+    if final_dest_path.exists() && is_protected(options, &final_dest_path) {
+        return;
+    }
+    if is_identical(options, &source_path, &final_dest_path) {
+        return;
+    }
+    if final_dest_path.exists() && options.interactive {
+        print!("cp: overwrite '{final_destination}'? ");
+        io::stdout().flush().unwrap();
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer).unwrap();
+
+        // Si l'utilisateur ne confirme pas, on annule la copie
+        if !answer.trim().eq_ignore_ascii_case("y") {
+            println!("cp: not overwritten.");
+            return;
         }
     }
 
-    
+
     //    Copie du fichier (sauf si l’utilisateur a refusé précédemment).
     //    Si le flag -v (verbose) est activé, on affiche le déplacement effectué.
-    match fs::copy(source, &final_destination) {
+    match copy_contents(options, &source_path, &final_dest_path) {
         Ok(_) => {
-            if let Some(f) = flag {
-                if f == "-v" {
-                    println!("'{source}' -> '{final_destination}'");
+            if options.archive
+                && let Err(e) = preserve_attributes(&source_path, &final_dest_path)
+            {
+                eprintln!("cp: cannot preserve attributes of '{final_destination}': {e}");
+            }
+            if options.verbose {
+                println!("'{source}' -> '{final_destination}'");
+            }
+            if options.print_paths {
+                print_final_paths(&source_path, &final_dest_path);
+            }
+        }
+        Err(e) => eprintln!("cp: cannot copy '{source}' to '{final_destination}': {e}"),
+    }
+}
+
+
+/// # Fonction : `copy_as`
+///
+/// Copie chaque source dans le dossier `destination`, sous le nom donné par le
+/// modèle `--as` ([`Template::expand`]), puis délègue la copie à [`copy_file`].
+///
+/// ## Fonctionnement :
+/// - `destination` doit être un dossier existant.
+/// - Une source contenant un joker (`*`, `?`, `[...]`) est développée avec [`glob::expand`].
+/// - `{date}` est calculée une seule fois : toutes les copies portent la même date.
+/// - Une source dont le nom produit serait déjà celui d’une autre copie de la même
+///   commande est ignorée avec un avertissement, au lieu de l’écraser ([`Batch`]).
+///
+/// ## Exemple :
+/// ```text
+/// cp --as "{name}-{date}.{ext}" app.log db.log backup/
+/// → backup/app-2024-05-01.log, backup/db-2024-05-01.log
+/// ```
+fn copy_as(options: &Options, template: &Template, sources: &[&str], destination: &str) {
+    let mut batch = match Batch::new(template, destination, date::format_day(SystemTime::now())) {
+        Ok(batch) => batch,
+        Err(e) => {
+            eprintln!("cp: {e}");
+            return;
+        }
+    };
+
+    let mut targets: Vec<String> = Vec::new();
+    for source in sources {
+        if glob::is_pattern(source) {
+            match glob::expand(source) {
+                Ok(matches) if matches.is_empty() => eprintln!("cp: no match for '{source}'"),
+                Ok(matches) => targets.extend(matches.iter().map(|p| p.display().to_string())),
+                Err(e) => eprintln!("cp: cannot expand '{source}': {e}"),
+            }
+        } else {
+            targets.push(source.to_string());
+        }
+    }
+
+    for source in targets {
+        if interrupt::is_interrupted() {
+            eprintln!("cp: interrupted; remaining files not copied");
+            return;
+        }
+
+        match batch.destination(&source, Path::new(&source)) {
+            Ok(final_destination) => copy_file(options, &source, &final_destination),
+            Err(e) => eprintln!("cp: {e}"),
+        }
+    }
+}
+
+
+/// # Fonction : `print_final_paths`
+///
+/// Affiche `source\tdestination` avec les chemins absolus résolus par
+/// [`path::canonicalize`] une fois la copie terminée (`--print-paths`).
+///
+/// Le nom final est ainsi connu même quand la destination était un dossier
+/// (`cp --print-paths notes.txt ~/docs` affiche `.../notes.txt\t/home/user/docs/notes.txt`).
+fn print_final_paths(source: &Path, destination: &Path) {
+    match final_paths(source, destination) {
+        Ok(line) => println!("{line}"),
+        Err(message) => eprintln!("cp: {message}"),
+    }
+}
+
+/// Ligne `source\tdestination` affichée par [`print_final_paths`], ou le message
+/// d'erreur nommant le chemin qui n'a pas pu être résolu.
+fn final_paths(source: &Path, destination: &Path) -> Result<String, String> {
+    let resolve = |path: &Path| path::canonicalize(path).map_err(|e| format!("cannot resolve '{}': {e}", path.display()));
+    Ok(format!("{}\t{}", resolve(source)?.display(), resolve(destination)?.display()))
+}
+
+
+/// # Fonction : `copy_dir`
+///
+/// Copie récursivement un dossier et tout son contenu.
+///
+/// ## Fonctionnement :
+/// 1. Crée le dossier de destination.
+/// 2. Parcourt les entrées du dossier source avec `symlink_metadata`,
+///    qui ne suit pas les liens symboliques.
+/// 3. Pour un lien symbolique : le recrée (`-P`, par défaut) ou copie sa cible (`-L`).
+/// 4. Pour un dossier : s’appelle récursivement.
+/// 5. Pour un fichier : le copie avec [`copy_contents`].
+/// 6. Avec `-a`, recopie les attributs de chaque fichier, puis ceux du dossier
+///    une fois son contenu copié (sinon sa date de modification serait écrasée).
+///
+/// `ancestors` contient les chemins réels des dossiers en cours de copie (voir
+/// [`enter_dir`]) : avec `-L`, un lien qui ramène vers l’un d’eux n’est pas suivi.
+///
+/// Avec `--jobs N` (N > 1), la copie est confiée à [`copy_dir_parallel`].
+/// Après Ctrl+C, s’arrête avant l’entrée suivante avec une erreur `Interrupted`.
+fn copy_dir(options: &Options, source: &Path, destination: &Path, ancestors: &mut Vec<PathBuf>) -> io::Result<()> {
+    if options.jobs > 1 {
+        return copy_dir_parallel(options, source, destination);
+    }
+    if !enter_dir(source, ancestors)? {
+        return Ok(());
+    }
+
+    fs::create_dir_all(destination)?;
+    if options.verbose {
+        println!("'{}' -> '{}'", source.display(), destination.display());
+    }
+
+    transfer_xattrs(options, source, destination);
+
+    for entry in fs::read_dir(source)? {
+        interrupt::check()?;
+        let from = entry?.path();
+        let to = destination.join(from.file_name().unwrap());
+
+        let is_symlink = fs::symlink_metadata(&from)?.file_type().is_symlink();
+        if is_symlink && !options.dereference {
+            copy_symlink(&from, &to)?;
+        } else if from.is_dir() {
+            copy_dir(options, &from, &to, ancestors)?;
+            continue;
+        } else if is_protected(options, &to) || is_identical(options, &from, &to) {
+            continue;
+        } else {
+            copy_contents(options, &from, &to)?;
+            if options.archive {
+                preserve_attributes(&from, &to)?;
+            }
+        }
+
+        if options.verbose {
+            println!("'{}' -> '{}'", from.display(), to.display());
+        }
+    }
+
+    if options.archive {
+        preserve_attributes(source, destination)?;
+    }
+
+    ancestors.pop();
+    Ok(())
+}
+
+
+/// # Fonction : `enter_dir`
+///
+/// Ajoute le chemin réel du dossier `dir` à `ancestors`, la pile des dossiers en
+/// cours de copie. Retourne `false`, après l’avoir signalé, si ce dossier y est
+/// déjà : un lien suivi avec `-L` y ramène, et le copier bouclerait sans fin.
+/// L’appelant retire le dossier de la pile une fois son contenu copié.
+fn enter_dir(dir: &Path, ancestors: &mut Vec<PathBuf>) -> io::Result<bool> {
+    let real = fs::canonicalize(dir)?;
+    if ancestors.contains(&real) {
+        eprintln!("cp: skipping '{}': symbolic link loop to '{}'", dir.display(), real.display());
+        return Ok(false);
+    }
+    ancestors.push(real);
+    Ok(true)
+}
+
+
+/// # Fonction : `is_protected`
+///
+/// Indique si la destination est protégée par un marqueur `<nom>.keep`
+/// ([`path::keep_marker`]) et ne doit pas être écrasée. Le refus est affiché ;
+/// avec `-f`, aucune destination n’est protégée.
+fn is_protected(options: &Options, destination: &Path) -> bool {
+    if options.force {
+        return false;
+    }
+    match path::keep_marker(destination) {
+        Some(marker) => {
+            eprintln!(
+                "cp: not overwriting '{}': protected by '{}' (use -f to force)",
+                destination.display(),
+                marker.display()
+            );
+            true
+        }
+        None => false,
+    }
+}
+
+
+/// # Fonction : `is_identical`
+///
+/// Avec `--skip-identical`, indique si la destination est un fichier dont le contenu
+/// est déjà celui de la source ([`sum::same_contents`]) : la copie est alors inutile
+/// et n’est pas faite, ce qui est affiché. Seul le contenu compte, pas les dates.
+/// Si la comparaison échoue, la copie a lieu normalement.
+fn is_identical(options: &Options, source: &Path, destination: &Path) -> bool {
+    if !options.skip_identical || !destination.is_file() {
+        return false;
+    }
+    if sum::same_contents(source, destination).unwrap_or(false) {
+        println!("cp: skipping '{}': identical to '{}'", destination.display(), source.display());
+        return true;
+    }
+    false
+}
+
+
+/// # Fonction : `copy_dir_structure`
+///
+/// Recrée l’arborescence des dossiers de la source, sans copier aucun fichier (`--dirs-only`).
+///
+/// ## Fonctionnement :
+/// 1. Crée le dossier de destination s’il n’existe pas encore (avec `-v`, il est affiché).
+/// 2. Parcourt les entrées de la source : seuls les dossiers sont pris en compte,
+///    les fichiers et les liens symboliques sont ignorés. Avec `-L`, un lien vers
+///    un dossier est suivi comme un dossier.
+/// 3. S’appelle récursivement pour chaque sous-dossier.
+/// 4. Avec `-a`, recopie les attributs de chaque dossier une fois ses sous-dossiers créés.
+///
+/// Comme pour [`copy_dir`], une boucle de liens est signalée et ignorée ([`enter_dir`]).
+/// Après Ctrl+C, s’arrête avant l’entrée suivante avec une erreur `Interrupted`.
+///
+/// ## Exemple :
+/// ```text
+/// $ cp -v --dirs-only projet squelette
+/// '/home/user/projet' -> '/home/user/squelette'
+/// '/home/user/projet/src' -> '/home/user/squelette/src'
+/// '/home/user/projet/src/bin' -> '/home/user/squelette/src/bin'
+/// ```
+fn copy_dir_structure(options: &Options, source: &Path, destination: &Path, ancestors: &mut Vec<PathBuf>) -> io::Result<()> {
+    if !enter_dir(source, ancestors)? {
+        return Ok(());
+    }
+    if !destination.is_dir() {
+        fs::create_dir_all(destination)?;
+        if options.verbose {
+            println!("'{}' -> '{}'", source.display(), destination.display());
+        }
+    }
+
+    for entry in fs::read_dir(source)? {
+        interrupt::check()?;
+        let from = entry?.path();
+
+        let is_symlink = fs::symlink_metadata(&from)?.file_type().is_symlink();
+        if from.is_dir() && (!is_symlink || options.dereference) {
+            copy_dir_structure(options, &from, &destination.join(from.file_name().unwrap()), ancestors)?;
+        }
+    }
+
+    if options.archive {
+        preserve_attributes(source, destination)?;
+    }
+
+    ancestors.pop();
+    Ok(())
+}
+
+
+/// # Fonction : `copy_dir_parallel`
+///
+/// Copie récursivement un dossier en répartissant les fichiers sur plusieurs threads (`--jobs N`).
+///
+/// ## Fonctionnement :
+/// 1. Parcourt l’arborescence avec [`plan_dir`] : les dossiers et les liens sont
+///    créés tout de suite, les fichiers sont mis en file d’attente.
+/// 2. Lance `N` threads qui se partagent la file avec un index atomique : chaque
+///    fichier est pris par un seul thread, et chaque destination est distincte,
+///    donc deux copies ne touchent jamais le même chemin.
+/// 3. Affiche la progression (si la sortie d’erreur est un terminal) puis un résumé.
+///    Avec plusieurs threads, `--progress` se limite à ce compteur de fichiers :
+///    les lignes d’avancement de chaque fichier se mélangeraient.
+/// 4. Avec `-a`, recopie les attributs des dossiers en dernier, du plus profond au moins profond.
+///
+/// Après Ctrl+C, les threads ne prennent plus de nouveau fichier : le résumé indique
+/// combien ont été copiés, puis une erreur `Interrupted` est retournée.
+fn copy_dir_parallel(options: &Options, source: &Path, destination: &Path) -> io::Result<()> {
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    plan_dir(options, source, destination, &mut files, &mut dirs, &mut Vec::new())?;
+    let options = &Options { progress: false, ..options.clone() };
+
+    let total = files.len();
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+    let failed = AtomicUsize::new(0);
+    let show_progress = io::stderr().is_terminal();
+
+    thread::scope(|scope| {
+        for _ in 0..options.jobs.min(total.max(1)) {
+            scope.spawn(|| {
+                while !interrupt::is_interrupted()
+                    && let Some((from, to)) = files.get(next.fetch_add(1, Ordering::Relaxed))
+                {
+                    let result = copy_contents(options, from, to).and_then(|_| {
+                        if options.archive {
+                            preserve_attributes(from, to)?;
+                        }
+                        Ok(())
+                    });
+                    match result {
+                        Ok(()) if options.verbose => println!("'{}' -> '{}'", from.display(), to.display()),
+                        Ok(()) => {}
+                        Err(e) => {
+                            failed.fetch_add(1, Ordering::Relaxed);
+                            eprintln!("cp: cannot copy '{}' to '{}': {e}", from.display(), to.display());
+                        }
+                    }
+
+                    let count = done.fetch_add(1, Ordering::Relaxed) + 1;
+                    if show_progress {
+                        eprint!("\rcp: {count}/{total} files");
+                    }
                 }
+            });
+        }
+    });
+
+    if show_progress && total > 0 {
+        eprintln!();
+    }
+    let failed = failed.into_inner();
+    let copied = done.into_inner() - failed;
+    println!("cp: copied {copied} of {total} files using {} jobs", options.jobs);
+    interrupt::check()?;
+
+    if options.archive {
+        for (from, to) in dirs.iter().rev() {
+            preserve_attributes(from, to)?;
+        }
+    }
+
+    if failed > 0 {
+        return Err(io::Error::other(format!("{failed} file(s) could not be copied")));
+    }
+    Ok(())
+}
+
+
+/// # Fonction : `plan_dir`
+///
+/// Prépare la copie parallèle d’un dossier : crée les dossiers et les liens symboliques
+/// de la destination, et ajoute les fichiers à copier dans `files`.
+/// Les couples de dossiers sont ajoutés à `dirs` dans l’ordre du parcours (parents d’abord).
+/// Comme pour [`copy_dir`], une boucle de liens est signalée et ignorée ([`enter_dir`]).
+fn plan_dir(
+    options: &Options,
+    source: &Path,
+    destination: &Path,
+    files: &mut Vec<(PathBuf, PathBuf)>,
+    dirs: &mut Vec<(PathBuf, PathBuf)>,
+    ancestors: &mut Vec<PathBuf>,
+) -> io::Result<()> {
+    if !enter_dir(source, ancestors)? {
+        return Ok(());
+    }
+    fs::create_dir_all(destination)?;
+    if options.verbose {
+        println!("'{}' -> '{}'", source.display(), destination.display());
+    }
+    dirs.push((source.to_path_buf(), destination.to_path_buf()));
+    transfer_xattrs(options, source, destination);
+
+    for entry in fs::read_dir(source)? {
+        let from = entry?.path();
+        let to = destination.join(from.file_name().unwrap());
+
+        let is_symlink = fs::symlink_metadata(&from)?.file_type().is_symlink();
+        if is_symlink && !options.dereference {
+            copy_symlink(&from, &to)?;
+            if options.verbose {
+                println!("'{}' -> '{}'", from.display(), to.display());
+            }
+        } else if from.is_dir() {
+            plan_dir(options, &from, &to, files, dirs, ancestors)?;
+        } else if !is_protected(options, &to) && !is_identical(options, &from, &to) {
+            files.push((from, to));
+        }
+    }
+
+    ancestors.pop();
+    Ok(())
+}
+
+
+/// # Fonction : `copy_contents`
+///
+/// Copie le contenu et les permissions d’un fichier selon le mode `--sparse`.
+///
+/// ## Fonctionnement :
+/// - `never` : copie classique avec `fs::copy`, par tranches de `--buffer-size` octets
+///   avec [`copy_buffered`], ou [`copy_with_progress`] avec `--progress`.
+/// - `always` : copie creuse avec [`copy_sparse`] (sans affichage de l’avancement).
+/// - `auto` : copie creuse seulement si la source est creuse (voir [`is_sparse`]).
+///
+/// Avec `--verify`, la copie est vérifiée par [`copy_verified`].
+///
+/// Les attributs étendus sont ensuite traités avec [`transfer_xattrs`].
+fn copy_contents(options: &Options, source: &Path, destination: &Path) -> io::Result<()> {
+    let sparse = match options.sparse {
+        Sparse::Always => true,
+        Sparse::Never => false,
+        Sparse::Auto => is_sparse(&fs::metadata(source)?),
+    };
+
+    copy_verified(options.verify, source, destination, || {
+        if sparse {
+            copy_sparse(source, destination)
+        } else if options.progress {
+            copy_with_progress(source, destination, options.buffer_size.unwrap_or(PROGRESS_BUFFER_SIZE))
+        } else if let Some(buffer_size) = options.buffer_size {
+            copy_buffered(source, destination, buffer_size)
+        } else {
+            fs::copy(source, destination).map(|_| ())
+        }
+    })?;
+
+    transfer_xattrs(options, source, destination);
+    Ok(())
+}
+
+
+/// # Fonction : `copy_verified`
+///
+/// Effectue une copie avec `copy`, puis, si `verify` est vrai (`--verify`), relit la
+/// copie et la compare à la source avec [`sum::same_contents`] : en cas de différence,
+/// la copie est recommencée jusqu’à `VERIFY_RETRIES` fois, puis une erreur
+/// `InvalidData` est retournée.
+fn copy_verified(
+    verify: bool,
+    source: &Path,
+    destination: &Path,
+    mut copy: impl FnMut() -> io::Result<()>,
+) -> io::Result<()> {
+    let mut attempt = 0;
+    loop {
+        copy()?;
+
+        if !verify || sum::same_contents(source, destination)? {
+            return Ok(());
+        }
+        if attempt == VERIFY_RETRIES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "verification failed: copy differs from source",
+            ));
+        }
+        attempt += 1;
+        eprintln!("cp: verification of '{}' failed, copying again", destination.display());
+    }
+}
+
+
+/// # Fonction : `copy_buffered`
+///
+/// Copie un fichier par tranches de `buffer_size` octets (`--buffer-size`), puis
+/// recopie les permissions de la source comme `fs::copy`.
+///
+/// ## Exemple :
+/// ```rust
+/// // Une tranche de 3 octets suffit : le fichier est recopié à l’identique
+/// copy_buffered(Path::new("notes.txt"), Path::new("copie.txt"), 3)?;
+/// assert_eq!(fs::read("notes.txt")?, fs::read("copie.txt")?);
+/// ```
+fn copy_buffered(source: &Path, destination: &Path, buffer_size: usize) -> io::Result<()> {
+    let mut reader = File::open(source)?;
+    let mut writer = File::create(destination)?;
+    let mut buffer = vec![0; buffer_size];
+
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        writer.write_all(&buffer[..read])?;
+    }
+
+    fs::set_permissions(destination, fs::metadata(source)?.permissions())
+}
+
+
+/// # Fonction : `copy_with_progress`
+///
+/// Copie un fichier par tranches de `buffer_size` octets (`PROGRESS_BUFFER_SIZE` sauf
+/// avec `--buffer-size`) en affichant sur stderr
+/// son avancement (`--progress`), par exemple :
+/// `cp: video.mp4  45.0 Mo / 120.0 Mo (37%)  12.3 Mo/s  reste 0:06`.
+///
+/// ## Fonctionnement :
+/// 1. Après chaque tranche, enregistre le total copié dans un [`Throughput`].
+/// 2. Réécrit la ligne sur place avec `\r`, au plus toutes les `PROGRESS_INTERVAL`.
+/// 3. À la fin, affiche une dernière fois la ligne avec le débit moyen, puis recopie
+///    les permissions de la source comme `fs::copy`.
+///
+/// Après Ctrl+C, la copie partielle est supprimée et une erreur `Interrupted` est retournée.
+fn copy_with_progress(source: &Path, destination: &Path, buffer_size: usize) -> io::Result<()> {
+    let total = fs::metadata(source)?.len();
+    let name = source.file_name().unwrap_or(source.as_os_str()).to_string_lossy().into_owned();
+    let mut reader = File::open(source)?;
+    let mut writer = File::create(destination)?;
+    let mut buffer = vec![0; buffer_size];
+
+    let start = Instant::now();
+    let mut throughput = Throughput::default();
+    throughput.record(Duration::ZERO, 0);
+    let mut copied = 0;
+    let mut last_display: Option<Instant> = None;
+    let mut width = 0;
+
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if interrupt::is_interrupted() {
+            drop(writer);
+            eprintln!();
+            fs::remove_file(destination)?;
+            return interrupt::check();
+        }
+        writer.write_all(&buffer[..read])?;
+        copied += read as u64;
+        throughput.record(start.elapsed(), copied);
+
+        if last_display.is_none_or(|shown| shown.elapsed() >= PROGRESS_INTERVAL) {
+            let line = progress_line(&name, copied, total, throughput.rate(), throughput.eta(total.saturating_sub(copied)));
+            width = display_progress(&line, width);
+            last_display = Some(Instant::now());
+        }
+    }
+
+    // Dernier affichage : débit moyen sur toute la copie
+    let seconds = start.elapsed().as_secs_f64();
+    let average = (seconds > 0.0).then(|| copied as f64 / seconds);
+    display_progress(&progress_line(&name, copied, total, average, Some(Duration::ZERO)), width);
+    eprintln!();
+
+    fs::set_permissions(destination, fs::metadata(source)?.permissions())
+}
+
+
+/// # Fonction : `progress_line`
+///
+/// Construit la ligne d’avancement de `--progress` : nom, octets copiés sur le total,
+/// pourcentage, débit et temps restant (`--` tant qu’ils ne sont pas connus).
+fn progress_line(name: &str, copied: u64, total: u64, rate: Option<f64>, eta: Option<Duration>) -> String {
+    let percent = (copied * 100).checked_div(total).unwrap_or(100);
+    let rate = match rate {
+        Some(rate) => format!("{}/s", size::human_readable(rate as u64)),
+        None => "--/s".to_string(),
+    };
+    let eta = match eta {
+        Some(eta) => format_duration(eta),
+        None => "--".to_string(),
+    };
+    format!(
+        "cp: {name}  {} / {} ({percent}%)  {rate}  reste {eta}",
+        size::human_readable(copied),
+        size::human_readable(total),
+    )
+}
+
+
+/// # Fonction : `display_progress`
+///
+/// Réécrit la ligne d’avancement sur stderr avec `\r`, en effaçant avec des espaces
+/// la fin d’une ligne précédente plus longue. Retourne la largeur de la ligne affichée.
+fn display_progress(line: &str, previous_width: usize) -> usize {
+    let width = line.chars().count();
+    eprint!("\r{line}{}", " ".repeat(previous_width.saturating_sub(width)));
+    width
+}
+
+
+/// # Fonction : `format_duration`
+///
+/// Affiche une durée arrondie à la seconde sous la forme `m:ss` (ou `h:mm:ss`).
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs_f64().round() as u64;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+
+/// # Fonction : `transfer_xattrs`
+///
+/// Recopie (`--xattrs`) ou supprime (`--no-xattrs`) les attributs étendus de la destination.
+/// Comme pour le propriétaire avec `-a`, un échec (plateforme ou système de fichiers
+/// non pris en charge, droits insuffisants) affiche un avertissement sans interrompre la copie.
+fn transfer_xattrs(options: &Options, source: &Path, destination: &Path) {
+    let result = match options.xattrs {
+        Xattrs::Default => return,
+        Xattrs::Copy => xattr::copy(source, destination),
+        Xattrs::Strip => xattr::strip(destination),
+    };
+
+    if let Err(e) = result {
+        eprintln!("cp: warning: cannot update extended attributes of '{}': {e}", destination.display());
+    }
+}
+
+
+/// # Fonction : `copy_sparse`
+///
+/// Copie un fichier en recréant des trous à la place des blocs de zéros.
+///
+/// ## Fonctionnement :
+/// 1. Lit la source par tranches de `SPARSE_BUFFER_SIZE` octets.
+/// 2. Découpe chaque tranche en blocs de `SPARSE_BLOCK_SIZE` octets :
+///    un bloc entièrement nul est sauté avec `seek` au lieu d’être écrit.
+/// 3. Fixe la taille finale avec `set_len`, ce qui crée aussi un trou
+///    pour une suite de zéros en fin de fichier.
+/// 4. Recopie les permissions, comme `fs::copy`.
+fn copy_sparse(source: &Path, destination: &Path) -> io::Result<()> {
+    let mut input = File::open(source)?;
+    let mut output = File::create(destination)?;
+    let mut buffer = vec![0u8; SPARSE_BUFFER_SIZE];
+    let mut length: u64 = 0;
+
+    loop {
+        let n = match input.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        for block in buffer[..n].chunks(SPARSE_BLOCK_SIZE) {
+            if block.iter().all(|&byte| byte == 0) {
+                output.seek(SeekFrom::Current(block.len() as i64))?;
+            } else {
+                output.write_all(block)?;
             }
         }
-        Err(_) => eprintln!("cp: cannot copy '{source}' to '{final_destination}'"),
+        length += n as u64;
+    }
+
+    output.set_len(length)?;
+    fs::set_permissions(destination, input.metadata()?.permissions())
+}
+
+
+/// # Fonction : `is_sparse`
+///
+/// Indique si un fichier est creux : sous Unix, l’espace réellement alloué
+/// (`blocks` × 512 octets) est inférieur à sa taille apparente.
+#[cfg(unix)]
+fn is_sparse(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    metadata.is_file() && metadata.blocks() * 512 < metadata.len()
+}
+
+/// # Fonction : `is_sparse`
+///
+/// Sans information sur l’espace alloué, un fichier n’est jamais considéré comme creux.
+#[cfg(not(unix))]
+fn is_sparse(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+
+/// # Fonction : `preserve_attributes`
+///
+/// Recopie sur la destination les attributs de la source (flag `-a`).
+///
+/// ## Fonctionnement :
+/// 1. Sous Unix, recopie le propriétaire (uid/gid) avec `chown`. Seul root peut
+///    en général le faire : un refus affiche un avertissement sans interrompre la copie.
+/// 2. Recopie les dates d’accès et de modification.
+/// 3. Recopie les permissions en dernier, car `chown` peut effacer les bits setuid/setgid.
+fn preserve_attributes(source: &Path, destination: &Path) -> io::Result<()> {
+    let metadata = fs::metadata(source)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        match std::os::unix::fs::chown(destination, Some(metadata.uid()), Some(metadata.gid())) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                eprintln!("cp: warning: cannot preserve ownership of '{}': {e}", destination.display());
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    let times = FileTimes::new()
+        .set_accessed(metadata.accessed()?)
+        .set_modified(metadata.modified()?);
+    File::open(destination)?.set_times(times)?;
+
+    fs::set_permissions(destination, metadata.permissions())
+}
+
+
+/// # Fonction : `copy_symlink`
+///
+/// Recrée à la destination un lien symbolique pointant vers la même cible que la source.
+/// Un éventuel fichier existant à la destination est remplacé.
+#[cfg(unix)]
+fn copy_symlink(source: &Path, destination: &Path) -> io::Result<()> {
+    let target = fs::read_link(source)?;
+    if fs::symlink_metadata(destination).is_ok() {
+        fs::remove_file(destination)?;
+    }
+    std::os::unix::fs::symlink(target, destination)
+}
+
+/// # Fonction : `copy_symlink`
+///
+/// Recrée à la destination un lien symbolique pointant vers la même cible que la source.
+/// Sous Windows, le type de lien (fichier ou dossier) dépend de la cible.
+#[cfg(windows)]
+fn copy_symlink(source: &Path, destination: &Path) -> io::Result<()> {
+    let target = fs::read_link(source)?;
+    if fs::symlink_metadata(destination).is_ok() {
+        fs::remove_file(destination)?;
+    }
+    if source.is_dir() {
+        std::os::windows::fs::symlink_dir(target, destination)
+    } else {
+        std::os::windows::fs::symlink_file(target, destination)
+    }
+}
+
+/// # Fonction : `copy_symlink`
+///
+/// Sur les plateformes sans liens symboliques, copie simplement la cible.
+#[cfg(not(any(unix, windows)))]
+fn copy_symlink(source: &Path, destination: &Path) -> io::Result<()> {
+    fs::copy(source, destination).map(|_| ())
+}
+
+
+/// # Fonction : `parse_option`
+///
+/// Met à jour les [`Options`] selon un flag. Les flags courts peuvent être combinés
+/// (ex. `-rv`). Retourne `false` si le flag est inconnu.
+fn parse_option(flag: &str, options: &mut Options) -> bool {
+    match flag {
+        "-i" => options.interactive = true,
+        "-v" => options.verbose = true,
+        "-f" | "--force" => options.force = true,
+        "-r" | "-R" | "--recursive" => options.recursive = true,
+        "-L" | "--dereference" => options.dereference = true,
+        "-P" | "--no-dereference" => options.dereference = false,
+        "-a" | "--archive" => {
+            options.archive = true;
+            options.recursive = true;
+        }
+        "--sparse=auto" => options.sparse = Sparse::Auto,
+        "--sparse=always" => options.sparse = Sparse::Always,
+        "--sparse=never" => options.sparse = Sparse::Never,
+        "--xattrs" => options.xattrs = Xattrs::Copy,
+        "--no-xattrs" => options.xattrs = Xattrs::Strip,
+        "--verify" => options.verify = true,
+        "--progress" => options.progress = true,
+        "--print-paths" => options.print_paths = true,
+        "--skip-identical" => options.skip_identical = true,
+        "--dirs-only" => {
+            options.dirs_only = true;
+            options.recursive = true;
+        }
+        _ if !flag.starts_with("--") && flag.len() > 2 => {
+            return flag[1..].chars().all(|c| parse_option(&format!("-{c}"), options));
+        }
+        _ => return false,
     }
+    true
 }
 
 
@@ -98,37 +1117,413 @@ fn copy_file(flag: Option<&str>, source: &str, destination: &str) {
 /// [`copy_file`] pour exécuter la copie réelle du fichier.
 ///
 /// ## Fonctionnement :
-/// 1. Vérifie qu’il y a suffisamment d’arguments.  
-/// 2. Détermine si le premier argument est un flag (`-i` ou `-v`).  
-/// 3. Identifie le fichier source et la destination.  
-/// 4. Appelle la fonction [`copy_file`] avec les bons paramètres.
+/// 1. Sépare les flags (arguments commençant par `-`) des chemins.
+///    `--jobs` et `--buffer-size` prennent leur valeur dans l’argument suivant (ou après `=`).
+///    Sans `--buffer-size`, la taille des tranches peut venir de `UTILS_BUFFER_SIZE`.
+/// 2. Vérifie qu’il y a bien une source et une destination.
+/// 3. Appelle la fonction [`copy_file`] avec les bons paramètres.
 pub fn handle_cp(args: &[String]) {
-    
-    
+    // Ctrl+C arrête la copie entre deux fichiers au lieu de quitter le programme
+    let _guard = interrupt::guard();
+    let mut options = Options { jobs: 1, ..Default::default() };
+    let mut paths: Vec<&str> = Vec::new();
+
+    //    Les arguments commençant par '-' sont des flags (ex. "-i", "-v", "-rL"),
+    //    les autres correspondent à la source puis à la destination.
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--jobs" || arg.starts_with("--jobs=") {
+            let value = match arg.strip_prefix("--jobs=") {
+                Some(value) => Some(value),
+                None => iter.next().map(|v| v.as_str()),
+            };
+            match value.and_then(|v| v.parse::<usize>().ok()) {
+                Some(n) if n > 0 => options.jobs = n,
+                _ => {
+                    eprintln!("cp: invalid number of jobs: '{}'", value.unwrap_or(""));
+                    return;
+                }
+            }
+        } else if arg == "--buffer-size" || arg.starts_with("--buffer-size=") {
+            let value = match arg.strip_prefix("--buffer-size=") {
+                Some(value) => Some(value),
+                None => iter.next().map(|v| v.as_str()),
+            };
+            match value.map(size::parse_buffer_size) {
+                Some(Ok(buffer_size)) => options.buffer_size = Some(buffer_size),
+                _ => {
+                    eprintln!("cp: invalid buffer size: '{}' (e.g. 4096, 64K, 1M; at most 64M)", value.unwrap_or(""));
+                    return;
+                }
+            }
+        } else if arg == "--as" || arg.starts_with("--as=") {
+            let value = match arg.strip_prefix("--as=") {
+                Some(value) => Some(value),
+                None => iter.next().map(|v| v.as_str()),
+            };
+            let Some(value) = value else {
+                eprintln!("cp: option '--as' requires an argument");
+                return;
+            };
+            match Template::parse(value) {
+                Ok(template) => options.as_template = Some(template),
+                Err(e) => {
+                    eprintln!("cp: invalid template: {e}");
+                    return;
+                }
+            }
+        } else if arg.starts_with('-') && arg.len() > 1 {
+            if !parse_option(arg, &mut options) {
+                eprintln!("cp: invalid option -- '{arg}'");
+                eprintln!("Try 'cp --help' for more information.");
+                return;
+            }
+        } else {
+            paths.push(arg);
+        }
+    }
+
+
+    //    Sans --buffer-size, la taille des tranches peut venir de l'environnement
+    if options.buffer_size.is_none() {
+        match size::env_buffer_size() {
+            Ok(buffer_size) => options.buffer_size = buffer_size,
+            Err(_) => {
+                eprintln!("cp: invalid buffer size in {} (e.g. 4096, 64K, 1M; at most 64M)", size::BUFFER_SIZE_VAR);
+                return;
+            }
+        }
+    }
+
     //    Vérifie qu'il y a suffisamment d'arguments :
-    //    - Si le nombre d'arguments est inférieur à 2,
+    //    - Si le nombre de chemins est inférieur à 2,
     //      on affiche un message d'erreur et on arrête la fonction.
-    if args.len() < 2 {
+    if paths.len() < 2 {
         eprintln!("cp: missing file operand");
         eprintln!("Try 'cp --help' for more information.");
         return;
     }
 
-    let mut flag: Option<&str> = None;
-    let (source, destination);
+    //    Avec --as, toutes les sources sont copiées dans le dernier chemin (un dossier)
+    if let Some(template) = &options.as_template {
+        let (destination, sources) = paths.split_last().unwrap();
+        copy_as(&options, template, sources, destination);
+        return;
+    }
 
-    //    Si l’utilisateur a passé au moins 3 arguments,
-    //    le premier est considéré comme un flag (ex. "-i" ou "-v").
-    //    Sinon, les deux premiers arguments correspondent
-    //    directement à la source et à la destination.
-    if args.len() == 3 {
-        flag = Some(args[0].as_str());
-        source = &args[1];
-        destination = &args[2];
-    } else {
-        source = &args[0];
-        destination = &args[1];
+    copy_file(&options, paths[0], paths[1]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdir::TestDir;
+
+    fn options(flags: &[&str]) -> Options {
+        let mut options = Options { jobs: 1, ..Default::default() };
+        for flag in flags {
+            assert!(parse_option(flag, &mut options), "flag {flag}");
+        }
+        options
     }
 
-    copy_file(flag, source, destination);
-}
\ No newline at end of file
+    fn text(path: &Path) -> &str {
+        path.to_str().unwrap()
+    }
+
+    fn copy(options: &Options, source: &Path, destination: &Path) {
+        copy_file(options, source.to_str().unwrap(), destination.to_str().unwrap());
+    }
+
+    #[test]
+    fn recursive_copy_keeps_tree() {
+        let dir = TestDir::new("cp-tree");
+        dir.write("src/a.txt", "a");
+        dir.write("src/sous/b.txt", "b");
+
+        copy(&options(&["-r"]), &dir.join("src"), &dir.join("copie"));
+        assert_eq!(fs::read_to_string(dir.join("copie/a.txt")).unwrap(), "a");
+        assert_eq!(fs::read_to_string(dir.join("copie/sous/b.txt")).unwrap(), "b");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn dereference_skips_symlink_loops() {
+        let dir = TestDir::new("cp-loop");
+        dir.write("src/a.txt", "a");
+        std::os::unix::fs::symlink(dir.join("src"), dir.join("src/boucle")).unwrap();
+
+        let parallel = Options { jobs: 2, ..options(&["-rL"]) };
+        for (name, options) in [("simple", options(&["-rL"])), ("dossiers", options(&["-rL", "--dirs-only"])), ("jobs", parallel)] {
+            let destination = dir.join(name);
+            copy(&options, &dir.join("src"), &destination);
+            assert!(destination.is_dir(), "{name}");
+            assert!(!destination.join("boucle").exists(), "{name}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn dereference_follows_links_outside_the_copy() {
+        let dir = TestDir::new("cp-follow");
+        dir.write("ailleurs/c.txt", "c");
+        dir.mkdir("src");
+        std::os::unix::fs::symlink(dir.join("ailleurs"), dir.join("src/lien")).unwrap();
+
+        copy(&options(&["-rL"]), &dir.join("src"), &dir.join("copie"));
+        let copied = dir.join("copie/lien");
+        assert!(!fs::symlink_metadata(&copied).unwrap().file_type().is_symlink());
+        assert_eq!(fs::read_to_string(copied.join("c.txt")).unwrap(), "c");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn top_level_directory_link_is_recreated_without_dereference() {
+        let dir = TestDir::new("cp-top-link");
+        dir.write("cible/a.txt", "a");
+        std::os::unix::fs::symlink(dir.join("cible"), dir.join("lien")).unwrap();
+
+        copy(&options(&["-r"]), &dir.join("lien"), &dir.join("copie"));
+        assert_eq!(fs::read_link(dir.join("copie")).unwrap(), dir.join("cible"));
+
+        // Avec un '/' final, c'est le dossier pointé qui est copié
+        let source = format!("{}/", dir.join("lien").display());
+        copy_file(&options(&["-r"]), &source, dir.join("contenu").to_str().unwrap());
+        assert!(!fs::symlink_metadata(dir.join("contenu")).unwrap().file_type().is_symlink());
+        assert_eq!(fs::read_to_string(dir.join("contenu/a.txt")).unwrap(), "a");
+
+        copy(&options(&["-rL"]), &dir.join("lien"), &dir.join("suivi"));
+        assert!(!fs::symlink_metadata(dir.join("suivi")).unwrap().file_type().is_symlink());
+    }
+
+    #[test]
+    fn parallel_copy_copies_every_file() {
+        let dir = TestDir::new("cp-jobs");
+        for i in 0..60 {
+            dir.write(&format!("src/d{}/f{i}.txt", i % 5), format!("fichier {i}"));
+        }
+
+        for jobs in [1, 3, 8] {
+            let destination = dir.join(&format!("copie{jobs}"));
+            copy(&Options { jobs, ..options(&["-r"]) }, &dir.join("src"), &destination);
+            for i in 0..60 {
+                let copied = destination.join(format!("d{}/f{i}.txt", i % 5));
+                assert_eq!(fs::read_to_string(copied).unwrap(), format!("fichier {i}"), "jobs {jobs}");
+            }
+        }
+    }
+
+    #[test]
+    fn refuses_to_copy_into_itself() {
+        let dir = TestDir::new("cp-itself");
+        dir.write("d/a.txt", "a");
+        let file = dir.write("a", "contenu");
+
+        copy(&options(&["-r"]), &dir.join("d"), &dir.join("d/sub"));
+        assert!(!dir.join("d/sub").exists());
+        dir.mkdir("d/sub");
+        copy(&options(&["-r"]), &dir.join("d"), &dir.join("d/sub"));
+        assert_eq!(fs::read_dir(dir.join("d/sub")).unwrap().count(), 0);
+
+        copy(&options(&[]), &file, &file);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "contenu");
+    }
+
+    #[test]
+    fn trailing_slash_requires_a_directory() {
+        let dir = TestDir::new("cp-slash");
+        let file = dir.write("a.txt", "a");
+        let existing = dir.write("existant", "garde");
+        dir.write("src/b.txt", "b");
+        let slashed = |path: &Path| format!("{}/", path.display());
+
+        copy_file(&options(&[]), text(&file), &slashed(&dir.join("dest")));
+        assert!(!dir.join("dest").exists());
+
+        copy_file(&options(&[]), text(&file), &slashed(&existing));
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "garde");
+
+        copy_file(&options(&["-r"]), text(&dir.join("src")), &slashed(&dir.join("dest")));
+        assert_eq!(fs::read_to_string(dir.join("dest/b.txt")).unwrap(), "b");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sparse_copy_keeps_bytes_and_holes() {
+        use std::os::unix::fs::MetadataExt;
+
+        let dir = TestDir::new("cp-sparse");
+        let source = dir.join("creux.bin");
+        let mut content = vec![0u8; 4 * SPARSE_BUFFER_SIZE + 3];
+        content[10] = 1;
+        content[3 * SPARSE_BUFFER_SIZE] = 2;
+        fs::write(&source, &content).unwrap();
+
+        let destination = dir.join("copie.bin");
+        copy_sparse(&source, &destination).unwrap();
+        assert_eq!(fs::read(&destination).unwrap(), content);
+        let (copied, original) = (fs::metadata(&destination).unwrap(), fs::metadata(&source).unwrap());
+        assert!(copied.blocks() < original.blocks(), "{} blocs pour {}", copied.blocks(), original.blocks());
+    }
+
+    #[test]
+    fn verify_copies_again_until_the_copy_matches() {
+        let dir = TestDir::new("cp-verify");
+        let source = dir.write("a.txt", "contenu");
+        let destination = dir.join("copie.txt");
+
+        // Première copie abîmée, la seconde est correcte
+        let mut attempts = 0;
+        copy_verified(true, &source, &destination, || {
+            attempts += 1;
+            fs::write(&destination, if attempts == 1 { "abîmé" } else { "contenu" })
+        })
+        .unwrap();
+        assert_eq!(attempts, 2);
+
+        let mut attempts = 0;
+        let error = copy_verified(true, &source, &destination, || {
+            attempts += 1;
+            fs::write(&destination, "abîmé")
+        })
+        .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(attempts, VERIFY_RETRIES + 1);
+
+        // Sans --verify, la copie n'est pas relue
+        let mut attempts = 0;
+        copy_verified(false, &source, &destination, || {
+            attempts += 1;
+            fs::write(&destination, "abîmé")
+        })
+        .unwrap();
+        assert_eq!(attempts, 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn archive_keeps_mode_and_modification_time() {
+        use std::os::unix::fs::PermissionsExt;
+        use std::time::{Duration, SystemTime};
+
+        let dir = TestDir::new("cp-archive");
+        let source = dir.write("script.sh", "#!/bin/sh\n");
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        fs::set_permissions(&source, fs::Permissions::from_mode(0o751)).unwrap();
+        File::options().write(true).open(&source).unwrap().set_modified(modified).unwrap();
+
+        let plain = dir.join("plain.sh");
+        copy(&options(&[]), &source, &plain);
+        assert_ne!(fs::metadata(&plain).unwrap().modified().unwrap(), modified);
+
+        let archived = dir.join("archived.sh");
+        copy(&options(&["-a"]), &source, &archived);
+        let metadata = fs::metadata(&archived).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o7777, 0o751);
+        assert_eq!(metadata.modified().unwrap(), modified);
+
+        // Le dossier copié par -a garde aussi sa date, fixée après ses fichiers.
+        dir.write("src/a.txt", "a");
+        File::open(dir.join("src")).unwrap().set_modified(modified).unwrap();
+        copy(&options(&["-a"]), &dir.join("src"), &dir.join("copie"));
+        assert_eq!(fs::metadata(dir.join("copie")).unwrap().modified().unwrap(), modified);
+        assert_eq!(fs::read_to_string(dir.join("copie/a.txt")).unwrap(), "a");
+    }
+
+    #[test]
+    fn throughput_uses_the_recent_window() {
+        let mut throughput = Throughput::default();
+        assert_eq!((throughput.rate(), throughput.eta(10)), (None, None));
+        throughput.record(Duration::ZERO, 0);
+        assert_eq!(throughput.rate(), None);
+        throughput.record(Duration::from_secs(2), 20_000_000);
+        assert_eq!(throughput.rate(), Some(10_000_000.0));
+        assert_eq!(throughput.eta(30_000_000), Some(Duration::from_secs(3)));
+
+        // Les mesures plus anciennes que la fenêtre sont oubliées : le débit suit le ralentissement.
+        let later = PROGRESS_WINDOW + Duration::from_secs(4);
+        throughput.record(later, 20_000_000);
+        throughput.record(later + Duration::from_secs(1), 20_000_000);
+        assert_eq!(throughput.rate(), Some(0.0));
+        assert_eq!(throughput.eta(1), None);
+    }
+
+    #[test]
+    fn durations_are_rounded_to_the_second() {
+        assert_eq!(format_duration(Duration::ZERO), "0:00");
+        assert_eq!(format_duration(Duration::from_millis(59_600)), "1:00");
+        assert_eq!(format_duration(Duration::from_secs(754)), "12:34");
+        assert_eq!(format_duration(Duration::from_secs(3600 + 2 * 60 + 3)), "1:02:03");
+    }
+
+    #[test]
+    fn final_paths_are_absolute_and_name_the_copied_file() {
+        let dir = TestDir::new("cp-print-paths");
+        let source = dir.write("notes.txt", "n");
+        dir.mkdir("docs");
+        let destination = dir.join("docs/./notes.txt");
+        copy(&options(&["--print-paths"]), &source, &dir.join("docs"));
+
+        let root = path::canonicalize(dir.path()).unwrap();
+        let expected = format!("{}\t{}", root.join("notes.txt").display(), root.join("docs/notes.txt").display());
+        assert_eq!(final_paths(&source, &destination), Ok(expected));
+
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.join("docs"), dir.join("lien")).unwrap();
+            let through_link = final_paths(&dir.join("lien/notes.txt"), &dir.join("lien")).unwrap();
+            assert_eq!(through_link, format!("{}\t{}", root.join("docs/notes.txt").display(), root.join("lien").display()));
+        }
+    }
+
+    #[test]
+    fn dirs_only_recreates_directories_without_files() {
+        let dir = TestDir::new("cp-dirs-only");
+        dir.write("projet/README.md", "r");
+        dir.write("projet/src/main.rs", "m");
+        dir.mkdir("projet/src/bin");
+        dir.mkdir("projet/vide");
+
+        copy(&options(&["-r", "--dirs-only"]), &dir.join("projet"), &dir.join("squelette"));
+        assert!(dir.join("squelette/src/bin").is_dir());
+        assert!(dir.join("squelette/vide").is_dir());
+        assert!(!dir.join("squelette/README.md").exists());
+        assert!(!dir.join("squelette/src/main.rs").exists());
+
+        // Un lien vers un dossier n'est suivi qu'avec -L.
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.join("projet/src"), dir.join("projet/lien")).unwrap();
+            copy(&options(&["-r", "--dirs-only"]), &dir.join("projet"), &dir.join("sans-l"));
+            assert!(fs::symlink_metadata(dir.join("sans-l/lien")).is_err());
+            copy(&options(&["-r", "-L", "--dirs-only"]), &dir.join("projet"), &dir.join("avec-l"));
+            assert!(dir.join("avec-l/lien/bin").is_dir());
+            assert!(!dir.join("avec-l/lien/main.rs").exists());
+        }
+    }
+
+    #[test]
+    fn skip_identical_leaves_matching_destinations_untouched() {
+        let dir = TestDir::new("cp-skip-identical");
+        let source = dir.write("src/a.txt", "meme contenu");
+        dir.write("src/b.txt", "nouveau");
+        let same = dir.write("dest/src/a.txt", "meme contenu");
+        let different = dir.write("dest/src/b.txt", "ancien");
+        let old = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        for path in [&same, &different] {
+            File::options().write(true).open(path).unwrap().set_modified(old).unwrap();
+        }
+
+        let skip = options(&["--skip-identical"]);
+        assert!(is_identical(&skip, &source, &same));
+        assert!(!is_identical(&skip, &source, &different));
+        assert!(!is_identical(&skip, &source, &dir.join("dest/absent.txt")));
+        assert!(!is_identical(&options(&[]), &source, &same));
+
+        copy(&options(&["-r", "--skip-identical"]), &dir.join("src"), &dir.join("dest"));
+        assert_eq!(fs::metadata(&same).unwrap().modified().unwrap(), old);
+        assert_eq!(fs::read_to_string(&different).unwrap(), "nouveau");
+        assert_ne!(fs::metadata(&different).unwrap().modified().unwrap(), old);
+    }
+}
@@ -0,0 +1,103 @@
+//! # Module `date`
+//!
+//! Ce module formate les dates des fichiers (`fs::Metadata::modified`...) sans dépendance
+//! externe. Les dates sont affichées en UTC, au format `AAAA-MM-JJ HH:MM:SS`.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Nombre de secondes dans une journée.
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// # Fonction : `format`
+///
+/// Retourne la date sous la forme `AAAA-MM-JJ HH:MM:SS UTC`.
+/// Une date antérieure à 1970 est affichée comme le 1er janvier 1970.
+///
+/// ## Exemple :
+/// ```rust
+/// let time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+/// assert_eq!(date::format(time), "2023-11-14 22:13:20 UTC");
+/// ```
+pub fn format(time: SystemTime) -> String {
+    let seconds = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let (year, month, day) = civil_from_days(seconds / SECONDS_PER_DAY);
+    let rest = seconds % SECONDS_PER_DAY;
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        rest / 3600,
+        rest % 3600 / 60,
+        rest % 60
+    )
+}
+
+/// # Fonction : `format_day`
+///
+/// Retourne seulement le jour, sous la forme `AAAA-MM-JJ` (en UTC, comme [`format`]).
+///
+/// ## Exemple :
+/// ```rust
+/// let time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+/// assert_eq!(date::format_day(time), "2023-11-14");
+/// ```
+pub fn format_day(time: SystemTime) -> String {
+    let seconds = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let (year, month, day) = civil_from_days(seconds / SECONDS_PER_DAY);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// # Fonction : `civil_from_days`
+///
+/// Convertit un nombre de jours depuis le 1er janvier 1970 en `(année, mois, jour)`
+/// du calendrier grégorien (algorithme de Howard Hinnant, par ères de 400 ans).
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    // Décalage pour faire commencer les ères au 1er mars de l'an 0
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153; // 0 = mars
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn at(seconds: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn dates_are_formatted_in_utc() {
+        assert_eq!(format(at(1_700_000_000)), "2023-11-14 22:13:20 UTC");
+        assert_eq!(format_day(at(1_700_000_000)), "2023-11-14");
+        assert_eq!(format(at(0)), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format(at(86_399)), "1970-01-01 23:59:59 UTC");
+    }
+
+    #[test]
+    fn dates_before_1970_show_the_epoch() {
+        assert_eq!(format(UNIX_EPOCH - Duration::from_secs(3600)), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_day(UNIX_EPOCH - Duration::from_secs(3600)), "1970-01-01");
+    }
+
+    #[test]
+    fn leap_days_and_century_years() {
+        assert_eq!(format_day(at(1_709_164_800)), "2024-02-29");
+        assert_eq!(format_day(at(1_709_251_200)), "2024-03-01");
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(47_541), (2100, 3, 1));
+        assert_eq!(civil_from_days(47_540), (2100, 2, 28));
+    }
+}
@@ -0,0 +1,151 @@
+//! # Module `echo`
+//!
+//! Ce module implémente la commande Unix **`echo`** en Rust.
+//!
+//! Il affiche ses arguments séparés par une espace, suivis d’un retour à la ligne,
+//! avec la prise en charge des options suivantes :
+//!
+//! - `-n` : n’ajoute pas de retour à la ligne final.
+//! - `-e` : interprète les séquences d’échappement (`\n`, `\t`, `\x41`...).
+//! - `-E` : n’interprète pas les séquences d’échappement (par défaut).
+
+use std::io::{self, Write};
+
+use crate::{escape, pipeline};
+
+/// Structure représentant les options de la commande `echo`.
+///
+/// Chaque champ correspond à une option possible de `echo`.
+#[derive(Default, Clone)]
+struct Options {
+    /// N'ajoute pas de retour à la ligne final (`-n`).
+    no_newline: bool,
+    /// Interprète les séquences d'échappement (`-e`).
+    interpret_escapes: bool,
+}
+
+/// # Fonction : `echo`
+///
+/// Construit le texte affiché par **`echo`** à partir des options et des mots.
+///
+/// ## Exemple :
+/// ```rust
+/// let options = Options { interpret_escapes: true, ..Default::default() };
+/// assert_eq!(echo(&options, &["a\\tb", "c"]), "a\tb c\n");
+/// ```
+fn echo(options: &Options, words: &[&str]) -> String {
+    let mut text = words.join(" ");
+    if options.interpret_escapes {
+        text = escape::unescape(&text);
+    }
+    if !options.no_newline {
+        text.push('\n');
+    }
+    text
+}
+
+/// # Fonction : `parse_arguments`
+///
+/// Lit les options en tête (`-n`, `-e`, `-E`, éventuellement combinées : `-ne`).
+/// Comme le `echo` du shell, le premier argument qui n’est pas une option valide
+/// (ex. `-x` ou `texte`) et tous les suivants sont affichés tels quels.
+///
+/// Retourne les options et les mots à afficher.
+fn parse_arguments(args: &[String]) -> (Options, Vec<&str>) {
+    let mut options = Options::default();
+    let mut start = 0;
+
+    for arg in args {
+        let Some(flags) = arg.strip_prefix('-') else {
+            break;
+        };
+        if flags.is_empty() || !flags.chars().all(|c| matches!(c, 'n' | 'e' | 'E')) {
+            break;
+        }
+        for flag in flags.chars() {
+            match flag {
+                'n' => options.no_newline = true,
+                'e' => options.interpret_escapes = true,
+                _ => options.interpret_escapes = false,
+            }
+        }
+        start += 1;
+    }
+
+    let words = args[start..].iter().map(|s| s.as_str()).collect();
+    (options, words)
+}
+
+/// # Fonction : `handle_echo`
+///
+/// Gère la commande **`echo`** en ligne de commande : analyse les arguments
+/// avec [`parse_arguments`] puis affiche le texte construit par [`echo`].
+pub fn handle_echo(args: &[String]) {
+    let (options, words) = parse_arguments(args);
+    print!("{}", echo(&options, &words));
+    io::stdout().flush().unwrap();
+}
+
+/// Commande `echo` utilisable dans un pipeline (`echo texte | wc -w`).
+///
+/// L'entrée éventuelle est ignorée, comme avec le `echo` du shell.
+pub struct EchoCommand {
+    args: Vec<String>,
+}
+
+impl EchoCommand {
+    pub fn new(args: &[String]) -> Self {
+        EchoCommand { args: args.to_vec() }
+    }
+}
+
+impl pipeline::Command for EchoCommand {
+    fn run(&self, _input: Option<&[u8]>, _terminal: bool) -> io::Result<Vec<u8>> {
+        let (options, words) = parse_arguments(&self.args);
+        Ok(echo(&options, &words).into_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::Command;
+
+    fn args(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    fn run(words: &[&str]) -> String {
+        let args = args(words);
+        let (options, words) = parse_arguments(&args);
+        echo(&options, &words)
+    }
+
+    #[test]
+    fn words_are_joined_with_a_final_newline() {
+        assert_eq!(run(&["a  b", "c"]), "a  b c\n");
+        assert_eq!(run(&[]), "\n");
+        assert_eq!(run(&["-n", "sans", "fin"]), "sans fin");
+    }
+
+    #[test]
+    fn escapes_are_interpreted_with_e_only() {
+        assert_eq!(run(&["-e", "a\\tb\\nc"]), "a\tb\nc\n");
+        assert_eq!(run(&["a\\tb"]), "a\\tb\n");
+        assert_eq!(run(&["-ne", "x\\x41"]), "xA");
+        assert_eq!(run(&["-e", "-E", "a\\n"]), "a\\n\n");
+    }
+
+    #[test]
+    fn first_non_option_stops_option_parsing() {
+        assert_eq!(run(&["-x", "-n"]), "-x -n\n");
+        assert_eq!(run(&["texte", "-n"]), "texte -n\n");
+        assert_eq!(run(&["-", "a"]), "- a\n");
+    }
+
+    #[test]
+    fn pipeline_command_ignores_its_input() {
+        let command = EchoCommand::new(&args(&["-n", "b"]));
+        assert_eq!(command.run(Some(b"entree"), false).unwrap(), b"b");
+    }
+}
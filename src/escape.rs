@@ -0,0 +1,73 @@
+//! # Module `escape`
+//!
+//! Ce module interprète les séquences d'échappement (`\n`, `\t`, `\x41`...) saisies
+//! par l'utilisateur. Il est partagé par `echo -e` et les options de `cat` et de `wc`.
+
+/// Interprète les séquences d’échappement dans une chaîne.
+///
+/// # Algorithme
+/// - Parcours de la chaîne caractère par caractère.
+/// - Si `\`, détermine la séquence (`n`, `t`, `r`, `xFF`, etc...).
+/// - Remplace par le caractère correspondant.
+///
+/// # Arguments
+/// * `input` - Chaîne avec séquences d’échappement.
+///
+/// # Retour
+/// Chaîne transformée.
+///
+/// # Exemple
+/// ```rust
+/// let text = escape::unescape("Hello\\nWorld");
+/// assert_eq!(text, "Hello\nWorld");
+/// // Affiche :
+/// // Hello
+/// // World
+/// ```
+pub fn unescape(input: &str) -> String {
+    let mut output = String::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.peek() {
+                Some('n') => { output.push('\n'); chars.next(); }
+                Some('t') => { output.push('\t'); chars.next(); }
+                Some('r') => { output.push('\r'); chars.next(); }
+                Some('v') => { output.push('\u{000B}'); chars.next(); }
+                Some('a') => { output.push('\u{0007}'); chars.next(); }
+                Some('x') => {
+                    chars.next();
+                    let hex: String = chars.by_ref().take(2).collect();
+                    if let Ok(val) = u8::from_str_radix(&hex, 16) {
+                        output.push(val as char);
+                    }
+                }
+                Some('\\') => { output.push('\\'); chars.next(); }
+                _ => output.push(c),
+            }
+        } else {
+            output.push(c);
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_sequences_are_replaced() {
+        assert_eq!(unescape("a\\nb\\tc\\rd\\ve\\af"), "a\nb\tc\rd\u{b}e\u{7}f");
+        assert_eq!(unescape("\\x41\\x7a"), "Az");
+        assert_eq!(unescape("C:\\\\dossier"), "C:\\dossier");
+    }
+
+    #[test]
+    fn unknown_or_incomplete_sequences_are_kept() {
+        assert_eq!(unescape("\\q"), "\\q");
+        assert_eq!(unescape("fin\\"), "fin\\");
+    }
+}
@@ -0,0 +1,190 @@
+//! # Module `ext`
+//!
+//! Ce module lance des commandes externes (scripts, programmes de l'utilisateur)
+//! depuis le menu principal ou la ligne de commande, pour étendre l'utilitaire
+//! sans le modifier.
+//!
+//! Pour ne jamais exécuter une saisie par erreur, une commande externe doit être
+//! demandée explicitement avec le préfixe [`PREFIX`] (`ext:nom arguments...`).
+//! Le programme est cherché :
+//!
+//! 1. dans le dossier de plugins indiqué par la variable [`PLUGIN_DIR_VAR`], s'il est défini ;
+//! 2. puis dans les dossiers de `PATH`.
+//!
+//! Le nom ne peut pas contenir de chemin (`/`) : seuls les exécutables de ces dossiers
+//! peuvent être lancés.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+
+use crate::interrupt;
+
+/// Préfixe qui désigne une commande externe (ex. `ext:sauvegarde --dry-run`).
+pub const PREFIX: &str = "ext:";
+
+/// Variable d'environnement indiquant le dossier des plugins, consulté avant `PATH`.
+pub const PLUGIN_DIR_VAR: &str = "UTILS_PLUGIN_DIR";
+
+/// # Fonction : `find`
+///
+/// Cherche l'exécutable `name` dans le dossier de plugins puis dans `PATH`.
+///
+/// Retourne `None` si le nom est vide, contient un séparateur de chemin, ou
+/// ne correspond à aucun fichier exécutable.
+///
+/// ## Exemple :
+/// ```rust
+/// // PATH=/usr/local/bin:/usr/bin
+/// assert_eq!(ext::find("true"), Some(PathBuf::from("/usr/bin/true")));
+/// assert_eq!(ext::find("../bin/true"), None);
+/// ```
+pub fn find(name: &str) -> Option<PathBuf> {
+    let plugin_dir = env::var_os(PLUGIN_DIR_VAR).filter(|dir| !dir.is_empty());
+    let path = env::var_os("PATH").unwrap_or_default();
+    let dirs = plugin_dir
+        .into_iter()
+        .map(PathBuf::from)
+        .chain(env::split_paths(&path));
+
+    find_in(name, dirs)
+}
+
+/// Cherche l'exécutable `name` dans `dirs`, dans l'ordre (voir [`find`]).
+fn find_in(name: &str, mut dirs: impl Iterator<Item = PathBuf>) -> Option<PathBuf> {
+    if name.is_empty() || name.contains(['/', '\\']) || name == "." || name == ".." {
+        return None;
+    }
+
+    dirs.find_map(|dir| Some(dir.join(name)).filter(|candidate| is_executable(candidate)))
+}
+
+/// Indique si le chemin est un fichier exécutable (droit `x` sous Unix).
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::metadata(path).is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+/// Hors Unix, tout fichier trouvé est considéré comme exécutable.
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|metadata| metadata.is_file())
+}
+
+/// # Fonction : `run`
+///
+/// Lance la commande externe `name` avec ses arguments, en lui laissant le terminal
+/// (entrée, sortie et erreurs), et attend qu'elle se termine.
+///
+/// Pendant son exécution, Ctrl+C n'arrête que la commande externe et ramène au
+/// menu (voir [`interrupt::guard`]).
+///
+/// Retourne une erreur `NotFound` si la commande est introuvable (voir [`find`]).
+///
+/// ## Exemple :
+/// ```rust
+/// let status = ext::run("echo", &["bonjour".into()])?;
+/// assert!(status.success());
+/// ```
+pub fn run(name: &str, args: &[String]) -> io::Result<ExitStatus> {
+    let Some(program) = find(name) else {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("commande externe '{}' introuvable dans {} ni dans PATH", name, PLUGIN_DIR_VAR),
+        ));
+    };
+
+    let _guard = interrupt::guard();
+    Command::new(program).args(args).status()
+}
+
+/// # Fonction : `run_line`
+///
+/// Exécute une saisie `ext:nom arguments...` du menu principal (le préfixe déjà retiré)
+/// et affiche le résultat : rien si la commande réussit, son code de sortie sinon.
+///
+/// Retourne `false` si la commande n'a pas pu être lancée (nom manquant ou introuvable).
+pub fn run_line(name: &str, args: &[String]) -> bool {
+    if name.is_empty() {
+        eprintln!("Erreur : nom de commande externe manquant (ex. {}sauvegarde --dry-run)", PREFIX);
+        return false;
+    }
+
+    match run(name, args) {
+        Ok(status) if status.success() => true,
+        Ok(status) => {
+            match status.code() {
+                Some(code) => eprintln!("⚠️  La commande externe '{}' s'est terminée avec le code {}", name, code),
+                None => eprintln!("⚠️  La commande externe '{}' a été interrompue", name),
+            }
+            true
+        }
+        Err(e) => {
+            eprintln!("Erreur : {}", e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdir::TestDir;
+
+    /// Écrit un script dans `dir`, exécutable ou non.
+    #[cfg_attr(not(unix), allow(unused_variables))]
+    fn script(dir: &TestDir, name: &str, executable: bool) -> PathBuf {
+        let path = dir.write(name, "#!/bin/sh\n");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = if executable { 0o755 } else { 0o644 };
+            fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+        }
+        path
+    }
+
+    #[test]
+    fn plugin_dir_comes_before_path() {
+        let plugins = TestDir::new("ext-plugins");
+        let bin = TestDir::new("ext-bin");
+        let plugin = script(&plugins, "sauvegarde", true);
+        script(&bin, "sauvegarde", true);
+        let other = script(&bin, "autre", true);
+
+        let dirs = || [plugins.path().to_path_buf(), bin.path().to_path_buf()].into_iter();
+        assert_eq!(find_in("sauvegarde", dirs()), Some(plugin));
+        assert_eq!(find_in("autre", dirs()), Some(other));
+        assert_eq!(find_in("absent", dirs()), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn files_without_execute_permission_are_skipped() {
+        let first = TestDir::new("ext-noexec");
+        let second = TestDir::new("ext-exec");
+        script(&first, "outil", false);
+        let executable = script(&second, "outil", true);
+        first.mkdir("dossier");
+
+        let dirs = || [first.path().to_path_buf(), second.path().to_path_buf()].into_iter();
+        assert_eq!(find_in("outil", dirs()), Some(executable));
+        assert_eq!(find_in("dossier", dirs()), None);
+    }
+
+    #[test]
+    fn names_with_a_path_are_refused() {
+        let bin = TestDir::new("ext-names");
+        script(&bin, "outil", true);
+        let dirs = || std::iter::once(bin.path().to_path_buf());
+
+        for name in ["", ".", "..", "./outil", "../outil", "sous/outil", "a\\b"] {
+            assert_eq!(find_in(name, dirs()), None, "{name}");
+        }
+        assert_eq!(run("../outil", &[]).unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
+}
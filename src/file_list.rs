@@ -0,0 +1,303 @@
+//! # Module `file_list`
+//!
+//! Ce module rassemble la liste des fichiers à traiter par `cat`, `wc` et `head`,
+//! qui peuvent venir de plusieurs sources :
+//!
+//! - les noms donnés en arguments ;
+//! - un fichier listant des chemins séparés par `\0` (`--files0-from F`), comme la
+//!   sortie de `find -print0` ;
+//! - un fichier listant un chemin par ligne (`--files-from F`) ;
+//! - l'entrée standard : `-` à la place de `F`, ou `head --args-from-stdin`.
+//!
+//! Toutes les listes sont lues de la même façon : les entrées vides ou blanches sont
+//! ignorées et une fin de ligne `\r\n` est acceptée. Les sources s'ajoutent dans
+//! l'ordre ci-dessus.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+use crate::path;
+
+/// Séparateur des chemins d'une liste.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Separator {
+    /// Un chemin par ligne (`--files-from`).
+    Line,
+    /// Chemins séparés par `\0` (`--files0-from`, `head -0`).
+    Null,
+}
+
+impl Separator {
+    /// Octet qui sépare deux chemins.
+    fn byte(self) -> u8 {
+        match self {
+            Separator::Line => b'\n',
+            Separator::Null => b'\0',
+        }
+    }
+}
+
+/// Listes de fichiers à ajouter aux noms donnés en arguments.
+#[derive(Default, Clone, PartialEq)]
+pub struct Sources {
+    /// Fichier listant un chemin par ligne (`--files-from F`, `-` pour l'entrée standard).
+    pub files_from: Option<String>,
+    /// Fichier listant des chemins séparés par `\0` (`--files0-from F`, `-` pour l'entrée standard).
+    pub files0_from: Option<String>,
+    /// Liste lue sur l'entrée standard, avec ce séparateur (`head --args-from-stdin`, `-0`).
+    pub stdin: Option<Separator>,
+}
+
+impl Sources {
+    /// Indique qu'aucune liste n'a été demandée.
+    pub fn is_empty(&self) -> bool {
+        self.files_from.is_none() && self.files0_from.is_none() && self.stdin.is_none()
+    }
+
+    /// Indique qu'une des listes est lue sur l'entrée standard, qui ne peut donc
+    /// plus servir de texte à traiter.
+    pub fn reads_stdin(&self) -> bool {
+        self.stdin.is_some() || self.files_from.as_deref() == Some("-") || self.files0_from.as_deref() == Some("-")
+    }
+}
+
+/// # Fonction : `parse_option`
+///
+/// Reconnaît les options `--files-from F` et `--files0-from F` (ou `=F`), communes
+/// aux commandes qui acceptent une liste de fichiers. Sans `=`, la valeur est
+/// l'argument suivant, obtenu avec `next`.
+///
+/// Retourne `Ok(true)` si `arg` est l'une de ces options, `Ok(false)` sinon, et
+/// le nom de l'option si sa valeur manque (chaque commande affiche alors son message).
+///
+/// ## Exemple :
+/// ```rust
+/// let mut sources = file_list::Sources::default();
+/// assert_eq!(file_list::parse_option(&mut sources, "--files0-from=liste", || None), Ok(true));
+/// assert_eq!(file_list::parse_option(&mut sources, "-n", || None), Ok(false));
+/// assert_eq!(file_list::parse_option(&mut sources, "--files-from", || None), Err("--files-from"));
+/// ```
+pub fn parse_option<'a>(
+    sources: &mut Sources,
+    arg: &str,
+    next: impl FnOnce() -> Option<&'a str>,
+) -> Result<bool, &'static str> {
+    let (option, target) = if arg == "--files-from" || arg.starts_with("--files-from=") {
+        ("--files-from", &mut sources.files_from)
+    } else if arg == "--files0-from" || arg.starts_with("--files0-from=") {
+        ("--files0-from", &mut sources.files0_from)
+    } else {
+        return Ok(false);
+    };
+
+    let value = match arg.strip_prefix(option).and_then(|rest| rest.strip_prefix('=')) {
+        Some(value) => Some(value),
+        None => next(),
+    };
+    match value {
+        Some(value) if !value.is_empty() => {
+            *target = Some(value.to_string());
+            Ok(true)
+        }
+        _ => Err(option),
+    }
+}
+
+/// # Fonction : `parse`
+///
+/// Découpe une liste selon `separator`, en ignorant les entrées vides ou blanches
+/// et un `\r` final.
+///
+/// ## Exemple :
+/// ```rust
+/// let list = file_list::parse(b"a.txt\n\n  \nb.txt\r\n", file_list::Separator::Line);
+/// assert_eq!(list, [PathBuf::from("a.txt"), PathBuf::from("b.txt")]);
+/// ```
+pub fn parse(bytes: &[u8], separator: Separator) -> Vec<PathBuf> {
+    bytes
+        .split(|&byte| byte == separator.byte())
+        .map(|entry| entry.strip_suffix(b"\r").unwrap_or(entry))
+        .filter(|entry| !entry.iter().all(u8::is_ascii_whitespace))
+        .map(path_from_bytes)
+        .collect()
+}
+
+/// Convertit un nom lu dans une liste en chemin. Sous Unix, les octets sont gardés
+/// tels quels, même s'ils ne sont pas de l'UTF-8 valide.
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    PathBuf::from(OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// # Fonction : `read_source`
+///
+/// Lit une liste depuis le fichier `source`, ou depuis l'entrée standard si `source`
+/// vaut `-` : le texte reçu d'un pipeline (`input`) s'il y en a un, sinon le flux
+/// standard du programme.
+///
+/// Une erreur de lecture indique le nom de la liste fautive.
+pub fn read_source(source: &str, separator: Separator, input: Option<&[u8]>) -> io::Result<Vec<PathBuf>> {
+    if source == "-" {
+        return read_stdin(separator, input);
+    }
+
+    let mut bytes = Vec::new();
+    path::resolve(source)
+        .and_then(File::open)
+        .and_then(|mut file| file.read_to_end(&mut bytes))
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", source, e)))?;
+    Ok(parse(&bytes, separator))
+}
+
+/// Lit une liste sur l'entrée standard (voir [`read_source`]).
+fn read_stdin(separator: Separator, input: Option<&[u8]>) -> io::Result<Vec<PathBuf>> {
+    match input {
+        Some(input) => Ok(parse(input, separator)),
+        None => {
+            let mut bytes = Vec::new();
+            io::stdin().lock().read_to_end(&mut bytes)?;
+            Ok(parse(&bytes, separator))
+        }
+    }
+}
+
+/// # Fonction : `collect`
+///
+/// Retourne tous les fichiers à traiter : les noms donnés en arguments (`operands`),
+/// puis ceux de `--files0-from`, de `--files-from` et enfin de l'entrée standard
+/// (voir [`Sources`]).
+///
+/// ## Exemple :
+/// ```rust
+/// // liste.txt contient "b.txt\nc.txt\n"
+/// let sources = file_list::Sources { files_from: Some("liste.txt".into()), ..Default::default() };
+/// let files = file_list::collect(&["a.txt"], &sources, None)?;
+/// assert_eq!(files, [PathBuf::from("a.txt"), PathBuf::from("b.txt"), PathBuf::from("c.txt")]);
+/// ```
+pub fn collect(operands: &[impl AsRef<str>], sources: &Sources, input: Option<&[u8]>) -> io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = operands.iter().map(|operand| PathBuf::from(operand.as_ref())).collect();
+
+    if let Some(source) = &sources.files0_from {
+        files.extend(read_source(source, Separator::Null, input)?);
+    }
+    if let Some(source) = &sources.files_from {
+        files.extend(read_source(source, Separator::Line, input)?);
+    }
+    if let Some(separator) = sources.stdin {
+        files.extend(read_stdin(separator, input)?);
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdir::TestDir;
+
+    fn paths(names: &[&str]) -> Vec<PathBuf> {
+        names.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn options_take_the_next_argument_or_an_equal_sign() {
+        let mut sources = Sources::default();
+        assert_eq!(parse_option(&mut sources, "--files-from", || Some("liste.txt")), Ok(true));
+        assert_eq!(parse_option(&mut sources, "--files0-from=-", || None), Ok(true));
+        assert_eq!(sources.files_from.as_deref(), Some("liste.txt"));
+        assert_eq!(sources.files0_from.as_deref(), Some("-"));
+
+        assert_eq!(parse_option(&mut sources, "--files", || None), Ok(false));
+        assert_eq!(parse_option(&mut sources, "--files-from=", || Some("ignoré")), Err("--files-from"));
+        assert_eq!(parse_option(&mut sources, "--files0-from", || None), Err("--files0-from"));
+    }
+
+    #[test]
+    fn lists_skip_blank_entries() {
+        assert_eq!(parse(b"a.txt\n\n  \nb c.txt\r\n", Separator::Line), paths(&["a.txt", "b c.txt"]));
+        assert_eq!(parse(b"a\nb.txt\0\0c.txt\0", Separator::Null), paths(&["a\nb.txt", "c.txt"]));
+        assert!(parse(b"", Separator::Line).is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn names_keep_their_bytes() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let list = parse(b"caf\xe9.txt\n", Separator::Line);
+        assert_eq!(list[0].as_os_str().as_bytes(), b"caf\xe9.txt");
+    }
+
+    #[test]
+    fn each_source_is_read() {
+        let dir = TestDir::new("file-list-sources");
+        let lines = dir.write("lignes.txt", "b.txt\nc.txt\n");
+        let nulls = dir.write("nuls.bin", "d.txt\0e.txt\0");
+        let lines = lines.to_str().unwrap();
+        let nulls = nulls.to_str().unwrap();
+        let none: [&str; 0] = [];
+
+        let sources = Sources { files_from: Some(lines.to_string()), ..Default::default() };
+        assert_eq!(collect(&none, &sources, None).unwrap(), paths(&["b.txt", "c.txt"]));
+
+        let sources = Sources { files0_from: Some(nulls.to_string()), ..Default::default() };
+        assert_eq!(collect(&none, &sources, None).unwrap(), paths(&["d.txt", "e.txt"]));
+
+        let sources = Sources { files_from: Some("-".to_string()), ..Default::default() };
+        assert_eq!(collect(&none, &sources, Some(b"f.txt\n")).unwrap(), paths(&["f.txt"]));
+
+        let sources = Sources { stdin: Some(Separator::Null), ..Default::default() };
+        assert_eq!(collect(&none, &sources, Some(b"g.txt\0")).unwrap(), paths(&["g.txt"]));
+
+        assert_eq!(collect(&["a.txt"], &Sources::default(), None).unwrap(), paths(&["a.txt"]));
+    }
+
+    #[test]
+    fn sources_are_combined_in_order() {
+        let dir = TestDir::new("file-list-combined");
+        let lines = dir.write("lignes.txt", "b.txt\n");
+        let nulls = dir.write("nuls.bin", "c.txt\0");
+        let sources = Sources {
+            files_from: Some(lines.to_str().unwrap().to_string()),
+            files0_from: Some(nulls.to_str().unwrap().to_string()),
+            stdin: Some(Separator::Line),
+        };
+
+        let files = collect(&["a.txt"], &sources, Some(b"d.txt\n")).unwrap();
+        assert_eq!(files, paths(&["a.txt", "c.txt", "b.txt", "d.txt"]));
+    }
+
+    #[test]
+    fn unreadable_lists_are_named_in_the_error() {
+        let sources = Sources { files_from: Some("liste_absente.txt".to_string()), ..Default::default() };
+        let error = collect(&["a.txt"], &sources, None).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        assert!(error.to_string().starts_with("liste_absente.txt: "), "{error}");
+    }
+
+    #[test]
+    fn stdin_is_reserved_by_dash_or_stdin_lists() {
+        assert!(Sources::default().is_empty());
+        assert!(!Sources::default().reads_stdin());
+
+        let from_file = Sources { files_from: Some("liste.txt".to_string()), ..Default::default() };
+        assert!(!from_file.is_empty() && !from_file.reads_stdin());
+
+        for sources in [
+            Sources { files_from: Some("-".to_string()), ..Default::default() },
+            Sources { files0_from: Some("-".to_string()), ..Default::default() },
+            Sources { stdin: Some(Separator::Line), ..Default::default() },
+        ] {
+            assert!(sources.reads_stdin());
+        }
+    }
+}
@@ -0,0 +1,352 @@
+//! # Module `git`
+//!
+//! Ce module lit l'état d'un dépôt git sans lancer la commande `git`,
+//! pour afficher un indicateur de statut à côté des fichiers (`ls --git`).
+//!
+//! Il se limite au strict nécessaire :
+//!
+//! - l'index (`.git/index`, versions 2 et 3) fournit la liste des fichiers suivis,
+//!   avec leur taille et leur date de modification au moment du dernier `git add` ;
+//! - le fichier `.gitignore` à la racine du dépôt est lu avec [`glob::IgnoreList`].
+//!
+//! Un fichier suivi est considéré comme modifié si sa taille ou sa date de modification
+//! diffère de celle enregistrée dans l'index (le contenu n'est pas relu). Comme le
+//! raccourci utilisé par git lui-même, cela peut signaler un fichier dont seule la date a changé.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::glob;
+
+/// Taille de l'en-tête de l'index : signature `DIRC`, version et nombre d'entrées.
+const INDEX_HEADER_SIZE: usize = 12;
+/// Taille fixe d'une entrée de l'index, avant son chemin.
+const ENTRY_FIXED_SIZE: usize = 62;
+
+/// Statut d'une entrée par rapport au dépôt.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Status {
+    /// Suivie et identique à l'index.
+    Clean,
+    /// Suivie mais modifiée (ou, pour un dossier, contenant un fichier modifié).
+    Modified,
+    /// Ni suivie ni exclue par `.gitignore`.
+    Untracked,
+    /// Exclue par `.gitignore`.
+    Ignored,
+}
+
+impl Status {
+    /// Retourne la lettre affichée, comme `git status --short` : `M`, `?`, `!` ou une espace.
+    pub fn letter(self) -> char {
+        match self {
+            Status::Clean => ' ',
+            Status::Modified => 'M',
+            Status::Untracked => '?',
+            Status::Ignored => '!',
+        }
+    }
+}
+
+/// Informations d'un fichier suivi, telles qu'enregistrées dans l'index.
+struct IndexEntry {
+    /// Date de modification, en secondes depuis 1970 (tronquée à 32 bits par git).
+    mtime: u32,
+    /// Taille en octets (tronquée à 32 bits par git).
+    size: u32,
+}
+
+/// Dépôt git ouvert avec [`Repository::discover`].
+pub struct Repository {
+    /// Dossier contenant `.git`.
+    root: PathBuf,
+    /// Fichiers suivis, indexés par leur chemin relatif à la racine (séparateurs `/`).
+    entries: HashMap<String, IndexEntry>,
+    /// Motifs du `.gitignore` de la racine.
+    ignore: glob::IgnoreList,
+}
+
+impl Repository {
+    /// # Fonction : `discover`
+    ///
+    /// Cherche le dépôt contenant `dir` en remontant ses dossiers parents
+    /// jusqu'à trouver un dossier `.git`.
+    ///
+    /// ## Retour
+    /// - `Ok(None)` si `dir` n'est dans aucun dépôt.
+    /// - Une erreur si l'index existe mais ne peut pas être lu (ex. version 4, non prise en charge).
+    ///
+    /// Un dépôt sans index (aucun `git add`) est accepté : tout y est alors non suivi.
+    pub fn discover(dir: &Path) -> io::Result<Option<Repository>> {
+        let dir = fs::canonicalize(dir)?;
+        let Some(root) = dir.ancestors().find(|ancestor| ancestor.join(".git").is_dir()) else {
+            return Ok(None);
+        };
+
+        let entries = match fs::read(root.join(".git").join("index")) {
+            Ok(bytes) => parse_index(&bytes)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e),
+        };
+        let ignore = fs::read_to_string(root.join(".gitignore"))
+            .map(|content| glob::IgnoreList::parse(&content))
+            .unwrap_or_default();
+
+        Ok(Some(Repository { root: root.to_path_buf(), entries, ignore }))
+    }
+
+    /// # Fonction : `status`
+    ///
+    /// Retourne le statut d'une entrée du dépôt.
+    ///
+    /// ## Fonctionnement :
+    /// - Un fichier suivi est comparé à l'index (taille et date de modification).
+    /// - Un dossier est modifié si l'un des fichiers suivis qu'il contient l'est,
+    ///   propre s'il contient des fichiers suivis, et non suivi sinon.
+    /// - Une entrée non suivie est exclue si elle (ou l'un de ses dossiers parents)
+    ///   correspond au `.gitignore`.
+    ///
+    /// Le chemin doit être absolu et sans lien symbolique dans ses dossiers parents
+    /// (ex. le dossier listé passé par `fs::canonicalize`, suivi du nom de l'entrée).
+    pub fn status(&self, path: &Path) -> Status {
+        let Some(relative) = self.relative(path) else {
+            return Status::Untracked;
+        };
+        if relative == ".git" {
+            return Status::Ignored;
+        }
+
+        let metadata = fs::symlink_metadata(path);
+        let is_dir = metadata.as_ref().is_ok_and(|metadata| metadata.is_dir());
+
+        if !is_dir && let Some(entry) = self.entries.get(&relative) {
+            return match metadata {
+                Ok(metadata) if !is_modified(entry, &metadata) => Status::Clean,
+                _ => Status::Modified,
+            };
+        }
+
+        if is_dir {
+            let prefix = format!("{}/", relative);
+            let mut tracked = self
+                .entries
+                .keys()
+                .filter(|name| name.starts_with(&prefix))
+                .peekable();
+            if tracked.peek().is_some() {
+                // Un fichier suivi supprimé compte comme une modification
+                let modified = tracked.any(|name| match fs::symlink_metadata(self.root.join(name)) {
+                    Ok(metadata) => is_modified(&self.entries[name], &metadata),
+                    Err(_) => true,
+                });
+                return if modified { Status::Modified } else { Status::Clean };
+            }
+        }
+
+        if self.is_ignored(&relative, is_dir) {
+            Status::Ignored
+        } else {
+            Status::Untracked
+        }
+    }
+
+    /// Retourne le chemin relatif à la racine du dépôt, avec des `/` comme séparateurs.
+    fn relative(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        let parts: Vec<String> = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        Some(parts.join("/"))
+    }
+
+    /// Indique si une entrée ou l'un de ses dossiers parents est exclu par le `.gitignore`.
+    fn is_ignored(&self, relative: &str, is_dir: bool) -> bool {
+        let mut parent = String::new();
+        for part in relative.split('/') {
+            if !parent.is_empty() {
+                if self.ignore.is_ignored(&parent, true) {
+                    return true;
+                }
+                parent.push('/');
+            }
+            parent.push_str(part);
+        }
+        self.ignore.is_ignored(relative, is_dir)
+    }
+}
+
+/// Compare un fichier à son entrée de l'index : taille et date de modification (en secondes).
+fn is_modified(entry: &IndexEntry, metadata: &fs::Metadata) -> bool {
+    let mtime = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs() as u32);
+    metadata.len() as u32 != entry.size || mtime != Some(entry.mtime)
+}
+
+/// # Fonction : `parse_index`
+///
+/// Lit les entrées d'un fichier `.git/index` (versions 2 et 3).
+///
+/// ## Format :
+/// - En-tête : `DIRC`, version et nombre d'entrées (entiers 32 bits big-endian).
+/// - Chaque entrée : 62 octets fixes (dates, mode, taille, empreinte SHA-1, drapeaux),
+///   2 octets de drapeaux supplémentaires en version 3 si le bit 0x4000 est levé,
+///   puis le chemin terminé par des octets nuls jusqu'à un multiple de 8 octets.
+///
+/// Les extensions qui suivent les entrées sont ignorées.
+fn parse_index(bytes: &[u8]) -> io::Result<HashMap<String, IndexEntry>> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, format!("index git invalide : {}", message));
+    let read_u32 = |offset: usize| -> Option<u32> {
+        let field = bytes.get(offset..offset + 4)?;
+        Some(u32::from_be_bytes([field[0], field[1], field[2], field[3]]))
+    };
+
+    if bytes.len() < INDEX_HEADER_SIZE || &bytes[..4] != b"DIRC" {
+        return Err(invalid("signature DIRC absente"));
+    }
+    let version = read_u32(4).unwrap_or_default();
+    if version != 2 && version != 3 {
+        return Err(invalid(&format!("version {} non prise en charge", version)));
+    }
+    let count = read_u32(8).unwrap_or_default();
+
+    let mut entries = HashMap::new();
+    let mut offset = INDEX_HEADER_SIZE;
+    for _ in 0..count {
+        let truncated = || invalid("entrée tronquée");
+        let mtime = read_u32(offset + 8).ok_or_else(truncated)?;
+        let size = read_u32(offset + 36).ok_or_else(truncated)?;
+        let flags = bytes.get(offset + 60..offset + 62).ok_or_else(truncated)?;
+        let flags = u16::from_be_bytes([flags[0], flags[1]]);
+
+        let mut header_size = ENTRY_FIXED_SIZE;
+        if version == 3 && flags & 0x4000 != 0 {
+            header_size += 2;
+        }
+
+        let name_start = offset + header_size;
+        let name_len = bytes
+            .get(name_start..)
+            .and_then(|rest| rest.iter().position(|&b| b == 0))
+            .ok_or_else(truncated)?;
+        let name = String::from_utf8_lossy(&bytes[name_start..name_start + name_len]).into_owned();
+
+        // Au moins un octet nul, puis remplissage jusqu'à un multiple de 8
+        let entry_size = (header_size + name_len + 8) & !7;
+        offset += entry_size;
+
+        entries.insert(name, IndexEntry { mtime, size });
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdir::TestDir;
+    use std::time::Duration;
+
+    const MTIME: u32 = 1_700_000_000;
+
+    /// Construit un index `version` contenant `files` (chemin, taille), tous datés de `MTIME`.
+    /// En version 3, chaque entrée porte les 2 octets de drapeaux supplémentaires.
+    fn index(version: u32, files: &[(&str, u32)]) -> Vec<u8> {
+        let mut bytes = b"DIRC".to_vec();
+        bytes.extend(version.to_be_bytes());
+        bytes.extend((files.len() as u32).to_be_bytes());
+
+        for (name, size) in files {
+            let mut entry = vec![0u8; ENTRY_FIXED_SIZE];
+            entry[8..12].copy_from_slice(&MTIME.to_be_bytes());
+            entry[36..40].copy_from_slice(&size.to_be_bytes());
+            let mut flags = name.len() as u16;
+            if version == 3 {
+                flags |= 0x4000;
+                entry.extend([0, 0]);
+            }
+            entry[60..62].copy_from_slice(&flags.to_be_bytes());
+            entry.extend(name.as_bytes());
+            entry.resize((entry.len() + 8) & !7, 0);
+            bytes.extend(entry);
+        }
+        bytes
+    }
+
+    /// Écrit un fichier daté de `MTIME`, comme s'il venait d'être ajouté à l'index.
+    fn tracked(dir: &TestDir, name: &str, content: &str) {
+        let path = dir.write(name, content);
+        let modified = UNIX_EPOCH + Duration::from_secs(u64::from(MTIME));
+        fs::File::options().write(true).open(path).unwrap().set_modified(modified).unwrap();
+    }
+
+    fn letter(repository: &Repository, dir: &TestDir, name: &str) -> char {
+        let root = fs::canonicalize(dir.path()).unwrap();
+        repository.status(&root.join(name)).letter()
+    }
+
+    #[test]
+    fn index_entries_are_read_in_versions_2_and_3() {
+        for version in [2, 3] {
+            let entries = parse_index(&index(version, &[("a.txt", 3), ("src/long_nom_de_fichier.rs", 12)])).unwrap();
+            assert_eq!(entries.len(), 2);
+            let entry = &entries["src/long_nom_de_fichier.rs"];
+            assert_eq!((entry.mtime, entry.size), (MTIME, 12));
+            assert_eq!(entries["a.txt"].size, 3);
+        }
+    }
+
+    #[test]
+    fn unreadable_indexes_are_rejected() {
+        assert!(parse_index(b"PACK\0\0\0\x02\0\0\0\0").is_err());
+        assert!(parse_index(&index(4, &[])).is_err());
+
+        let mut truncated = index(2, &[("a.txt", 3)]);
+        truncated.truncate(INDEX_HEADER_SIZE + 20);
+        assert!(parse_index(&truncated).is_err());
+    }
+
+    #[test]
+    fn statuses_match_git_status_short() {
+        let dir = TestDir::new("git-status");
+        dir.write(".git/index", index(2, &[("propre.txt", 3), ("modifie.txt", 3), ("src/main.rs", 2), ("docs/supprime.md", 1)]));
+        dir.write(".gitignore", "*.log\nbuild/\n");
+        tracked(&dir, "propre.txt", "abc");
+        tracked(&dir, "modifie.txt", "abcdef");
+        tracked(&dir, "src/main.rs", "fn");
+        dir.mkdir("docs");
+        dir.write("nouveau.txt", "");
+        dir.write("trace.log", "");
+        dir.write("build/sortie.o", "");
+        dir.mkdir("vide");
+
+        let repository = Repository::discover(dir.path()).unwrap().unwrap();
+        let letters: Vec<char> = ["propre.txt", "modifie.txt", "nouveau.txt", "trace.log", "build", "build/sortie.o", ".git"]
+            .iter()
+            .map(|name| letter(&repository, &dir, name))
+            .collect();
+        assert_eq!(letters, [' ', 'M', '?', '!', '!', '!', '!']);
+
+        // Un dossier suit ses fichiers suivis ; un fichier suivi supprimé compte comme modifié
+        assert_eq!(letter(&repository, &dir, "src"), ' ');
+        assert_eq!(letter(&repository, &dir, "docs"), 'M');
+        assert_eq!(letter(&repository, &dir, "vide"), '?');
+    }
+
+    #[test]
+    fn repositories_are_found_from_a_subdirectory() {
+        let dir = TestDir::new("git-discover");
+        dir.mkdir(".git");
+        let sub = dir.mkdir("a/b");
+
+        let repository = Repository::discover(&sub).unwrap().unwrap();
+        assert!(repository.entries.is_empty(), "un dépôt sans index n'a aucun fichier suivi");
+        assert_eq!(repository.root, fs::canonicalize(dir.path()).unwrap());
+    }
+}
@@ -0,0 +1,324 @@
+//! # Module `glob`
+//!
+//! Ce module fournit l'expansion des motifs de noms de fichiers partagée par les commandes.
+//!
+//! Il prend en charge les jokers suivants, comme un shell :
+//!
+//! - `*` : n'importe quelle suite de caractères (éventuellement vide).
+//! - `?` : exactement un caractère.
+//! - `[abc]`, `[a-z]` : un caractère de l'ensemble ; `[!abc]` ou `[^abc]` pour l'exclure.
+//!
+//! Les fichiers cachés (commençant par `.`) ne correspondent qu'à un motif
+//! commençant lui-même par `.`.
+//!
+//! Il fournit aussi [`IgnoreList`], une liste de motifs d'exclusion à la manière
+//! d'un fichier `.gitignore` (ex. `.wcignore`).
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::path;
+
+/// # Fonction : `is_pattern`
+///
+/// Indique si `input` contient au moins un joker (`*`, `?` ou `[`).
+pub fn is_pattern(input: &str) -> bool {
+    input.contains(['*', '?', '['])
+}
+
+/// # Fonction : `matches`
+///
+/// Indique si le nom `name` correspond au motif `pattern`.
+///
+/// ## Exemple :
+/// ```rust
+/// assert!(glob::matches("*.tmp", "cache.tmp"));
+/// assert!(!glob::matches("file?.txt", "file10.txt"));
+/// ```
+pub fn matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    matches_from(&pattern, &name)
+}
+
+/// # Fonction : `expand`
+///
+/// Développe un motif en la liste triée des chemins existants qui lui correspondent.
+///
+/// ## Fonctionnement :
+/// 1. Résout le motif en chemin absolu avec [`path::resolve`].
+/// 2. Parcourt ses composants : un composant sans joker est ajouté tel quel,
+///    un composant avec joker est remplacé par les entrées correspondantes de chaque dossier candidat.
+/// 3. Ne garde que les chemins existants, triés par ordre alphabétique.
+///
+/// Les jokers sont acceptés dans n'importe quel composant (ex. `logs/*/*.log`).
+pub fn expand(pattern: &str) -> io::Result<Vec<PathBuf>> {
+    let resolved = path::resolve(pattern)?;
+    let mut candidates = vec![PathBuf::new()];
+
+    for component in resolved.components() {
+        let part = component.as_os_str().to_string_lossy();
+        if !is_pattern(&part) {
+            for candidate in &mut candidates {
+                candidate.push(component.as_os_str());
+            }
+            continue;
+        }
+
+        let mut next = Vec::new();
+        for dir in &candidates {
+            // Un candidat qui n'est pas un dossier lisible ne produit aucune correspondance
+            let Ok(entries) = fs::read_dir(dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if name.starts_with('.') && !part.starts_with('.') {
+                    continue;
+                }
+                if matches(&part, &name) {
+                    next.push(entry.path());
+                }
+            }
+        }
+        candidates = next;
+    }
+
+    candidates.retain(|candidate| fs::symlink_metadata(candidate).is_ok());
+    candidates.sort();
+    Ok(candidates)
+}
+
+/// Règle d'un fichier d'exclusion (une ligne non vide).
+struct IgnoreRule {
+    /// Motif, sans `!` ni `/` de début et de fin.
+    pattern: String,
+    /// La ligne commençait par `!` : réintègre ce qu'une règle précédente excluait.
+    negated: bool,
+    /// La ligne se terminait par `/` : ne s'applique qu'aux dossiers.
+    dir_only: bool,
+    /// Le motif contenait un `/` : il est comparé au chemin relatif complet
+    /// plutôt qu'au seul nom de l'entrée.
+    anchored: bool,
+}
+
+/// Liste de motifs d'exclusion, au format simplifié d'un `.gitignore`.
+///
+/// - Une règle par ligne ; les lignes vides et celles commençant par `#` sont ignorées.
+/// - `motif/` ne désigne que des dossiers.
+/// - Un motif sans `/` (ex. `*.log`) s'applique au nom de l'entrée, à toute profondeur.
+/// - Un motif contenant un `/` (ex. `/build` ou `docs/*.md`) s'applique au chemin
+///   relatif à la racine ; un `/` initial est facultatif.
+/// - `!motif` réintègre une entrée exclue par une règle précédente :
+///   la dernière règle qui correspond l'emporte.
+#[derive(Default)]
+pub struct IgnoreList {
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreList {
+    /// # Fonction : `parse`
+    ///
+    /// Construit la liste à partir du contenu d'un fichier d'exclusion.
+    ///
+    /// ## Exemple :
+    /// ```rust
+    /// let ignore = glob::IgnoreList::parse("# généré\ntarget/\n*.log\n!garder.log\n");
+    /// assert!(ignore.is_ignored("target", true));
+    /// assert!(ignore.is_ignored("src/debug.log", false));
+    /// assert!(!ignore.is_ignored("garder.log", false));
+    /// ```
+    pub fn parse(content: &str) -> IgnoreList {
+        let rules = content
+            .lines()
+            .map(|line| line.trim_end())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let (negated, line) = match line.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                let (dir_only, line) = match line.strip_suffix('/') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                IgnoreRule {
+                    pattern: line.trim_start_matches('/').to_string(),
+                    negated,
+                    dir_only,
+                    anchored: line.contains('/'),
+                }
+            })
+            .collect();
+
+        IgnoreList { rules }
+    }
+
+    /// # Fonction : `is_ignored`
+    ///
+    /// Indique si une entrée est exclue.
+    ///
+    /// `relative` est son chemin relatif à la racine, avec des `/` comme séparateurs
+    /// (ex. `src/main.rs`), et `is_dir` indique s'il s'agit d'un dossier.
+    pub fn is_ignored(&self, relative: &str, is_dir: bool) -> bool {
+        let name = relative.rsplit('/').next().unwrap_or(relative);
+        let mut ignored = false;
+
+        for rule in &self.rules {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+            let target = if rule.anchored { relative } else { name };
+            if matches(&rule.pattern, target) {
+                ignored = !rule.negated;
+            }
+        }
+
+        ignored
+    }
+}
+
+/// # Fonction : `matches_from`
+///
+/// Compare le motif et le nom caractère par caractère, sans récursion.
+///
+/// Seul le dernier `*` rencontré est mémorisé : en cas d'échec, on y revient en lui
+/// faisant absorber un caractère de plus. Revenir à un `*` antérieur ne permettrait
+/// rien de plus, ce qui évite l'explosion combinatoire des motifs comme `*a*a*a*b`
+/// (le temps reste proportionnel à la longueur du motif fois celle du nom).
+fn matches_from(pattern: &[char], name: &[char]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // Position dans le motif juste après le dernier '*', et début de ce qu'il absorbe
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        let advance = match pattern.get(p) {
+            Some('*') => {
+                star = Some((p + 1, n));
+                p += 1;
+                continue;
+            }
+            Some('?') => Some(1),
+            Some('[') => match match_class(&pattern[p + 1..], name[n]) {
+                Some((true, len)) => Some(1 + len),
+                Some((false, _)) => None,
+                // Crochet non fermé : '[' est un caractère ordinaire
+                None => (name[n] == '[').then_some(1),
+            },
+            Some(&c) => (name[n] == c).then_some(1),
+            None => None,
+        };
+
+        match (advance, star) {
+            (Some(len), _) => {
+                p += len;
+                n += 1;
+            }
+            (None, Some((after_star, start))) => {
+                star = Some((after_star, start + 1));
+                p = after_star;
+                n = start + 1;
+            }
+            (None, None) => return false,
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// # Fonction : `match_class`
+///
+/// Teste le caractère `c` contre une classe `[...]` dont `pattern` commence juste après le `[`.
+///
+/// Retourne `(correspond, longueur)` où `longueur` inclut le `]` fermant,
+/// ou `None` si la classe n'est pas fermée. Un `]` placé en premier fait partie de la classe.
+fn match_class(pattern: &[char], c: char) -> Option<(bool, usize)> {
+    let negated = matches!(pattern.first(), Some('!') | Some('^'));
+    let start = usize::from(negated);
+    let mut found = false;
+    let mut i = start;
+
+    while i < pattern.len() {
+        if pattern[i] == ']' && i > start {
+            return Some((found != negated, i + 1));
+        }
+        if i + 2 < pattern.len() && pattern[i + 1] == '-' && pattern[i + 2] != ']' {
+            found |= pattern[i] <= c && c <= pattern[i + 2];
+            i += 3;
+        } else {
+            found |= pattern[i] == c;
+            i += 1;
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdir::TestDir;
+
+    #[test]
+    fn wildcards_match_like_a_shell() {
+        assert!(matches("*.tmp", "cache.tmp"));
+        assert!(matches("*", ""));
+        assert!(matches("a*b*c", "aXbYbZc"));
+        assert!(matches("file?.txt", "file1.txt"));
+        assert!(!matches("file?.txt", "file10.txt"));
+        assert!(!matches("*.tmp", "cache.tmp.bak"));
+        assert!(matches("**x", "abx"));
+    }
+
+    #[test]
+    fn classes_match_one_character() {
+        assert!(matches("report[0-9].txt", "report1.txt"));
+        assert!(!matches("report[!0-9].txt", "report1.txt"));
+        assert!(matches("report[^0-9].txt", "reportA.txt"));
+        assert!(matches("[]a]", "]"));
+        // Crochet non fermé : caractère ordinaire
+        assert!(matches("a[b", "a[b"));
+        assert!(!matches("a[b", "ab"));
+    }
+
+    #[test]
+    fn stacked_stars_do_not_backtrack_exponentially() {
+        let pattern = "*a".repeat(30) + "b";
+        let name = "a".repeat(60);
+        let start = std::time::Instant::now();
+        assert!(!matches(&pattern, &name));
+        assert!(start.elapsed() < std::time::Duration::from_secs(1));
+    }
+
+    #[test]
+    fn expand_lists_sorted_matches_without_hidden_files() {
+        let dir = TestDir::new("glob-expand");
+        dir.write("b.log", "");
+        dir.write("a.log", "");
+        dir.write(".cache.log", "");
+        dir.write("sous/c.log", "");
+        dir.write("notes.txt", "");
+
+        let found = expand(dir.join("*.log").to_str().unwrap()).unwrap();
+        let real = fs::canonicalize(dir.path()).unwrap();
+        assert_eq!(found, [real.join("a.log"), real.join("b.log")]);
+
+        let nested = expand(dir.join("*/*.log").to_str().unwrap()).unwrap();
+        assert_eq!(nested, [real.join("sous/c.log")]);
+
+        let hidden = expand(dir.join(".*.log").to_str().unwrap()).unwrap();
+        assert_eq!(hidden, [real.join(".cache.log")]);
+    }
+
+    #[test]
+    fn ignore_list_last_matching_rule_wins() {
+        let ignore = IgnoreList::parse("# commentaire\ntarget/\n*.log\n!garder.log\n/docs/*.md\n");
+        assert!(ignore.is_ignored("target", true));
+        assert!(!ignore.is_ignored("target", false));
+        assert!(ignore.is_ignored("src/debug.log", false));
+        assert!(!ignore.is_ignored("garder.log", false));
+        assert!(ignore.is_ignored("docs/guide.md", false));
+        assert!(!ignore.is_ignored("src/docs/guide.md", false));
+    }
+}
@@ -5,139 +5,779 @@
 //! Il permet d’afficher les premières lignes d’un fichier texte, avec prise en charge
 //! des options suivantes :
 //!
-//! - `-n <nombre>` : permet d’afficher un nombre spécifique de lignes.  
+//! - `-n <nombre>` : permet d’afficher un nombre spécifique de lignes.
 //! - `-v` : affiche le nom du fichier avant son contenu (mode *verbose*).
+//! - `-q` : n’affiche jamais le nom des fichiers (mode *quiet*).
+//! - `--latest` : parmi plusieurs fichiers, ne traite que le plus récemment modifié.
+//! - `--byte-offsets` : préfixe chaque ligne par sa position en octets dans le fichier.
+//! - `--strip-ansi` : supprime les séquences d’échappement ANSI (couleurs...).
+//! - `--step <N>` : n’affiche qu’une ligne sur `N` (lignes 1, 1+N, 1+2N...).
+//! - `-f`, `--follow` : continue ensuite d’afficher ce qui est ajouté aux fichiers.
+//! - `--goto <N>` (et `--context <C>`) : affiche la ligne `N` et les lignes qui l’entourent.
+//! - `--args-from-stdin` : sans nom de fichier, lit la liste des fichiers sur l’entrée standard
+//!   (un par ligne, ou séparés par `\0` avec `-0`), comme `xargs head`.
+//! - `--files-from <liste>` / `--files0-from <liste>` : ajoute les fichiers d’une liste
+//!   (un par ligne, ou séparés par `\0`), comme `wc` et `cat` (voir [`file_list`]).
 
 
-use std::fs;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, IsTerminal, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use crate::{ansi, file_list, glob, interrupt, lines, path, pipeline};
+
+/// Intervalle entre deux vérifications de la taille des fichiers suivis (`--follow`).
+const FOLLOW_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Structure représentant les options de la commande `head`.
+///
+/// Chaque champ correspond à une option possible de `head`.
+#[derive(Default, Clone)]
+struct Options {
+    /// Nombre de lignes à afficher (`-n`, 10 par défaut).
+    num_lines: usize,
+    /// Affiche le nom du fichier avant son contenu (`-v`).
+    verbose: bool,
+    /// N'affiche jamais le nom des fichiers (`-q`).
+    quiet: bool,
+    /// Ne traite que le fichier le plus récemment modifié (`--latest`).
+    latest: bool,
+    /// Préfixe chaque ligne par sa position en octets (`--byte-offsets`).
+    byte_offsets: bool,
+    /// Supprime les séquences d’échappement ANSI (`--strip-ansi`).
+    strip_ansi: bool,
+    /// N'affiche qu'une ligne sur `N` (`--step N`, 1 par défaut).
+    step: usize,
+    /// Continue d'afficher le texte ajouté aux fichiers (`-f`, `--follow`).
+    follow: bool,
+    /// Affiche la ligne `N` et son contexte au lieu du début du fichier (`--goto N`).
+    goto: Option<usize>,
+    /// Nombre de lignes affichées avant et après la ligne de `--goto` (`--context C`, 3 par défaut).
+    context: Option<usize>,
+    /// Listes de fichiers à afficher : `--files-from`, `--files0-from`, ou l'entrée
+    /// standard avec `--args-from-stdin` (séparés par `\0` avec `-0`).
+    lists: file_list::Sources,
+    /// La sortie est directement un terminal : la ligne de `--goto` y est mise en évidence.
+    /// Faux dans un pipeline interne, sauf pour la dernière commande (voir [`pipeline::Command::run`]).
+    terminal: bool,
+}
+
 /// # Fonction : `head`
 ///
 /// Affiche les premières lignes d’un fichier, en reproduisant le comportement
 /// de la commande Unix **`head`**.
 ///
 /// ## Fonctionnement :
-/// - Lit le contenu d’un fichier.
-/// - Par défaut, affiche les **10 premières lignes**.
-/// - Si le flag `-n` est utilisé, affiche le nombre de lignes spécifié.
-/// - Si le flag `-v` est utilisé, affiche le nom du fichier avant le contenu.
+/// - Lit le fichier ligne par ligne, sans le charger entièrement.
+/// - Affiche les `num_lines` premières lignes (**10** par défaut).
+/// - Avec `--step N`, n’affiche qu’une ligne sur `N`, jusqu’à en avoir affiché `num_lines`.
+/// - Si `header` est vrai, affiche la bannière `==> nom <==` avant le contenu,
+///   précédée d’une ligne vide sauf pour la première bannière (`first_header`).
+/// - Si le flag `--byte-offsets` est utilisé, préfixe chaque ligne par la position
+///   (en octets) de son premier octet, comme `grep -b`.
+/// - Si le flag `--strip-ansi` est utilisé, supprime les codes ANSI de chaque ligne
+///   (les positions restent celles du fichier d’origine).
 ///
-/// ## Flags pris en charge :
-/// - `-n <nombre>` : affiche le nombre de lignes indiqué.  
-/// - `-v` : *verbose* → affiche le nom du fichier avant son contenu.
-fn head(flag: Option<&str>, num: Option<&str>, filename: &str) {
-    // Définition du nombre de lignes à afficher par défaut
-    let mut num_lines = 10;
-
-
+/// Le texte est écrit dans `out` (sortie standard ou tampon d’un pipeline).
+/// Si la sortie est fermée par le lecteur (`BrokenPipe`), le programme s’arrête
+/// sans erreur avec [`pipeline::exit_on_broken_pipe`].
+/// Retourne `true` si une bannière a été affichée.
+fn head(options: &Options, filename: &str, header: bool, first_header: bool, out: &mut impl Write) -> bool {
     /*
-        Si le flag -n est utilisé :
-        - Vérifie qu’un argument numérique a bien été fourni après -n.
-        - Convertit cet argument en entier.
-        - En cas d’erreur (nombre manquant ou invalide), affiche un message d’erreur et quitte le programme.
+        Ouverture du fichier spécifié :
+        - En cas d’erreur, affiche un message d’erreur indiquant que le fichier est inaccessible.
     */
-    if flag == Some("-n") {
-        if num.is_none() {
-            eprintln!("head: option requires an argument -- 'n'");
-            std::process::exit(1);
-        }
-
-        // Conversion de l’argument en entier (nombre de lignes)
-        match num.unwrap().parse::<usize>() {
-            Ok(n) => num_lines = n,
-            Err(_) => {
-                eprintln!("head: invalid number of lines");
-                std::process::exit(1);
-            }
+    let file = match path::resolve(filename).and_then(File::open) {
+        Ok(file) => file,
+        Err(e) => {
+            eprintln!("head: cannot open '{}' for reading: {}", filename, e);
+            return false;
+        }
+    };
+
+    // Affiche la bannière, séparée de la précédente par une ligne vide
+    if header {
+        let separator = if first_header { "" } else { "\n" };
+        if let Err(e) = writeln!(out, "{}==> {} <==", separator, filename) {
+            pipeline::exit_on_broken_pipe(&e);
+            eprintln!("head: error writing '{}': {}", filename, e);
+            return false;
+        }
+    }
+
+    match write_lines(options, BufReader::new(file), out) {
+        Ok(()) => {}
+        // Ligne de --goto au-delà de la fin du fichier
+        Err(e) if e.kind() == io::ErrorKind::InvalidInput => eprintln!("head: '{}': {}", filename, e),
+        Err(e) => {
+            pipeline::exit_on_broken_pipe(&e);
+            eprintln!("head: error reading '{}': {}", filename, e);
+        }
+    }
+
+    header
+}
+
+/// # Fonction : `write_lines`
+///
+/// Écrit dans `out` les `num_lines` premières lignes lues depuis `reader`
+/// (un fichier ou le texte reçu d’un pipeline).
+///
+/// Avec `--step N`, seules les lignes dont l’index (à partir de 0) est un multiple
+/// de `N` sont retenues : `num_lines` compte alors les lignes affichées, si bien que
+/// `head -n 100 --step 10` lit jusqu’à 1000 lignes pour en afficher 100.
+///
+/// Les lignes sont lues avec [`lines::lines_with_terminators`] : la longueur du
+/// contenu et de la fin de ligne d'origine permet de suivre exactement la position
+/// pour `--byte-offsets`. Les octets non UTF-8 sont affichés comme `�`.
+///
+/// Avec `--goto N`, écrit seulement la ligne `N` et son contexte avec
+/// [`lines::write_window`] (ligne mise en évidence si `Options::terminal`), ou retourne
+/// une erreur `InvalidInput` si le texte compte moins de `N` lignes.
+fn write_lines(options: &Options, reader: impl BufRead, out: &mut impl Write) -> io::Result<()> {
+    if let Some(target) = options.goto {
+        let context = options.context.unwrap_or(lines::DEFAULT_CONTEXT);
+        let read = lines::write_window(reader, target, context, options.terminal, out)?;
+        if read < target {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("line {} is past the end of the file ({} lines)", target, read),
+            ));
+        }
+        return Ok(());
+    }
+
+    let mut offset = 0;
+    let mut printed = 0;
+
+    for (index, line) in lines::lines_with_terminators(reader).enumerate() {
+        if printed == options.num_lines {
+            break;
+        }
+        let (content, terminator) = line?;
+        let line_offset = offset;
+        offset += content.len() + terminator.len();
+        if index % options.step != 0 {
+            continue;
+        }
+        printed += 1;
+
+        let mut text = String::from_utf8_lossy(&content).into_owned();
+        if options.strip_ansi {
+            text = ansi::strip(&text);
+        }
+        if options.byte_offsets {
+            writeln!(out, "{}:{}", line_offset, text)?;
+        } else {
+            writeln!(out, "{}", text)?;
         }
     }
 
+    Ok(())
+}
+
+/// # Fonction : `latest_file`
+///
+/// Retourne, parmi les fichiers donnés, celui dont la date de modification
+/// (`fs::metadata`) est la plus récente. Seuls les fichiers ordinaires comptent :
+/// les dossiers et les fichiers inaccessibles sont ignorés.
+///
+/// Un nom contenant un joker (`*`, `?`, `[...]`) est développé avec [`glob::expand`],
+/// puisque le menu interactif ne le fait pas (ex. `--latest logs/*.log`).
+fn latest_file(filenames: &[&str]) -> Option<String> {
+    let mut candidates: Vec<String> = Vec::new();
+    for name in filenames {
+        if glob::is_pattern(name) {
+            let matches = glob::expand(name).unwrap_or_default();
+            candidates.extend(matches.iter().map(|p| p.display().to_string()));
+        } else {
+            candidates.push(name.to_string());
+        }
+    }
+
+    candidates
+        .into_iter()
+        .filter_map(|name| {
+            let meta = path::resolve(&name).and_then(fs::metadata).ok()?;
+            if !meta.is_file() {
+                return None;
+            }
+            Some((name, meta.modified().ok()?))
+        })
+        .max_by_key(|(_, modified)| *modified)
+        .map(|(name, _)| name)
+}
+
+/// # Fonction : `parse_arguments`
+///
+/// Sépare les flags (`-n <nombre>`, `-v`, `-q`, `--latest`, `--byte-offsets`, `--strip-ansi`, `--step <N>`,
+/// `-f`, `--goto <N>`, `--context <C>`, `--args-from-stdin`, `-0`, `--files-from <liste>`,
+/// `--files0-from <liste>`) des noms de fichiers.
+///
+/// Retourne le message d’erreur à afficher si un argument est invalide.
+fn parse_arguments(args: &[String]) -> Result<(Options, Vec<&str>), String> {
+    let mut options = Options { num_lines: 10, step: 1, ..Default::default() };
+    let mut filenames: Vec<&str> = Vec::new();
+
     /*
-        Lecture du fichier spécifié :
-        - Si la lecture réussit :
-            - Affiche le nom du fichier si le flag -v est activé.
-            - Affiche les premières lignes du fichier jusqu’à la limite définie.
-        - En cas d’erreur, affiche un message d’erreur indiquant que le fichier est inaccessible.
+        Analyse des arguments :
+        - "-n" doit être suivi d'un nombre de lignes valide.
+        - "--step" doit être suivi d'un pas strictement positif.
+        - "-v", "-q", "-f", "--latest", "--byte-offsets" et "--strip-ansi" sont de simples interrupteurs
+          (le dernier de -v et -q l'emporte, comme coreutils).
+        - Tout autre argument commençant par '-' est une option invalide.
+        - Les autres arguments sont des noms de fichiers.
     */
-    match fs::read_to_string(filename) {
-        // Affiche le nom du fichier si le flag -v est présent
-        Ok(content) => {
-            if flag == Some("-v") {
-                println!("==> {} <==", filename);
-            }
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let listed = file_list::parse_option(&mut options.lists, arg, || iter.next().map(String::as_str))
+            .map_err(|option| format!("head: option '{}' requires an argument\nUsage: head {} <liste> [fichier...]", option, option))?;
+        if listed {
+            continue;
+        }
 
-            // Afficher les premières lignes
-            for (i, line) in content.lines().enumerate() {
-                if i >= num_lines {
-                    break;
-                }
-                println!("{}", line);
+        match arg.as_str() {
+            "-n" => {
+                let Some(num) = iter.next() else {
+                    return Err("head: option requires an argument -- 'n'\nUsage: head -n <nombre> <fichier>".to_string());
+                };
+                // Conversion de l’argument en entier (nombre de lignes)
+                options.num_lines = num
+                    .parse::<usize>()
+                    .map_err(|_| format!("head: invalid number of lines: '{}'", num))?;
             }
+            "-v" | "--verbose" => {
+                options.verbose = true;
+                options.quiet = false;
+            }
+            "-q" | "--quiet" | "--silent" => {
+                options.quiet = true;
+                options.verbose = false;
+            }
+            "--step" => {
+                let Some(step) = iter.next() else {
+                    return Err("head: option '--step' requires an argument\nUsage: head --step <N> <fichier>".to_string());
+                };
+                options.step = match step.parse::<usize>() {
+                    Ok(step) if step > 0 => step,
+                    _ => return Err(format!("head: invalid step: '{}'", step)),
+                };
+            }
+            "--goto" => {
+                let Some(line) = iter.next() else {
+                    return Err("head: option '--goto' requires an argument\nUsage: head --goto <N> [--context <C>] <fichier>".to_string());
+                };
+                options.goto = match line.parse::<usize>() {
+                    Ok(line) if line > 0 => Some(line),
+                    _ => return Err(format!("head: invalid line number: '{}'", line)),
+                };
+            }
+            "--context" => {
+                let Some(context) = iter.next() else {
+                    return Err("head: option '--context' requires an argument\nUsage: head --goto <N> --context <C> <fichier>".to_string());
+                };
+                options.context = Some(
+                    context
+                        .parse::<usize>()
+                        .map_err(|_| format!("head: invalid context length: '{}'", context))?,
+                );
+            }
+            "-f" | "--follow" => options.follow = true,
+            "--latest" => options.latest = true,
+            "--byte-offsets" => options.byte_offsets = true,
+            "--strip-ansi" => options.strip_ansi = true,
+            // -0 l'emporte sur --args-from-stdin, quel que soit leur ordre
+            "--args-from-stdin" => {
+                options.lists.stdin.get_or_insert(file_list::Separator::Line);
+            }
+            "-0" | "--null" => options.lists.stdin = Some(file_list::Separator::Null),
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("head: invalid option -- '{}'\nTry 'head --help' for more information.", flag));
+            }
+            filename => filenames.push(filename),
         }
-        Err(e) => {
-            eprintln!("head: cannot open '{}' for reading: {}", filename, e);
-        }
     }
+
+    if options.context.is_some() && options.goto.is_none() {
+        return Err("head: option '--context' requires '--goto'".to_string());
+    }
+    if options.goto.is_some() && (options.follow || options.step > 1 || options.byte_offsets) {
+        return Err("head: option '--goto' cannot be combined with '--follow', '--step' or '--byte-offsets'".to_string());
+    }
+    if options.lists.stdin.is_some() && !filenames.is_empty() {
+        return Err("head: option '--args-from-stdin' cannot be used with file operands".to_string());
+    }
+
+    Ok((options, filenames))
 }
 
 /// # Fonction : `handle_head`
 ///
 /// Gère la commande **`head`** en ligne de commande.
-/// Elle analyse les arguments passés par l’utilisateur et appelle ensuite
-/// la fonction [`head`] pour afficher le contenu du fichier.
+/// Elle analyse les arguments avec [`parse_arguments`] et appelle ensuite
+/// [`head_files`] pour afficher le contenu du ou des fichiers.
 ///
-/// ## Fonctionnement :
-/// 1. Vérifie que l’utilisateur a bien passé un nom de fichier.  
-/// 2. Détermine si un flag (`-n` ou `-v`) est présent.  
-/// 3. Appelle la fonction [`head`] avec les bons paramètres.
+/// Les fichiers des listes (`--files-from`, `--args-from-stdin`...) sont ajoutés avec
+/// [`file_list::collect`] (ex. `find . -name '*.log' | head --args-from-stdin -n 3`).
+/// Une liste à lire sur une entrée standard qui est un terminal est refusée : elle
+/// doit venir d’un tube.
 pub fn handle_head(args: &[String]) {
+    let (mut options, filenames) = match parse_arguments(args) {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            eprintln!("{}", message);
+            return;
+        }
+    };
+    options.terminal = io::stdout().is_terminal();
+
+    if options.lists.is_empty() {
+        head_files(&options, filenames, &mut io::stdout());
+        return;
+    }
+
+    if options.lists.reads_stdin() && io::stdin().is_terminal() {
+        eprintln!("head: standard input is a terminal, pipe a list of files into head");
+        return;
+    }
+    match file_list::collect(&filenames, &options.lists, None) {
+        Ok(list) => head_list(&options, &list, &mut io::stdout()),
+        Err(e) => eprintln!("head: error reading file list: {}", e),
+    }
+}
+
+/// Affiche le début des fichiers d’une liste lue par [`file_list::collect`].
+fn head_list(options: &Options, list: &[PathBuf], out: &mut impl Write) {
+    let names: Vec<String> = list.iter().map(|file| file.to_string_lossy().into_owned()).collect();
+    head_files(options, names.iter().map(String::as_str).collect(), out);
+}
+
+/// # Fonction : `head_files`
+///
+/// Affiche le début de chaque fichier dans `out`.
+///
+/// ## Fonctionnement :
+/// 1. Vérifie que l’utilisateur a bien passé au moins un nom de fichier.
+/// 2. Avec `--latest`, sélectionne le fichier le plus récemment modifié.
+/// 3. Appelle la fonction [`head`] pour chaque fichier retenu. Comme coreutils,
+///    la bannière `==> nom <==` n’est affichée que s’il y a plusieurs fichiers
+///    (sauf avec `-q`) ou avec `-v`. Avec une liste (`--args-from-stdin`, `--files-from`...),
+///    elle est toujours affichée (sauf avec `-q`), puisque les noms ne figurent pas dans la commande.
+/// 4. Avec `--follow`, continue avec [`follow_files`] (jusqu’à Ctrl+C).
+fn head_files(options: &Options, filenames: Vec<&str>, out: &mut impl Write) {
     /*
         Vérifie qu'un fichier a été fourni en argument :
-        - Si la liste des arguments est vide,
+        - Si aucun nom de fichier n'a été trouvé,
           affiche un message d'erreur et propose d'utiliser "head --help".
      */
-    if args.is_empty() {
+    if filenames.is_empty() && !options.lists.is_empty() {
+        eprintln!("head: no file names read from the file list");
+        return;
+    }
+    if filenames.is_empty() {
         eprintln!("head: missing file operand");
         eprintln!("Try 'head --help' for more information.");
         return;
     }
 
-    let mut flag: Option<&str> = None;
-    let mut num: Option<&str> = None;
-    let filename;
-
-    /*
-        Analyse des arguments selon les cas possibles :
-        1. head fichier.txt          → args.len() == 1
-        2. head -v fichier.txt       → args.len() == 2
-        3. head -n 5 fichier.txt     → args.len() == 3
-    */
-    
-    // Cas 1 : Premier argument est un flag
-    if args[0].starts_with('-') {
-        if args[0] == "-n" {
-            if args.len() < 3 {
-                eprintln!("head: option requires an argument -- 'n'");
-                eprintln!("Usage: head -n <nombre> <fichier>");
-                return;
+    // Avec --latest, on ne garde que le fichier le plus récemment modifié
+    let latest;
+    let filenames = if options.latest {
+        match latest_file(&filenames) {
+            Some(name) => {
+                latest = name;
+                vec![latest.as_str()]
             }
-            flag = Some("-n");
-            num = Some(args[1].as_str());
-            filename = &args[2];
-        } else if args[0] == "-v" {
-            if args.len() < 2 {
-                eprintln!("head: missing file operand after '-v'");
+            None => {
+                eprintln!("head: no readable file among the given operands");
                 return;
             }
-            flag = Some("-v");
-            filename = &args[1];
-        } else {
-            eprintln!("head: invalid option -- '{}'", &args[0]);
-            eprintln!("Try 'head --help' for more information.");
-            return;
         }
     } else {
-        filename = &args[0];
+        filenames
+    };
+
+    let header = options.verbose || ((filenames.len() > 1 || !options.lists.is_empty()) && !options.quiet);
+    let mut first_header = true;
+    let mut current = None;
+    for (index, filename) in filenames.iter().enumerate() {
+        if head(options, filename, header, first_header, out) {
+            first_header = false;
+            current = Some(index);
+        }
+    }
+
+    if options.follow {
+        follow_files(&filenames, header, current, out);
+    }
+}
+
+/// # Fonction : `follow_files`
+///
+/// Affiche au fur et à mesure le texte ajouté à la fin des fichiers (`--follow`),
+/// comme `tail -f a.log b.log`. S’arrête avec Ctrl+C (voir [`interrupt`]), qui ramène
+/// au menu, ou si la sortie est fermée.
+///
+/// ## Fonctionnement :
+/// - Retient la taille actuelle de chaque fichier : seul ce qui est ajouté ensuite est affiché.
+/// - Toutes les [`FOLLOW_INTERVAL`], fait un tour de suivi ([`Follow::poll`]) : compare
+///   la taille de chaque fichier à celle retenue et affiche les nouveaux octets.
+/// - Si `header` est vrai, affiche `==> nom <==` à chaque fois que le texte affiché
+///   provient d’un autre fichier que le précédent (`current`, le dernier affiché).
+/// - Un fichier absent est vérifié à nouveau à chaque tour : quand il apparaît,
+///   tout son contenu est affiché.
+/// - Un fichier dont la taille diminue est considéré comme tronqué et relu depuis le début.
+fn follow_files(filenames: &[&str], header: bool, current: Option<usize>, out: &mut impl Write) {
+    let mut follow = Follow::new(filenames, header, current);
+    let _guard = interrupt::guard();
+
+    loop {
+        thread::sleep(FOLLOW_INTERVAL);
+        if interrupt::is_interrupted() {
+            return;
+        }
+        if let Err(e) = follow.poll(out) {
+            pipeline::exit_on_broken_pipe(&e);
+            eprintln!("head: {}", e);
+            return;
+        }
+    }
+}
+
+/// Fichiers suivis par [`follow_files`] et octets déjà affichés de chacun.
+struct Follow<'a> {
+    /// Fichiers suivis, dans l’ordre des opérandes.
+    filenames: &'a [&'a str],
+    /// Affiche `==> nom <==` quand le texte affiché change de fichier.
+    header: bool,
+    /// Position atteinte dans chaque fichier, ou `None` s’il n’existe pas encore.
+    positions: Vec<Option<u64>>,
+    /// Fichier dont provient le dernier texte affiché.
+    current: Option<usize>,
+}
+
+impl<'a> Follow<'a> {
+    /// Commence le suivi à la taille actuelle de chaque fichier.
+    fn new(filenames: &'a [&'a str], header: bool, current: Option<usize>) -> Self {
+        let positions = filenames.iter().map(|filename| file_size(filename)).collect();
+        Follow { filenames, header, positions, current }
+    }
+
+    /// Un tour de suivi : affiche le texte ajouté à chaque fichier depuis le tour précédent.
+    /// Retourne une erreur (nommant le fichier) si la sortie ne peut plus être écrite.
+    fn poll(&mut self, out: &mut impl Write) -> io::Result<()> {
+        for (index, filename) in self.filenames.iter().enumerate() {
+            let Some(len) = file_size(filename) else {
+                continue;
+            };
+            let position = match self.positions[index] {
+                Some(position) if len < position => {
+                    eprintln!("head: {}: file truncated", filename);
+                    0
+                }
+                Some(position) => position,
+                None => {
+                    eprintln!("head: '{}' has appeared;  following new file", filename);
+                    0
+                }
+            };
+            self.positions[index] = Some(position);
+            if len == position {
+                continue;
+            }
+
+            let appended = match read_from(filename, position, len) {
+                Ok(appended) => appended,
+                Err(e) => {
+                    eprintln!("head: error reading '{}': {}", filename, e);
+                    continue;
+                }
+            };
+            self.positions[index] = Some(position + appended.len() as u64);
+
+            let mut write = || -> io::Result<()> {
+                if self.header && self.current != Some(index) {
+                    let separator = if self.current.is_some() { "\n" } else { "" };
+                    writeln!(out, "{}==> {} <==", separator, filename)?;
+                }
+                out.write_all(&appended)?;
+                out.flush()
+            };
+            write().map_err(|e| io::Error::new(e.kind(), format!("error writing '{}': {}", filename, e)))?;
+            self.current = Some(index);
+        }
+        Ok(())
+    }
+}
+
+/// Taille actuelle d’un fichier, ou `None` s’il est absent ou illisible.
+fn file_size(filename: &str) -> Option<u64> {
+    path::resolve(filename).and_then(fs::metadata).map(|meta| meta.len()).ok()
+}
+
+/// Lit les octets d’un fichier entre les positions `start` et `end`.
+fn read_from(filename: &str, start: u64, end: u64) -> io::Result<Vec<u8>> {
+    let mut file = path::resolve(filename).and_then(File::open)?;
+    file.seek(SeekFrom::Start(start))?;
+    let mut bytes = Vec::new();
+    file.take(end - start).read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Commande `head` utilisable dans un pipeline (`cat fichier | head -n 3`).
+///
+/// Sans fichier, garde le début du texte reçu de la commande précédente.
+pub struct HeadCommand {
+    args: Vec<String>,
+}
+
+impl HeadCommand {
+    pub fn new(args: &[String]) -> Self {
+        HeadCommand { args: args.to_vec() }
+    }
+}
+
+impl pipeline::Command for HeadCommand {
+    fn run(&self, input: Option<&[u8]>, terminal: bool) -> io::Result<Vec<u8>> {
+        let (mut options, filenames) = parse_arguments(&self.args)
+            .map_err(|message| io::Error::new(io::ErrorKind::InvalidInput, message))?;
+        options.terminal = terminal;
+        if options.follow {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "head: option '--follow' cannot be used in a pipeline",
+            ));
+        }
+
+        let mut output = Vec::new();
+        match input {
+            // Le texte reçu peut être la liste des fichiers à traiter
+            _ if !options.lists.is_empty() => {
+                if options.lists.reads_stdin() && input.is_none() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "head: no file list received from the previous command",
+                    ));
+                }
+                head_list(&options, &file_list::collect(&filenames, &options.lists, input)?, &mut output);
+            }
+            Some(input) if filenames.is_empty() => write_lines(&options, input, &mut output)?,
+            _ => head_files(&options, filenames, &mut output),
+        }
+        Ok(output)
     }
+}
 
-    head(flag, num, filename);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::Command;
+    use crate::testdir::TestDir;
+    use std::path::Path;
+    use std::time::{Duration, SystemTime};
+
+    /// Crée un fichier modifié il y a `age` secondes.
+    fn aged_file(dir: &TestDir, name: &str, age: u64) -> String {
+        let path = dir.write(name, name);
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(age)).unwrap();
+        path.display().to_string()
+    }
+
+    #[test]
+    fn latest_picks_the_newest_file() {
+        let dir = TestDir::new("head-latest");
+        let old = aged_file(&dir, "ancien.log", 300);
+        let new = aged_file(&dir, "recent.log", 10);
+        let middle = aged_file(&dir, "moyen.log", 100);
+
+        assert_eq!(latest_file(&[&old, &new, &middle]), Some(new.clone()));
+        assert_eq!(latest_file(&[&old, "absent.log", &middle]), Some(middle));
+    }
+
+    #[test]
+    fn latest_ignores_directories() {
+        let dir = TestDir::new("head-latest-dir");
+        let file = aged_file(&dir, "a.log", 300);
+        let sub = dir.mkdir("sous");
+        assert_eq!(latest_file(&[&file, sub.to_str().unwrap()]), Some(file));
+        assert_eq!(latest_file(&[sub.to_str().unwrap()]), None);
+    }
+
+    #[test]
+    fn latest_expands_patterns() {
+        let dir = TestDir::new("head-latest-glob");
+        aged_file(&dir, "a.log", 300);
+        let newest = aged_file(&dir, "b.log", 10);
+        aged_file(&dir, "c.txt", 0);
+        dir.mkdir("d.log");
+
+        let pattern = dir.join("*.log").display().to_string();
+        let expected = path::resolve(&newest).unwrap().display().to_string();
+        assert_eq!(latest_file(&[&pattern]), Some(expected));
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    /// Sortie de `head` avec ces arguments sur `text`, comme dans un pipeline.
+    fn head_of(arguments: &[&str], text: &str, terminal: bool) -> io::Result<String> {
+        let output = HeadCommand::new(&args(arguments)).run(Some(text.as_bytes()), terminal)?;
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn goto_shows_the_line_and_its_context() {
+        let text: String = (1..=9).map(|n| format!("l{n}\n")).collect();
+        assert_eq!(head_of(&["--goto", "5", "--context", "1"], &text, false).unwrap(), "  4 | l4\n> 5 | l5\n  6 | l6\n");
+        assert_eq!(head_of(&["--goto", "1", "--context", "2"], &text, false).unwrap(), "> 1 | l1\n  2 | l2\n  3 | l3\n");
+    }
+
+    #[test]
+    fn goto_past_the_end_is_an_error() {
+        let error = head_of(&["--goto", "4"], "a\nb\n", false).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert!(error.to_string().contains("past the end of the file (2 lines)"), "{error}");
+    }
+
+    #[test]
+    fn goto_highlights_only_the_last_stage_in_a_terminal() {
+        let text = "a\nb\nc\n";
+        assert!(!head_of(&["--goto", "2"], text, false).unwrap().contains('\x1b'));
+        assert!(head_of(&["--goto", "2"], text, true).unwrap().contains("\x1b[7m> 2 | b"));
+    }
+
+    /// Sortie de `head` avec ces arguments, suivis des fichiers lus sur le disque.
+    fn files_of(arguments: &[&str], files: &[&Path]) -> String {
+        let names: Vec<&str> = files.iter().map(|file| file.to_str().unwrap()).collect();
+        let arguments = args(&[arguments, &names].concat());
+        let (options, filenames) = parse_arguments(&arguments).unwrap();
+        let mut output = Vec::new();
+        head_files(&options, filenames, &mut output);
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn banners_separate_several_files() {
+        let dir = TestDir::new("head-banners");
+        let first = dir.write("a.txt", "a1\na2\n");
+        let second = dir.write("b.txt", "b1\n");
+        let (a, b) = (first.display(), second.display());
+
+        assert_eq!(files_of(&["-n", "1"], &[&first]), "a1\n");
+        assert_eq!(files_of(&["-n", "1"], &[&first, &second]), format!("==> {a} <==\na1\n\n==> {b} <==\nb1\n"));
+        assert_eq!(files_of(&["-v", "-n", "1"], &[&first]), format!("==> {a} <==\na1\n"));
+        assert_eq!(files_of(&["-q", "-n", "1"], &[&first, &second]), "a1\nb1\n");
+    }
+
+    #[test]
+    fn byte_offsets_count_bytes_and_line_endings() {
+        // « é » occupe deux octets, la fin de ligne \r\n en occupe deux aussi
+        let text = "été\r\nb\n\nfin";
+        let expected = "0:été\n7:b\n9:\n10:fin\n";
+        assert_eq!(head_of(&["--byte-offsets"], text, false).unwrap(), expected);
+        assert_eq!(&text.as_bytes()[7..8], b"b");
+        assert_eq!(&text.as_bytes()[10..], b"fin");
+    }
+
+    #[test]
+    fn step_keeps_one_line_out_of_n() {
+        let text: String = (1..=10).map(|n| format!("{n}\n")).collect();
+        assert_eq!(head_of(&["--step", "3"], &text, false).unwrap(), "1\n4\n7\n10\n");
+        // -n compte les lignes affichées, pas les lignes lues
+        assert_eq!(head_of(&["-n", "2", "--step", "3"], &text, false).unwrap(), "1\n4\n");
+        assert_eq!(head_of(&["--step", "3", "--byte-offsets"], "a\nb\nc\nd\n", false).unwrap(), "0:a\n6:d\n");
+    }
+
+    #[test]
+    fn follow_prints_appended_text_with_banners() {
+        let dir = TestDir::new("head-follow");
+        let a = dir.write("a.log", "ancien\n");
+        let b = dir.write("b.log", "");
+        let c = dir.join("c.log");
+        let names = [a.display().to_string(), b.display().to_string(), c.display().to_string()];
+        let filenames: Vec<&str> = names.iter().map(String::as_str).collect();
+        let append = |path: &Path, text: &str| {
+            fs::File::options().append(true).open(path).unwrap().write_all(text.as_bytes()).unwrap();
+        };
+        let mut follow = Follow::new(&filenames, true, Some(0));
+        let mut poll = || {
+            let mut out = Vec::new();
+            follow.poll(&mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        // Rien d'ajouté : rien n'est affiché
+        assert_eq!(poll(), "");
+
+        // Le texte ajouté à a.log suit l'en-tête déjà affiché, celui de b.log en change
+        append(&a, "un\n");
+        append(&b, "deux\n");
+        assert_eq!(poll(), format!("un\n\n==> {} <==\ndeux\n", names[1]));
+
+        // Un fichier tronqué est relu depuis le début
+        fs::write(&a, "neuf\n").unwrap();
+        assert_eq!(poll(), format!("\n==> {} <==\nneuf\n", names[0]));
+
+        // Un fichier qui apparaît est affiché en entier
+        fs::write(&c, "trois\n").unwrap();
+        append(&c, "quatre\n");
+        assert_eq!(poll(), format!("\n==> {} <==\ntrois\nquatre\n", names[2]));
+        assert_eq!(poll(), "");
+    }
+
+    #[test]
+    fn follow_without_header_prints_only_the_text() {
+        let dir = TestDir::new("head-follow-quiet");
+        let a = dir.write("a.log", "");
+        let b = dir.write("b.log", "");
+        let names = [a.display().to_string(), b.display().to_string()];
+        let filenames: Vec<&str> = names.iter().map(String::as_str).collect();
+        let mut follow = Follow::new(&filenames, false, None);
+
+        fs::write(&b, "b\n").unwrap();
+        fs::write(&a, "a\n").unwrap();
+        let mut out = Vec::new();
+        follow.poll(&mut out).unwrap();
+        assert_eq!(out, b"a\nb\n");
+    }
+
+    #[test]
+    fn args_from_stdin_reads_null_separated_names() {
+        use crate::file_list::Separator;
+
+        let dir = TestDir::new("head-args-stdin");
+        let first = dir.write("a b.txt", "a1\na2\n");
+        let second = dir.write("retour\nligne.txt", "b1\n");
+
+        for arguments in [["--args-from-stdin", "-0"], ["-0", "--args-from-stdin"]] {
+            let arguments = args(&[&arguments[..], &["-n", "1"]].concat());
+            let (options, filenames) = parse_arguments(&arguments).unwrap();
+            assert_eq!(options.lists.stdin, Some(Separator::Null));
+            assert!(filenames.is_empty());
+        }
+        let (options, _) = parse_arguments(&args(&["--args-from-stdin", "-n", "1"])).unwrap();
+        assert_eq!(options.lists.stdin, Some(Separator::Line));
+
+        let arguments = args(&["-0", "--args-from-stdin", "-n", "1"]);
+        let (options, filenames) = parse_arguments(&arguments).unwrap();
+        let input = format!("{}\0{}\0", first.display(), second.display());
+        let list = file_list::collect(&filenames, &options.lists, Some(input.as_bytes())).unwrap();
+        assert_eq!(list, [first.clone(), second.clone()]);
+        let mut output = Vec::new();
+        head_list(&options, &list, &mut output);
+        let expected = format!("==> {} <==\na1\n\n==> {} <==\nb1\n", first.display(), second.display());
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+
+    #[test]
+    fn args_from_stdin_refuses_file_operands() {
+        let expected = "head: option '--args-from-stdin' cannot be used with file operands";
+        for arguments in [&["--args-from-stdin", "a.txt"][..], &["a.txt", "-0"], &["-0", "--args-from-stdin", "-n", "2", "a.txt"]] {
+            assert_eq!(parse_arguments(&args(arguments)).err().as_deref(), Some(expected));
+        }
+    }
 }
@@ -0,0 +1,187 @@
+//! # Module `interrupt`
+//!
+//! Ce module gère Ctrl+C (signal `SIGINT`) pour que les opérations longues
+//! s'arrêtent proprement au lieu de tuer le programme en pleine écriture.
+//!
+//! - En dehors d'une opération, Ctrl+C quitte le programme comme d'habitude.
+//! - Pendant une opération protégée par un [`Guard`] (copie ou suppression récursive,
+//!   `head -f`...), Ctrl+C lève seulement un drapeau atomique : la boucle de
+//!   l'opération le consulte avec [`is_interrupted`], s'arrête entre deux éléments,
+//!   affiche ce qui a été fait et rend la main au menu.
+//!
+//! Le gestionnaire est installé avec la fonction `signal` de la bibliothèque C, sans
+//! dépendance externe. Hors Unix, [`install`] ne fait rien : Ctrl+C garde son
+//! comportement par défaut.
+
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// État partagé entre le gestionnaire de signal et les opérations en cours.
+static FLAGS: Flags = Flags::new();
+
+/// Drapeaux lus et écrits par le gestionnaire de signal et par les [`Guard`].
+struct Flags {
+    /// Une opération interruptible est en cours (un [`Guard`] existe).
+    busy: AtomicBool,
+    /// Ctrl+C a été pressé pendant l'opération en cours.
+    interrupted: AtomicBool,
+}
+
+impl Flags {
+    const fn new() -> Flags {
+        Flags { busy: AtomicBool::new(false), interrupted: AtomicBool::new(false) }
+    }
+
+    /// Début ou fin d'une opération : le drapeau d'interruption repart de zéro.
+    fn set_busy(&self, busy: bool) {
+        self.interrupted.store(false, Ordering::SeqCst);
+        self.busy.store(busy, Ordering::SeqCst);
+    }
+
+    /// Enregistre un Ctrl+C. Retourne `false` si aucune opération n'est en cours :
+    /// le programme doit alors quitter.
+    #[cfg_attr(not(unix), allow(dead_code))]
+    fn signal(&self) -> bool {
+        if self.busy.load(Ordering::SeqCst) {
+            self.interrupted.store(true, Ordering::SeqCst);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn is_interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::SeqCst)
+    }
+}
+
+/// Code de sortie d'un programme arrêté par `SIGINT` (128 + 2), comme un shell.
+#[cfg(unix)]
+const EXIT_INTERRUPTED: i32 = 130;
+
+/// # Fonction : `install`
+///
+/// Installe le gestionnaire de Ctrl+C. À appeler une fois au démarrage.
+#[cfg(unix)]
+pub fn install() {
+    use std::ffi::c_int;
+
+    const SIGINT: c_int = 2;
+
+    unsafe extern "C" {
+        fn signal(signum: c_int, handler: extern "C" fn(c_int)) -> usize;
+    }
+
+    // SAFETY : le gestionnaire ne fait que des opérations autorisées dans un
+    // gestionnaire de signal (lecture et écriture atomiques, `_exit`).
+    unsafe {
+        signal(SIGINT, on_interrupt);
+    }
+}
+
+#[cfg(not(unix))]
+pub fn install() {}
+
+/// Gestionnaire de `SIGINT` : lève le drapeau pendant une opération, sinon quitte.
+#[cfg(unix)]
+extern "C" fn on_interrupt(_signum: std::ffi::c_int) {
+    unsafe extern "C" {
+        fn _exit(status: std::ffi::c_int) -> !;
+    }
+
+    if !FLAGS.signal() {
+        // SAFETY : `_exit` peut être appelée depuis un gestionnaire de signal.
+        unsafe { _exit(EXIT_INTERRUPTED) }
+    }
+}
+
+/// Protège une opération longue : tant qu'il existe, Ctrl+C ne quitte plus le
+/// programme mais lève le drapeau lu par [`is_interrupted`].
+///
+/// Le drapeau est remis à zéro à la création et à la destruction du garde.
+///
+/// ## Exemple :
+/// ```rust
+/// let _guard = interrupt::guard();
+/// for path in &paths {
+///     if interrupt::is_interrupted() {
+///         println!("Interrompu après {} élément(s)", done);
+///         break;
+///     }
+///     // ...
+/// }
+/// ```
+pub struct Guard {
+    _private: (),
+}
+
+/// # Fonction : `guard`
+///
+/// Commence une opération interruptible et retourne son [`Guard`].
+pub fn guard() -> Guard {
+    FLAGS.set_busy(true);
+    Guard { _private: () }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        FLAGS.set_busy(false);
+    }
+}
+
+/// # Fonction : `is_interrupted`
+///
+/// Indique si Ctrl+C a été pressé depuis la création du [`Guard`] en cours.
+pub fn is_interrupted() -> bool {
+    FLAGS.is_interrupted()
+}
+
+/// # Fonction : `check`
+///
+/// Retourne une erreur `Interrupted` si Ctrl+C a été pressé, pour arrêter une
+/// fonction qui propage ses erreurs avec `?`.
+pub fn check() -> io::Result<()> {
+    if is_interrupted() {
+        Err(io::Error::new(io::ErrorKind::Interrupted, "interrupted by user"))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Les tests utilisent leurs propres drapeaux : ceux du programme sont partagés
+    // avec les copies et suppressions des autres tests, exécutés en parallèle.
+
+    #[test]
+    fn ctrl_c_outside_an_operation_quits() {
+        let flags = Flags::new();
+        assert!(!flags.signal());
+        assert!(!flags.is_interrupted());
+    }
+
+    #[test]
+    fn ctrl_c_during_an_operation_only_raises_the_flag() {
+        let flags = Flags::new();
+        flags.set_busy(true);
+        assert!(!flags.is_interrupted());
+        assert!(flags.signal());
+        assert!(flags.is_interrupted());
+
+        // Le drapeau ne survit pas à l'opération
+        flags.set_busy(false);
+        assert!(!flags.is_interrupted());
+        assert!(!flags.signal());
+    }
+
+    #[test]
+    fn a_new_operation_starts_uninterrupted() {
+        let flags = Flags::new();
+        flags.set_busy(true);
+        flags.signal();
+        flags.set_busy(true);
+        assert!(!flags.is_interrupted());
+    }
+}
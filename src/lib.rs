@@ -0,0 +1,46 @@
+// lib.rs
+
+//! # Bibliothèque `projet_rust_utils_cli`
+//!
+//! Les modules des utilitaires sont déclarés ici pour être utilisés à la fois par le
+//! programme (`main.rs`) et par d'autres crates. Par exemple, le comptage de `wc`
+//! s'applique à n'importe quelle source implémentant `Read` :
+//!
+//! ```rust
+//! use projet_rust_utils_cli::wc::{count_reader, Options};
+//!
+//! let result = count_reader(&b"Bonjour le monde\n"[..], &Options::default())?;
+//! assert_eq!((result.lines, result.words), (1, 3));
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+pub mod cat; // Déclare le module cat.rs
+pub mod ls;  // Déclare le module ls.rs (à créer)
+pub mod wc;  // Déclare le module wc.rs (à créer)
+pub mod cp;  // Déclare le module cp.rs
+pub mod mv;  // Déclare le module mv.rs
+pub mod rm;  // Déclare le module rm.rs (à créer)
+pub mod head; // Déclare le module head.rs
+pub mod path; // Déclare le module path.rs (résolution des chemins)
+pub mod glob; // Déclare le module glob.rs (expansion des motifs *, ? et [...])
+pub mod ansi; // Déclare le module ansi.rs (suppression des séquences d'échappement)
+pub mod size; // Déclare le module size.rs (affichage lisible des tailles)
+pub mod date; // Déclare le module date.rs (affichage des dates des fichiers)
+pub mod xattr; // Déclare le module xattr.rs (attributs étendus)
+pub mod escape; // Déclare le module escape.rs (séquences d'échappement \n, \t...)
+pub mod echo; // Déclare le module echo.rs
+pub mod pipeline; // Déclare le module pipeline.rs (enchaînement des commandes avec |)
+pub mod lines; // Déclare le module lines.rs (lecture des lignes avec leur fin de ligne)
+pub mod git; // Déclare le module git.rs (statut des fichiers d'un dépôt git)
+pub mod menu; // Déclare le module menu.rs (retour au menu et effacement de l'écran)
+pub mod interrupt; // Déclare le module interrupt.rs (arrêt propre avec Ctrl+C)
+pub mod sum; // Déclare le module sum.rs (empreintes SHA-256 des fichiers)
+pub mod width; // Déclare le module width.rs (largeur d'affichage et coupure des noms)
+pub mod ext; // Déclare le module ext.rs (commandes externes lancées avec ext:)
+pub mod file_list; // Déclare le module file_list.rs (listes de fichiers --files-from, stdin...)
+pub mod tar; // Déclare le module tar.rs (lecture des fichiers d'une archive .tar)
+pub mod template; // Déclare le module template.rs (modèles de noms de cp/mv --as)
+pub mod syntax; // Déclare le module syntax.rs (couleurs des formats json, diff et log)
+pub mod bench; // Déclare le module bench.rs (mesure des performances avec --bench)
+#[cfg(test)]
+mod testdir; // Déclare le module testdir.rs (dossiers temporaires des tests)
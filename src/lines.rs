@@ -0,0 +1,198 @@
+//! # Module `lines`
+//!
+//! Ce module lit un texte ligne par ligne en conservant la fin de ligne d'origine,
+//! que `str::lines()` supprime. Il est partagé par `cat`, `head` et `wc`.
+//!
+//! Les fins de ligne reconnues sont `\n` (LF) et `\r\n` (CRLF). Un `\r` seul ne termine
+//! pas une ligne, comme avec les commandes Unix. La dernière ligne peut ne pas avoir
+//! de fin de ligne : elle est alors retournée avec un terminateur vide.
+//!
+//! Il fournit aussi [`write_window`], qui affiche une ligne et son contexte
+//! (`cat --goto`, `head --goto`).
+
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Write};
+
+/// Nombre de lignes de contexte affichées par défaut autour de la ligne de `--goto`.
+pub const DEFAULT_CONTEXT: usize = 3;
+
+/// Itérateur retourné par [`lines_with_terminators`].
+pub struct LinesWithTerminators<R> {
+    reader: R,
+}
+
+/// # Fonction : `lines_with_terminators`
+///
+/// Retourne un itérateur sur les lignes de `reader`, sous la forme de paires
+/// `(contenu, terminateur)` : le contenu en octets bruts (sans fin de ligne)
+/// et le terminateur `"\n"`, `"\r\n"` ou `""`.
+///
+/// Le contenu n'est pas décodé : une séquence UTF-8 invalide est laissée à
+/// l'appelant (ex. `String::from_utf8_lossy`). La longueur totale de la ligne
+/// dans la source est donc toujours `contenu.len() + terminateur.len()`.
+///
+/// ## Exemple :
+/// ```rust
+/// let lines: Vec<_> = lines::lines_with_terminators(&b"a\nb\r\nc"[..])
+///     .collect::<io::Result<_>>()?;
+/// assert_eq!(lines, vec![
+///     (b"a".to_vec(), "\n"),
+///     (b"b".to_vec(), "\r\n"),
+///     (b"c".to_vec(), ""),
+/// ]);
+/// ```
+pub fn lines_with_terminators<R: BufRead>(reader: R) -> LinesWithTerminators<R> {
+    LinesWithTerminators { reader }
+}
+
+impl<R: BufRead> Iterator for LinesWithTerminators<R> {
+    type Item = io::Result<(Vec<u8>, &'static str)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut line = Vec::new();
+        match self.reader.read_until(b'\n', &mut line) {
+            Ok(0) => None, // Fin du texte
+            Ok(_) => {
+                let terminator = if line.ends_with(b"\r\n") {
+                    "\r\n"
+                } else if line.ends_with(b"\n") {
+                    "\n"
+                } else {
+                    ""
+                };
+                line.truncate(line.len() - terminator.len());
+                Some(Ok((line, terminator)))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// # Fonction : `write_window`
+///
+/// Écrit la ligne numéro `target` (à partir de 1) de `reader`, entourée de
+/// `context` lignes avant et après, comme un petit visualiseur de fichier.
+///
+/// ## Fonctionnement :
+/// - Lit le texte ligne par ligne en ne gardant que les `context` dernières
+///   lignes lues : la mémoire utilisée ne dépend pas de la taille du fichier.
+/// - Une fois la ligne `target` atteinte, écrit les lignes gardées, la ligne
+///   elle-même, puis les `context` suivantes, et arrête la lecture.
+/// - Chaque ligne est précédée de son numéro ; la ligne `target` est marquée
+///   par `>` et, si `highlight` est vrai, affichée en vidéo inverse.
+///
+/// ## Retour :
+/// Le nombre de lignes lues. S'il est inférieur à `target`, la ligne n'existe
+/// pas et rien n'a été écrit : c'est à l'appelant de le signaler.
+///
+/// ## Exemple :
+/// ```rust
+/// let mut out = Vec::new();
+/// let read = lines::write_window(&b"a\nb\nc\nd\n"[..], 2, 1, false, &mut out)?;
+/// assert_eq!(read, 3);
+/// assert_eq!(out, b"  1 | a\n> 2 | b\n  3 | c\n");
+/// ```
+pub fn write_window<R: BufRead>(
+    reader: R,
+    target: usize,
+    context: usize,
+    highlight: bool,
+    out: &mut impl Write,
+) -> io::Result<usize> {
+    let width = (target + context).to_string().len();
+    let mut before: VecDeque<String> = VecDeque::with_capacity(context + 1);
+    let mut read = 0;
+
+    for line in lines_with_terminators(reader) {
+        let (content, _) = line?;
+        read += 1;
+        let text = String::from_utf8_lossy(&content).into_owned();
+
+        if read < target {
+            // Garder seulement les `context` lignes qui précèdent la cible
+            before.push_back(text);
+            if before.len() > context {
+                before.pop_front();
+            }
+            continue;
+        }
+
+        if read == target {
+            let first = target - before.len();
+            for (index, previous) in before.drain(..).enumerate() {
+                writeln!(out, "  {:>width$} | {}", first + index, previous)?;
+            }
+            if highlight {
+                writeln!(out, "\x1b[7m> {:>width$} | {}\x1b[0m", read, text)?;
+            } else {
+                writeln!(out, "> {:>width$} | {}", read, text)?;
+            }
+        } else {
+            writeln!(out, "  {:>width$} | {}", read, text)?;
+        }
+
+        if read == target + context {
+            break;
+        }
+    }
+
+    Ok(read)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(text: &[u8]) -> Vec<(Vec<u8>, &'static str)> {
+        lines_with_terminators(text).collect::<io::Result<_>>().unwrap()
+    }
+
+    fn window(text: &str, target: usize, context: usize) -> (usize, String) {
+        let mut out = Vec::new();
+        let read = write_window(text.as_bytes(), target, context, false, &mut out).unwrap();
+        (read, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn terminators_are_kept_apart() {
+        assert_eq!(
+            split(b"a\nb\r\nc"),
+            vec![(b"a".to_vec(), "\n"), (b"b".to_vec(), "\r\n"), (b"c".to_vec(), "")]
+        );
+        assert_eq!(split(b"x\ry\n"), vec![(b"x\ry".to_vec(), "\n")]);
+        assert_eq!(split(b"\n\n"), vec![(Vec::new(), "\n"), (Vec::new(), "\n")]);
+        assert!(split(b"").is_empty());
+    }
+
+    #[test]
+    fn invalid_utf8_is_left_untouched() {
+        assert_eq!(split(b"\xff\xfe\n"), vec![(b"\xff\xfe".to_vec(), "\n")]);
+    }
+
+    #[test]
+    fn window_shows_the_context_around_the_target() {
+        let text = "a\nb\nc\nd\ne\n";
+        assert_eq!(window(text, 3, 1), (4, "  2 | b\n> 3 | c\n  4 | d\n".to_string()));
+        assert_eq!(window(text, 1, 2), (3, "> 1 | a\n  2 | b\n  3 | c\n".to_string()));
+        assert_eq!(window(text, 5, 1), (5, "  4 | d\n> 5 | e\n".to_string()));
+    }
+
+    #[test]
+    fn window_pads_numbers_to_the_widest() {
+        let text: String = (1..=12).map(|n| format!("l{n}\n")).collect();
+        let (_, out) = window(&text, 9, 1);
+        assert_eq!(out, "   8 | l8\n>  9 | l9\n  10 | l10\n");
+    }
+
+    #[test]
+    fn missing_target_writes_nothing() {
+        assert_eq!(window("a\nb\n", 5, 1), (2, String::new()));
+    }
+
+    #[test]
+    fn highlighted_target_uses_reverse_video() {
+        let mut out = Vec::new();
+        write_window(&b"a\nb\n"[..], 2, 0, true, &mut out).unwrap();
+        assert_eq!(out, b"\x1b[7m> 2 | b\x1b[0m\n");
+    }
+}
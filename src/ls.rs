@@ -12,6 +12,12 @@
 //! # Lister le contenu d'un dossier par chemin absolu
 //! > ls "C:/Users/test.txt"
 //!
+//! # Lister uniquement les entrées correspondant à un motif
+//! > ls "src/*.rs"
+//!
+//! # Comparer le contenu de deux dossiers (ex. deux sauvegardes)
+//! > ls sauvegarde_lundi --diff sauvegarde_mardi
+//!
 //! # Quitter le menu
 //! > quit
 //! ```
@@ -20,13 +26,172 @@
 //! - Si le chemin n'existe pas → message d'avertissement et possibilité de retaper.
 //! - Si la commande est mal utilisée → rappel de la syntaxe correcte et possibilité de retaper.
 
-use std::{fs, path::Path, env};
-use std::io::{self, Write};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::{date, git, glob, menu, path, pipeline, size, sum, width};
+use std::io::{self, IsTerminal, Write};
+
+/// Structure représentant les options d'affichage pour la commande `ls`.
+///
+/// Chaque champ correspond à une option possible de `ls`.
+#[derive(Default, Clone)]
+struct Options {
+    /// Affiche aussi les entrées cachées, dont le nom commence par `.` (`-a`, `--all`).
+    all: bool,
+    /// Affiche les dossiers avant les fichiers (`--group-directories-first`).
+    group_directories_first: bool,
+    /// Affiche le contenu sous forme d'arborescence (`--tree`).
+    tree: bool,
+    /// Profondeur maximale de l'arborescence (`--depth N`).
+    depth: Option<usize>,
+    /// Affiche seulement le nombre d'entrées de chaque dossier, sur N niveaux (`--count-depth N`).
+    count_depth: Option<usize>,
+    /// Affiche le nombre de dossiers, fichiers et liens après la liste (`--summary`).
+    summary: bool,
+    /// Ajoute un indicateur de type après chaque nom (`-F`, `--classify`).
+    classify: bool,
+    /// Ajoute `/` après le nom des dossiers uniquement (`--dir-slash`).
+    dir_slash: bool,
+    /// Affiche permissions, taille et date de modification avant chaque nom (`-l`).
+    long: bool,
+    /// Avec `-l`, affiche les permissions en octal (`0644`) plutôt qu'en symbolique (`--octal-perms`).
+    octal_perms: bool,
+    /// Affiche une ligne CSV ou TSV par entrée au lieu de la liste (`--format=csv|tsv`).
+    format: Option<Format>,
+    /// Dans un dépôt git, affiche le statut de chaque entrée (`--git`).
+    git: bool,
+    /// Affiche les liens cassés en rouge (activé seulement si la sortie est un terminal).
+    color: bool,
+    /// Avec `-l`, ajoute une barre proportionnelle à la taille de chaque fichier (`--bars`).
+    bars: bool,
+    /// Taille du plus gros fichier de la liste, qui sert d'échelle aux barres de `--bars`.
+    bar_scale: Option<u64>,
+    /// Avec `-l`, colore les noms selon l'ancienneté de leur modification (`--age-color`).
+    age_color: bool,
+    /// Affiche le nom, la taille et l'empreinte SHA-256 de chaque fichier (`--manifest`).
+    manifest: bool,
+    /// Fichier dans lequel écrire le manifeste au lieu de l'afficher (`--output FICHIER`).
+    output: Option<String>,
+    /// Coupe les noms plus larges que `N` colonnes avec `…` (`--max-name-width N`),
+    /// désactivé par `--no-truncate`.
+    max_name_width: Option<usize>,
+    /// Dossier auquel comparer le dossier listé (`--diff AUTRE`).
+    diff: Option<String>,
+    /// Regroupe les entrées par extension, sous un titre par groupe (`--by-ext`).
+    by_ext: bool,
+}
+
+/// Largeur (en colonnes) d'une barre de `--bars` pour le plus gros fichier.
+const BAR_WIDTH: usize = 10;
+/// Blocs de largeur croissante (1/8 à 8/8 de colonne) pour dessiner les barres.
+const BAR_BLOCKS: [char; 8] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+
+/// En dessous de cet âge, une entrée de `--age-color` est récente (en vert).
+const RECENT_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+/// En dessous de cet âge, une entrée de `--age-color` date de la semaine (en jaune).
+const WEEK_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Tranche d'ancienneté d'une entrée pour `--age-color`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AgeBucket {
+    /// Modifiée depuis moins de [`RECENT_AGE`].
+    Recent,
+    /// Modifiée depuis moins de [`WEEK_AGE`].
+    ThisWeek,
+    /// Plus ancienne : couleur par défaut du terminal.
+    Older,
+}
+
+impl AgeBucket {
+    /// Classe une entrée selon le temps écoulé depuis sa modification.
+    ///
+    /// # Exemple
+    /// ```rust
+    /// assert_eq!(AgeBucket::from_age(Duration::from_secs(3600)), AgeBucket::Recent);
+    /// assert_eq!(AgeBucket::from_age(Duration::from_secs(3 * 86_400)), AgeBucket::ThisWeek);
+    /// assert_eq!(AgeBucket::from_age(Duration::from_secs(30 * 86_400)), AgeBucket::Older);
+    /// ```
+    fn from_age(age: Duration) -> Self {
+        if age < RECENT_AGE {
+            AgeBucket::Recent
+        } else if age < WEEK_AGE {
+            AgeBucket::ThisWeek
+        } else {
+            AgeBucket::Older
+        }
+    }
+
+    /// Code couleur ANSI de la tranche, ou `None` pour la couleur par défaut.
+    fn color_code(self) -> Option<&'static str> {
+        match self {
+            AgeBucket::Recent => Some("32"),
+            AgeBucket::ThisWeek => Some("33"),
+            AgeBucket::Older => None,
+        }
+    }
+}
+
+/// Format de sortie tabulaire (`--format=csv`, `--format=tsv`).
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    /// Champs séparés par des virgules.
+    Csv,
+    /// Champs séparés par des tabulations.
+    Tsv,
+}
+
+impl Format {
+    /// Retourne le séparateur de champs du format.
+    fn delimiter(self) -> char {
+        match self {
+            Format::Csv => ',',
+            Format::Tsv => '\t',
+        }
+    }
+}
+
+/// Nombre d'entrées de chaque type, affiché avec `--summary`.
+#[derive(Default)]
+struct Summary {
+    directories: usize,
+    files: usize,
+    symlinks: usize,
+}
+
+impl Summary {
+    /// Classe une entrée selon son type, sans suivre les liens symboliques.
+    fn add(&mut self, entry: &fs::DirEntry) {
+        match entry.file_type() {
+            Ok(t) if t.is_symlink() => self.symlinks += 1,
+            Ok(t) if t.is_dir() => self.directories += 1,
+            _ => self.files += 1,
+        }
+    }
+}
+
+impl fmt::Display for Summary {
+    /// Affiche par exemple `3 dossiers, 12 fichiers, 1 lien symbolique`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let plural = |n: usize| if n > 1 { "s" } else { "" };
+        write!(
+            f,
+            "{} dossier{}, {} fichier{}, {} lien{} symbolique{}",
+            self.directories, plural(self.directories),
+            self.files, plural(self.files),
+            self.symlinks, plural(self.symlinks), plural(self.symlinks),
+        )
+    }
+}
 
 /// Point d'entrée principal de la commande `ls`.
 ///
 /// Cette fonction démarre un shell interactif permettant à l'utilisateur de saisir
-/// des commandes `ls`. La boucle continue jusqu'à ce que l'utilisateur tape `quit`.
+/// des commandes `ls`. La boucle continue jusqu'à ce que l'utilisateur tape `menu` (ou `b`, `0`, `quit`).
 /// En cas d'erreur, l'utilisateur peut simplement retaper une nouvelle commande.
 ///
 /// # Fonctionnement
@@ -56,7 +221,7 @@ use std::io::{self, Write};
 pub fn ls() {
     loop {
         println!("\n=== Programme utilitaire ls ===");
-        println!("Entrez votre commande (ou 'quit' pour quitter) :");
+        println!("Entrez votre commande (ou 'menu' pour revenir au menu principal) :");
         print!("> ");
         io::stdout().flush().unwrap();
 
@@ -65,8 +230,8 @@ pub fn ls() {
 
         let input = input.trim();
 
-        // Si l'utilisateur tape quit, on sort du programme
-        if input == "quit" {
+        // Si l'utilisateur tape quit (ou menu, b, 0), on revient au menu principal
+        if input == "quit" || menu::is_back(input) {
             break;
         }
 
@@ -97,6 +262,15 @@ pub fn ls() {
     }
 }
 
+/// Exécute une commande `ls` en mode non interactif.
+///
+/// Les arguments sont ceux passés au programme après le nom de la commande
+/// (ex. `projet ls mon_dossier`).
+pub fn run(args: &[String]) {
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    process_command(&args);
+}
+
 /// Parse une ligne de commande en respectant les guillemets et échappements.
 ///
 /// # Algorithme
@@ -171,9 +345,12 @@ fn parse_command_line(input: &str) -> Vec<String> {
 /// 1. Gère l’option `--help` si elle est présente.
 /// 2. Analyse les arguments pour extraire les options éventuelles et le chemin cible.
 /// 3. Vérifie qu’un chemin a bien été fourni.
-/// 4. Résout le chemin (par exemple, gère `.` pour le dossier courant).
+/// 4. Résout le chemin (gère `.`, `..` et `~`) via le module `path`.
 /// 5. Vérifie l’existence du chemin.
 /// 6. Si le chemin est valide et correspond à un dossier, affiche la liste de ses contenus.
+/// 7. Avec `--git`, précède chaque nom de son statut dans le dépôt (voir [`git_letters`]).
+/// 8. Avec `--summary`, affiche ensuite le nombre d'entrées de chaque type
+///    (sur toute l'arborescence avec `--tree`).
 ///
 /// # Arguments
 /// * `args` – Les arguments passés à la commande (sans le mot-clé `ls`).
@@ -181,7 +358,7 @@ fn parse_command_line(input: &str) -> Vec<String> {
 /// # Exemple
 /// ```no_run
 /// process_command(&["mon_dossier"]);
-/// // ✅ Les contenus du dossier :
+/// // ✅ Les contenus du dossier (3 entrées) :
 /// // - fichier1.txt
 /// // - sous_dossier
 /// // - ....
@@ -193,8 +370,15 @@ fn process_command(args: &[&str]) {
         return;
     }
 
-    // Parser les arguments pour extraire le chemin
-    let (recursive,target) = parse_arguments(args);
+    // Parser les arguments pour extraire les options et le chemin
+    let (mut options, target) = match parse_arguments(args) {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            println!("❌ Erreur : {}", message);
+            println!("💡 Utilisez 'ls --help' pour plus d'informations");
+            return; // Retour à la boucle pour retaper
+        }
+    };
 
     // Si aucun chemin n'est fourni
     if target.is_none() {
@@ -203,98 +387,1097 @@ fn process_command(args: &[&str]) {
         return; // Retour à la boucle pour retaper
     }
 
-    let target_name = target.unwrap();
-    
-    // Résoudre le chemin (gérer . pour le dossier courant)
-    let file_path = match resolve_path(&target_name) {
-        Some(path) => path,
-        None => {
-            println!("❌ Erreur : Impossible de récupérer le dossier courant");
+    let mut target_name = target.unwrap();
+
+    // Un motif (ex. "src/*.rs") liste le dossier parent en filtrant ses entrées
+    let pattern = match split_pattern(&target_name) {
+        Ok(Some((dir, pattern))) => {
+            target_name = dir;
+            Some(pattern)
+        }
+        Ok(None) => None,
+        Err(message) => {
+            println!("❌ Erreur : {}", message);
+            return; // Retour à la boucle pour retaper
+        }
+    };
+    if pattern.is_some() && options.tree {
+        println!("❌ Erreur : L'option --tree ne peut pas être combinée avec un motif");
+        return; // Retour à la boucle pour retaper
+    }
+    if options.format.is_some() && options.tree {
+        println!("❌ Erreur : L'option --format ne peut pas être combinée avec --tree");
+        return; // Retour à la boucle pour retaper
+    }
+    if options.git && (options.tree || options.format.is_some()) {
+        println!("❌ Erreur : L'option --git ne peut pas être combinée avec --tree ou --format");
+        return; // Retour à la boucle pour retaper
+    }
+    if options.age_color && (!options.long || options.format.is_some()) {
+        println!("❌ Erreur : L'option --age-color nécessite -l et ne peut pas être combinée avec --format");
+        return; // Retour à la boucle pour retaper
+    }
+    if options.bars && (!options.long || options.tree || options.format.is_some()) {
+        println!("❌ Erreur : L'option --bars nécessite -l et ne peut pas être combinée avec --tree ou --format");
+        return; // Retour à la boucle pour retaper
+    }
+    if options.count_depth.is_some() && (pattern.is_some() || options.tree || options.format.is_some() || options.git) {
+        println!("❌ Erreur : L'option --count-depth ne peut pas être combinée avec un motif, --tree, --format ou --git");
+        return; // Retour à la boucle pour retaper
+    }
+    if options.manifest && (options.tree || options.format.is_some() || options.git || options.long || options.count_depth.is_some()) {
+        println!("❌ Erreur : L'option --manifest ne peut pas être combinée avec --tree, --format, --git, -l ou --count-depth");
+        return; // Retour à la boucle pour retaper
+    }
+    if options.diff.is_some() && (options.tree || options.format.is_some() || options.git || options.manifest || options.count_depth.is_some() || options.bars) {
+        println!("❌ Erreur : L'option --diff ne peut pas être combinée avec --tree, --format, --git, --manifest, --count-depth ou --bars");
+        return; // Retour à la boucle pour retaper
+    }
+    if options.by_ext && (options.tree || options.format.is_some() || options.manifest || options.count_depth.is_some() || options.diff.is_some()) {
+        println!("❌ Erreur : L'option --by-ext ne peut pas être combinée avec --tree, --format, --manifest, --count-depth ou --diff");
+        return; // Retour à la boucle pour retaper
+    }
+    if options.output.is_some() && !options.manifest {
+        println!("❌ Erreur : L'option --output nécessite --manifest");
+        return; // Retour à la boucle pour retaper
+    }
+
+    // Résoudre le chemin (gérer ., .. et ~)
+    let file_path = match path::resolve(&target_name) {
+        Ok(path) => path,
+        Err(e) => {
+            println!("❌ Erreur : Impossible de résoudre le chemin : {}", e);
             return; // Retour à la boucle pour retaper
         }
     };
 
-    let path_obj = Path::new(&file_path);
+    let path_obj = file_path.as_path();
 
     // Si le chemin n'existe pas
     if !path_obj.exists() {
-        println!("⚠️  Le chemin '{}' n'existe pas.", file_path);
+        println!("⚠️  Le chemin '{}' n'existe pas.", file_path.display());
         println!("💡 Vérifiez le chemin et réessayez");
         return; // Retour à la boucle pour retaper
     }
 
-    match fs::read_dir(path_obj) {
+    // Les couleurs et les barres n'ont de sens que dans un terminal :
+    // redirigée, la sortie de --bars garde seulement les tailles en chiffres
+    // et celle de --age-color reste sans couleur
+    options.color = io::stdout().is_terminal();
+    options.bars &= options.color;
+    options.age_color &= options.color;
+
+    // Manifeste enregistré dans un fichier : seul le bilan est affiché
+    if let Some(output) = &options.output {
+        match save_manifest(path_obj, pattern.as_deref(), &options, output) {
+            Ok(count) => {
+                let plural = if count > 1 { "s" } else { "" };
+                println!("✅ Manifeste de {} fichier{} écrit dans '{}'", count, plural, output);
+            }
+            Err(e) => println!("❌ Erreur : Impossible d'écrire le manifeste : {}", e),
+        }
+        return;
+    }
+
+    // Si le lecteur de la sortie s'arrête (ex. `ls gros_dossier | head`), quitter sans erreur
+    if let Err(e) = write_listing(path_obj, &file_path, pattern.as_deref(), &options, &mut io::stdout()) {
+        pipeline::exit_on_broken_pipe(&e);
+        println!("❌ Erreur : {}", e);
+    }
+}
+
+
+/// Écrit le contenu du dossier dans `out`, en liste, en arborescence (`--tree`),
+/// en tableau (`--format`), en nombre d'entrées par dossier (`--count-depth`),
+/// en manifeste (`--manifest`) ou en comparaison avec un autre dossier (`--diff`),
+/// suivi du résumé avec `--summary`.
+///
+/// Les erreurs de lecture du dossier sont affichées dans la sortie ; seule une erreur
+/// d'écriture (ex. `BrokenPipe`) est retournée.
+///
+/// # Arguments
+/// * `path_obj` - Dossier à lister.
+/// * `file_path` - Chemin résolu, affiché en tête de l'arborescence.
+/// * `pattern` - Motif filtrant les entrées, s'il y en a un.
+/// * `options` - Options d'affichage.
+/// * `out` - Destination de l'affichage.
+fn write_listing(
+    path_obj: &Path,
+    file_path: &Path,
+    pattern: Option<&str>,
+    options: &Options,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let mut summary = Summary::default();
+
+    // Comparaison avec un autre dossier
+    if let Some(other) = &options.diff {
+        return write_diff(path_obj, other, pattern, options, out);
+    }
+
+    // Vue d'ensemble : un nombre d'entrées par dossier
+    if let Some(max_depth) = options.count_depth {
+        writeln!(out, "✅ Nombre d'entrées par dossier (profondeur {}) : ", max_depth)?;
+        return write_counts(path_obj, max_depth, options, out);
+    }
+
+    // Affichage en arborescence
+    if options.tree {
+        writeln!(out, "✅ Arborescence du dossier: ")?;
+        writeln!(out, "{}", file_path.display())?;
+        print_tree(path_obj, "", 1, options, &mut summary, out)?;
+        if options.summary {
+            writeln!(out)?;
+            writeln!(out, "📊 {}", summary)?;
+        }
+        return Ok(());
+    }
+
+    match read_entries(path_obj, pattern, options) {
+        Ok(entries) if options.manifest => {
+            write_manifest(&entries, out)?;
+        }
         Ok(entries) => {
-            println!("✅ Les contenus du dossier: ");
-            for entry in entries.flatten() {
-                println!(" - {}", entry.file_name().to_string_lossy());
+            if let Some(pattern) = pattern
+                && entries.is_empty()
+            {
+                writeln!(out, "⚠️  Aucune entrée ne correspond au motif '{}'.", pattern)?;
+                return Ok(());
+            }
+
+            // Sortie tabulaire brute, destinée à un tableur
+            if let Some(format) = options.format {
+                return write_delimited(out, &entries, format);
+            }
+
+            // Hors d'un dépôt git, --git n'a aucun effet
+            let letters = if options.git {
+                match git_letters(path_obj, &entries) {
+                    Ok(letters) => letters,
+                    Err(e) => {
+                        writeln!(out, "❌ Erreur : Impossible de lire le dépôt git : {}", e)?;
+                        return Ok(());
+                    }
+                }
+            } else {
+                None
+            };
+
+            // L'échelle des barres dépend du plus gros fichier affiché
+            let scaled;
+            let options = if options.bars {
+                scaled = Options { bar_scale: Some(largest_file_size(&entries)), ..options.clone() };
+                &scaled
+            } else {
+                options
+            };
+
+            // Avec --by-ext, un groupe par extension ; sinon un seul groupe, sans titre
+            let groups = if options.by_ext {
+                extension_groups(&entries).into_iter().map(|(label, indices)| (Some(label), indices)).collect()
+            } else {
+                vec![(None, (0..entries.len()).collect::<Vec<_>>())]
+            };
+
+            // Le nombre est celui des entrées affichées, après le filtrage par motif
+            let plural = if entries.len() > 1 { "s" } else { "" };
+            writeln!(out, "✅ Les contenus du dossier ({} entrée{}) : ", entries.len(), plural)?;
+            for (label, indices) in groups {
+                if let Some(label) = label {
+                    writeln!(out, "📂 {} ({}) :", label, indices.len())?;
+                }
+                for index in indices {
+                    let entry = &entries[index];
+                    match &letters {
+                        Some(letters) => writeln!(out, " - {} {}", letters[index], entry_label(entry, options))?,
+                        None => writeln!(out, " - {}", entry_label(entry, options))?,
+                    }
+                    summary.add(entry);
+                }
+            }
+
+            // Signaler les liens dont la cible n'existe plus
+            let broken = entries.iter().filter(|entry| is_broken_symlink(&entry.path())).count();
+            if broken > 0 {
+                let plural = if broken > 1 { "s" } else { "" };
+                writeln!(out)?;
+                let marker = if options.color { format!(" (marqué{} par !)", plural) } else { String::new() };
+                writeln!(out, "⚠️  {} lien{} symbolique{} cassé{}{}", broken, plural, plural, plural, marker)?;
+            }
+
+            if options.summary {
+                writeln!(out)?;
+                writeln!(out, "📊 {}", summary)?;
             }
         }
         Err(e) => {
-            println!("❌ Erreur lors de la suppression : {}", e);
-            println!("💡 Vérifiez les permissions et réessayez");
+            writeln!(out, "❌ Erreur lors de la suppression : {}", e)?;
+            writeln!(out, "💡 Vérifiez les permissions et réessayez")?;
         }
     }
+
+    Ok(())
 }
 
 
-/// Parse l'argument qui est  le chemin cible.
+/// Parse les arguments pour extraire les options et le chemin cible.
 ///
 /// # Algorithme
-/// - On considère l'argument comme le chemin cible.
-/// - Seul le premier chemin trouvé est conservé.
+/// - Si l'argument est une option connue, met à jour `Options`.
+/// - Sinon, on considère l'argument comme le chemin cible.
+/// - Seul le dernier chemin trouvé est conservé.
 ///
 /// # Arguments
 /// * `args`.
 ///
 /// # Retour
-/// Retourne un tuple `(bool, Option<String>)` où : 
+/// Retourne un tuple `(Options, Option<String>)` où : 
+/// - `Options` contient les options d'affichage.
 /// - L’`Option<String>` contient le chemin cible s’il est trouvé.
 ///
+/// Retourne un message d'erreur si une option attend une valeur invalide.
+///
 /// # Exemple
 /// ```rust
-/// let (_, path) = parse_arguments(&["dossier_test"]);
+/// let (options, path) = parse_arguments(&["--group-directories-first", "dossier_test"]).unwrap();
+/// assert!(options.group_directories_first);
 /// assert_eq!(path.unwrap(), "dossier_test");
 /// ```
-fn parse_arguments(args: &[&str]) -> (bool, Option<String>) {
+fn parse_arguments(args: &[&str]) -> Result<(Options, Option<String>), String> {
+    let mut options = Options::default();
     let mut target: Option<String> = None;
-    let recursive = false;
 
-    for arg in args {
-        // Prendre le premier argument qui comme chemin
-        target = Some(arg.to_string());
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match *arg {
+            "-a" | "--all" => options.all = true,
+            "--group-directories-first" => options.group_directories_first = true,
+            "--tree" => options.tree = true,
+            "--summary" => options.summary = true,
+            "-F" | "--classify" => options.classify = true,
+            "-l" => options.long = true,
+            "--dir-slash" => options.dir_slash = true,
+            "--octal-perms" => options.octal_perms = true,
+            "--git" => options.git = true,
+            "--bars" => options.bars = true,
+            "--age-color" => options.age_color = true,
+            "--format=csv" => options.format = Some(Format::Csv),
+            "--format=tsv" => options.format = Some(Format::Tsv),
+            flag if flag.starts_with("--format") => {
+                return Err(format!("Format invalide : '{}' (attendu : --format=csv ou --format=tsv)", flag));
+            }
+            "--depth" => {
+                // La profondeur est l'argument suivant
+                match iter.next().and_then(|n| n.parse::<usize>().ok()) {
+                    Some(n) if n > 0 => options.depth = Some(n),
+                    _ => return Err("L'option --depth attend un nombre strictement positif".to_string()),
+                }
+            }
+            "--count-depth" => match iter.next().and_then(|n| n.parse::<usize>().ok()) {
+                Some(n) if n > 0 => options.count_depth = Some(n),
+                _ => return Err("L'option --count-depth attend un nombre strictement positif".to_string()),
+            },
+            "--max-name-width" => match iter.next().and_then(|n| n.parse::<usize>().ok()) {
+                Some(n) if n > 0 => options.max_name_width = Some(n),
+                _ => return Err("L'option --max-name-width attend un nombre de colonnes strictement positif".to_string()),
+            },
+            // La dernière de --max-name-width et --no-truncate l'emporte
+            "--no-truncate" => options.max_name_width = None,
+            "--manifest" => options.manifest = true,
+            "--by-ext" => options.by_ext = true,
+            "--diff" => match iter.next() {
+                Some(dir) if !dir.is_empty() => options.diff = Some(dir.to_string()),
+                _ => return Err("L'option --diff attend le dossier à comparer".to_string()),
+            },
+            "--output" => match iter.next() {
+                Some(file) if !file.is_empty() => options.output = Some(file.to_string()),
+                _ => return Err("L'option --output attend un nom de fichier".to_string()),
+            },
+            // Prendre l'argument comme chemin
+            _ => target = Some(arg.to_string()),
+        }
     }
 
-    (recursive, target)
+    Ok((options, target))
 }
 
-
-/// Résout un chemin en gérant les cas spéciaux comme `.` (dossier courant).
+/// Affiche récursivement le contenu d'un dossier sous forme d'arborescence.
 ///
 /// # Algorithme
-/// - Si le chemin est `.`, retourne le dossier courant.
-/// - Sinon, retourne le chemin tel quel.
+/// - Lit et trie les entrées du dossier.
+/// - Affiche chaque entrée précédée du préfixe courant et du connecteur
+///   `├──` (ou `└──` pour la dernière entrée).
+/// - Pour un sous-dossier, s'appelle récursivement en prolongeant le préfixe
+///   avec `│   ` (ou des espaces après la dernière entrée).
+/// - S'arrête à la profondeur `--depth` si elle est fournie.
+///
+/// Les liens symboliques vers des dossiers sont affichés mais jamais parcourus,
+/// ce qui évite les boucles infinies.
 ///
 /// # Arguments
-/// * `path` - Chemin à résoudre.
+/// * `dir` - Dossier à afficher.
+/// * `prefix` - Préfixe des connecteurs des niveaux supérieurs.
+/// * `level` - Niveau courant (1 pour le dossier cible).
+/// * `options` - Options d'affichage.
+/// * `summary` - Compteurs mis à jour pour chaque entrée affichée.
+/// * `out` - Destination de l'affichage.
+fn print_tree(
+    dir: &Path,
+    prefix: &str,
+    level: usize,
+    options: &Options,
+    summary: &mut Summary,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => sort_entries(visible_entries(entries.flatten().collect(), options), options),
+        Err(e) => return writeln!(out, "{}└── ❌ {}", prefix, e),
+    };
+
+    let count = entries.len();
+    for (index, entry) in entries.into_iter().enumerate() {
+        let is_last = index + 1 == count;
+        let connector = if is_last { "└── " } else { "├── " };
+        writeln!(out, "{}{}{}", prefix, connector, entry_label(&entry, options))?;
+        summary.add(&entry);
+
+        // file_type() ne suit pas les liens symboliques
+        let is_real_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+        if is_real_dir && options.depth.is_none_or(|d| level < d) {
+            let child_prefix = format!("{}{}", prefix, if is_last { "    " } else { "│   " });
+            print_tree(&entry.path(), &child_prefix, level + 1, options, summary, out)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Affiche le nombre d'entrées directes de chaque dossier, sur `max_depth` niveaux (`--count-depth`).
+///
+/// # Algorithme
+/// - Parcours en largeur avec une file : le dossier cible (niveau 1), puis ses
+///   sous-dossiers (niveau 2), etc., jusqu'au niveau `max_depth` inclus.
+/// - Chaque dossier est lu une seule fois : son nombre d'entrées est affiché et
+///   ses sous-dossiers sont ajoutés à la file s'ils restent dans la limite.
+/// - Un dossier illisible (ex. permission refusée) est signalé sur sa ligne
+///   et le parcours continue avec les suivants.
+///
+/// Comme `--tree`, les liens symboliques vers des dossiers ne sont pas parcourus.
+///
+/// # Exemple
+/// ```text
+/// ✅ Nombre d'entrées par dossier (profondeur 2) :
+///  - . : 4 entrées
+///  - src : 12 entrées
+///  - tests : ❌ Permission denied (os error 13)
+/// ```
+fn write_counts(root: &Path, max_depth: usize, options: &Options, out: &mut impl Write) -> io::Result<()> {
+    let mut queue: VecDeque<(PathBuf, usize)> = VecDeque::from([(PathBuf::new(), 1)]);
+
+    while let Some((relative, level)) = queue.pop_front() {
+        let label = if relative.as_os_str().is_empty() {
+            ".".to_string()
+        } else {
+            relative.display().to_string()
+        };
+
+        let entries = match fs::read_dir(root.join(&relative)) {
+            Ok(entries) => sort_entries(visible_entries(entries.flatten().collect(), options), options),
+            Err(e) => {
+                writeln!(out, " - {} : ❌ {}", label, e)?;
+                continue;
+            }
+        };
+
+        let plural = if entries.len() > 1 { "s" } else { "" };
+        writeln!(out, " - {} : {} entrée{}", label, entries.len(), plural)?;
+
+        if level < max_depth {
+            for entry in &entries {
+                // file_type() ne suit pas les liens symboliques
+                if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                    queue.push_back((relative.join(entry.file_name()), level + 1));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Compare le dossier listé avec le dossier `other` (`--diff`).
+///
+/// # Algorithme
+/// - Lit les deux dossiers (filtrés par le motif éventuel) et range leurs noms
+///   dans deux ensembles.
+/// - Écrit trois sections : les entrées seulement dans le dossier listé, seulement
+///   dans `other`, puis dans les deux, chacune dans l'ordre de [`sort_entries`].
+/// - Pour une entrée commune qui est un fichier des deux côtés, indique les deux
+///   tailles si elles diffèrent (le contenu n'est pas comparé).
+///
+/// Les erreurs de lecture sont affichées dans la sortie ; seule une erreur
+/// d'écriture est retournée.
+///
+/// # Exemple
+/// ```text
+/// ✅ Comparaison de '/sauvegardes/lundi' avec '/sauvegardes/mardi' :
+///
+/// 📂 Seulement dans '/sauvegardes/lundi' (1) :
+///  - ancien.txt
+/// 📂 Seulement dans '/sauvegardes/mardi' (1) :
+///  - nouveau.txt
+/// 📂 Dans les deux (2) :
+///  - notes.txt (taille : 120 o → 2.4 Ko)
+///  - photos
+/// ```
+fn write_diff(dir: &Path, other: &str, pattern: Option<&str>, options: &Options, out: &mut impl Write) -> io::Result<()> {
+    let other = match path::resolve(other) {
+        Ok(other) => other,
+        Err(e) => {
+            writeln!(out, "❌ Erreur : Impossible de résoudre le chemin : {}", e)?;
+            return Ok(());
+        }
+    };
+
+    let (entries, other_entries) = match (read_entries(dir, pattern, options), read_entries(&other, pattern, options)) {
+        (Ok(entries), Ok(other_entries)) => (entries, other_entries),
+        (Err(e), _) => {
+            writeln!(out, "❌ Erreur : Impossible de lire '{}' : {}", dir.display(), e)?;
+            return Ok(());
+        }
+        (_, Err(e)) => {
+            writeln!(out, "❌ Erreur : Impossible de lire '{}' : {}", other.display(), e)?;
+            return Ok(());
+        }
+    };
+
+    let names: HashSet<OsString> = entries.iter().map(|entry| entry.file_name()).collect();
+    let other_by_name: HashMap<OsString, &fs::DirEntry> =
+        other_entries.iter().map(|entry| (entry.file_name(), entry)).collect();
+
+    let only_here: Vec<&fs::DirEntry> =
+        entries.iter().filter(|entry| !other_by_name.contains_key(&entry.file_name())).collect();
+    let only_there: Vec<&fs::DirEntry> =
+        other_entries.iter().filter(|entry| !names.contains(&entry.file_name())).collect();
+    let common: Vec<(&fs::DirEntry, &fs::DirEntry)> = entries
+        .iter()
+        .filter_map(|entry| other_by_name.get(&entry.file_name()).map(|other_entry| (entry, *other_entry)))
+        .collect();
+
+    writeln!(out, "✅ Comparaison de '{}' avec '{}' :", dir.display(), other.display())?;
+    writeln!(out)?;
+
+    let sections = [
+        (format!("Seulement dans '{}'", dir.display()), only_here),
+        (format!("Seulement dans '{}'", other.display()), only_there),
+    ];
+    for (title, section) in sections {
+        writeln!(out, "📂 {} ({}) :", title, section.len())?;
+        if section.is_empty() {
+            writeln!(out, "   (aucune)")?;
+        }
+        for entry in section {
+            writeln!(out, " - {}", entry_label(entry, options))?;
+        }
+    }
+
+    writeln!(out, "📂 Dans les deux ({}) :", common.len())?;
+    if common.is_empty() {
+        writeln!(out, "   (aucune)")?;
+    }
+    for (entry, other_entry) in common {
+        match size_change(entry, other_entry) {
+            Some(change) => writeln!(out, " - {} (taille : {})", entry_label(entry, options), change)?,
+            None => writeln!(out, " - {}", entry_label(entry, options))?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Répartit les entrées par extension (`--by-ext`).
+///
+/// # Algorithme
+/// - Lit l'extension de chaque nom, en minuscules (`.RS` et `.rs` vont ensemble).
+///   Un nom sans point, ou qui commence par son seul point (`.gitignore`), n'en a pas.
+/// - Trie les groupes par extension, le groupe `(sans extension)` en dernier.
+/// - Dans chaque groupe, garde l'ordre des entrées (celui de [`sort_entries`]).
+///
+/// # Retour
+/// Pour chaque groupe, son titre (`*.rs` ou `(sans extension)`) et la position de
+/// ses entrées dans `entries`.
+///
+/// # Exemple
+/// ```text
+/// entrées : Makefile, a.rs, b.RS, notes.txt
+/// groupes : *.rs → [1, 2], *.txt → [3], (sans extension) → [0]
+/// ```
+fn extension_groups(entries: &[fs::DirEntry]) -> Vec<(String, Vec<usize>)> {
+    let mut by_extension: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    let mut without_extension = Vec::new();
+
+    for (index, entry) in entries.iter().enumerate() {
+        match Path::new(&entry.file_name()).extension() {
+            Some(extension) => by_extension
+                .entry(extension.to_string_lossy().to_lowercase())
+                .or_default()
+                .push(index),
+            None => without_extension.push(index),
+        }
+    }
+
+    let mut groups: Vec<(String, Vec<usize>)> = by_extension
+        .into_iter()
+        .map(|(extension, indices)| (format!("*.{}", extension), indices))
+        .collect();
+    if !without_extension.is_empty() {
+        groups.push(("(sans extension)".to_string(), without_extension));
+    }
+    groups
+}
+
+/// Retourne `"avant → après"` si deux entrées sont des fichiers de tailles différentes
+/// (tailles lisibles, ou en octets si l'arrondi les rend identiques), sinon `None`.
+/// Les liens symboliques ne sont pas suivis.
+fn size_change(entry: &fs::DirEntry, other: &fs::DirEntry) -> Option<String> {
+    let len = |entry: &fs::DirEntry| {
+        fs::symlink_metadata(entry.path()).ok().filter(|metadata| metadata.is_file()).map(|metadata| metadata.len())
+    };
+    let (before, after) = (len(entry)?, len(other)?);
+    if before == after {
+        return None;
+    }
+
+    let (readable_before, readable_after) = (size::human_readable(before), size::human_readable(after));
+    if readable_before == readable_after {
+        Some(format!("{} o → {} o", before, after))
+    } else {
+        Some(format!("{} → {}", readable_before, readable_after))
+    }
+}
+
+/// Écrit le manifeste des fichiers réguliers de `entries` (`--manifest`).
+///
+/// # Algorithme
+/// - Ignore les dossiers, les liens symboliques et les fichiers spéciaux.
+/// - Écrit une ligne par fichier, dans l'ordre des entrées (triées par nom) :
+///   nom, taille en octets et empreinte SHA-256 ([`sum::file_digest`]),
+///   séparés par des tabulations.
+/// - Un fichier illisible garde sa ligne, avec l'erreur à la place de l'empreinte.
+///
+/// Le même dossier donne donc toujours le même manifeste, qui peut être
+/// comparé avec `diff` pour vérifier son intégrité.
 ///
 /// # Retour
-/// `Option<String>` contenant le chemin résolu, ou `None` en cas d'erreur.
+/// Le nombre de fichiers écrits.
+///
+/// # Exemple
+/// ```text
+/// a.txt    6  5891b5b522d5df086d0ff0b110fbd9d21bb4fc7163af34d08286a2e846f6be03
+/// vide.txt 0  e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855
+/// ```
+fn write_manifest(entries: &[fs::DirEntry], out: &mut impl Write) -> io::Result<usize> {
+    let mut count = 0;
+
+    for entry in entries {
+        // file_type() ne suit pas les liens symboliques
+        if !entry.file_type().is_ok_and(|t| t.is_file()) {
+            continue;
+        }
+
+        let name = entry.file_name().to_string_lossy().to_string();
+        let size = entry.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        match sum::file_digest(&entry.path()) {
+            Ok(digest) => writeln!(out, "{}\t{}\t{}", name, size, digest)?,
+            Err(e) => writeln!(out, "{}\t{}\t❌ {}", name, size, e)?,
+        }
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+/// Écrit le manifeste du dossier dans le fichier `output` (`--manifest --output`).
+///
+/// Le fichier de sortie lui-même est exclu s'il se trouve dans le dossier listé,
+/// sinon son empreinte serait celle d'un fichier en cours d'écriture.
+///
+/// # Retour
+/// Le nombre de fichiers du manifeste.
+fn save_manifest(dir: &Path, pattern: Option<&str>, options: &Options, output: &str) -> io::Result<usize> {
+    let output_path = path::resolve(output)?;
+    let mut entries = read_entries(dir, pattern, options)?;
+    entries.retain(|entry| entry.path() != output_path);
+
+    let mut file = io::BufWriter::new(File::create(&output_path)?);
+    let count = write_manifest(&entries, &mut file)?;
+    file.flush()?;
+    Ok(count)
+}
+
+/// Sépare un argument contenant un motif en `(dossier, motif)`.
+///
+/// Seul le dernier composant peut contenir des jokers (`*`, `?`, `[...]`) :
+/// `"src/*.rs"` donne `("src", "*.rs")` et `"*.txt"` donne `(".", "*.txt")`.
+///
+/// # Retour
+/// `Ok(None)` si l'argument n'est pas un motif (dossier à lister normalement),
+/// ou un message d'erreur si un joker apparaît dans le chemin du dossier.
+fn split_pattern(target: &str) -> Result<Option<(String, String)>, String> {
+    if !glob::is_pattern(target) {
+        return Ok(None);
+    }
+
+    let (dir, pattern) = match target.rfind(['/', '\\']) {
+        Some(0) => ("/", &target[1..]),
+        Some(index) => (&target[..index], &target[index + 1..]),
+        None => (".", target),
+    };
+
+    if glob::is_pattern(dir) {
+        return Err(format!("Les jokers ne sont acceptés que dans le dernier élément du chemin : '{}'", target));
+    }
+
+    Ok(Some((dir.to_string(), pattern.to_string())))
+}
+
+/// Lit les entrées d'un dossier, filtrées par le motif éventuel et triées
+/// selon les options (voir [`sort_entries`]).
+///
+/// Sans motif, les entrées cachées sont écartées sauf avec `-a` ([`visible_entries`]) ;
+/// avec un motif, c'est lui qui décide (voir [`matches_pattern`]).
+fn read_entries(dir: &Path, pattern: Option<&str>, options: &Options) -> io::Result<Vec<fs::DirEntry>> {
+    let entries: Vec<fs::DirEntry> = fs::read_dir(dir)?.flatten().collect();
+    let entries = match pattern {
+        Some(pattern) => entries.into_iter().filter(|entry| matches_pattern(pattern, entry)).collect(),
+        None => visible_entries(entries, options),
+    };
+    Ok(sort_entries(entries, options))
+}
+
+/// Écarte les entrées cachées (nom commençant par `.`), sauf avec `-a`.
+///
+/// Comme `ls -A`, les entrées spéciales `.` et `..` ne sont jamais listées :
+/// `fs::read_dir` ne les retourne pas.
+fn visible_entries(entries: Vec<fs::DirEntry>, options: &Options) -> Vec<fs::DirEntry> {
+    if options.all {
+        return entries;
+    }
+    entries
+        .into_iter()
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .collect()
+}
+
+/// Retourne la lettre de statut git de chaque entrée (`--git`), dans le même ordre.
+///
+/// Les lettres sont celles de `git status --short` : `M` (modifié), `?` (non suivi),
+/// `!` (exclu par `.gitignore`) ou une espace (propre). Voir [`git::Repository::status`].
+///
+/// # Retour
+/// `Ok(None)` si le dossier n'est pas dans un dépôt git.
+fn git_letters(dir: &Path, entries: &[fs::DirEntry]) -> io::Result<Option<Vec<char>>> {
+    let Some(repository) = git::Repository::discover(dir)? else {
+        return Ok(None);
+    };
+
+    let dir = fs::canonicalize(dir)?;
+    let letters = entries
+        .iter()
+        .map(|entry| repository.status(&dir.join(entry.file_name())).letter())
+        .collect();
+    Ok(Some(letters))
+}
+
+/// Commande `ls` utilisable dans un pipeline (`ls src | wc -l`).
+///
+/// Comme `ls` dont la sortie n'est pas un terminal, écrit un nom par ligne,
+/// sans en-tête ni décoration. Sans chemin, liste le dossier courant.
+/// L'entrée éventuelle est ignorée.
+pub struct LsCommand {
+    args: Vec<String>,
+}
+
+impl LsCommand {
+    pub fn new(args: &[String]) -> Self {
+        LsCommand { args: args.to_vec() }
+    }
+}
+
+impl pipeline::Command for LsCommand {
+    fn run(&self, _input: Option<&[u8]>, _terminal: bool) -> io::Result<Vec<u8>> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidInput, message);
+
+        let args: Vec<&str> = self.args.iter().map(|s| s.as_str()).collect();
+        let (options, target) = parse_arguments(&args).map_err(invalid)?;
+        if options.tree || options.count_depth.is_some() || options.by_ext {
+            return Err(invalid("Les options --tree, --count-depth et --by-ext ne peuvent pas être utilisées dans un pipeline".to_string()));
+        }
+        if options.git && options.format.is_some() {
+            return Err(invalid("L'option --git ne peut pas être combinée avec --format".to_string()));
+        }
+        if options.output.is_some() || options.diff.is_some() {
+            return Err(invalid("Les options --output et --diff ne peuvent pas être utilisées dans un pipeline".to_string()));
+        }
+
+        let target = target.unwrap_or_else(|| ".".to_string());
+        let (dir, pattern) = match split_pattern(&target).map_err(invalid)? {
+            Some((dir, pattern)) => (dir, Some(pattern)),
+            None => (target, None),
+        };
+
+        let dir = path::resolve(&dir)?;
+        let entries = read_entries(&dir, pattern.as_deref(), &options)?;
+        let mut output = Vec::new();
+        if options.manifest {
+            write_manifest(&entries, &mut output)?;
+            return Ok(output);
+        }
+        if let Some(format) = options.format {
+            write_delimited(&mut output, &entries, format)?;
+            return Ok(output);
+        }
+        let letters = if options.git { git_letters(&dir, &entries)? } else { None };
+        for (index, entry) in entries.iter().enumerate() {
+            match &letters {
+                Some(letters) => writeln!(output, "{} {}", letters[index], entry_label(entry, &options))?,
+                None => writeln!(output, "{}", entry_label(entry, &options))?,
+            }
+        }
+        Ok(output)
+    }
+}
+
+/// Écrit les entrées au format CSV ou TSV (`--format`).
+///
+/// # Algorithme
+/// - Écrit l'en-tête `name`, `type`, `size`, `modified`.
+/// - Écrit une ligne par entrée : nom, type (`directory`, `file`, `symlink` ou `other`),
+///   taille en octets et date de modification (UTC), lus comme `-l` sans suivre les liens.
+/// - Protège chaque champ avec [`quote_field`].
+///
+/// # Exemple
+/// ```text
+/// name,type,size,modified
+/// src,directory,4096,2026-10-16 09:30:00 UTC
+/// "a,b.txt",file,12,2026-10-16 09:31:00 UTC
+/// ```
+fn write_delimited(out: &mut impl Write, entries: &[fs::DirEntry], format: Format) -> io::Result<()> {
+    let delimiter = format.delimiter();
+    let write_row = |out: &mut dyn Write, fields: [&str; 4]| {
+        let fields: Vec<String> = fields.iter().map(|field| quote_field(field, delimiter)).collect();
+        writeln!(out, "{}", fields.join(&delimiter.to_string()))
+    };
+
+    write_row(out, ["name", "type", "size", "modified"])?;
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let (kind, size, modified) = match fs::symlink_metadata(entry.path()) {
+            Ok(metadata) => {
+                let file_type = metadata.file_type();
+                let kind = if file_type.is_symlink() {
+                    "symlink"
+                } else if file_type.is_dir() {
+                    "directory"
+                } else if file_type.is_file() {
+                    "file"
+                } else {
+                    "other"
+                };
+                let modified = metadata.modified().map(date::format).unwrap_or_default();
+                (kind, metadata.len().to_string(), modified)
+            }
+            Err(_) => ("other", String::new(), String::new()),
+        };
+        write_row(out, [&name, kind, &size, &modified])?;
+    }
+
+    Ok(())
+}
+
+/// Protège un champ CSV/TSV : un champ contenant le séparateur, un guillemet
+/// ou un retour à la ligne est entouré de guillemets, ses guillemets étant doublés.
 ///
 /// # Exemple
 /// ```rust
-/// let resolved = resolve_path(".").unwrap();
-/// // resolved contient le chemin absolu du dossier courant
+/// assert_eq!(quote_field("notes.txt", ','), "notes.txt");
+/// assert_eq!(quote_field("a,b.txt", ','), "\"a,b.txt\"");
+/// assert_eq!(quote_field("dit \"oui\"", ','), "\"dit \"\"oui\"\"\"");
 /// ```
-fn resolve_path(path: &str) -> Option<String> {
-    if path == "." {
-        // Récupérer le dossier courant
-        env::current_dir()
-            .ok()
-            .map(|p| p.to_string_lossy().to_string())
+fn quote_field(field: &str, delimiter: char) -> String {
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
     } else {
-        Some(path.to_string())
+        field.to_string()
+    }
+}
+
+/// Indique si le nom d'une entrée correspond au motif.
+///
+/// Comme dans un shell, les entrées cachées (commençant par `.`) ne correspondent
+/// qu'à un motif commençant lui-même par `.`.
+fn matches_pattern(pattern: &str, entry: &fs::DirEntry) -> bool {
+    let name = entry.file_name().to_string_lossy().into_owned();
+    (!name.starts_with('.') || pattern.starts_with('.')) && glob::matches(pattern, &name)
+}
+
+/// Retourne le nom à afficher pour une entrée, suivi de son indicateur avec `-F`
+/// (ou d'un `/` pour les dossiers avec `--dir-slash`).
+///
+/// Avec `--max-name-width N`, un nom plus large que `N` colonnes est coupé par
+/// [`width::truncate`] avant l'ajout de l'indicateur, qui reste donc visible.
+///
+/// Dans un terminal, un lien symbolique cassé (voir [`is_broken_symlink`]) est suivi de
+/// `!` et affiché en rouge ; redirigé ou dans un pipeline, son nom reste intact. Avec
+/// `-l`, il est suivi de la cible introuvable (`-> cible`).
+///
+/// # Arguments
+/// * `entry` - Entrée du dossier.
+/// * `options` - Options d'affichage.
+fn entry_label(entry: &fs::DirEntry, options: &Options) -> String {
+    let mut name = entry.file_name().to_string_lossy().to_string();
+    if let Some(max_width) = options.max_name_width {
+        name = width::truncate(&name, max_width);
+    }
+    if options.classify {
+        name.push_str(classify_indicator(entry));
+    } else if options.dir_slash && fs::metadata(entry.path()).is_ok_and(|m| m.is_dir()) {
+        // fs::metadata suit les liens : un lien vers un dossier est aussi marqué
+        name.push('/');
+    }
+    if is_broken_symlink(&entry.path()) {
+        if options.color {
+            name = format!("\x1b[31m{}!\x1b[0m", name);
+        }
+        if options.long
+            && let Ok(target) = fs::read_link(entry.path())
+        {
+            name = format!("{} -> {}", name, target.display());
+        }
+    } else if options.age_color
+        && let Some(code) = entry_age_bucket(entry).and_then(AgeBucket::color_code)
+    {
+        name = format!("\x1b[{}m{}\x1b[0m", code, name);
+    }
+    if options.long {
+        name = format!("{} {}", long_details(entry, options), name);
+    }
+    name
+}
+
+/// Retourne la tranche d'ancienneté d'une entrée (`--age-color`), d'après la date de
+/// modification lue sans suivre les liens. Une date dans le futur compte comme récente ;
+/// `None` si la date est illisible.
+fn entry_age_bucket(entry: &fs::DirEntry) -> Option<AgeBucket> {
+    let modified = fs::symlink_metadata(entry.path()).ok()?.modified().ok()?;
+    let age = SystemTime::now().duration_since(modified).unwrap_or_default();
+    Some(AgeBucket::from_age(age))
+}
+
+/// Indique si le chemin est un lien symbolique dont la cible n'existe pas :
+/// `symlink_metadata` (le lien lui-même) réussit mais `metadata` (la cible) échoue.
+fn is_broken_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_symlink())
+        && fs::metadata(path).is_err()
+}
+
+/// Retourne les colonnes de `-l` : permissions, taille et date de modification.
+///
+/// Les métadonnées sont lues sans suivre les liens symboliques.
+/// Une entrée illisible affiche des `?` à la place.
+fn long_details(entry: &fs::DirEntry, options: &Options) -> String {
+    let Ok(metadata) = fs::symlink_metadata(entry.path()) else {
+        return format!("{:10} {:>9} {:23}", "?", "?", "?");
+    };
+
+    let modified = metadata.modified().map(date::format).unwrap_or_else(|_| "?".to_string());
+    let mut details = format!(
+        "{} {:>9}",
+        entry_mode(&metadata, options.octal_perms),
+        size::human_readable(metadata.len()),
+    );
+    // Les dossiers et les liens n'ont pas de barre : la colonne reste alignée
+    if let Some(scale) = options.bar_scale {
+        let bar = if metadata.is_file() { size_bar(metadata.len(), scale) } else { String::new() };
+        details.push_str(&format!(" {:width$}", bar, width = BAR_WIDTH));
+    }
+    format!("{} {}", details, modified)
+}
+
+/// Retourne la taille du plus gros fichier parmi les entrées (liens non suivis),
+/// ou 0 s'il n'y a aucun fichier.
+fn largest_file_size(entries: &[fs::DirEntry]) -> u64 {
+    entries
+        .iter()
+        .filter_map(|entry| fs::symlink_metadata(entry.path()).ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .max()
+        .unwrap_or(0)
+}
+
+/// Dessine la barre de `--bars` d'un fichier de `size` octets, le plus gros fichier
+/// (`scale` octets) occupant [`BAR_WIDTH`] colonnes.
+///
+/// # Algorithme
+/// - Convertit la taille en huitièmes de colonne, arrondis au plus proche.
+/// - Dessine une colonne `█` par tranche de huit, puis un bloc partiel (`▏` à `▉`)
+///   pour le reste.
+/// - Un fichier non vide a toujours au moins `▏`, pour le distinguer d'un fichier vide.
+///
+/// # Exemple
+/// ```rust
+/// assert_eq!(size_bar(1000, 1000), "██████████");
+/// assert_eq!(size_bar(250, 1000), "██▌");
+/// assert_eq!(size_bar(1, 1000), "▏");
+/// assert_eq!(size_bar(0, 1000), "");
+/// ```
+fn size_bar(size: u64, scale: u64) -> String {
+    let total = (BAR_WIDTH * BAR_BLOCKS.len()) as u128;
+    let Some(eighths) = (u128::from(size) * total + u128::from(scale) / 2).checked_div(u128::from(scale)) else {
+        return String::new();
+    };
+    let eighths = if size > 0 { eighths.max(1) } else { 0 } as usize;
+
+    let mut bar = BAR_BLOCKS[7].to_string().repeat(eighths / 8);
+    if let Some(partial) = (eighths % 8).checked_sub(1) {
+        bar.push(BAR_BLOCKS[partial]);
+    }
+    bar
+}
+
+/// Retourne les permissions d'une entrée avec [`format_mode`] (Unix).
+#[cfg(unix)]
+fn entry_mode(metadata: &fs::Metadata, octal: bool) -> String {
+    use std::os::unix::fs::MetadataExt;
+
+    format_mode(metadata.mode(), octal)
+}
+
+/// Sans permissions Unix, affiche un emplacement vide de même largeur.
+#[cfg(not(unix))]
+fn entry_mode(_metadata: &fs::Metadata, octal: bool) -> String {
+    if octal { "----".to_string() } else { "----------".to_string() }
+}
+
+/// Formate un mode Unix (`st_mode`) en symbolique ou en octal.
+///
+/// - Symbolique : type (`d`, `l`, `-`, `p`, `s`, `c`, `b`) suivi des droits
+///   propriétaire, groupe et autres (ex. `drwxr-xr-x`). Les bits setuid/setgid
+///   et sticky s'affichent `s`/`S` et `t`/`T` à la place du `x`.
+/// - Octal : quatre chiffres, bits spéciaux compris (ex. `0644`, `4755`).
+///
+/// # Exemple
+/// ```rust
+/// assert_eq!(format_mode(0o100644, false), "-rw-r--r--");
+/// assert_eq!(format_mode(0o040755, false), "drwxr-xr-x");
+/// assert_eq!(format_mode(0o041777, false), "drwxrwxrwt");
+/// assert_eq!(format_mode(0o104754, true), "4754");
+/// ```
+#[cfg(unix)]
+fn format_mode(mode: u32, octal: bool) -> String {
+    if octal {
+        return format!("{:04o}", mode & 0o7777);
+    }
+
+    let file_type = match mode & 0o170000 {
+        0o040000 => 'd',
+        0o120000 => 'l',
+        0o010000 => 'p',
+        0o140000 => 's',
+        0o020000 => 'c',
+        0o060000 => 'b',
+        _ => '-',
+    };
+
+    let mut symbolic = String::with_capacity(10);
+    symbolic.push(file_type);
+
+    // (décalage des bits rwx, bit spécial, lettre du bit spécial)
+    for (shift, special, letter) in [(6, 0o4000, 's'), (3, 0o2000, 's'), (0, 0o1000, 't')] {
+        let bits = (mode >> shift) & 0o7;
+        symbolic.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        symbolic.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        symbolic.push(match (bits & 0o1 != 0, mode & special != 0) {
+            (true, true) => letter,
+            (false, true) => letter.to_ascii_uppercase(),
+            (true, false) => 'x',
+            (false, false) => '-',
+        });
+    }
+
+    symbolic
+}
+
+/// Retourne l'indicateur de type d'une entrée, comme `ls -F`.
+///
+/// # Algorithme
+/// - `@` pour un lien symbolique (le lien n'est pas suivi).
+/// - `/` pour un dossier.
+/// - `|` pour un tube nommé (FIFO) et `=` pour une socket (Unix).
+/// - `*` pour un fichier exécutable (un des bits `x` positionné, Unix).
+/// - Rien pour un fichier ordinaire.
+///
+/// # Exemple
+/// ```no_run
+/// // dossier/  lien@  script.sh*  notes.txt
+/// ```
+fn classify_indicator(entry: &fs::DirEntry) -> &'static str {
+    let Ok(metadata) = fs::symlink_metadata(entry.path()) else {
+        return "";
+    };
+    let file_type = metadata.file_type();
+
+    if file_type.is_symlink() {
+        return "@";
+    }
+    if file_type.is_dir() {
+        return "/";
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+        if file_type.is_fifo() {
+            return "|";
+        }
+        if file_type.is_socket() {
+            return "=";
+        }
+        if metadata.permissions().mode() & 0o111 != 0 {
+            return "*";
+        }
+    }
+
+    ""
+}
+
+/// Trie les entrées d'un dossier selon les options.
+///
+/// # Algorithme
+/// - Trie les entrées par nom.
+/// - Si `group_directories_first`, sépare les dossiers des fichiers
+///   puis place les dossiers en premier (chaque groupe reste trié).
+///
+/// # Arguments
+/// * `entries` - Entrées lues avec `fs::read_dir`.
+/// * `options` - Options d'affichage.
+///
+/// # Retour
+/// Vecteur d'entrées dans l'ordre d'affichage.
+fn sort_entries(mut entries: Vec<fs::DirEntry>, options: &Options) -> Vec<fs::DirEntry> {
+    entries.sort_by_key(|entry| entry.file_name());
+
+    if options.group_directories_first {
+        let (mut dirs, files): (Vec<_>, Vec<_>) = entries
+            .into_iter()
+            .partition(|entry| entry.path().is_dir());
+        dirs.extend(files);
+        entries = dirs;
     }
+
+    entries
 }
 
 
@@ -311,13 +1494,472 @@ fn display_help() {
     println!();
     println!("Options:");
     println!("  .                       Afficher les contenus du dossier courant");
+    println!("  MOTIF                    Filtre les entrées avec *, ? ou [...] (ex: \"*.txt\")");
+    println!("  -a, --all                Affiche aussi les entrées cachées (nom commençant par .)");
+    println!("      --group-directories-first");
+    println!("                           Affiche les dossiers avant les fichiers");
+    println!("      --tree               Affiche le contenu sous forme d'arborescence");
+    println!("      --depth N            Limite la profondeur de l'arborescence à N niveaux");
+    println!("      --count-depth N      Affiche seulement le nombre d'entrées de chaque dossier,");
+    println!("                           sur N niveaux (plus léger que --tree)");
+    println!("      --by-ext             Regroupe les entrées par extension (*.rs, *.txt...,");
+    println!("                           puis (sans extension)), chaque groupe dans l'ordre habituel");
+    println!("      --summary            Affiche le nombre de dossiers, fichiers et liens symboliques");
+    println!("  -F, --classify           Ajoute un indicateur de type : / (dossier), @ (lien),");
+    println!("                           * (exécutable), | (FIFO), = (socket)");
+    println!("      --dir-slash          Ajoute / après le nom des dossiers (fichiers inchangés)");
+    println!("  -l                       Affiche permissions, taille et date de modification");
+    println!("                           (et la cible des liens symboliques cassés, marqués par !)");
+    println!("      --octal-perms        Avec -l, affiche les permissions en octal (ex: 0644)");
+    println!("      --bars               Avec -l, ajoute une barre proportionnelle à la taille de chaque");
+    println!("                           fichier (uniquement dans un terminal)");
+    println!("      --age-color          Avec -l, colore les noms modifiés depuis moins d'un jour (vert)");
+    println!("                           ou d'une semaine (jaune) (uniquement dans un terminal)");
+    println!("      --max-name-width N   Coupe les noms plus larges que N colonnes avec …");
+    println!("                           (caractères larges comptés sur deux colonnes)");
+    println!("      --no-truncate        Affiche les noms en entier (annule --max-name-width)");
+    println!("      --format=csv|tsv     Une ligne par entrée (nom, type, taille, date) pour un tableur");
+    println!("      --manifest           Une ligne par fichier : nom, taille et empreinte SHA-256");
+    println!("                           (séparés par des tabulations, triés par nom)");
+    println!("      --output FICHIER     Avec --manifest, écrit le manifeste dans FICHIER");
+    println!("      --diff AUTRE         Compare avec le dossier AUTRE : entrées seulement d'un côté,");
+    println!("                           puis communes (avec les tailles des fichiers qui diffèrent)");
+    println!("      --git                Dans un dépôt git, précède chaque nom de son statut :");
+    println!("                           M (modifié), ? (non suivi), ! (ignoré), espace (propre)");
     println!("      --help               Affiche cette aide et quitte");
     println!();
     println!("Exemples:");
     println!("  ls \"fichier avec espaces.txt\"  Liste tous les fichiers ou dossiers avec des espaces dans le chemin");
     println!("  ls .                     Liste le contenu du dossier courant");
+    println!("  ls \"src/*.rs\"             Liste uniquement les entrées correspondant au motif");
     println!();
     println!("Attention:");
     println!("  ⚠️  Attention avec le chemin et le dossier à lister !");
     println!("  Utilisez cette commande avec précaution.");
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdir::TestDir;
+
+    /// Noms des entrées dans l'ordre où `ls` les afficherait.
+    fn listed(dir: &TestDir, options: &Options) -> Vec<String> {
+        read_entries(dir.path(), None, options)
+            .unwrap()
+            .iter()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    fn sample() -> TestDir {
+        let dir = TestDir::new("ls");
+        dir.write("b.txt", "b");
+        dir.mkdir("z_dossier");
+        dir.write("a.txt", "a");
+        dir.mkdir("c_dossier");
+        dir.write(".cache", "");
+        dir.mkdir(".git");
+        dir
+    }
+
+    #[test]
+    fn directories_come_first_each_group_sorted() {
+        let dir = sample();
+        let options = Options { group_directories_first: true, ..Default::default() };
+        assert_eq!(listed(&dir, &options), ["c_dossier", "z_dossier", "a.txt", "b.txt"]);
+    }
+
+    #[test]
+    fn without_grouping_entries_are_sorted_by_name() {
+        let dir = sample();
+        assert_eq!(listed(&dir, &Options::default()), ["a.txt", "b.txt", "c_dossier", "z_dossier"]);
+    }
+
+    #[test]
+    fn hidden_entries_only_with_all() {
+        let dir = sample();
+        let options = Options { all: true, group_directories_first: true, ..Default::default() };
+        assert_eq!(listed(&dir, &options), [".git", "c_dossier", "z_dossier", ".cache", "a.txt", "b.txt"]);
+    }
+
+    #[test]
+    fn dot_pattern_lists_hidden_entries_without_all() {
+        let dir = sample();
+        let entries = read_entries(dir.path(), Some(".*"), &Options::default()).unwrap();
+        let names: Vec<_> = entries.iter().map(|entry| entry.file_name()).collect();
+        assert_eq!(names, [".cache", ".git"]);
+    }
+
+    #[test]
+    fn parse_all_flag() {
+        let (options, target) = parse_arguments(&["-a", "dossier"]).unwrap();
+        assert!(options.all);
+        assert_eq!(target.as_deref(), Some("dossier"));
+        assert!(parse_arguments(&["--all"]).unwrap().0.all);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn broken_link_marker_only_in_a_terminal() {
+        use crate::pipeline::Command;
+
+        let dir = TestDir::new("ls-broken");
+        std::os::unix::fs::symlink(dir.join("absent"), dir.join("lien")).unwrap();
+        let entry = fs::read_dir(dir.path()).unwrap().next().unwrap().unwrap();
+
+        assert_eq!(entry_label(&entry, &Options::default()), "lien");
+        let terminal = Options { color: true, ..Default::default() };
+        assert_eq!(entry_label(&entry, &terminal), "\x1b[31mlien!\x1b[0m");
+
+        let output = LsCommand::new(&[dir.path().display().to_string()]).run(None, false).unwrap();
+        assert_eq!(output, b"lien\n");
+    }
+
+    #[test]
+    fn git_letters_follow_the_listing_order() {
+        let outside = TestDir::new("ls-git-outside");
+        outside.write("a.txt", "");
+        let entries = read_entries(outside.path(), None, &Options::default()).unwrap();
+        assert_eq!(git_letters(outside.path(), &entries).unwrap(), None);
+
+        let dir = TestDir::new("ls-git");
+        dir.mkdir(".git");
+        dir.write(".gitignore", "*.log\n");
+        dir.write("b.log", "");
+        dir.write("a.txt", "");
+        let entries = read_entries(dir.path(), None, &Options::default()).unwrap();
+        assert_eq!(git_letters(dir.path(), &entries).unwrap(), Some(vec!['?', '!']));
+    }
+
+    #[test]
+    fn manifest_lists_regular_files_with_their_hashes() {
+        let dir = TestDir::new("ls-manifest");
+        dir.write("b.txt", "hello\n");
+        dir.write("a.txt", "");
+        dir.mkdir("dossier");
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("b.txt", dir.join("lien")).unwrap();
+
+        let entries = read_entries(dir.path(), None, &Options::default()).unwrap();
+        let mut out = Vec::new();
+        assert_eq!(write_manifest(&entries, &mut out).unwrap(), 2);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "a.txt\t0\te3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855\n\
+             b.txt\t6\t5891b5b522d5df086d0ff0b110fbd9d21bb4fc7163af34d08286a2e846f6be03\n"
+        );
+    }
+
+    #[test]
+    fn saved_manifest_leaves_out_its_own_file() {
+        let dir = TestDir::new("ls-manifest-output");
+        dir.write("a.txt", "");
+        let output = dir.join("manifeste.txt");
+        dir.write("manifeste.txt", "ancien contenu");
+
+        let count = save_manifest(dir.path(), None, &Options::default(), output.to_str().unwrap()).unwrap();
+        assert_eq!(count, 1);
+        assert!(fs::read_to_string(&output).unwrap().starts_with("a.txt\t0\t"));
+    }
+
+    #[test]
+    fn max_name_width_truncates_before_the_indicator() {
+        let dir = TestDir::new("ls-width");
+        dir.mkdir("un_dossier_au_nom_très_long");
+        dir.write("court.txt", "");
+
+        let (options, _) = parse_arguments(&["-F", "--max-name-width", "10"]).unwrap();
+        let labels: Vec<String> = read_entries(dir.path(), None, &options)
+            .unwrap()
+            .iter()
+            .map(|entry| entry_label(entry, &options))
+            .collect();
+        assert_eq!(labels, ["court.txt", "un_dossie…/"]);
+
+        let (options, _) = parse_arguments(&["--max-name-width", "10", "--no-truncate"]).unwrap();
+        assert_eq!(options.max_name_width, None);
+        assert!(parse_arguments(&["--max-name-width", "0"]).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn modes_show_type_permissions_and_special_bits() {
+        assert_eq!(format_mode(0o100644, false), "-rw-r--r--");
+        assert_eq!(format_mode(0o040755, false), "drwxr-xr-x");
+        assert_eq!(format_mode(0o120777, false), "lrwxrwxrwx");
+        assert_eq!(format_mode(0o104755, false), "-rwsr-xr-x");
+        assert_eq!(format_mode(0o104644, false), "-rwSr--r--");
+        assert_eq!(format_mode(0o102755, false), "-rwxr-sr-x");
+        assert_eq!(format_mode(0o041777, false), "drwxrwxrwt");
+        assert_eq!(format_mode(0o041776, false), "drwxrwxrwT");
+        assert_eq!(format_mode(0o010600, false), "prw-------");
+
+        assert_eq!(format_mode(0o100644, true), "0644");
+        assert_eq!(format_mode(0o104754, true), "4754");
+        assert_eq!(format_mode(0o041777, true), "1777");
+    }
+
+    #[test]
+    fn fields_are_quoted_only_when_needed() {
+        assert_eq!(quote_field("notes.txt", ','), "notes.txt");
+        assert_eq!(quote_field("a,b.txt", ','), "\"a,b.txt\"");
+        assert_eq!(quote_field("dit \"oui\"", ','), "\"dit \"\"oui\"\"\"");
+        assert_eq!(quote_field("a\tb", ','), "a\tb");
+        assert_eq!(quote_field("a,b", '\t'), "a,b");
+        assert_eq!(quote_field("a\tb", '\t'), "\"a\tb\"");
+        assert_eq!(quote_field("ligne\nsuivante", '\t'), "\"ligne\nsuivante\"");
+    }
+
+    #[test]
+    fn delimited_rows_quote_names() {
+        let dir = TestDir::new("ls-format");
+        dir.write("a,b.txt", "abc");
+        dir.write("t\tab.txt", "");
+        let entries = read_entries(dir.path(), None, &Options::default()).unwrap();
+
+        let cases = [
+            (Format::Csv, ["\"a,b.txt\",file,3,", "t\tab.txt,file,0,"]),
+            (Format::Tsv, ["a,b.txt\tfile\t3\t", "\"t\tab.txt\"\tfile\t0\t"]),
+        ];
+        for (format, expected) in cases {
+            let mut out = Vec::new();
+            write_delimited(&mut out, &entries, format).unwrap();
+            let out = String::from_utf8(out).unwrap();
+            let rows: Vec<&str> = out.lines().collect();
+            assert_eq!(rows.len(), 3);
+            assert_eq!(rows[0], ["name", "type", "size", "modified"].join(&format.delimiter().to_string()));
+            for (row, start) in rows[1..].iter().zip(expected) {
+                assert!(row.starts_with(start), "{row:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn bars_are_proportional_to_the_largest_file() {
+        assert_eq!(size_bar(1000, 1000), "█".repeat(BAR_WIDTH));
+        assert_eq!(size_bar(500, 1000), "█".repeat(BAR_WIDTH / 2));
+        assert_eq!(size_bar(250, 1000), "██▌");
+        assert_eq!(size_bar(1, 1000), "▏");
+        assert_eq!(size_bar(0, 1000), "");
+        // Aucun fichier non vide : pas d'échelle
+        assert_eq!(size_bar(0, 0), "");
+        assert_eq!(size_bar(u64::MAX, u64::MAX), "█".repeat(BAR_WIDTH));
+    }
+
+    #[test]
+    fn age_buckets_change_exactly_at_their_limits() {
+        let just_before = |limit: Duration| limit - Duration::from_nanos(1);
+        assert_eq!(AgeBucket::from_age(Duration::ZERO), AgeBucket::Recent);
+        assert_eq!(AgeBucket::from_age(just_before(RECENT_AGE)), AgeBucket::Recent);
+        assert_eq!(AgeBucket::from_age(RECENT_AGE), AgeBucket::ThisWeek);
+        assert_eq!(AgeBucket::from_age(just_before(WEEK_AGE)), AgeBucket::ThisWeek);
+        assert_eq!(AgeBucket::from_age(WEEK_AGE), AgeBucket::Older);
+    }
+
+    #[test]
+    fn diff_lists_added_removed_and_resized_entries() {
+        let before = TestDir::new("ls-diff-avant");
+        before.write("ancien.txt", "");
+        before.write("notes.txt", "abc");
+        before.write("pareil.txt", "x");
+        let after = TestDir::new("ls-diff-apres");
+        after.write("nouveau.txt", "");
+        after.write("notes.txt", "abcde");
+        after.write("pareil.txt", "y");
+
+        let mut out = Vec::new();
+        write_diff(before.path(), after.path().to_str().unwrap(), None, &Options::default(), &mut out).unwrap();
+        let (here, there) = (before.path().display(), after.path().display());
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!(
+                "✅ Comparaison de '{here}' avec '{there}' :\n\n\
+                 📂 Seulement dans '{here}' (1) :\n - ancien.txt\n\
+                 📂 Seulement dans '{there}' (1) :\n - nouveau.txt\n\
+                 📂 Dans les deux (2) :\n - notes.txt (taille : 3 o → 5 o)\n - pareil.txt\n"
+            )
+        );
+    }
+
+    #[test]
+    fn extension_groups_put_names_without_extension_last() {
+        let dir = TestDir::new("ls-by-ext");
+        for name in ["Makefile", "a.rs", "b.RS", "notes.txt", ".gitignore", ".env.local", "archive.tar.gz"] {
+            dir.write(name, "");
+        }
+        let options = Options { all: true, ..Default::default() };
+        let entries = read_entries(dir.path(), None, &options).unwrap();
+
+        let groups: Vec<(String, Vec<String>)> = extension_groups(&entries)
+            .into_iter()
+            .map(|(title, indices)| {
+                let names = indices.iter().map(|&i| entries[i].file_name().to_string_lossy().into_owned()).collect();
+                (title, names)
+            })
+            .collect();
+        let expected = [
+            ("*.gz", vec!["archive.tar.gz"]),
+            ("*.local", vec![".env.local"]),
+            ("*.rs", vec!["a.rs", "b.RS"]),
+            ("*.txt", vec!["notes.txt"]),
+            ("(sans extension)", vec![".gitignore", "Makefile"]),
+        ];
+        assert_eq!(groups.len(), expected.len());
+        for ((title, names), (expected_title, expected_names)) in groups.iter().zip(expected) {
+            assert_eq!(title, expected_title);
+            assert_eq!(names, &expected_names);
+        }
+    }
+
+    /// Arborescence de `dir` et résumé des entrées affichées.
+    fn tree_of(dir: &Path, options: &Options) -> (String, String) {
+        let mut summary = Summary::default();
+        let mut out = Vec::new();
+        print_tree(dir, "", 1, options, &mut summary, &mut out).unwrap();
+        (String::from_utf8(out).unwrap(), summary.to_string())
+    }
+
+    #[test]
+    fn tree_draws_connectors_and_counts_entries() {
+        let dir = TestDir::new("ls-tree");
+        dir.write("a.txt", "");
+        dir.write("src/lib.rs", "");
+        dir.write("src/sous/mod.rs", "");
+        dir.write("z.txt", "");
+
+        let (tree, summary) = tree_of(dir.path(), &Options::default());
+        assert_eq!(tree, "├── a.txt\n├── src\n│   ├── lib.rs\n│   └── sous\n│       └── mod.rs\n└── z.txt\n");
+        assert_eq!(summary, "2 dossiers, 4 fichiers, 0 lien symbolique");
+
+        let (tree, summary) = tree_of(dir.path(), &Options { depth: Some(1), ..Default::default() });
+        assert_eq!(tree, "├── a.txt\n├── src\n└── z.txt\n");
+        assert_eq!(summary, "1 dossier, 2 fichiers, 0 lien symbolique");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn tree_does_not_descend_into_linked_directories() {
+        let dir = TestDir::new("ls-tree-link");
+        dir.write("d/a.txt", "");
+        std::os::unix::fs::symlink(dir.path(), dir.join("d/boucle")).unwrap();
+
+        let (tree, summary) = tree_of(dir.path(), &Options::default());
+        assert_eq!(tree, "└── d\n    ├── a.txt\n    └── boucle\n");
+        assert_eq!(summary, "1 dossier, 1 fichier, 1 lien symbolique");
+    }
+
+    #[test]
+    fn counts_stop_at_the_requested_depth() {
+        let dir = TestDir::new("ls-count-depth");
+        dir.write("a.txt", "");
+        dir.write("src/lib.rs", "");
+        dir.write("src/main.rs", "");
+        dir.write("src/sous/mod.rs", "");
+        dir.mkdir("vide");
+
+        let counts = |depth: usize| {
+            let mut out = Vec::new();
+            write_counts(dir.path(), depth, &Options::default(), &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        assert_eq!(counts(1), " - . : 3 entrées\n");
+        assert_eq!(counts(2), " - . : 3 entrées\n - src : 3 entrées\n - vide : 0 entrée\n");
+        assert_eq!(counts(3), " - . : 3 entrées\n - src : 3 entrées\n - vide : 0 entrée\n - src/sous : 1 entrée\n");
+    }
+
+    /// Sortie de `ls` avec ces arguments, la cible (dossier ou motif) en dernier.
+    fn listing_of(args: &[&str], target: &str) -> String {
+        let (options, _) = parse_arguments(args).unwrap();
+        let (dir, pattern) = match split_pattern(target).unwrap() {
+            Some((dir, pattern)) => (dir, Some(pattern)),
+            None => (target.to_string(), None),
+        };
+        let mut out = Vec::new();
+        write_listing(Path::new(&dir), Path::new(&dir), pattern.as_deref(), &options, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn summary_counts_each_kind_of_entry() {
+        let dir = TestDir::new("ls-summary");
+        dir.write("a.txt", "");
+        dir.write("b.txt", "");
+        dir.write("src/lib.rs", "");
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("a.txt", dir.join("lien")).unwrap();
+        let target = dir.path().to_str().unwrap();
+
+        let links = if cfg!(unix) { "1 lien symbolique" } else { "0 lien symbolique" };
+        let listing = listing_of(&["--summary"], target);
+        assert!(listing.ends_with(&format!("\n\n📊 1 dossier, 2 fichiers, {links}\n")), "{listing}");
+        // Avec --tree, le contenu des sous-dossiers est compté aussi
+        let tree = listing_of(&["--summary", "--tree"], target);
+        assert!(tree.ends_with(&format!("\n\n📊 1 dossier, 3 fichiers, {links}\n")), "{tree}");
+        assert!(!listing_of(&[], target).contains('📊'));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn classify_marks_directories_links_and_executables() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TestDir::new("ls-classify");
+        dir.mkdir("dossier");
+        dir.write("notes.txt", "");
+        let script = dir.write("script.sh", "");
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        std::os::unix::fs::symlink("dossier", dir.join("lien")).unwrap();
+
+        let listing = listing_of(&["-F"], dir.path().to_str().unwrap());
+        assert!(listing.ends_with(" - dossier/\n - lien@\n - notes.txt\n - script.sh*\n"), "{listing}");
+    }
+
+    #[test]
+    fn pattern_lists_only_matching_entries() {
+        let dir = TestDir::new("ls-pattern");
+        dir.write("main.rs", "");
+        dir.write("lib.rs", "");
+        dir.write("notes.txt", "");
+        dir.mkdir("src.rs.bak");
+        let target = dir.join("*.rs");
+
+        let listing = listing_of(&[], target.to_str().unwrap());
+        assert_eq!(listing, "✅ Les contenus du dossier (2 entrées) : \n - lib.rs\n - main.rs\n");
+        let none = listing_of(&[], dir.join("*.md").to_str().unwrap());
+        assert_eq!(none, "⚠️  Aucune entrée ne correspond au motif '*.md'.\n");
+        assert!(split_pattern(&dir.join("*/a.rs").display().to_string()).is_err());
+    }
+
+    #[test]
+    fn dir_slash_marks_only_directories() {
+        let dir = TestDir::new("ls-dir-slash");
+        dir.mkdir("dossier");
+        dir.write("notes.txt", "");
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("dossier", dir.join("lien")).unwrap();
+        let target = dir.path().to_str().unwrap();
+
+        let expected = if cfg!(unix) { " - dossier/\n - lien/\n - notes.txt\n" } else { " - dossier/\n - notes.txt\n" };
+        let listing = listing_of(&["--dir-slash"], target);
+        assert!(listing.ends_with(expected), "{listing}");
+        assert!(!listing_of(&[], target).contains('/'));
+    }
+
+    #[test]
+    fn header_counts_the_listed_entries() {
+        let dir = TestDir::new("ls-header");
+        dir.write("a.txt", "");
+        dir.write("b.txt", "");
+        dir.write(".cache", "");
+        dir.mkdir("dossier");
+        let target = dir.path().to_str().unwrap();
+
+        assert!(listing_of(&[], target).starts_with("✅ Les contenus du dossier (3 entrées) : \n"));
+        assert!(listing_of(&["-a"], target).starts_with("✅ Les contenus du dossier (4 entrées) : \n"));
+        let single = listing_of(&[], dir.join("a.*").to_str().unwrap());
+        assert!(single.starts_with("✅ Les contenus du dossier (1 entrée) : \n"), "{single}");
+        // Les sorties destinées à un programme n'ont pas d'en-tête
+        assert!(listing_of(&["--format=csv"], target).starts_with("name,type,size,modified\n"));
+    }
+}
@@ -1,19 +1,18 @@
 // main.rs
 
-mod cat; // Déclare le module cat.rs
-mod ls;  // Déclare le module ls.rs (à créer)
-mod wc;  // Déclare le module wc.rs (à créer)
-mod cp;  // Déclare le module cp.rs
-mod mv;  // Déclare le module mv.rs
-mod rm;  // Déclare le module rm.rs (à créer)
-mod head; // Déclare le module head.rs
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant};
 
-use std::io::{self, Write};
+// Les modules des utilitaires sont déclarés dans lib.rs
+use projet_rust_utils_cli::{bench, cat, cp, echo, ext, head, interrupt, ls, menu, mv, pipeline, rm, wc};
 
 /// Point d'entrée de l'application.
 ///
 /// Cette fonction affiche un menu interactif permettant de choisir un utilitaire Linux
-/// parmi : ls, cat, cp, mv, rm, wc. L'utilisateur peut entrer le numéro correspondant
+/// parmi : ls, cat, cp, mv, rm, wc, head, echo. L'utilisateur peut entrer le numéro correspondant
 /// ou `quit` pour quitter l'application.
 ///
 /// L'application boucle tant que l'utilisateur ne choisit pas de quitter.
@@ -25,6 +24,11 @@ use std::io::{self, Write};
 ///  - Demande à l'utilisateur de saisir son choix.
 ///  - Lit l'entrée utilisateur et supprime les espaces et retours à la ligne.
 ///  - Vérifie si l'entrée est `quit` : si oui, quitte la boucle et termine le programme.
+///  - Si l'entrée est `clear` (ou `c`), efface l'écran et réaffiche l'accueil et le menu.
+///  - Si l'entrée contient `|` (ex. `cat notes.txt | wc -l`), l'exécute comme un pipeline
+///    (voir [`pipeline::run_line`]).
+///  - Si l'entrée commence par `ext:` (ex. `ext:sauvegarde --dry-run`), lance la commande
+///    externe correspondante (voir [`ext::run_line`]).
 ///  - Sinon, effectue un `match` sur la saisie :
 ///    - `"1"` : appelle la fonction `ls::ls()`
 ///    - `"2"` : appelle la fonction `cat::cat()`
@@ -32,15 +36,34 @@ use std::io::{self, Write};
 ///    - `"4"` : appelle la fonction `mv::mv()`
 ///    - `"5"` : appelle la fonction `rm::rm()`
 ///    - `"6"` : appelle la fonction `wc::wc()`
+///    - `"7"` : appelle la fonction `head::handle_head()`
+///    - `"8"` : appelle la fonction `echo::handle_echo()`
 ///    - Autre : affiche un message d'erreur et redemande une saisie.
 ///  - Affiche une ligne vide pour séparer les itérations.
 ///
+/// Dans chaque utilitaire, `menu` (ou `b`, `0`) ramène au menu principal
+/// (voir [`menu::is_back`]).
+///
 /// Ce processus se répète jusqu'à ce que l'utilisateur décide de quitter.
+///
+/// Si des arguments sont passés au programme, le menu n'est pas affiché :
+/// la commande est exécutée directement (voir [`run_cli`]).
 pub fn main() {
+    // Ctrl+C arrête proprement les opérations longues (voir le module interrupt)
+    interrupt::install();
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    if !args.is_empty() {
+        run_cli(&args);
+        return;
+    }
+
+    // Le message d'accueil n'est affiché qu'au démarrage et après `clear`
+    println!("Bonjour et bienvenue dans l'utilitaire de commande linux.");
+    println!();
+
     loop {
         // Affichage du menu
-        println!("Bonjour et bienvenue dans l'utilitaire de commande linux.");
-        println!();
         println!("Veuillez choisir votre utilitaire (tapez quit pour quitter) :");
         println!("1) ls");
         println!("2) cat");
@@ -49,6 +72,9 @@ pub fn main() {
         println!("5) rm");
         println!("6) wc");
         println!("7) head");
+        println!("8) echo");
+        println!("c) clear (efface l'écran)");
+        println!("ext:<commande> [arguments] (commande externe du PATH ou de {})", ext::PLUGIN_DIR_VAR);
         println!();
         print!("Votre choix : ");
         io::stdout().flush().unwrap(); // Assure que le prompt s'affiche avant la saisie
@@ -66,6 +92,33 @@ pub fn main() {
             break;
         }
 
+        // Effacer l'écran puis réafficher l'accueil et le menu
+        if choix.eq_ignore_ascii_case("clear") || choix.eq_ignore_ascii_case("c") {
+            menu::clear_screen();
+            println!("Bonjour et bienvenue dans l'utilitaire de commande linux.");
+            println!();
+            continue;
+        }
+
+        // Une commande complète avec pipe est exécutée directement
+        if choix.contains('|') {
+            if let Err(e) = pipeline::run_line(choix) {
+                eprintln!("Erreur : {}", e);
+            }
+            println!();
+            continue;
+        }
+
+        // Une commande externe doit être demandée explicitement avec ext:
+        if let Some(line) = choix.strip_prefix(ext::PREFIX) {
+            let mut words = line.split_whitespace().map(String::from);
+            let name = words.next().unwrap_or_default();
+            let args: Vec<String> = words.collect();
+            ext::run_line(&name, &args);
+            println!();
+            continue;
+        }
+
         // Match sur l'entrée utilisateur
         match choix {
             "1" => {
@@ -78,44 +131,45 @@ pub fn main() {
             }
             "3" => {
                 println!("Exécution de cp...");
-                println!("Syntaxe : [option] <source> <destination>");
-                println!("Options disponibles : -i (interactive), -v (verbose)");
-                print!("Entrez vos arguments : ");
-                io::stdout().flush().unwrap();
-                
-                let mut args_input = String::new();
-                io::stdin()
-                    .read_line(&mut args_input)
-                    .expect("Erreur lors de la lecture de l'entrée");
-                
-                // Parse les arguments en Vec<String>
-                let args: Vec<String> = args_input
-                    .trim()
-                    .split_whitespace()
-                    .map(String::from)
-                    .collect();
-                
+                println!("Syntaxe : [options] <source> <destination>");
+                println!("Options disponibles : -i (interactive), -v (verbose), -r (récursif),");
+                println!("                      -L (suivre les liens), -P (recréer les liens),");
+                println!("                      -a (archive : conserve permissions, propriétaire et dates),");
+                println!("                      --sparse=auto|always|never (fichiers creux),");
+                println!("                      --jobs N (copie récursive sur N threads),");
+                println!("                      --xattrs / --no-xattrs (copie ou supprime les attributs étendus),");
+                println!("                      --verify (relit la copie et la compare à la source),");
+                println!("                      --progress (avancement, débit et temps restant),");
+                println!("                      --print-paths (chemins absolus de la source et de la copie),");
+                println!("                      --dirs-only (recrée seulement les dossiers, sans les fichiers),");
+                println!("                      -f (écrase aussi les fichiers protégés par un marqueur .keep),");
+                println!("                      --skip-identical (ne recopie pas un fichier déjà identique),");
+                println!("                      --as MODELE (nom de chaque copie : {{name}}, {{ext}}, {{date}} ;");
+                println!("                                   ex. --as \"{{name}}-{{date}}.{{ext}}\" *.log backup/),");
+                println!("                      --buffer-size N (copie par tranches de N octets, ex. 1M)");
+                // 'menu', 'b' ou '0' ramène au menu sans exécuter la commande
+                let Some(args) = read_arguments("cp") else {
+                    continue;
+                };
+
                 // Appelle handle_cp avec les arguments
                 cp::handle_cp(&args);
             }
             "4" => {
                 println!("Exécution de mv...");
-                println!("Syntaxe : [option] <source> <destination>");
-                println!("Options disponibles : -i (interactive), -v (verbose)");
-                print!("Entrez vos arguments : ");
-                io::stdout().flush().unwrap();
-
-                let mut args_input = String::new();
-                io::stdin()
-                    .read_line(&mut args_input)
-                    .expect("Erreur lors de la lecture de l'entrée");
-
-                // Parse les arguments en Vec<String>
-                let args: Vec<String> = args_input
-                    .trim()
-                    .split_whitespace()
-                    .map(String::from)
-                    .collect();
+                println!("Syntaxe : [options] <source> <destination>  |  --undo <journal>  |  --swap <a> <b>");
+                println!("          --normalize <fichier|motif>... (renomme en minuscules, sans espaces ni accents)");
+                println!("Options disponibles : -i (interactive), -v (verbose), --journal <fichier>,");
+                println!("                      --xattrs (signale les attributs étendus non recopiés), --no-xattrs (les supprime),");
+                println!("                      --print-paths (chemins absolus d'origine et d'arrivée),");
+                println!("                      -f (écrase aussi les fichiers protégés par un marqueur .keep),");
+                println!("                      --skip-identical (ne déplace pas un fichier si la destination est identique),");
+                println!("                      --jobs N (copie sur N threads vers un autre système de fichiers),");
+                println!("                      --as MODELE (nouveau nom de chaque fichier : {{name}}, {{ext}}, {{date}})");
+                // 'menu', 'b' ou '0' ramène au menu sans exécuter la commande
+                let Some(args) = read_arguments("mv") else {
+                    continue;
+                };
 
                 // Appelle handle_mv avec les arguments
                 mv::handle_mv(&args);
@@ -130,27 +184,38 @@ pub fn main() {
             }
             "7" => {
                 println!("Exécution de head...");
-                println!("Exécution de head...");
-                println!("Syntaxe : [option] <fichier>");
-                println!("Options disponibles : -n <nombre> (nombre de lignes), -v (verbose)");
-                print!("Entrez vos arguments : ");
-                io::stdout().flush().unwrap();
-
-                let mut args_input = String::new();
-                io::stdin()
-                    .read_line(&mut args_input)
-                    .expect("Erreur lors de la lecture de l'entrée");
-
-                // Parse les arguments en Vec<String>
-                let args: Vec<String> = args_input
-                    .trim()
-                    .split_whitespace()
-                    .map(String::from)
-                    .collect();
+                println!("Syntaxe : [options] <fichier>...");
+                println!("Options disponibles : -n <nombre> (nombre de lignes), -v (verbose), -q (quiet),");
+                println!("                      --latest (fichier le plus récemment modifié, jokers acceptés : '*.log'),");
+                println!("                      --byte-offsets (position en octets de chaque ligne),");
+                println!("                      --strip-ansi (supprime les codes couleur ANSI),");
+                println!("                      --step <N> (une ligne sur N),");
+                println!("                      --goto <N> [--context <C>] (ligne N et son contexte),");
+                println!("                      --args-from-stdin [-0] (liste des fichiers reçue d'un tube),");
+                println!("                      --files-from <liste> / --files0-from <liste> (fichiers listés dans <liste>),");
+                println!("                      -f (continue d'afficher les ajouts, Ctrl+C pour arrêter)");
+                // 'menu', 'b' ou '0' ramène au menu sans exécuter la commande
+                let Some(args) = read_arguments("head") else {
+                    continue;
+                };
 
                 // Appelle handle_head avec les arguments
                 head::handle_head(&args);
             }
+            "8" => {
+                println!("Exécution de echo...");
+                println!("Syntaxe : [options] [texte]...");
+                println!("Options disponibles : -n (pas de retour à la ligne final),");
+                println!("                      -e (interprète \\n, \\t, \\xHH...), -E (texte brut, par défaut)");
+                println!("Guillemets : \"a  b\" ou 'a  b' ; tapez 'echo b' pour afficher b.");
+                // 'menu', 'b' ou '0' ramène au menu sans exécuter la commande
+                let Some(args) = read_arguments("echo") else {
+                    continue;
+                };
+
+                // Appelle handle_echo avec les arguments
+                echo::handle_echo(&args);
+            }
 
             _ => {
                 // Gestion des entrées invalides
@@ -161,3 +226,265 @@ pub fn main() {
         println!(); // Ligne vide avant le prochain tour
     }
 }
+
+/// Demande les arguments de l'utilitaire `command` et les découpe avec [`parse_arguments`].
+///
+/// Retourne `None` si l'utilisateur demande le retour au menu (voir [`menu::is_back`]).
+fn read_arguments(command: &str) -> Option<Vec<String>> {
+    print!("Entrez vos arguments (ou 'menu' pour revenir) : ");
+    io::stdout().flush().unwrap();
+
+    let mut args_input = String::new();
+    io::stdin()
+        .read_line(&mut args_input)
+        .expect("Erreur lors de la lecture de l'entrée");
+
+    parse_arguments(&args_input, command)
+}
+
+/// # Fonction : `parse_arguments`
+///
+/// Découpe la saisie de l'invite des arguments avec [`pipeline::split_words`] : les
+/// guillemets et apostrophes regroupent les mots et gardent leurs espaces.
+///
+/// La saisie peut commencer par le nom de la commande (`echo b`) : il est retiré, et la
+/// suite est toujours passée à la commande. Sinon, une saisie réduite à `menu`, `b` ou
+/// `0` ramène au menu (retourne `None`).
+///
+/// ## Exemple :
+/// ```rust
+/// assert_eq!(parse_arguments("-n \"a  b\"\n", "echo"), Some(vec!["-n".into(), "a  b".into()]));
+/// assert_eq!(parse_arguments("b\n", "echo"), None);
+/// assert_eq!(parse_arguments("echo b\n", "echo"), Some(vec!["b".into()]));
+/// ```
+fn parse_arguments(input: &str, command: &str) -> Option<Vec<String>> {
+    let mut args = pipeline::split_words(input.trim_end_matches(['\r', '\n']));
+
+    if args.first().is_some_and(|name| name == command) {
+        args.remove(0);
+        return Some(args);
+    }
+    if menu::is_back(input.trim()) {
+        return None;
+    }
+    Some(args)
+}
+
+/// Mode non interactif : `projet [--watch <secondes>] <commande> [arguments...]`.
+///
+/// # Algorithme
+///  - Sans `--watch`, exécute une seule fois la commande avec [`dispatch`], et quitte
+///    avec son code de sortie s'il n'est pas nul (1 si la commande a échoué).
+///  - Avec `--watch <secondes>`, vérifie que l'intervalle est un nombre positif
+///    et que la sortie est un terminal, puis délègue à [`watch`].
+///  - En cas d'erreur d'utilisation, quitte avec le code 2.
+fn run_cli(args: &[String]) {
+    // Option cachée : mesure les performances d'une commande
+    if args[0] == "--bench" {
+        let Some(runs) = args.get(1).and_then(|n| n.parse::<usize>().ok()).filter(|n| *n > 0) else {
+            eprintln!("--bench : nombre d'exécutions invalide (entier positif attendu)");
+            eprintln!("Usage : projet --bench <N> <commande> [arguments...]");
+            process::exit(2);
+        };
+        if args.len() < 3 {
+            eprintln!("--bench : commande manquante");
+            eprintln!("Usage : projet --bench <N> <commande> [arguments...]");
+            process::exit(2);
+        }
+        bench(runs, &args[2..]);
+        return;
+    }
+
+    if args[0] != "--watch" {
+        let status = dispatch(&args[0], &args[1..]);
+        if status != 0 {
+            process::exit(status);
+        }
+        return;
+    }
+
+    let Some(seconds) = args
+        .get(1)
+        .and_then(|s| s.parse::<f64>().ok())
+        .filter(|s| s.is_finite() && *s > 0.0)
+    else {
+        eprintln!("--watch : intervalle invalide (nombre de secondes positif attendu)");
+        eprintln!("Usage : projet --watch <secondes> <commande> [arguments...]");
+        process::exit(2);
+    };
+
+    if args.len() < 3 {
+        eprintln!("--watch : commande manquante");
+        eprintln!("Usage : projet --watch <secondes> <commande> [arguments...]");
+        process::exit(2);
+    }
+
+    // L'effacement de l'écran n'a de sens que dans un terminal
+    if !io::stdout().is_terminal() {
+        eprintln!("--watch : la sortie doit être un terminal");
+        process::exit(1);
+    }
+
+    watch(Duration::from_secs_f64(seconds), &args[2..]);
+}
+
+/// Exécute la commande `command` avec ses arguments.
+///
+/// Un argument `|` (à protéger du shell : `projet cat notes.txt '|' wc -l`)
+/// enchaîne plusieurs commandes avec [`pipeline::run`].
+///
+/// Une commande `ext:nom` lance la commande externe `nom` (voir [`ext::run_line`]).
+///
+/// Retourne le code de sortie sans jamais quitter le programme : `0` en cas de succès,
+/// `1` si la commande (`cat`, `wc`) ou une étape d'un pipeline a échoué, `2` si la
+/// commande est inconnue ou mal utilisée.
+fn dispatch(command: &str, args: &[String]) -> i32 {
+    if args.iter().any(|arg| arg == "|") {
+        let words: Vec<String> = std::iter::once(command.to_string()).chain(args.iter().cloned()).collect();
+        let stages: Vec<Vec<String>> = words.split(|word| word == "|").map(|stage| stage.to_vec()).collect();
+        if let Err(e) = pipeline::run(&stages) {
+            pipeline::exit_on_broken_pipe(&e);
+            eprintln!("Erreur : {}", e);
+            return if e.kind() == io::ErrorKind::InvalidInput { 2 } else { 1 };
+        }
+        return 0;
+    }
+
+    match command {
+        "cat" => return cat::run(args),
+        "wc" => return wc::run(args),
+        "ls" => ls::run(args),
+        "cp" => cp::handle_cp(args),
+        "mv" => mv::handle_mv(args),
+        "rm" => rm::run(args),
+        "head" => head::handle_head(args),
+        "echo" => echo::handle_echo(args),
+        other if other.starts_with(ext::PREFIX) => {
+            return if ext::run_line(&other[ext::PREFIX.len()..], args) { 0 } else { 2 };
+        }
+        other => {
+            eprintln!("Commande inconnue : '{}' (ls, cat, cp, mv, rm, wc, head, echo ou ext:<commande>)", other);
+            return 2;
+        }
+    }
+    0
+}
+
+/// Exécute une commande `runs` fois et affiche ses temps d'exécution (`--bench`).
+///
+/// # Algorithme
+///  - Masque la sortie standard de la commande avec [`bench::Silence`] (les erreurs
+///    restent affichées).
+///  - Mesure chaque exécution avec [`Instant`], vidage de la sortie compris.
+///  - Une exécution en échec (code 1, ex. fichier absent) est mesurée comme les autres ;
+///    seule une commande inconnue ou mal utilisée (code 2) arrête les mesures.
+///  - Affiche le minimum, la médiane, la moyenne et le maximum (voir [`bench::Stats`]),
+///    puis quitte avec le code 1 si une exécution a échoué.
+///
+/// Permet par exemple de comparer `cat --buffer-size 4K gros.txt` et
+/// `cat --buffer-size 1M gros.txt`.
+fn bench(runs: usize, command: &[String]) {
+    let (name, args) = command.split_first().expect("commande non vide");
+    let mut durations = Vec::with_capacity(runs);
+    let mut failures = 0;
+
+    for _ in 0..runs {
+        let silence = match bench::Silence::new() {
+            Ok(silence) => silence,
+            Err(e) => {
+                eprintln!("--bench : impossible de masquer la sortie : {}", e);
+                process::exit(1);
+            }
+        };
+
+        let start = Instant::now();
+        let status = dispatch(name, args);
+        let _ = io::stdout().flush();
+        let elapsed = start.elapsed();
+        drop(silence);
+
+        if status == 2 {
+            process::exit(2);
+        }
+        if status != 0 {
+            failures += 1;
+        }
+        durations.push(elapsed);
+    }
+
+    let stats = bench::Stats::compute(&durations).expect("au moins une exécution");
+    if let Err(e) = bench::write_report(&command.join(" "), &stats, &mut io::stdout()) {
+        eprintln!("--bench : {}", e);
+    }
+    if failures > 0 {
+        eprintln!("--bench : {} exécution(s) sur {} en échec", failures, runs);
+        process::exit(1);
+    }
+}
+
+/// Ré-exécute une commande à intervalle régulier, comme `watch`.
+///
+/// # Algorithme
+///  - Efface l'écran et replace le curseur en haut avec [`menu::clear_screen`].
+///  - Affiche un en-tête avec l'intervalle et la commande, puis l'exécute.
+///  - Attend `interval` avant de recommencer, jusqu'à l'interruption par Ctrl-C.
+fn watch(interval: Duration, command: &[String]) {
+    let (name, args) = command.split_first().expect("commande non vide");
+
+    loop {
+        menu::clear_screen();
+        println!("Toutes les {}s : {}", interval.as_secs_f64(), command.join(" "));
+        println!();
+
+        // Une commande inconnue arrête la surveillance ; un échec (fichier absent...) est réessayé au tour suivant
+        if dispatch(name, args) == 2 {
+            process::exit(2);
+        }
+
+        io::stdout().flush().unwrap();
+        thread::sleep(interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(line: &str) -> Vec<String> {
+        line.split(' ').map(String::from).collect()
+    }
+
+    #[test]
+    fn failing_pipeline_stage_gives_status_1() {
+        assert_eq!(dispatch("cat", &words("fichier-absent-du-test.txt | wc -l")), 1);
+    }
+
+    #[test]
+    fn prompt_arguments_keep_quotes_and_allow_back_words() {
+        let parsed = |input: &str| parse_arguments(input, "echo");
+
+        assert_eq!(parsed("-n \"a  b\"  'c | d'\n"), Some(vec!["-n".into(), "a  b".into(), "c | d".into()]));
+        for back in ["b\n", "0\n", " MENU \n"] {
+            assert_eq!(parsed(back), None, "{back:?}");
+        }
+        assert_eq!(parsed("echo b\n"), Some(vec!["b".into()]));
+        assert_eq!(parsed("echo 0\n"), Some(vec!["0".into()]));
+        assert_eq!(parsed("echo\n"), Some(vec![]));
+        assert_eq!(parsed("\"b\"\n"), Some(vec!["b".into()]));
+        assert_eq!(parse_arguments("b\n", "cp"), None);
+    }
+
+    #[test]
+    fn failing_commands_return_status_1_without_exiting() {
+        assert_eq!(dispatch("wc", &words("fichier-absent-du-test.txt")), 1);
+        assert_eq!(dispatch("cat", &words("fichier-absent-du-test.txt")), 1);
+        assert_eq!(dispatch("wc", &words("--version")), 0);
+    }
+
+    #[test]
+    fn unknown_or_rejected_commands_give_status_2() {
+        assert_eq!(dispatch("inconnue", &[]), 2);
+        assert_eq!(dispatch("echo", &words("a | inconnue")), 2);
+        assert_eq!(dispatch("echo", &words("a | rm b")), 2);
+    }
+}
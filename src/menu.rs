@@ -0,0 +1,50 @@
+//! # Module `menu`
+//!
+//! Ce module regroupe la navigation partagée par le menu principal et les
+//! utilitaires interactifs (`ls`, `cat`, `rm`, `wc`...) :
+//!
+//! - le retour au menu principal (`menu`, `b` ou `0`) ;
+//! - l'effacement de l'écran (menu `clear`, mode `--watch`).
+
+use std::io::{self, Write};
+
+/// Séquence ANSI qui efface l'écran (`ESC[2J`) et replace le curseur en haut à gauche (`ESC[H`).
+const CLEAR_SCREEN: &str = "\x1B[2J\x1B[H";
+
+/// Saisies qui ramènent au menu principal depuis un utilitaire.
+const BACK_COMMANDS: [&str; 3] = ["menu", "b", "0"];
+
+/// # Fonction : `is_back`
+///
+/// Indique si la saisie demande le retour au menu principal (`menu`, `b` ou `0`,
+/// sans tenir compte de la casse). `quit` reste accepté par les utilitaires.
+pub fn is_back(input: &str) -> bool {
+    BACK_COMMANDS.iter().any(|command| input.eq_ignore_ascii_case(command))
+}
+
+/// # Fonction : `clear_screen`
+///
+/// Efface l'écran du terminal et replace le curseur en haut.
+pub fn clear_screen() {
+    print!("{}", CLEAR_SCREEN);
+    io::stdout().flush().unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn back_words_ignore_case() {
+        for input in ["menu", "MENU", "Menu", "b", "B", "0"] {
+            assert!(is_back(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn other_inputs_stay_in_the_utility() {
+        for input in ["", "quit", "back", "bb", "00", "menu principal", "ls"] {
+            assert!(!is_back(input), "{input}");
+        }
+    }
+}
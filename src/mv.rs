@@ -5,12 +5,74 @@
 //! Il permet de **déplacer ou renommer** un fichier ou un dossier, avec la prise en charge
 //! des options suivantes :
 //!
-//! - `-i` : demande confirmation avant d’écraser une destination existante (*interactive*).  
+//! - `-i` : demande confirmation avant d’écraser une destination existante (*interactive*).
 //! - `-v` : affiche le nom des fichiers déplacés ou renommés (*verbose*).
+//! - `--journal <fichier>` : enregistre chaque déplacement (`source\tdestination\tdate\ttaille`).
+//! - `--undo <fichier>` : annule les déplacements d’un journal, du plus récent au plus ancien.
+//! - `--swap` : échange deux fichiers (atomiquement sous Linux).
+//! - `--xattrs` / `--no-xattrs` : conserve (par défaut) ou supprime les attributs étendus (Linux),
+//!   y compris quand le déplacement passe par une copie.
+//! - `--normalize` : renomme des fichiers sur place en une forme sûre (`Mon Fichier.PDF` → `mon-fichier.pdf`).
+//! - `--print-paths` : affiche les chemins absolus d’origine et d’arrivée (`source\tdestination`).
+//! - `-f` / `--force` : écrase aussi une destination protégée par un marqueur `<nom>.keep`.
+//! - `--skip-identical` : ne déplace pas un fichier si la destination a déjà le même contenu.
+//! - `--as MODELE` : renomme chaque fichier d’après un modèle (`{name}`, `{ext}`, `{date}`)
+//!   en le déplaçant dans un dossier (voir [`crate::template`]).
+//! - `--jobs N` : vers un autre système de fichiers, où le déplacement devient une copie
+//!   suivie d’une suppression, copie les fichiers sur `N` threads.
 
-use std::fs;
-use std::io::{self, Write};
-use std::path::Path;
+use std::fs::{self, File, FileTimes, OpenOptions};
+use std::io::{self, IsTerminal, Write};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::SystemTime;
+
+use crate::template::{Batch, Template};
+use crate::{date, escape, glob, interrupt, path, sum, xattr};
+
+/// Structure représentant les options de la commande `mv`.
+///
+/// Chaque champ correspond à une option possible de `mv`.
+#[derive(Default, Clone)]
+struct Options {
+    /// Demande confirmation avant d'écraser (`-i`).
+    interactive: bool,
+    /// Affiche les déplacements effectués (`-v`).
+    verbose: bool,
+    /// Journal dans lequel enregistrer les déplacements (`--journal <fichier>`).
+    journal: Option<String>,
+    /// Traitement des attributs étendus (`--xattrs`, `--no-xattrs`).
+    xattrs: Xattrs,
+    /// Affiche les chemins absolus d'origine et d'arrivée après le déplacement (`--print-paths`).
+    print_paths: bool,
+    /// Écrase aussi une destination protégée par un marqueur `.keep` (`-f`).
+    force: bool,
+    /// Ne déplace pas un fichier si la destination a déjà le même contenu (`--skip-identical`).
+    skip_identical: bool,
+    /// Modèle du nom de chaque fichier déplacé (`--as MODELE`).
+    as_template: Option<Template>,
+    /// Nombre de threads de la copie vers un autre système de fichiers (`--jobs N`, 1 par défaut).
+    jobs: usize,
+}
+
+/// Traitement des attributs étendus (`--xattrs`, `--no-xattrs`).
+///
+/// Un renommage conserve toujours les attributs ; la différence entre `Default` et
+/// `Copy` n'apparaît que pour un déplacement vers un autre système de fichiers.
+#[derive(Default, Clone, Copy, PartialEq)]
+enum Xattrs {
+    /// Recopie les attributs lors d'une copie, sans avertir si la destination
+    /// ne les prend pas en charge (par défaut).
+    #[default]
+    Default,
+    /// Recopie les attributs lors d'une copie et avertit en cas d'échec.
+    Copy,
+    /// Supprime tous les attributs de la destination.
+    Strip,
+}
 
 /// # Fonction : `move_file`
 /// 
@@ -18,42 +80,91 @@ use std::path::Path;
 ///
 /// # Fonctionnement
 /// - Vérifie si la source existe.
-/// - Détermine si la destination est un dossier ou un fichier.
+/// - Détermine si la destination est un dossier ou un fichier. Une destination
+///   terminée par `/` doit être un dossier (ou le devenir, pour le renommage d’un dossier).
+/// - Refuse de déplacer un fichier sur lui-même ou un dossier dans l’un de ses descendants.
 /// - Si la destination existe déjà :
+///   - et qu’elle est protégée par un marqueur `<nom>.keep` ([`path::keep_marker`]),
+///     refuse de l’écraser, sauf avec `-f` ;
+///   - et que le flag `--skip-identical` est activé, ne fait rien si son contenu est
+///     déjà celui de la source ([`sum::same_contents`]) : la source est conservée ;
 ///   - et que le flag `-i` est activé, demande confirmation avant d’écraser.
 /// - Supprime la destination si nécessaire.
-/// - Déplace ou renomme la source vers la destination.
+/// - Déplace ou renomme la source vers la destination avec [`move_path`] (copie puis
+///   suppression si la destination est sur un autre système de fichiers).
 /// - Si le flag `-v` est activé, affiche le déplacement effectué.
+/// - Si un journal est demandé, y ajoute le déplacement effectué.
+/// - Avec `--no-xattrs`, supprime les attributs étendus de la destination. Un
+///   renommage conserve toujours les attributs, et une copie les recopie (voir [`Xattrs`]).
 ///
 /// # Flags pris en charge
 /// - `-i` : interactive → demande confirmation avant d’écraser un fichier existant.
 /// - `-v` : verbose → affiche les fichiers déplacés ou renommés.
-fn move_file(flag: Option<&str>, source: &str, destination: &str) {
-    
+/// - `--journal <fichier>` : enregistre le déplacement pour pouvoir l’annuler.
+/// - `--print-paths` : affiche `source\tdestination` en chemins absolus. La source
+///   n’existant plus après le déplacement, son chemin est résolu juste avant.
+fn move_file(options: &Options, source: &str, destination: &str) {
+
+    // Résout les chemins (gère ., .. et ~)
+    let (source_path, destination_path) = match (path::resolve(source), path::resolve(destination)) {
+        (Ok(s), Ok(d)) => (s, d),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("mv: cannot resolve path: {e}");
+            return;
+        }
+    };
+
     // Vérifie si le fichier source existe
-    let source_path = Path::new(source);
     if !source_path.exists() {
         eprintln!("mv: cannot stat '{source}': No such file or directory");
         return;
     }
 
+    //   Une destination terminée par un séparateur désigne forcément un dossier :
+    //   - s'il existe mais n'est pas un dossier, erreur ;
+    //   - s'il n'existe pas, seul un dossier peut être renommé ainsi.
+    if destination.ends_with(['/', '\\']) && !destination_path.is_dir() {
+        if destination_path.exists() {
+            eprintln!("mv: failed to access '{destination}': Not a directory");
+            return;
+        }
+        if !source_path.is_dir() {
+            eprintln!("mv: cannot move '{source}' to '{destination}': Not a directory");
+            return;
+        }
+    }
+
     
     //   Vérifie si la destination est un fichier ou un répertoire :
     //   - Si c’est un répertoire, on ajoute le nom du fichier source à la fin.
     //   - Sinon, on considère que la destination est un fichier et on garde son nom tel quel.
-    let final_destination = if Path::new(destination).is_dir() {
+    let (final_destination, final_dest_path) = if destination_path.is_dir() {
         let name = source_path
             .file_name()
             .unwrap()
             .to_str()
             .unwrap();
-        format!("{}/{}", destination.trim_end_matches('/').trim_end_matches('\\'), name)
+        (
+            format!("{}/{}", destination.trim_end_matches('/').trim_end_matches('\\'), name),
+            destination_path.join(name),
+        )
     } else {
-        destination.to_string()
+        (destination.to_string(), destination_path)
     };
 
-    
-    let final_dest_path = Path::new(&final_destination);
+
+    //    Refuse une destination identique à la source, ou située dans la source
+    //    quand c'est un dossier (ce qui provoquerait une récursion infinie).
+    if let (Ok(from), Ok(to)) = (path::canonicalize(&source_path), path::canonicalize(&final_dest_path)) {
+        if from == to {
+            eprintln!("mv: '{source}' and '{final_destination}' are the same file");
+            return;
+        }
+        if source_path.is_dir() && to.starts_with(&from) {
+            eprintln!("mv: cannot move '{source}' to a subdirectory of itself, '{final_destination}'");
+            return;
+        }
+    }
 
     
     
@@ -64,17 +175,31 @@ fn move_file(flag: Option<&str>, source: &str, destination: &str) {
     //      la destination plus tard lors du déplacement final.
     //    - Sinon, l’opération est annulée immédiatement.
     if final_dest_path.exists() {
-        if let Some(f) = flag {
-            if f == "-i" {
-                print!("mv: overwrite '{final_destination}'? ");
-                io::stdout().flush().unwrap();
-                let mut answer = String::new();
-                io::stdin().read_line(&mut answer).unwrap();
-
-                if !answer.trim().eq_ignore_ascii_case("y") {
-                    println!("mv: not overwritten.");
-                    return;
-                }
+        if !options.force
+            && let Some(marker) = path::keep_marker(&final_dest_path)
+        {
+            eprintln!("mv: not overwriting '{final_destination}': protected by '{}' (use -f to force)", marker.display());
+            return;
+        }
+
+        if options.skip_identical
+            && source_path.is_file()
+            && final_dest_path.is_file()
+            && sum::same_contents(&source_path, &final_dest_path).unwrap_or(false)
+        {
+            println!("mv: skipping '{source}': '{final_destination}' is identical (source kept)");
+            return;
+        }
+
+        if options.interactive {
+            print!("mv: overwrite '{final_destination}'? ");
+            io::stdout().flush().unwrap();
+            let mut answer = String::new();
+            io::stdin().read_line(&mut answer).unwrap();
+
+            if !answer.trim().eq_ignore_ascii_case("y") {
+                println!("mv: not overwritten.");
+                return;
             }
         }
         
@@ -83,12 +208,12 @@ fn move_file(flag: Option<&str>, source: &str, destination: &str) {
         //    - Si c’est un dossier, on le supprime récursivement.
         //    - Si c’est un fichier, on le supprime directement.
         if final_dest_path.is_dir() {
-            if let Err(e) = fs::remove_dir_all(&final_destination) {
+            if let Err(e) = fs::remove_dir_all(&final_dest_path) {
                 eprintln!("mv: cannot remove '{final_destination}': {e}");
                 return;
             }
         } else {
-            if let Err(e) = fs::remove_file(&final_destination) {
+            if let Err(e) = fs::remove_file(&final_dest_path) {
                 eprintln!("mv: cannot remove '{final_destination}': {e}");
                 return;
             }
@@ -96,22 +221,721 @@ fn move_file(flag: Option<&str>, source: &str, destination: &str) {
     }
 
     
+    // La source disparaît avec le déplacement : son chemin absolu est résolu avant
+    let canonical_source = path::canonicalize(&source_path);
+
     //    Déplace ou renomme le fichier ou dossier :
     //    - Si le flag "-v" est activé, affiche le déplacement effectué.
     //    - Si une erreur survient, affiche un message d’erreur.
-    match fs::rename(source, &final_destination) {
+    match move_path(options, &source_path, &final_dest_path) {
         Ok(_) => {
-            if let Some(f) = flag {
-                if f == "-v" {
-                    println!("renamed '{source}' -> '{final_destination}'");
+            if options.verbose {
+                println!("renamed '{source}' -> '{final_destination}'");
+            }
+
+            if options.print_paths {
+                match (canonical_source, path::canonicalize(&final_dest_path)) {
+                    (Ok(from), Ok(to)) => println!("{}\t{}", from.display(), to.display()),
+                    (Err(e), _) | (_, Err(e)) => eprintln!("mv: cannot resolve '{final_destination}': {e}"),
                 }
             }
+
+            if options.xattrs == Xattrs::Strip
+                && let Err(e) = xattr::strip(&final_dest_path)
+            {
+                eprintln!("mv: warning: cannot remove extended attributes of '{final_destination}': {e}");
+            }
+
+            // Enregistre le déplacement dans le journal (chemins absolus)
+            if let Some(journal) = &options.journal
+                && let Err(e) = append_journal(journal, &source_path, &final_dest_path)
+            {
+                eprintln!("mv: cannot write journal '{journal}': {e}");
+            }
         }
         Err(e) => eprintln!("mv: cannot move '{source}' to '{final_destination}': {e}"),
     }
 }
 
 
+/// # Fonction : `move_path`
+///
+/// Déplace `source` vers `destination` avec `fs::rename`.
+///
+/// Un renommage est impossible entre deux systèmes de fichiers (erreur `EXDEV`, par
+/// exemple de `/tmp` vers une clé USB) : la source est alors copiée avec
+/// [`copy_across`], puis supprimée une fois la copie complète. Si la copie échoue,
+/// ce qui en a déjà été fait est supprimé et la source reste intacte.
+fn move_path(options: &Options, source: &Path, destination: &Path) -> io::Result<()> {
+    match fs::rename(source, destination) {
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            copy_across(options, source, destination)?;
+            remove_any(source)
+        }
+        result => result,
+    }
+}
+
+
+/// # Fonction : `copy_across`
+///
+/// Recopie `source` (fichier, lien symbolique ou dossier complet) en `destination`,
+/// pour un déplacement entre deux systèmes de fichiers.
+///
+/// ## Fonctionnement :
+/// 1. Parcourt la source avec [`plan_copy`] : les dossiers et les liens symboliques
+///    sont recréés tout de suite, les fichiers sont mis en file d’attente.
+/// 2. Copie les fichiers avec [`copy_files`], sur `--jobs N` threads.
+/// 3. Recopie les attributs étendus, dates et permissions des dossiers, du plus
+///    profond au moins profond (copier leur contenu a changé leur date de modification).
+///
+/// En cas d’erreur (ou de Ctrl+C), la copie partielle est supprimée ; une destination
+/// qui existait déjà n’est jamais touchée.
+fn copy_across(options: &Options, source: &Path, destination: &Path) -> io::Result<()> {
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    let existed = fs::symlink_metadata(destination).is_ok();
+
+    let result = plan_copy(source, destination, &mut files, &mut dirs)
+        .and_then(|_| copy_files(options, &files))
+        .and_then(|_| {
+            for (from, to) in dirs.iter().rev() {
+                copy_xattrs(options, from, to);
+                copy_times(from, to)?;
+                fs::set_permissions(to, fs::metadata(from)?.permissions())?;
+            }
+            Ok(())
+        });
+
+    if result.is_err() && !existed && fs::symlink_metadata(destination).is_ok() {
+        let _ = remove_any(destination);
+    }
+    result
+}
+
+
+/// # Fonction : `plan_copy`
+///
+/// Prépare la copie de `source` : crée les dossiers et recrée les liens symboliques
+/// (sans les suivre) de la destination, et ajoute les fichiers à copier dans `files`.
+/// Les couples de dossiers sont ajoutés à `dirs` dans l’ordre du parcours (parents d’abord).
+fn plan_copy(
+    source: &Path,
+    destination: &Path,
+    files: &mut Vec<(PathBuf, PathBuf)>,
+    dirs: &mut Vec<(PathBuf, PathBuf)>,
+) -> io::Result<()> {
+    let metadata = fs::symlink_metadata(source)?;
+
+    if metadata.file_type().is_symlink() {
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(fs::read_link(source)?, destination)?;
+        #[cfg(not(unix))]
+        fs::copy(source, destination)?;
+    } else if metadata.is_dir() {
+        fs::create_dir(destination)?;
+        dirs.push((source.to_path_buf(), destination.to_path_buf()));
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            plan_copy(&entry.path(), &destination.join(entry.file_name()), files, dirs)?;
+        }
+    } else {
+        files.push((source.to_path_buf(), destination.to_path_buf()));
+    }
+
+    Ok(())
+}
+
+
+/// # Fonction : `copy_files`
+///
+/// Copie chaque couple `(source, destination)` de `files` avec `fs::copy` (qui recopie
+/// les permissions), puis ses attributs étendus avec [`copy_xattrs`] et ses dates avec
+/// [`copy_times`].
+///
+/// Avec `--jobs N` (N > 1), `N` threads se partagent la file avec un index atomique :
+/// chaque fichier est pris par un seul thread, et chaque destination est distincte,
+/// donc deux copies ne touchent jamais le même chemin. L’avancement est affiché si la
+/// sortie d’erreur est un terminal, puis un résumé.
+///
+/// La première erreur arrête les threads et est retournée, comme un Ctrl+C
+/// (erreur `Interrupted`).
+fn copy_files(options: &Options, files: &[(PathBuf, PathBuf)]) -> io::Result<()> {
+    let jobs = options.jobs.max(1);
+    let total = files.len();
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+    let error: Mutex<Option<io::Error>> = Mutex::new(None);
+    let show_progress = jobs > 1 && io::stderr().is_terminal();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(total.max(1)) {
+            scope.spawn(|| {
+                while error.lock().unwrap().is_none()
+                    && let Some((from, to)) = files.get(next.fetch_add(1, Ordering::Relaxed))
+                {
+                    let result = interrupt::check()
+                        .and_then(|_| fs::copy(from, to))
+                        .and_then(|_| {
+                            copy_xattrs(options, from, to);
+                            copy_times(from, to)
+                        });
+                    if let Err(e) = result {
+                        error.lock().unwrap().get_or_insert(e);
+                        return;
+                    }
+
+                    let count = done.fetch_add(1, Ordering::Relaxed) + 1;
+                    if show_progress {
+                        eprint!("\rmv: {count}/{total} files");
+                    }
+                }
+            });
+        }
+    });
+
+    if show_progress && total > 0 {
+        eprintln!();
+    }
+    if jobs > 1 {
+        println!("mv: copied {} of {total} files using {jobs} jobs", done.into_inner());
+    }
+    match error.into_inner().unwrap() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+
+/// # Fonction : `copy_xattrs`
+///
+/// Recopie les attributs étendus de `source` sur `destination` lors d'une copie, sauf
+/// avec `--no-xattrs`. Un échec ne fait pas échouer le déplacement : il n'est signalé
+/// qu'avec `--xattrs`, car beaucoup de systèmes de fichiers (FAT des clés USB) n'en
+/// gèrent pas.
+fn copy_xattrs(options: &Options, source: &Path, destination: &Path) {
+    if options.xattrs == Xattrs::Strip {
+        return;
+    }
+
+    if let Err(e) = xattr::copy(source, destination)
+        && options.xattrs == Xattrs::Copy
+    {
+        eprintln!("mv: warning: cannot copy extended attributes of '{}': {e}", destination.display());
+    }
+}
+
+
+/// # Fonction : `copy_times`
+///
+/// Recopie les dates d’accès et de modification de `source` sur `destination`,
+/// pour qu’un déplacement par copie les conserve comme un renommage.
+fn copy_times(source: &Path, destination: &Path) -> io::Result<()> {
+    let metadata = fs::metadata(source)?;
+    let times = FileTimes::new()
+        .set_accessed(metadata.accessed()?)
+        .set_modified(metadata.modified()?);
+    File::open(destination)?.set_times(times)
+}
+
+
+/// # Fonction : `remove_any`
+///
+/// Supprime un fichier, un lien symbolique (sans le suivre) ou un dossier complet.
+fn remove_any(path: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(path)?.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+
+/// # Fonction : `append_journal`
+///
+/// Ajoute une ligne `source\tdestination\tdate\ttaille` à la fin du journal (créé si besoin).
+///
+/// La date de modification et la taille de la destination ([`journal_stamp`]) permettent
+/// à `--undo` de vérifier qu’elle n’a pas changé depuis. Les chemins sont échappés avec
+/// [`escape_field`], pour qu’une tabulation ou un retour à la ligne dans un nom ne
+/// casse pas le format.
+fn append_journal(journal: &str, source: &Path, destination: &Path) -> io::Result<()> {
+    let stamp = journal_stamp(destination)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path::resolve(journal)?)?;
+    writeln!(
+        file,
+        "{}\t{}\t{}",
+        escape_field(&source.display().to_string()),
+        escape_field(&destination.display().to_string()),
+        stamp
+    )
+}
+
+
+/// # Fonction : `journal_stamp`
+///
+/// Retourne `date\ttaille` pour `path` (sans suivre un lien symbolique) : date de
+/// modification en secondes et nanosecondes depuis l’époque Unix, taille en octets.
+///
+/// ## Exemple :
+/// ```text
+/// 1714550400.123456789<TAB>2048
+/// ```
+fn journal_stamp(path: &Path) -> io::Result<String> {
+    let metadata = fs::symlink_metadata(path)?;
+    let modified = metadata
+        .modified()?
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    Ok(format!("{}.{:09}\t{}", modified.as_secs(), modified.subsec_nanos(), metadata.len()))
+}
+
+
+/// # Fonction : `escape_field`
+///
+/// Échappe `\`, la tabulation, le retour à la ligne et le retour chariot d’un champ du
+/// journal (`\\`, `\t`, `\n`, `\r`). [`escape::unescape`] fait l’opération inverse.
+///
+/// ## Exemple :
+/// ```rust
+/// assert_eq!(escape_field("a\tb\\c\n"), "a\\tb\\\\c\\n");
+/// ```
+fn escape_field(field: &str) -> String {
+    let mut escaped = String::with_capacity(field.len());
+    for c in field.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+
+/// # Fonction : `undo_journal`
+///
+/// Annule les déplacements enregistrés dans un journal.
+///
+/// # Fonctionnement
+/// - Lit les lignes `source\tdestination\tdate\ttaille` du journal (voir [`append_journal`]).
+///   Les lignes `source\tdestination` des anciens journaux restent acceptées, sans vérification.
+/// - Les rejoue de la plus récente à la plus ancienne (ordre LIFO), en déplaçant
+///   chaque destination vers sa source d’origine.
+/// - Si la destination a disparu, si sa date de modification ou sa taille ne sont plus
+///   celles du journal (elle a été modifiée ou remplacée), ou si l’emplacement d’origine
+///   est de nouveau occupé, affiche un avertissement au lieu de déplacer quoi que ce soit.
+/// - Réécrit le journal avec les seules entrées non annulées (ou le supprime si
+///   tout a été annulé).
+fn undo_journal(journal: &str, verbose: bool) {
+    let journal_path = match path::resolve(journal) {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("mv: cannot resolve path: {e}");
+            return;
+        }
+    };
+
+    let content = match fs::read_to_string(&journal_path) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("mv: cannot read journal '{journal}': {e}");
+            return;
+        }
+    };
+
+    let mut remaining: Vec<&str> = Vec::new();
+
+    for line in content.lines().rev().filter(|l| !l.is_empty()) {
+        let fields: Vec<&str> = line.splitn(3, '\t').collect();
+        let (from, to, stamp) = match fields[..] {
+            [from, to] => (escape::unescape(from), escape::unescape(to), None),
+            [from, to, stamp] => (escape::unescape(from), escape::unescape(to), Some(stamp)),
+            _ => {
+                eprintln!("mv: invalid journal entry: '{line}'");
+                remaining.push(line);
+                continue;
+            }
+        };
+
+        if fs::symlink_metadata(&to).is_err() {
+            eprintln!("mv: warning: '{to}' no longer exists, cannot restore '{from}'");
+            remaining.push(line);
+            continue;
+        }
+        if let Some(stamp) = stamp
+            && journal_stamp(Path::new(&to)).ok().as_deref() != Some(stamp)
+        {
+            eprintln!("mv: warning: '{to}' has changed since it was moved, not restoring it to '{from}'");
+            remaining.push(line);
+            continue;
+        }
+        if fs::symlink_metadata(&from).is_ok() {
+            eprintln!("mv: warning: '{from}' exists again, not overwriting it with '{to}'");
+            remaining.push(line);
+            continue;
+        }
+
+        match move_path(&Options::default(), Path::new(&to), Path::new(&from)) {
+            Ok(_) => {
+                if verbose {
+                    println!("restored '{to}' -> '{from}'");
+                }
+            }
+            Err(e) => {
+                eprintln!("mv: cannot restore '{to}' to '{from}': {e}");
+                remaining.push(line);
+            }
+        }
+    }
+
+    // Ne conserve dans le journal que les entrées non annulées
+    let result = if remaining.is_empty() {
+        fs::remove_file(&journal_path)
+    } else {
+        remaining.reverse();
+        fs::write(&journal_path, remaining.join("\n") + "\n")
+    };
+    if let Err(e) = result {
+        eprintln!("mv: cannot update journal '{journal}': {e}");
+    }
+}
+
+
+/// # Fonction : `swap_files`
+///
+/// Échange deux fichiers ou dossiers existants : chacun prend le nom de l’autre.
+///
+/// # Fonctionnement
+/// - Vérifie que les deux chemins existent et sont distincts.
+/// - Sous Linux, tente un échange atomique avec [`exchange`] (`renameat2` et `RENAME_EXCHANGE`).
+/// - Si l’échange atomique n’est pas disponible (autre système, noyau ou système de
+///   fichiers qui ne le gère pas), procède en trois déplacements via un nom temporaire
+///   placé à côté du premier fichier.
+fn swap_files(options: &Options, first: &str, second: &str) {
+    let (first_path, second_path) = match (path::resolve(first), path::resolve(second)) {
+        (Ok(a), Ok(b)) => (a, b),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("mv: cannot resolve path: {e}");
+            return;
+        }
+    };
+
+    for (name, path) in [(first, &first_path), (second, &second_path)] {
+        if fs::symlink_metadata(path).is_err() {
+            eprintln!("mv: cannot stat '{name}': No such file or directory");
+            return;
+        }
+    }
+    if let (Ok(a), Ok(b)) = (path::canonicalize(&first_path), path::canonicalize(&second_path))
+        && a == b
+    {
+        eprintln!("mv: '{first}' and '{second}' are the same file");
+        return;
+    }
+
+    let result = match exchange(&first_path, &second_path) {
+        Ok(true) => Ok(()),
+        Ok(false) => swap_with_temporary(&first_path, &second_path),
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(()) => {
+            if options.verbose {
+                println!("swapped '{first}' <-> '{second}'");
+            }
+        }
+        Err(e) => eprintln!("mv: cannot swap '{first}' and '{second}': {e}"),
+    }
+}
+
+
+/// # Fonction : `exchange`
+///
+/// Échange atomiquement deux chemins avec `renameat2(RENAME_EXCHANGE)`.
+///
+/// Retourne `Ok(false)` si l’échange atomique n’est pas pris en charge
+/// (`ENOSYS`, `EINVAL` : noyau trop ancien ou système de fichiers incompatible).
+#[cfg(target_os = "linux")]
+fn exchange(first: &Path, second: &Path) -> io::Result<bool> {
+    use std::ffi::{CString, c_char, c_int, c_uint};
+    use std::os::unix::ffi::OsStrExt;
+
+    const AT_FDCWD: c_int = -100;
+    const RENAME_EXCHANGE: c_uint = 1 << 1;
+    const EINVAL: i32 = 22;
+    const ENOSYS: i32 = 38;
+
+    unsafe extern "C" {
+        fn renameat2(
+            olddirfd: c_int,
+            oldpath: *const c_char,
+            newdirfd: c_int,
+            newpath: *const c_char,
+            flags: c_uint,
+        ) -> c_int;
+    }
+
+    let first = CString::new(first.as_os_str().as_bytes())?;
+    let second = CString::new(second.as_os_str().as_bytes())?;
+
+    // SAFETY : les deux chaînes sont terminées par un octet nul et restent valides pendant l’appel.
+    let status = unsafe { renameat2(AT_FDCWD, first.as_ptr(), AT_FDCWD, second.as_ptr(), RENAME_EXCHANGE) };
+    if status == 0 {
+        return Ok(true);
+    }
+
+    let error = io::Error::last_os_error();
+    match error.raw_os_error() {
+        Some(EINVAL) | Some(ENOSYS) => Ok(false),
+        _ => Err(error),
+    }
+}
+
+/// # Fonction : `exchange`
+///
+/// Hors Linux, l’échange atomique n’est pas disponible.
+#[cfg(not(target_os = "linux"))]
+fn exchange(_first: &Path, _second: &Path) -> io::Result<bool> {
+    Ok(false)
+}
+
+
+/// # Fonction : `swap_with_temporary`
+///
+/// Échange deux chemins en trois déplacements : `first` → temporaire,
+/// `second` → `first`, temporaire → `second`. Si une étape échoue, les
+/// déplacements déjà faits sont annulés.
+fn swap_with_temporary(first: &Path, second: &Path) -> io::Result<()> {
+    let name = first.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let temporary = first.with_file_name(format!(".{}.swap-{}", name, std::process::id()));
+
+    fs::rename(first, &temporary)?;
+    if let Err(e) = fs::rename(second, first) {
+        let _ = fs::rename(&temporary, first);
+        return Err(e);
+    }
+    if let Err(e) = fs::rename(&temporary, second) {
+        let _ = fs::rename(first, second);
+        let _ = fs::rename(&temporary, first);
+        return Err(e);
+    }
+    Ok(())
+}
+
+
+/// # Fonction : `normalize_files`
+///
+/// Renomme chaque fichier ou dossier sur place avec le nom donné par [`slugify`] (`--normalize`).
+///
+/// # Fonctionnement
+/// - Un opérande contenant un joker (`*`, `?`, `[...]`) est développé avec [`glob::expand`].
+/// - Un nom déjà normalisé est laissé tel quel.
+/// - Un nom qui deviendrait vide, ou déjà pris (par un fichier existant ou par un
+///   autre fichier de la même commande), est ignoré avec un avertissement : rien n’est écrasé.
+/// - Chaque renommage est affiché, et ajouté au journal avec `--journal`.
+///
+/// Seul le dernier composant du chemin est renommé : le fichier reste dans son dossier.
+fn normalize_files(options: &Options, operands: &[&str]) {
+    let targets = expand_operands(operands);
+
+    // Noms déjà attribués pendant cette commande, pour ne pas renommer deux fichiers pareil
+    let mut claimed: HashSet<PathBuf> = HashSet::new();
+
+    for (name, source_path) in targets {
+        // Après Ctrl+C, les fichiers déjà renommés le restent
+        if interrupt::is_interrupted() {
+            eprintln!("mv: interrupted; {} file(s) renamed", claimed.len());
+            return;
+        }
+        if fs::symlink_metadata(&source_path).is_err() {
+            eprintln!("mv: cannot stat '{name}': No such file or directory");
+            continue;
+        }
+        let Some(file_name) = source_path.file_name().map(|n| n.to_string_lossy().into_owned()) else {
+            eprintln!("mv: cannot normalize '{name}'");
+            continue;
+        };
+
+        let slug = slugify(&file_name);
+        if slug.is_empty() {
+            eprintln!("mv: skipping '{name}': nothing left after normalization");
+            continue;
+        }
+        if slug == file_name {
+            continue;
+        }
+
+        let destination = source_path.with_file_name(&slug);
+        // Sur un système insensible à la casse, "Photo.JPG" et "photo.jpg" sont le même fichier
+        let same_file = matches!(
+            (path::canonicalize(&source_path), path::canonicalize(&destination)),
+            (Ok(a), Ok(b)) if a == b
+        );
+        if claimed.contains(&destination) || (fs::symlink_metadata(&destination).is_ok() && !same_file) {
+            eprintln!("mv: skipping '{name}': '{slug}' already exists");
+            continue;
+        }
+
+        match fs::rename(&source_path, &destination) {
+            Ok(_) => {
+                println!("renamed '{file_name}' -> '{slug}'");
+                if let Some(journal) = &options.journal
+                    && let Err(e) = append_journal(journal, &source_path, &destination)
+                {
+                    eprintln!("mv: cannot write journal '{journal}': {e}");
+                }
+                claimed.insert(destination);
+            }
+            Err(e) => eprintln!("mv: cannot rename '{name}' to '{slug}': {e}"),
+        }
+    }
+}
+
+
+/// # Fonction : `expand_operands`
+///
+/// Retourne chaque opérande avec son chemin résolu. Un opérande contenant un joker
+/// (`*`, `?`, `[...]`) est remplacé par les chemins trouvés par [`glob::expand`].
+/// Les erreurs sont affichées et l’opérande fautif est ignoré.
+fn expand_operands(operands: &[&str]) -> Vec<(String, PathBuf)> {
+    let mut targets: Vec<(String, PathBuf)> = Vec::new();
+    for operand in operands {
+        if glob::is_pattern(operand) {
+            match glob::expand(operand) {
+                Ok(matches) if matches.is_empty() => eprintln!("mv: no match for '{operand}'"),
+                Ok(matches) => targets.extend(matches.into_iter().map(|p| (p.display().to_string(), p))),
+                Err(e) => eprintln!("mv: cannot expand '{operand}': {e}"),
+            }
+            continue;
+        }
+        match path::resolve(operand) {
+            Ok(p) => targets.push((operand.to_string(), p)),
+            Err(e) => eprintln!("mv: cannot resolve path: {e}"),
+        }
+    }
+    targets
+}
+
+
+/// # Fonction : `move_as`
+///
+/// Déplace chaque source dans le dossier `destination`, sous le nom donné par le
+/// modèle `--as` ([`Template::expand`]), puis délègue le déplacement à [`move_file`]
+/// (`-i`, `-f`, `--journal`... s’appliquent donc normalement).
+///
+/// - `destination` doit être un dossier existant.
+/// - Les jokers des sources sont développés avec [`expand_operands`].
+/// - `{date}` est calculée une seule fois : tous les fichiers portent la même date.
+/// - Une source dont le nom produit serait déjà celui d’un autre fichier de la même
+///   commande est laissée en place avec un avertissement ([`Batch`]).
+///
+/// # Exemple
+/// ```text
+/// mv --as "{date}-{name}.{ext}" scan1.pdf scan2.pdf archives/
+/// → archives/2024-05-01-scan1.pdf, archives/2024-05-01-scan2.pdf
+/// ```
+fn move_as(options: &Options, template: &Template, sources: &[&str], destination: &str) {
+    let mut batch = match Batch::new(template, destination, date::format_day(SystemTime::now())) {
+        Ok(batch) => batch,
+        Err(e) => {
+            eprintln!("mv: {e}");
+            return;
+        }
+    };
+
+    for (source, source_path) in expand_operands(sources) {
+        if interrupt::is_interrupted() {
+            eprintln!("mv: interrupted; remaining files not moved");
+            return;
+        }
+
+        match batch.destination(&source, &source_path) {
+            Ok(final_destination) => move_file(options, &source, &final_destination),
+            Err(e) => eprintln!("mv: {e}"),
+        }
+    }
+}
+
+
+/// # Fonction : `slugify`
+///
+/// Transforme un nom de fichier en une forme sûre, en conservant son extension.
+///
+/// # Fonctionnement
+/// - Sépare l’extension (après le dernier `.`) ; un `.` initial (fichier caché) est conservé.
+/// - Met le nom en minuscules et remplace les lettres accentuées courantes par
+///   leur équivalent ASCII (`é` → `e`, `ç` → `c`, `œ` → `oe`...).
+/// - Garde les lettres et chiffres ASCII ; toute suite d’autres caractères
+///   (espaces, ponctuation, `_`...) devient un seul `-`, sans `-` au début ni à la fin.
+/// - L’extension est mise en minuscules, sans ses caractères non alphanumériques.
+///
+/// Retourne une chaîne vide si le nom ne contient aucune lettre ni chiffre.
+///
+/// # Exemple
+/// ```rust
+/// assert_eq!(slugify("Mon Rapport FINAL (2).PDF"), "mon-rapport-final-2.pdf");
+/// assert_eq!(slugify("Été  à_Noël!!.txt"), "ete-a-noel.txt");
+/// assert_eq!(slugify(".Config Locale"), ".config-locale");
+/// ```
+fn slugify(name: &str) -> String {
+    let (prefix, name) = match name.strip_prefix('.') {
+        Some(rest) => (".", rest),
+        None => ("", name),
+    };
+    let (stem, extension) = match name.rfind('.') {
+        Some(index) if index > 0 => (&name[..index], Some(&name[index + 1..])),
+        _ => (name, None),
+    };
+
+    let mut slug = String::new();
+    for c in stem.chars().flat_map(char::to_lowercase) {
+        let ascii = match c {
+            'à' | 'â' | 'ä' | 'á' | 'ã' | 'å' => "a",
+            'é' | 'è' | 'ê' | 'ë' => "e",
+            'î' | 'ï' | 'í' | 'ì' => "i",
+            'ô' | 'ö' | 'ó' | 'ò' | 'õ' => "o",
+            'ù' | 'û' | 'ü' | 'ú' => "u",
+            'ç' => "c",
+            'ñ' => "n",
+            'ÿ' => "y",
+            'œ' => "oe",
+            'æ' => "ae",
+            c if c.is_ascii_alphanumeric() => {
+                slug.push(c);
+                continue;
+            }
+            _ => "-",
+        };
+        if ascii != "-" || !slug.ends_with('-') {
+            slug.push_str(ascii);
+        }
+    }
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        return String::new();
+    }
+
+    let extension: String = extension
+        .unwrap_or_default()
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    if extension.is_empty() {
+        format!("{prefix}{slug}")
+    } else {
+        format!("{prefix}{slug}.{extension}")
+    }
+}
+
+
 /// # Fonction : `handle_mv`
 /// Gère la commande **`mv`** en ligne de commande.
 ///
@@ -119,33 +943,381 @@ fn move_file(flag: Option<&str>, source: &str, destination: &str) {
 /// [`move_file()`] pour effectuer le déplacement ou le renommage.
 ///
 /// # Fonctionnement
-/// 1. Vérifie qu’il y a suffisamment d’arguments.  
-/// 2. Détermine si le premier argument est un flag (`-i` ou `-v`).  
-/// 3. Identifie le fichier source et la destination.  
-/// 4. Appelle la fonction [`move_file()`] avec les bons paramètres.
+/// 1. Sépare les flags (`-i`, `-v`, `--swap`, `--journal <fichier>`, `--undo <fichier>`) des chemins.
+/// 2. Avec `--undo`, annule les déplacements du journal via [`undo_journal()`].
+/// 3. Avec `--swap`, échange les deux chemins via [`swap_files()`].
+/// 4. Avec `--normalize`, renomme chaque chemin sur place via [`normalize_files()`].
+/// 5. Sinon, vérifie qu’il y a une source et une destination.
+/// 6. Avec `--as <modèle>`, déplace toutes les sources dans le dernier chemin via [`move_as()`].
+/// 7. Sinon, appelle la fonction [`move_file()`] avec les bons paramètres.
 pub fn handle_mv(args: &[String]) {
+    // Ctrl+C arrête --normalize entre deux fichiers au lieu de quitter le programme
+    let _guard = interrupt::guard();
+    let mut options = Options { jobs: 1, ..Default::default() };
+    let mut undo: Option<&str> = None;
+    let mut swap = false;
+    let mut normalize = false;
+    let mut paths: Vec<&str> = Vec::new();
+
+    // Les arguments commençant par '-' sont des flags, les autres sont des chemins.
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-i" => options.interactive = true,
+            "-v" => options.verbose = true,
+            "-f" | "--force" => options.force = true,
+            "--swap" => swap = true,
+            "--normalize" => normalize = true,
+            "--xattrs" => options.xattrs = Xattrs::Copy,
+            "--no-xattrs" => options.xattrs = Xattrs::Strip,
+            "--print-paths" => options.print_paths = true,
+            "--skip-identical" => options.skip_identical = true,
+            as_arg if as_arg == "--as" || as_arg.starts_with("--as=") => {
+                let value = match as_arg.strip_prefix("--as=") {
+                    Some(value) => Some(value),
+                    None => iter.next().map(|v| v.as_str()),
+                };
+                let Some(value) = value else {
+                    eprintln!("mv: option '--as' requires an argument");
+                    return;
+                };
+                match Template::parse(value) {
+                    Ok(template) => options.as_template = Some(template),
+                    Err(e) => {
+                        eprintln!("mv: invalid template: {e}");
+                        return;
+                    }
+                }
+            }
+            "--jobs" => {
+                let value = iter.next();
+                match value.and_then(|v| v.parse::<usize>().ok()) {
+                    Some(n) if n > 0 => options.jobs = n,
+                    _ => {
+                        eprintln!("mv: invalid number of jobs: '{}'", value.map(|v| v.as_str()).unwrap_or(""));
+                        return;
+                    }
+                }
+            }
+            "--journal" | "--undo" => {
+                let Some(file) = iter.next() else {
+                    eprintln!("mv: option '{arg}' requires an argument");
+                    return;
+                };
+                if arg == "--journal" {
+                    options.journal = Some(file.to_string());
+                } else {
+                    undo = Some(file);
+                }
+            }
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                eprintln!("mv: invalid option -- '{flag}'");
+                eprintln!("Try 'mv --help' for more information.");
+                return;
+            }
+            other => paths.push(other),
+        }
+    }
+
+    // --undo rejoue le journal à l'envers et ne déplace rien d'autre
+    if let Some(journal) = undo {
+        undo_journal(journal, options.verbose);
+        return;
+    }
+
+    // --swap échange exactement deux chemins existants
+    if swap {
+        if paths.len() != 2 {
+            eprintln!("mv: --swap requires exactly two operands");
+            return;
+        }
+        swap_files(&options, paths[0], paths[1]);
+        return;
+    }
+
+    // --normalize renomme chaque opérande sur place
+    if normalize {
+        if paths.is_empty() {
+            eprintln!("mv: --normalize requires at least one operand");
+            return;
+        }
+        normalize_files(&options, &paths);
+        return;
+    }
+
     // Vérifie qu'il y a suffisamment d'arguments.
-    if args.len() < 2 {
+    if paths.len() < 2 {
         eprintln!("mv: missing file operand");
         eprintln!("Try 'mv --help' for more information.");
         return;
     }
 
-    let mut flag: Option<&str> = None;
-    let (source, destination);
+    // Avec --as, toutes les sources sont déplacées dans le dernier chemin (un dossier)
+    if let Some(template) = &options.as_template {
+        let (destination, sources) = paths.split_last().unwrap();
+        move_as(&options, template, sources, destination);
+        return;
+    }
+
+    move_file(&options, paths[0], paths[1]);
+}
 
-    // Si l’utilisateur a passé au moins 3 arguments :
-    // - le premier est considéré comme un flag (ex. "-i" ou "-v").
-    // Sinon :
-    // - les deux premiers arguments correspondent directement à la source et la destination.
-    if args.len() == 3 {
-        flag = Some(args[0].as_str());
-        source = &args[1];
-        destination = &args[2];
-    } else {
-        source = &args[0];
-        destination = &args[1];
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdir::TestDir;
+
+    fn text(path: &Path) -> &str {
+        path.to_str().unwrap()
     }
 
-    move_file(flag, source, destination);
-}
\ No newline at end of file
+    #[test]
+    fn journal_fields_round_trip() {
+        for field in ["simple.txt", "a\tb", "ligne\nsuivante\r", "C:\\dossier\\x41", ""] {
+            let escaped = escape_field(field);
+            assert!(!escaped.contains(['\t', '\n', '\r']));
+            assert_eq!(escape::unescape(&escaped), field);
+        }
+    }
+
+    #[test]
+    fn undo_restores_names_with_tabs_and_newlines() {
+        let dir = TestDir::new("mv-journal");
+        let source = dir.write("avec\ttab\net retour.txt", "contenu");
+        let destination = dir.join("range.txt");
+        let journal = dir.join("journal.txt");
+        let options = Options { journal: Some(text(&journal).to_string()), ..Default::default() };
+
+        move_file(&options, text(&source), text(&destination));
+        assert!(!source.exists());
+        assert_eq!(fs::read_to_string(&journal).unwrap().lines().count(), 1);
+
+        undo_journal(text(&journal), false);
+        assert_eq!(fs::read_to_string(&source).unwrap(), "contenu");
+        assert!(!destination.exists());
+        assert!(!journal.exists());
+    }
+
+    #[test]
+    fn undo_refuses_a_modified_destination() {
+        let dir = TestDir::new("mv-journal-changed");
+        let source = dir.write("a.txt", "avant");
+        let destination = dir.join("b.txt");
+        let journal = dir.join("journal.txt");
+        let options = Options { journal: Some(text(&journal).to_string()), ..Default::default() };
+
+        move_file(&options, text(&source), text(&destination));
+        fs::write(&destination, "modifié après le déplacement").unwrap();
+
+        undo_journal(text(&journal), false);
+        assert!(!source.exists());
+        assert_eq!(fs::read_to_string(&destination).unwrap(), "modifié après le déplacement");
+        assert_eq!(fs::read_to_string(&journal).unwrap().lines().count(), 1);
+    }
+
+    #[test]
+    fn undo_accepts_entries_without_stamp() {
+        let dir = TestDir::new("mv-journal-old");
+        let destination = dir.write("b.txt", "x");
+        let source = dir.join("a.txt");
+        let journal = dir.write("journal.txt", format!("{}\t{}\n", text(&source), text(&destination)));
+
+        undo_journal(text(&journal), false);
+        assert!(source.exists());
+        assert!(!destination.exists());
+    }
+
+    #[test]
+    fn copy_across_copies_many_files_with_any_number_of_jobs() {
+        let dir = TestDir::new("mv-across");
+        for i in 0..40 {
+            dir.write(&format!("src/d{}/f{i}.txt", i % 4), format!("fichier {i}"));
+        }
+        let old = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        File::options().write(true).open(dir.join("src/d0/f0.txt")).unwrap().set_modified(old).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("d1/f1.txt", dir.join("src/lien")).unwrap();
+
+        for jobs in [1, 4] {
+            let destination = dir.join(&format!("copie{jobs}"));
+            copy_across(&Options { jobs, ..Default::default() }, &dir.join("src"), &destination).unwrap();
+            for i in 0..40 {
+                let copied = destination.join(format!("d{}/f{i}.txt", i % 4));
+                assert_eq!(fs::read_to_string(copied).unwrap(), format!("fichier {i}"), "jobs {jobs}");
+            }
+            assert_eq!(fs::metadata(destination.join("d0/f0.txt")).unwrap().modified().unwrap(), old);
+            #[cfg(unix)]
+            assert_eq!(fs::read_link(destination.join("lien")).unwrap(), Path::new("d1/f1.txt"));
+        }
+        assert!(dir.join("src/d0/f0.txt").exists());
+    }
+
+    #[test]
+    fn failed_copy_across_keeps_source_and_existing_destination() {
+        let dir = TestDir::new("mv-across-failed");
+        dir.write("src/a.txt", "a");
+        dir.write("copie/garde.txt", "g");
+
+        assert!(copy_across(&Options::default(), &dir.join("src"), &dir.join("copie")).is_err());
+        assert!(dir.join("src/a.txt").exists());
+        assert_eq!(fs::read_to_string(dir.join("copie/garde.txt")).unwrap(), "g");
+    }
+
+    #[test]
+    fn move_path_moves_files_and_trees() {
+        let dir = TestDir::new("mv-move-path");
+        dir.write("arbre/sous/b.txt", "b");
+
+        move_path(&Options::default(), &dir.join("arbre"), &dir.join("deplace")).unwrap();
+        assert!(!dir.join("arbre").exists());
+        assert_eq!(fs::read_to_string(dir.join("deplace/sous/b.txt")).unwrap(), "b");
+
+        remove_any(&dir.join("deplace")).unwrap();
+        assert!(!dir.join("deplace").exists());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn copy_across_keeps_extended_attributes_unless_stripped() {
+        let dir = TestDir::new("mv-across-xattrs");
+        let file = dir.write("src/a.txt", "a");
+        if !xattr::set(&file, "user.origine", b"mv") || !xattr::set(&dir.join("src"), "user.dossier", b"1") {
+            return;
+        }
+
+        for (name, xattrs) in [("defaut", Xattrs::Default), ("xattrs", Xattrs::Copy), ("sans", Xattrs::Strip)] {
+            let destination = dir.join(name);
+            copy_across(&Options { xattrs, ..Default::default() }, &dir.join("src"), &destination).unwrap();
+            let (file_attributes, dir_attributes) =
+                (xattr::user_attributes(&destination.join("a.txt")), xattr::user_attributes(&destination));
+            if xattrs == Xattrs::Strip {
+                assert!(file_attributes.is_empty() && dir_attributes.is_empty());
+            } else {
+                assert_eq!(file_attributes, [("user.origine".to_string(), b"mv".to_vec())], "{name}");
+                assert_eq!(dir_attributes, [("user.dossier".to_string(), b"1".to_vec())], "{name}");
+            }
+        }
+    }
+
+    #[test]
+    fn as_template_accepts_both_spellings() {
+        let dir = TestDir::new("mv-as");
+        let archive = dir.mkdir("archives");
+        let first = dir.write("a.txt", "a");
+        let second = dir.write("b.txt", "b");
+
+        let args = |template: &str, source: &Path| -> Vec<String> {
+            [template, text(source), text(&archive)].iter().map(|s| s.to_string()).collect()
+        };
+        handle_mv(&args("--as={name}.bak", &first));
+        handle_mv(&[vec!["--as".to_string()], args("{name}-2.{ext}", &second)].concat());
+
+        assert_eq!(fs::read_to_string(archive.join("a.bak")).unwrap(), "a");
+        assert_eq!(fs::read_to_string(archive.join("b-2.txt")).unwrap(), "b");
+        assert!(!first.exists() && !second.exists());
+    }
+
+    #[test]
+    fn as_template_leaves_colliding_sources_in_place() {
+        let dir = TestDir::new("mv-as-collision");
+        let archive = dir.mkdir("archives");
+        let first = dir.write("rapport.txt", "1");
+        let second = dir.write("rapport.md", "2");
+        let template = Template::parse("{name}").unwrap();
+
+        move_as(&Options::default(), &template, &[text(&first), text(&second)], text(&archive));
+        assert_eq!(fs::read_to_string(archive.join("rapport")).unwrap(), "1");
+        assert!(second.exists());
+    }
+
+    #[test]
+    fn refuses_to_move_into_itself() {
+        let dir = TestDir::new("mv-itself");
+        dir.write("d/a.txt", "a");
+        dir.mkdir("d/sub");
+        let file = dir.write("a", "contenu");
+
+        move_file(&Options::default(), text(&dir.join("d")), text(&dir.join("d/sub")));
+        assert_eq!(fs::read_to_string(dir.join("d/a.txt")).unwrap(), "a");
+        assert_eq!(fs::read_dir(dir.join("d/sub")).unwrap().count(), 0);
+
+        move_file(&Options::default(), text(&file), text(&file));
+        assert_eq!(fs::read_to_string(&file).unwrap(), "contenu");
+    }
+
+    #[test]
+    fn trailing_slash_requires_a_directory() {
+        let dir = TestDir::new("mv-slash");
+        let file = dir.write("a.txt", "a");
+        let existing = dir.write("existant", "garde");
+        dir.write("src/b.txt", "b");
+        let slashed = |path: &Path| format!("{}/", path.display());
+
+        move_file(&Options::default(), text(&file), &slashed(&dir.join("dest")));
+        assert!(file.exists() && !dir.join("dest").exists());
+
+        move_file(&Options::default(), text(&file), &slashed(&existing));
+        assert!(file.exists());
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "garde");
+
+        move_file(&Options::default(), text(&dir.join("src")), &slashed(&dir.join("dest")));
+        assert!(!dir.join("src").exists());
+        assert_eq!(fs::read_to_string(dir.join("dest/b.txt")).unwrap(), "b");
+    }
+
+    #[test]
+    fn swap_exchanges_files_and_directories() {
+        let dir = TestDir::new("mv-swap");
+        let file = dir.write("a.txt", "a");
+        dir.write("d/b.txt", "b");
+
+        swap_files(&Options::default(), text(&file), text(&dir.join("d")));
+        assert_eq!(fs::read_to_string(dir.join("d")).unwrap(), "a");
+        assert_eq!(fs::read_to_string(dir.join("a.txt/b.txt")).unwrap(), "b");
+
+        swap_files(&Options::default(), text(&file), text(&dir.join("absent")));
+        assert!(dir.join("a.txt").is_dir());
+    }
+
+    #[test]
+    fn swap_with_temporary_exchanges_and_leaves_no_temporary() {
+        let dir = TestDir::new("mv-swap-temporary");
+        let first = dir.write("a.txt", "a");
+        let second = dir.write("b.txt", "b");
+
+        swap_with_temporary(&first, &second).unwrap();
+        assert_eq!(fs::read_to_string(&first).unwrap(), "b");
+        assert_eq!(fs::read_to_string(&second).unwrap(), "a");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+
+        // Le second chemin manque : le premier est remis en place
+        assert!(swap_with_temporary(&first, &dir.join("absent")).is_err());
+        assert_eq!(fs::read_to_string(&first).unwrap(), "b");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn slugify_keeps_a_safe_name_and_the_extension() {
+        assert_eq!(slugify("Mon Rapport FINAL (2).PDF"), "mon-rapport-final-2.pdf");
+        assert_eq!(slugify("Été  à_Noël!!.txt"), "ete-a-noel.txt");
+        assert_eq!(slugify(".Config Locale"), ".config-locale");
+        assert_eq!(slugify("Cœur.tar.GZ"), "coeur-tar.gz");
+        assert_eq!(slugify("déjà-propre"), "deja-propre");
+        assert_eq!(slugify("!!!.txt"), "");
+    }
+
+    #[test]
+    fn normalize_never_overwrites() {
+        let dir = TestDir::new("mv-normalize");
+        let first = dir.write("Photo Été.JPG", "1");
+        let second = dir.write("photo_ete.jpg", "2");
+        let taken = dir.write("Notes.txt", "3");
+        let existing = dir.write("notes.txt", "4");
+
+        normalize_files(&Options::default(), &[text(&first), text(&second), text(&taken)]);
+        assert_eq!(fs::read_to_string(dir.join("photo-ete.jpg")).unwrap(), "1");
+        assert_eq!(fs::read_to_string(&second).unwrap(), "2");
+        assert_eq!(fs::read_to_string(&taken).unwrap(), "3");
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "4");
+    }
+}
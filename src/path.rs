@@ -0,0 +1,330 @@
+//! # Module `path`
+//!
+//! Ce module fournit la résolution des chemins partagée par toutes les commandes.
+//!
+//! Il prend en charge les cas spéciaux suivants :
+//!
+//! - `.` : le dossier courant.
+//! - `..` : le dossier parent.
+//! - `~` et `~/...` : le dossier personnel de l'utilisateur.
+//! - les chemins relatifs, résolus à partir du dossier courant.
+//! - les URI `file://` (ex. `file:///home/moi/mes%20notes.txt`), convertis en chemin local.
+//!
+//! Il repère aussi les fichiers protégés par un marqueur `.keep` (voir [`keep_marker`]).
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// # Fonction : `resolve`
+///
+/// Résout un chemin saisi par l'utilisateur en chemin absolu.
+///
+/// ## Fonctionnement :
+/// 1. Convertit une URI `file://` avec [`uri_to_path`], ou remplace `~`
+///    par le dossier personnel de l'utilisateur.
+/// 2. Rend le chemin absolu à partir du dossier courant s'il est relatif.
+/// 3. Normalise les composants `.` et `..` avec [`normalize`] : avant de remonter
+///    d'un niveau, la partie existante du chemin est résolue sur le disque, pour que
+///    `lien/..` désigne le parent de la cible du lien, comme pour le système.
+///
+/// Le chemin n'a pas besoin d'exister, ce qui permet de résoudre une destination.
+/// Les liens symboliques qui ne sont pas suivis d'un `..` sont conservés tels quels
+/// (`rm lien` supprime donc le lien, pas sa cible).
+///
+/// ## Exemple :
+/// ```rust
+/// let resolved = path::resolve("~/notes.txt")?;
+/// // resolved contient "/home/utilisateur/notes.txt"
+/// ```
+pub fn resolve(input: &str) -> io::Result<PathBuf> {
+    let expanded = if is_uri(input) {
+        uri_to_path(input)?
+    } else {
+        expand_home(input)?
+    };
+
+    let absolute = if expanded.is_absolute() {
+        expanded
+    } else {
+        env::current_dir()?.join(expanded)
+    };
+
+    Ok(normalize(&absolute))
+}
+
+/// # Fonction : `canonicalize`
+///
+/// Retourne le chemin réel d'un chemin absolu : les liens symboliques de ses dossiers
+/// parents sont suivis, mais pas son dernier composant.
+///
+/// Le chemin n'a pas besoin d'exister : seule la partie existante est résolue sur
+/// le disque. Deux chemins désignant le même emplacement ont ainsi la même forme,
+/// ce qui permet de comparer une source et une destination.
+///
+/// ## Exemple :
+/// ```rust
+/// // /tmp/lien -> /tmp/dossier
+/// let real = path::canonicalize(Path::new("/tmp/lien/nouveau"))?;
+/// // real contient "/tmp/dossier/nouveau"
+/// ```
+pub fn canonicalize(path: &Path) -> io::Result<PathBuf> {
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        // La racine n'a ni parent ni nom
+        return Ok(path.to_path_buf());
+    };
+
+    let parent = if parent.exists() {
+        fs::canonicalize(parent)?
+    } else {
+        canonicalize(parent)?
+    };
+
+    Ok(parent.join(name))
+}
+
+/// # Fonction : `keep_marker`
+///
+/// Retourne le marqueur de protection d'un chemin s'il existe : le fichier
+/// `<nom>.keep` placé à côté de lui (`rapport.pdf.keep` pour `rapport.pdf`).
+///
+/// `rm`, `mv` et `cp` refusent de supprimer ou d'écraser un chemin protégé,
+/// sauf avec `-f` (`--force`).
+///
+/// ## Exemple :
+/// ```rust
+/// // /tmp/rapport.pdf.keep existe
+/// assert_eq!(
+///     path::keep_marker(Path::new("/tmp/rapport.pdf")),
+///     Some(PathBuf::from("/tmp/rapport.pdf.keep"))
+/// );
+/// ```
+pub fn keep_marker(path: &Path) -> Option<PathBuf> {
+    let mut name = path.file_name()?.to_os_string();
+    name.push(".keep");
+    let marker = path.with_file_name(name);
+    fs::symlink_metadata(&marker).is_ok().then_some(marker)
+}
+
+/// # Fonction : `uri_to_path`
+///
+/// Convertit une URI `file://` en chemin local.
+///
+/// ## Fonctionnement :
+/// 1. Refuse tout autre schéma que `file` (ex. `http://`).
+/// 2. Accepte un hôte vide (`file:///chemin`) ou `localhost`, et refuse les autres.
+/// 3. Ignore une éventuelle requête (`?...`) ou ancre (`#...`).
+/// 4. Décode les séquences `%XX` (ex. `%20` pour une espace).
+/// 5. Sous Windows, retire le `/` placé devant la lettre de lecteur (`/C:/...`).
+///
+/// ## Exemple :
+/// ```rust
+/// let path = path::uri_to_path("file:///home/moi/mes%20notes.txt")?;
+/// assert_eq!(path, PathBuf::from("/home/moi/mes notes.txt"));
+/// assert!(path::uri_to_path("https://exemple.com/notes.txt").is_err());
+/// ```
+pub fn uri_to_path(uri: &str) -> io::Result<PathBuf> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidInput, message);
+
+    let Some((scheme, rest)) = uri.split_once("://") else {
+        return Err(invalid(format!("URI invalide : '{}'", uri)));
+    };
+    if !scheme.eq_ignore_ascii_case("file") {
+        return Err(invalid(format!("Schéma '{}' non pris en charge (seul file:// est accepté) : '{}'", scheme, uri)));
+    }
+
+    let rest = rest.split(['?', '#']).next().unwrap_or("");
+    let (host, encoded_path) = match rest.find('/') {
+        Some(index) => rest.split_at(index),
+        None => (rest, ""),
+    };
+    if !host.is_empty() && !host.eq_ignore_ascii_case("localhost") {
+        return Err(invalid(format!("Hôte '{}' non pris en charge dans '{}'", host, uri)));
+    }
+    if encoded_path.is_empty() {
+        return Err(invalid(format!("URI sans chemin : '{}'", uri)));
+    }
+
+    let decoded = percent_decode(encoded_path)
+        .ok_or_else(|| invalid(format!("Encodage %XX invalide dans '{}'", uri)))?;
+
+    // "/C:/dossier" désigne le lecteur C: sous Windows
+    #[cfg(windows)]
+    let decoded = match decoded.as_bytes() {
+        [b'/', letter, b':', ..] if letter.is_ascii_alphabetic() => decoded[1..].to_string(),
+        _ => decoded,
+    };
+
+    Ok(PathBuf::from(decoded))
+}
+
+/// # Fonction : `is_uri`
+///
+/// Indique si l'entrée commence par un schéma d'URI (`schéma://`), c'est-à-dire
+/// au moins deux caractères parmi lettres, chiffres, `+`, `-` et `.`, le premier
+/// étant une lettre. Les deux caractères minimum évitent de confondre un lecteur Windows (`C:`).
+fn is_uri(input: &str) -> bool {
+    let Some((scheme, _)) = input.split_once("://") else {
+        return false;
+    };
+
+    scheme.len() >= 2
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// # Fonction : `percent_decode`
+///
+/// Décode les séquences `%XX` (octet en hexadécimal) d'une chaîne.
+/// Retourne `None` si une séquence est incomplète ou si le résultat n'est pas de l'UTF-8 valide.
+fn percent_decode(input: &str) -> Option<String> {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(decoded).ok()
+}
+
+/// # Fonction : `expand_home`
+///
+/// Remplace un `~` en tête du chemin par le dossier personnel de l'utilisateur
+/// (variable `HOME`, ou `USERPROFILE` sous Windows).
+fn expand_home(input: &str) -> io::Result<PathBuf> {
+    let rest = if input == "~" {
+        ""
+    } else if let Some(rest) = input.strip_prefix("~/").or_else(|| input.strip_prefix("~\\")) {
+        rest
+    } else {
+        return Ok(PathBuf::from(input));
+    };
+
+    let home = env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .ok_or_else(|| io::Error::new(
+            io::ErrorKind::NotFound,
+            "Impossible de déterminer le dossier personnel",
+        ))?;
+
+    Ok(PathBuf::from(home).join(rest))
+}
+
+/// # Fonction : `normalize`
+///
+/// Supprime les composants `.` et applique les `..` d'un chemin absolu.
+///
+/// Avant un `..`, le chemin déjà parcouru est remplacé par son chemin réel
+/// (`fs::canonicalize`) s'il existe : si c'est un lien symbolique vers un dossier,
+/// on remonte ainsi au parent de sa cible, et non au dossier qui contient le lien.
+/// Une partie qui n'existe pas encore est remontée de manière lexicale.
+/// Un `..` à la racine est ignoré, comme dans un shell.
+fn normalize(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if let Ok(real) = fs::canonicalize(&result) {
+                    result = real;
+                }
+                result.pop();
+            }
+            other => result.push(other.as_os_str()),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdir::TestDir;
+
+    #[test]
+    fn dots_are_normalized() {
+        assert_eq!(normalize(Path::new("/a/./b/../c")), PathBuf::from("/a/c"));
+        assert_eq!(normalize(Path::new("/../..")), PathBuf::from("/"));
+    }
+
+    #[test]
+    fn relative_paths_start_from_current_directory() {
+        assert_eq!(resolve("notes.txt").unwrap(), env::current_dir().unwrap().join("notes.txt"));
+    }
+
+    #[test]
+    fn missing_paths_are_resolved_lexically() {
+        let dir = TestDir::new("path-missing");
+        let input = dir.join("nouveau/sous/../fichier.txt");
+        let real_dir = fs::canonicalize(dir.path()).unwrap();
+        assert_eq!(resolve(input.to_str().unwrap()).unwrap(), real_dir.join("nouveau/fichier.txt"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn parent_of_a_symlinked_directory_is_the_parent_of_its_target() {
+        let dir = TestDir::new("path-symlink");
+        dir.mkdir("a/b");
+        dir.mkdir("c");
+        std::os::unix::fs::symlink(dir.join("a/b"), dir.join("c/lien")).unwrap();
+
+        let input = dir.join("c/lien/../fichier.txt");
+        let real_dir = fs::canonicalize(dir.path()).unwrap();
+        assert_eq!(resolve(input.to_str().unwrap()).unwrap(), real_dir.join("a/fichier.txt"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_final_symlink_is_kept() {
+        let dir = TestDir::new("path-final-link");
+        dir.write("cible.txt", "");
+        std::os::unix::fs::symlink(dir.join("cible.txt"), dir.join("lien")).unwrap();
+
+        let input = dir.join("lien");
+        assert_eq!(resolve(input.to_str().unwrap()).unwrap(), input);
+    }
+
+    #[test]
+    fn home_is_expanded() {
+        let home = PathBuf::from(env::var_os("HOME").or_else(|| env::var_os("USERPROFILE")).unwrap());
+        assert_eq!(expand_home("~/notes.txt").unwrap(), home.join("notes.txt"));
+        assert_eq!(expand_home("a~/b").unwrap(), PathBuf::from("a~/b"));
+    }
+
+    #[test]
+    fn file_uris_are_decoded() {
+        assert_eq!(uri_to_path("file:///home/moi/mes%20notes.txt").unwrap(), PathBuf::from("/home/moi/mes notes.txt"));
+        assert_eq!(uri_to_path("file://localhost/tmp/a?x=1#ancre").unwrap(), PathBuf::from("/tmp/a"));
+        assert!(uri_to_path("https://exemple.com/notes.txt").is_err());
+        assert!(uri_to_path("file://serveur/partage").is_err());
+        assert!(uri_to_path("file:///a%2").is_err());
+    }
+
+    #[test]
+    fn canonicalize_follows_parents_of_missing_paths() {
+        let dir = TestDir::new("path-canonicalize");
+        let real_dir = fs::canonicalize(dir.path()).unwrap();
+        assert_eq!(canonicalize(&dir.join("absent/fichier")).unwrap(), real_dir.join("absent/fichier"));
+    }
+
+    #[test]
+    fn keep_marker_is_found_next_to_the_file() {
+        let dir = TestDir::new("path-keep");
+        let file = dir.write("rapport.pdf", "");
+        assert_eq!(keep_marker(&file), None);
+        let marker = dir.write("rapport.pdf.keep", "");
+        assert_eq!(keep_marker(&file), Some(marker));
+    }
+}
@@ -0,0 +1,264 @@
+//! # Module `pipeline`
+//!
+//! Ce module enchaîne plusieurs commandes avec l'opérateur `|`, sans processus externe :
+//! la sortie de chaque commande est passée en entrée à la suivante.
+//!
+//! ```bash
+//! > cat fichier.txt | wc -l
+//! > echo -e "b\na\nc" | head -n 2 | cat -n
+//! > ls src | wc -l
+//! ```
+//!
+//! Chaque commande utilisable dans un pipeline implémente le trait [`Command`] :
+//! `echo`, `cat`, `head`, `wc` et `ls`. Les commandes qui modifient des fichiers
+//! (`cp`, `mv`, `rm`) ne produisent pas de données et sont refusées.
+
+use std::io::{self, IsTerminal, Write};
+use std::process;
+
+use crate::{cat, echo, head, ls, wc};
+
+/// Commande pouvant être chaînée dans un pipeline.
+pub trait Command {
+    /// Exécute la commande et retourne sa sortie.
+    ///
+    /// `input` contient la sortie de la commande précédente, ou `None` pour la première
+    /// commande du pipeline. Une commande qui lit des fichiers ne lit `input` que si
+    /// aucun fichier ne lui est donné, comme avec l'entrée standard.
+    ///
+    /// `terminal` n'est vrai que pour la dernière commande, quand la sortie standard
+    /// est un terminal : les couleurs et le surlignage ne doivent jamais arriver dans
+    /// l'entrée de la commande suivante.
+    fn run(&self, input: Option<&[u8]>, terminal: bool) -> io::Result<Vec<u8>>;
+}
+
+/// # Fonction : `run_line`
+///
+/// Découpe une ligne saisie avec [`split_stages`] puis l'exécute avec [`run`].
+///
+/// ## Exemple :
+/// ```no_run
+/// pipeline::run_line(r#"echo "Bonjour le monde" | wc -w"#)?;
+/// // Affiche : 3
+/// ```
+pub fn run_line(line: &str) -> io::Result<()> {
+    run(&split_stages(line))
+}
+
+/// # Fonction : `run`
+///
+/// Exécute les étapes d'un pipeline (nom de la commande suivi de ses arguments)
+/// et affiche la sortie de la dernière.
+///
+/// ## Fonctionnement :
+/// 1. Calcule la sortie finale avec [`output`], colorée seulement si la sortie
+///    standard est un terminal.
+/// 2. L'écrit sur la sortie standard.
+pub fn run(stages: &[Vec<String>]) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    let data = output(stages, stdout.is_terminal())?;
+
+    stdout.write_all(&data)?;
+    stdout.flush()
+}
+
+/// # Fonction : `output`
+///
+/// Exécute les étapes d'un pipeline et retourne la sortie de la dernière.
+///
+/// ## Fonctionnement :
+/// 1. Construit toutes les commandes avec [`command_for`] : une commande inconnue
+///    ou une étape vide (`cat f |`) arrête le pipeline avant toute exécution.
+/// 2. Exécute chaque commande avec la sortie de la précédente en entrée. La première
+///    erreur arrête le pipeline et est retournée. Seule la dernière reçoit `terminal`
+///    (voir [`Command::run`]).
+fn output(stages: &[Vec<String>], terminal: bool) -> io::Result<Vec<u8>> {
+    let commands = stages
+        .iter()
+        .map(|stage| command_for(stage))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let mut data: Option<Vec<u8>> = None;
+    for (index, command) in commands.iter().enumerate() {
+        let last = index + 1 == commands.len();
+        data = Some(command.run(data.as_deref(), terminal && last)?);
+    }
+    Ok(data.unwrap_or_default())
+}
+
+/// # Fonction : `split_stages`
+///
+/// Découpe une ligne en étapes séparées par `|`, chaque étape en mots.
+///
+/// Les règles sont celles de la saisie des commandes : les guillemets `"` regroupent
+/// les mots (et protègent un `|`), `\"` et `\\` sont échappés, les autres `\` sont conservés
+/// (pour `echo -e`). Des apostrophes `'...'` regroupent un texte pris tel quel.
+///
+/// ## Exemple :
+/// ```rust
+/// let stages = pipeline::split_stages(r#"echo "a | b" | wc -c"#);
+/// assert_eq!(stages, vec![vec!["echo", "a | b"], vec!["wc", "-c"]]);
+/// ```
+pub fn split_stages(line: &str) -> Vec<Vec<String>> {
+    split(line, true)
+}
+
+/// # Fonction : `split_words`
+///
+/// Découpe une ligne en mots avec les règles de [`split_stages`], sans `|` séparateur :
+/// c'est le découpage des arguments saisis à l'invite d'un utilitaire.
+///
+/// ## Exemple :
+/// ```rust
+/// assert_eq!(pipeline::split_words(r#"-e "a  b" 'c|d'"#), vec!["-e", "a  b", "c|d"]);
+/// ```
+pub fn split_words(line: &str) -> Vec<String> {
+    split(line, false).remove(0)
+}
+
+/// Découpage commun de [`split_stages`] et [`split_words`] : un `|` hors guillemets
+/// termine l'étape si `pipes` est vrai, et reste un caractère ordinaire sinon.
+/// Il y a toujours au moins une étape.
+fn split(line: &str, pipes: bool) -> Vec<Vec<String>> {
+    let mut stages = Vec::new();
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => in_quotes = !in_quotes,
+            '\'' if !in_quotes => current.extend(chars.by_ref().take_while(|&c| c != '\'')),
+            '|' if pipes && !in_quotes => {
+                if !current.is_empty() {
+                    words.push(std::mem::take(&mut current));
+                }
+                stages.push(std::mem::take(&mut words));
+            }
+            ' ' | '\t' if !in_quotes => {
+                if !current.is_empty() {
+                    words.push(std::mem::take(&mut current));
+                }
+            }
+            '\\' => match chars.peek() {
+                Some(&next) if next == '"' || next == '\\' => {
+                    current.push(next);
+                    chars.next();
+                }
+                _ => current.push(ch),
+            },
+            _ => current.push(ch),
+        }
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+    stages.push(words);
+    stages
+}
+
+/// # Fonction : `command_for`
+///
+/// Construit la commande d'une étape à partir de son nom et de ses arguments.
+fn command_for(stage: &[String]) -> io::Result<Box<dyn Command>> {
+    let Some((name, args)) = stage.split_first() else {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "Commande manquante dans le pipeline"));
+    };
+
+    let command: Box<dyn Command> = match name.as_str() {
+        "echo" => Box::new(echo::EchoCommand::new(args)),
+        "cat" => Box::new(cat::CatCommand::new(args)),
+        "head" => Box::new(head::HeadCommand::new(args)),
+        "wc" => Box::new(wc::WcCommand::new(args)),
+        "ls" => Box::new(ls::LsCommand::new(args)),
+        "cp" | "mv" | "rm" => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("La commande '{}' ne peut pas être utilisée dans un pipeline", name),
+            ));
+        }
+        other => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Commande inconnue dans le pipeline : '{}' (echo, cat, head, wc, ls)", other),
+            ));
+        }
+    };
+
+    Ok(command)
+}
+
+/// # Fonction : `exit_on_broken_pipe`
+///
+/// Termine le programme sans message et avec succès si `error` vient d'une écriture
+/// vers un lecteur qui s'est arrêté (`BrokenPipe`), par exemple `projet cat gros.txt | head -1`.
+/// C'est le comportement de coreutils, où le signal `SIGPIPE` arrête la commande.
+///
+/// Ne fait rien pour les autres erreurs, que l'appelant affiche normalement.
+pub fn exit_on_broken_pipe(error: &io::Error) {
+    if error.kind() == io::ErrorKind::BrokenPipe {
+        process::exit(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdir::TestDir;
+
+    fn output_of(line: &str) -> io::Result<String> {
+        output(&split_stages(line), false).map(|data| String::from_utf8(data).unwrap())
+    }
+
+    #[test]
+    fn three_commands_are_chained() {
+        assert_eq!(output_of(r#"echo -e "b\na\nc" | head -n 2 | cat -n"#).unwrap(), "     1\tb\n     2\ta\n");
+        assert_eq!(output_of("echo un deux trois | wc -w | cat -E").unwrap(), "      3$\n");
+    }
+
+    #[test]
+    fn files_are_read_by_the_first_stage() {
+        let dir = TestDir::new("pipeline");
+        let file = dir.write("notes.txt", "x\ny\nz\n");
+
+        let line = format!("cat {} | head -n 2 | wc -l", file.display());
+        assert_eq!(output_of(&line).unwrap().trim(), "2");
+    }
+
+    #[test]
+    fn writing_commands_and_empty_stages_are_rejected() {
+        for line in ["echo a | cp a b", "echo a | mv a b", "rm a | wc", "echo a |", "echo a | inconnue"] {
+            let error = output_of(line).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput, "{line}");
+        }
+    }
+
+    #[test]
+    fn a_failing_stage_stops_the_pipeline() {
+        let error = output_of("cat fichier-absent-du-test.txt | wc -l").unwrap_err();
+        assert_ne!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn stages_and_words_respect_quotes() {
+        assert_eq!(split_stages(r#"echo "a | b" | wc -c"#), vec![vec!["echo", "a | b"], vec!["wc", "-c"]]);
+        assert_eq!(split_stages("echo 'x\"y' \\\" | cat"), vec![vec!["echo", "x\"y", "\""], vec!["cat"]]);
+        assert_eq!(split_words("a | b"), vec!["a", "|", "b"]);
+        assert_eq!(split_words(""), Vec::<String>::new());
+    }
+
+    #[test]
+    fn only_the_last_stage_writes_to_the_terminal() {
+        let dir = TestDir::new("pipeline-terminal");
+        let patch = dir.write("d.patch", "+a\n-b\n");
+
+        let line = format!("cat --syntax diff {} | wc -c", patch.display());
+        assert_eq!(output(&split_stages(&line), true).unwrap(), b"      6\n");
+
+        let line = format!("cat --syntax diff {}", patch.display());
+        assert!(output(&split_stages(&line), true).unwrap().starts_with(b"\x1b[32m+a"));
+        assert_eq!(output(&split_stages(&line), false).unwrap(), b"+a\n-b\n");
+    }
+}
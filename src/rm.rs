@@ -7,7 +7,21 @@
 //! - Si l'utilisateur indique un **fichier**, il est supprimé avec [`fs::remove_file`].  
 //! - Si l'utilisateur indique un **dossier** :  
 //!   - Sans `-r` → erreur, car [`fs::remove_file`] ne peut pas supprimer de dossier.  
-//!   - Avec `-r` → le dossier (et tout son contenu) est supprimé via [`fs::remove_dir_all`].
+//!   - Avec `-r` → le dossier et tout son contenu sont supprimés élément par élément
+//!     (voir [`remove_tree`]), ce qui permet de s'arrêter proprement avec Ctrl+C.
+//! - Si l'utilisateur indique un **motif** (`*`, `?`, `[...]`), la liste des chemins
+//!   correspondants est affichée puis une confirmation est demandée (sauf avec `-f`).
+//! - Avec `--report-size`, l'espace libéré est affiché à la fin (ex. `1.2 Go`).
+//! - Avec `--older-than <durée>` (ex. `7d`, `24h`), seuls les éléments modifiés
+//!   il y a plus longtemps que la durée sont supprimés.
+//! - Avec `--empty`, le contenu d'un dossier (fichiers cachés compris) est supprimé
+//!   après confirmation (sauf avec `-f`), mais le dossier lui-même est conservé.
+//! - Avec `--atomic`, l'élément est d'abord renommé en un nom caché du même dossier
+//!   (il disparaît d'un coup de son emplacement), puis supprimé sous ce nom.
+//! - Avec `--progress` (et `-r`), le nombre de fichiers déjà supprimés est affiché
+//!   et mis à jour sur place pendant la suppression, puis le total à la fin.
+//! - Un fichier accompagné d'un marqueur `<nom>.keep` (ex. `notes.txt.keep`) est
+//!   protégé : il n'est pas supprimé, ni le dossier qui le contient, sauf avec `-f`.
 //!
 //! ## Utilisation en ligne de commande
 //! ```bash
@@ -20,6 +34,15 @@
 //! # Supprimer un fichier par chemin absolu
 //! > rm C:/Users/test.txt
 //!
+//! # Supprimer tous les fichiers .tmp (après confirmation)
+//! > rm *.tmp
+//!
+//! # Supprimer les journaux de plus de 30 jours
+//! > rm -f --older-than 30d *.log
+//!
+//! # Vider un dossier sans le supprimer
+//! > rm --empty cache
+//!
 //! # Quitter le menu
 //! > quit
 //! ```
@@ -29,13 +52,89 @@
 //! - Si le dossier n'est pas supprimé sans `-r` → message d'erreur explicite et possibilité de retaper.
 //! - Si la commande est mal utilisée → rappel de la syntaxe correcte et possibilité de retaper.
 
-use std::{fs, path::Path, env};
-use std::io::{self, Write};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::{glob, interrupt, menu, path, size};
+use std::io::{self, IsTerminal, Write};
+
+/// Délai minimal entre deux affichages de l'avancement (`--progress`).
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Structure représentant les options de la commande `rm`.
+///
+/// Chaque champ correspond à une option possible de `rm`.
+#[derive(Default, Clone)]
+struct Options {
+    /// Supprime les dossiers et leur contenu (`-r`).
+    recursive: bool,
+    /// Supprime sans demander de confirmation, même les fichiers protégés par un marqueur `.keep` (`-f`).
+    force: bool,
+    /// Affiche l'espace disque libéré (`--report-size`).
+    report_size: bool,
+    /// Ne supprime que les éléments modifiés il y a plus longtemps (`--older-than <durée>`).
+    older_than: Option<Duration>,
+    /// Vide le dossier au lieu de le supprimer (`--empty`).
+    empty: bool,
+    /// Renomme l'élément en un nom temporaire avant de le supprimer (`--atomic`).
+    atomic: bool,
+    /// Affiche le nombre de fichiers supprimés pendant une suppression récursive (`--progress`).
+    progress: bool,
+}
+
+/// Avancement d'une suppression récursive (voir [`remove_tree`]).
+///
+/// Compte les éléments supprimés et, avec `--progress` dans un terminal, réécrit
+/// le nombre de fichiers sur place avec `\r`, au plus toutes les `PROGRESS_INTERVAL`.
+#[derive(Default)]
+struct Removal {
+    /// Nombre de fichiers (et liens symboliques) supprimés.
+    files: usize,
+    /// Nombre de dossiers supprimés.
+    dirs: usize,
+    /// Réécrit l'avancement sur place.
+    live: bool,
+    /// Moment du dernier affichage de l'avancement.
+    last_display: Option<Instant>,
+}
+
+impl Removal {
+    /// Commence une suppression ; l'avancement n'est réécrit sur place que dans un terminal.
+    fn new(progress: bool) -> Self {
+        Removal { live: progress && io::stdout().is_terminal(), ..Default::default() }
+    }
+
+    /// Nombre total d'éléments supprimés.
+    fn count(&self) -> usize {
+        self.files + self.dirs
+    }
+
+    /// Compte un fichier supprimé et met à jour l'affichage si nécessaire.
+    fn file_removed(&mut self) {
+        self.files += 1;
+        if self.live && self.last_display.is_none_or(|shown| shown.elapsed() >= PROGRESS_INTERVAL) {
+            print!("\r🗑️  {} fichier(s) supprimé(s)...", self.files);
+            let _ = io::stdout().flush();
+            self.last_display = Some(Instant::now());
+        }
+    }
+
+    /// Termine la ligne réécrite sur place avec le nombre exact de fichiers, avant
+    /// le message suivant.
+    fn end_line(&self) {
+        if self.last_display.is_some() {
+            println!("\r🗑️  {} fichier(s) supprimé(s)   ", self.files);
+        }
+    }
+}
 
 /// Point d'entrée principal de la commande `rm`.
 ///
 /// Cette fonction démarre un shell interactif permettant à l'utilisateur de saisir
-/// des commandes `rm`. La boucle continue jusqu'à ce que l'utilisateur tape `quit`.
+/// des commandes `rm`. La boucle continue jusqu'à ce que l'utilisateur tape `menu` (ou `b`, `0`, `quit`).
 /// En cas d'erreur, l'utilisateur peut simplement retaper une nouvelle commande.
 ///
 /// # Fonctionnement
@@ -66,7 +165,7 @@ use std::io::{self, Write};
 pub fn rm() {
     loop {
         println!("\n=== Programme utilitaire rm ===");
-        println!("Entrez votre commande (ou 'quit' pour quitter) :");
+        println!("Entrez votre commande (ou 'menu' pour revenir au menu principal) :");
         print!("> ");
         io::stdout().flush().unwrap();
 
@@ -75,8 +174,8 @@ pub fn rm() {
 
         let input = input.trim();
 
-        // Si l'utilisateur tape quit, on sort du programme
-        if input == "quit" {
+        // Si l'utilisateur tape quit (ou menu, b, 0), on revient au menu principal
+        if input == "quit" || menu::is_back(input) {
             break;
         }
 
@@ -107,6 +206,15 @@ pub fn rm() {
     }
 }
 
+/// Exécute une commande `rm` en mode non interactif.
+///
+/// Les arguments sont ceux passés au programme après le nom de la commande
+/// (ex. `projet rm mon_dossier`).
+pub fn run(args: &[String]) {
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    process_command(&args);
+}
+
 /// Parse une ligne de commande en respectant les guillemets et échappements.
 ///
 /// # Algorithme
@@ -178,11 +286,13 @@ fn parse_command_line(input: &str) -> Vec<String> {
 ///
 /// # Algorithme
 /// 1. Gère `--help` si présent.
-/// 2. Parse les arguments pour extraire l'option `-r` et le chemin cible.
+/// 2. Parse les arguments pour extraire les options (`-r`, `-f`) et le chemin cible.
 /// 3. Vérifie qu'un chemin a été fourni (sinon affiche un message).
-/// 4. Résout le chemin (gère `.` pour le dossier courant).
-/// 5. Vérifie l'existence du chemin (sinon affiche un message).
-/// 6. Effectue la suppression appropriée selon le type et l'option `-r`.
+/// 4. Si le chemin est un motif, délègue à [`remove_matches`], sauf si un fichier
+///    porte exactement ce nom (ex. `report[1].txt`) : c'est alors lui qui est supprimé.
+/// 5. Résout le chemin (gère `.`, `..` et `~`) via le module `path`.
+/// 6. Vérifie l'existence du chemin (sinon affiche un message).
+/// 7. Effectue la suppression appropriée avec [`remove_path`].
 ///
 /// # Arguments
 /// * `args` - Arguments de la commande (sans "rm").
@@ -199,8 +309,18 @@ fn process_command(args: &[&str]) {
         return;
     }
 
-    // Parser les arguments pour extraire -r et le chemin
-    let (recursive, target) = parse_arguments(args);
+    // Ctrl+C arrête la suppression entre deux éléments au lieu de quitter le programme
+    let _guard = interrupt::guard();
+
+    // Parser les arguments pour extraire les options et le chemin
+    let (options, target) = match parse_arguments(args) {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            println!("❌ Erreur : {}", message);
+            println!("💡 Utilisez 'rm --help' pour plus d'informations");
+            return; // Retour à la boucle pour retaper
+        }
+    };
 
     // Si aucun chemin n'est fourni
     if target.is_none() {
@@ -210,47 +330,282 @@ fn process_command(args: &[&str]) {
     }
 
     let target_name = target.unwrap();
+
+    // Un motif (ex. *.tmp) est développé puis supprimé après confirmation,
+    // sauf si un fichier porte exactement ce nom (ex. report[1].txt)
+    let exists_literally = path::resolve(&target_name).is_ok_and(|path| fs::symlink_metadata(path).is_ok());
+    if glob::is_pattern(&target_name) && !exists_literally {
+        if options.empty {
+            println!("❌ Erreur : L'option --empty attend un dossier, pas un motif");
+            return;
+        }
+        remove_matches(&target_name, &options);
+        return;
+    }
     
-    // Résoudre le chemin (gérer . pour le dossier courant)
-    let file_path = match resolve_path(&target_name) {
-        Some(path) => path,
-        None => {
-            println!("❌ Erreur : Impossible de récupérer le dossier courant");
+    // Résoudre le chemin (gérer ., .. et ~)
+    let file_path = match path::resolve(&target_name) {
+        Ok(path) => path,
+        Err(e) => {
+            println!("❌ Erreur : Impossible de résoudre le chemin : {}", e);
             return; // Retour à la boucle pour retaper
         }
     };
 
-    let path_obj = Path::new(&file_path);
+    let path_obj = file_path.as_path();
 
-    // Si le chemin n'existe pas
-    if !path_obj.exists() {
-        println!("⚠️  Le chemin '{}' n'existe pas.", file_path);
+    // Si le chemin n'existe pas (un lien cassé existe : c'est lui qui est supprimé)
+    if fs::symlink_metadata(path_obj).is_err() {
+        println!("⚠️  Le chemin '{}' n'existe pas.", file_path.display());
         println!("💡 Vérifiez le chemin et réessayez");
         return; // Retour à la boucle pour retaper
     }
 
-    // Vérifier si c'est un dossier ou un fichier avant suppression
-    let is_dir = path_obj.is_dir();
+    // Avec --empty, seul le contenu du dossier est supprimé
+    if options.empty {
+        empty_directory(path_obj, &target_name, &options);
+        return;
+    }
+
+    // Avec --older-than, un élément trop récent est conservé
+    if !is_old_enough(path_obj, &target_name, &options) {
+        return;
+    }
+
+    let freed = remove_path(path_obj, &target_name, &options, &mut HashSet::new()).unwrap_or(0);
+    if options.report_size {
+        println!("💾 Espace libéré : {}", size::human_readable(freed));
+    }
+}
+
+/// Supprime tous les chemins correspondant à un motif, après confirmation.
+///
+/// # Algorithme
+/// 1. Développe le motif avec [`glob::expand`] ; avec `--older-than`,
+///    écarte (en le signalant) chaque élément trop récent.
+/// 2. Affiche la liste des chemins trouvés et leur nombre.
+/// 3. Sauf avec `-f`, demande confirmation avant de tout supprimer.
+/// 4. Supprime chaque chemin avec [`remove_path`].
+/// 5. Avec `--report-size`, affiche l'espace total libéré.
+///
+/// # Arguments
+/// * `pattern` - Motif saisi par l'utilisateur (ex. `*.tmp`).
+/// * `options` - Options de la commande.
+fn remove_matches(pattern: &str, options: &Options) {
+    let matches = match glob::expand(pattern) {
+        Ok(matches) => matches
+            .into_iter()
+            .filter(|path| is_old_enough(path, &path.display().to_string(), options))
+            .collect::<Vec<_>>(),
+        Err(e) => {
+            println!("❌ Erreur : Impossible de résoudre le chemin : {}", e);
+            return;
+        }
+    };
+
+    if matches.is_empty() {
+        println!("⚠️  Aucun fichier ne correspond au motif '{}', et aucun fichier ne porte ce nom.", pattern);
+        return;
+    }
+
+    // Aperçu de ce qui va être supprimé
+    println!("🔎 {} élément(s) correspondent au motif '{}' :", matches.len(), pattern);
+    for path in &matches {
+        println!(" - {}", path.display());
+    }
+
+    if !options.force && !confirm(&format!("Supprimer ces {} élément(s) ?", matches.len())) {
+        println!("❎ Suppression annulée.");
+        return;
+    }
+
+    let mut freed = 0;
+    // Fichiers déjà mesurés, pour ne compter qu'une fois les liens physiques
+    let mut measured = HashSet::new();
+    for (index, path) in matches.iter().enumerate() {
+        if interrupt::is_interrupted() {
+            println!("⚠️  Suppression interrompue : {} élément(s) sur {} traité(s).", index, matches.len());
+            break;
+        }
+        freed += remove_path(path, &path.display().to_string(), options, &mut measured).unwrap_or(0);
+    }
+    if options.report_size {
+        println!("💾 Espace libéré : {}", size::human_readable(freed));
+    }
+}
+
+/// Supprime le contenu d'un dossier sans supprimer le dossier lui-même (`--empty`).
+///
+/// # Algorithme
+/// 1. Vérifie que la cible est un dossier (un lien vers un dossier n'est pas suivi).
+/// 2. Lit toutes ses entrées, fichiers cachés compris ; avec `--older-than`,
+///    écarte (en le signalant) chaque élément trop récent.
+/// 3. Sauf avec `-f`, demande confirmation avant de vider le dossier.
+/// 4. Supprime chaque entrée avec [`remove_path`] : les fichiers et les liens
+///    symboliques directement (la cible d'un lien n'est jamais touchée), les
+///    sous-dossiers avec tout leur contenu (sans qu'il faille préciser `-r`).
+/// 5. Annonce que le dossier a été vidé seulement si toutes les entrées ont été
+///    supprimées ; sinon, indique combien sont restées.
+/// 6. Avec `--report-size`, affiche l'espace total libéré.
+///
+/// # Arguments
+/// * `dir` - Dossier résolu à vider.
+/// * `target_name` - Nom à afficher dans les messages.
+/// * `options` - Options de la commande.
+fn empty_directory(dir: &Path, target_name: &str, options: &Options) {
+    if !fs::symlink_metadata(dir).is_ok_and(|metadata| metadata.is_dir()) {
+        println!("❌ Erreur : '{}' n'est pas un dossier, impossible de le vider", target_name);
+        return;
+    }
+
+    let mut entries = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| is_old_enough(path, &path.display().to_string(), options))
+            .collect::<Vec<_>>(),
+        Err(e) => {
+            println!("❌ Erreur lors de la lecture du dossier : {}", e);
+            println!("💡 Vérifiez les permissions et réessayez");
+            return;
+        }
+    };
+
+    if entries.is_empty() {
+        println!("ℹ️  Le dossier '{}' est déjà vide.", target_name);
+        return;
+    }
+    entries.sort();
+
+    if !options.force
+        && !confirm(&format!("Vider le dossier '{}' ({} élément(s)) ?", target_name, entries.len()))
+    {
+        println!("❎ Suppression annulée.");
+        return;
+    }
+
+    // Les sous-dossiers sont supprimés avec leur contenu
+    let child_options = Options { recursive: true, ..options.clone() };
+    let mut freed = 0;
+    let mut failed = 0;
+    // Fichiers déjà mesurés, pour ne compter qu'une fois les liens physiques
+    let mut measured = HashSet::new();
+    for (index, path) in entries.iter().enumerate() {
+        if interrupt::is_interrupted() {
+            println!("⚠️  Suppression interrompue : {} élément(s) sur {} traité(s).", index, entries.len());
+            println!("💡 Le dossier '{}' n'a été que partiellement vidé.", target_name);
+            if options.report_size {
+                println!("💾 Espace libéré : {}", size::human_readable(freed));
+            }
+            return;
+        }
+        match remove_path(path, &path.display().to_string(), &child_options, &mut measured) {
+            Some(size) => freed += size,
+            None => failed += 1,
+        }
+    }
+
+    if failed == 0 {
+        println!("🧹 Le dossier '{}' a été vidé.", target_name);
+    } else {
+        println!("⚠️  Le dossier '{}' n'a été que partiellement vidé : {} élément(s) sur {} conservé(s).", target_name, failed, entries.len());
+    }
+    if options.report_size {
+        println!("💾 Espace libéré : {}", size::human_readable(freed));
+    }
+}
+
+/// Demande une confirmation (o/N) à l'utilisateur.
+///
+/// # Retour
+/// `true` si l'utilisateur répond `o`, `oui`, `y` ou `yes`.
+fn confirm(question: &str) -> bool {
+    print!("{} (o/N) : ", question);
+    io::stdout().flush().unwrap();
+
+    let mut answer = String::new();
+    // Ctrl+C pendant la question vaut un refus
+    if io::stdin().read_line(&mut answer).is_err() || interrupt::is_interrupted() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "o" | "oui" | "y" | "yes")
+}
+
+/// Supprime un fichier ou un dossier existant et affiche le résultat.
+///
+/// # Arguments
+/// * `path_obj` - Chemin résolu à supprimer.
+/// * `target_name` - Nom à afficher dans les messages.
+/// * `options` - Options de la commande (`-r`, `--report-size`).
+/// * `measured` - Fichiers déjà comptés par la même commande (voir [`disk_usage`]).
+///
+/// # Retour
+/// Nombre d'octets libérés (0 sans `--report-size`), ou `None` si l'élément n'a pas
+/// été entièrement supprimé.
+fn remove_path(path_obj: &Path, target_name: &str, options: &Options, measured: &mut HashSet<(u64, u64)>) -> Option<u64> {
+    let recursive = options.recursive;
+
+    // Vérifier si c'est un dossier ou un fichier avant suppression ; un lien vers
+    // un dossier n'est pas suivi : seul le lien est supprimé, jamais la cible
+    let is_dir = fs::symlink_metadata(path_obj).is_ok_and(|metadata| metadata.is_dir());
 
     // Si c'est un dossier et que -r n'est pas spécifié
     if is_dir && !recursive {
         println!("❌ Erreur : Impossible de supprimer un dossier sans l'option -r");
         println!("💡 Utilisez 'rm -r {}' pour supprimer ce dossier", target_name);
-        return; // Retour à la boucle pour retaper
+        return None; // Retour à la boucle pour retaper
+    }
+
+    // Un marqueur `.keep` protège le fichier, et donc le dossier qui le contient
+    if !options.force {
+        let protected = match path::keep_marker(path_obj) {
+            Some(marker) => Some((path_obj.to_path_buf(), marker)),
+            None if recursive && is_dir => find_protected(path_obj),
+            None => None,
+        };
+        if let Some((file, marker)) = protected {
+            println!("🔒 '{}' est protégé par '{}' : rien n'a été supprimé", file.display(), marker.display());
+            println!("💡 Utilisez -f pour le supprimer quand même");
+            return None;
+        }
     }
 
+    // Mesurer la taille avant que les fichiers ne disparaissent
+    let size = if options.report_size { disk_usage(path_obj, measured) } else { 0 };
+
+    // Avec --atomic, l'élément quitte son nom d'un seul coup avant d'être supprimé
+    let hidden = if options.atomic {
+        match hide_for_removal(path_obj) {
+            Ok(hidden) => Some(hidden),
+            Err(e) => {
+                println!("❌ Erreur : Impossible de renommer '{}' avant la suppression : {}", target_name, e);
+                println!("💡 Vérifiez les permissions et réessayez");
+                return None;
+            }
+        }
+    } else {
+        None
+    };
+    let removal_path = hidden.as_deref().unwrap_or(path_obj);
+
     // Effectuer la suppression
+    let mut removal = Removal::new(options.progress);
     let result = if recursive && is_dir {
-        fs::remove_dir_all(path_obj)
+        remove_tree(removal_path, &mut removal)
     } else {
-        fs::remove_file(path_obj)
+        fs::remove_file(removal_path)
     };
+    removal.end_line();
+    let removed = removal.count();
 
-    match result {
+    let freed = match &result {
         Ok(_) => {
             // Si c'est une suppression de dossier
             if is_dir {
                 println!("✅ Le dossier '{}' a été supprimé avec succès.", target_name);
+                if options.progress {
+                    println!("🗑️  Total : {} fichier(s) et {} dossier(s) supprimé(s).", removal.files, removal.dirs);
+                }
             } else {
                 // Sinon, on récupère le dossier parent, puis le fichier supprimé
                 let parent = path_obj.parent()
@@ -258,76 +613,306 @@ fn process_command(args: &[&str]) {
                     .unwrap_or("le dossier inconnu");
                 println!("✅ Le fichier '{}' dans '{}' a été supprimé avec succès.", target_name, parent);
             }
+            Some(size)
+        }
+        Err(e) if e.kind() == io::ErrorKind::Interrupted => {
+            println!("⚠️  Suppression interrompue : {} élément(s) de '{}' supprimé(s), le reste est conservé.", removed, target_name);
+            None
         }
         Err(e) => {
             println!("❌ Erreur lors de la suppression : {}", e);
             println!("💡 Vérifiez les permissions et réessayez");
+            None
+        }
+    };
+
+    // Échec avec --atomic : rien n'a été supprimé, l'élément reprend son nom ;
+    // sinon le reste est laissé sous le nom temporaire, qui est signalé
+    if let (Some(hidden), Err(_)) = (&hidden, &result) {
+        if restore_name(hidden, path_obj, removed) {
+            println!("↩️  '{}' a retrouvé son nom d'origine.", target_name);
+        } else {
+            println!("⚠️  Le reste de '{}' se trouve dans '{}'.", target_name, hidden.display());
         }
     }
+
+    freed
 }
 
-/// Parse les arguments pour extraire l'option `-r` et le chemin cible.
+/// Renomme un élément en un nom caché et inutilisé du même dossier (`--atomic`).
 ///
-/// # Algorithme
-/// - Parcourt tous les arguments.
-/// - Si l'argument est `-r`, active le mode récursif.
-/// - Sinon, considère l'argument comme le chemin cible.
-/// - Seul le premier chemin trouvé est conservé.
+/// Le renommage dans le même dossier est une opération unique du système de
+/// fichiers : l'élément disparaît de son nom d'origine d'un coup, même si la
+/// suppression qui suit est longue ou interrompue.
+///
+/// # Retour
+/// Le nouveau chemin, de la forme `.nom.rm-<pid>` (ou `.nom.rm-<pid>-2`, ... s'il existe déjà).
+///
+/// # Exemple
+/// ```no_run
+/// let hidden = hide_for_removal(Path::new("/tmp/cache"))?;
+/// assert_eq!(hidden, PathBuf::from(format!("/tmp/.cache.rm-{}", process::id())));
+/// ```
+fn hide_for_removal(path_obj: &Path) -> io::Result<PathBuf> {
+    let name = path_obj
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "chemin sans nom"))?
+        .to_string_lossy();
+    let base = format!(".{}.rm-{}", name, process::id());
+
+    let mut hidden = path_obj.with_file_name(&base);
+    let mut attempt = 1;
+    while fs::symlink_metadata(&hidden).is_ok() {
+        attempt += 1;
+        hidden = path_obj.with_file_name(format!("{}-{}", base, attempt));
+    }
+
+    fs::rename(path_obj, &hidden)?;
+    Ok(hidden)
+}
+
+/// Rend son nom d'origine à un élément caché par [`hide_for_removal`] dont la
+/// suppression a échoué.
+///
+/// Le nom n'est rendu que si rien n'a été supprimé (`removed` vaut 0) : un élément
+/// entamé reste sous son nom temporaire. Retourne `true` si le nom a été rendu.
+fn restore_name(hidden: &Path, path_obj: &Path, removed: usize) -> bool {
+    removed == 0 && fs::rename(hidden, path_obj).is_ok()
+}
+
+/// Cherche dans un dossier (récursivement, sans suivre les liens symboliques) un
+/// élément protégé par un marqueur `.keep`.
+///
+/// Retourne le premier élément protégé trouvé et son marqueur, ou `None`.
+fn find_protected(dir: &Path) -> Option<(PathBuf, PathBuf)> {
+    for entry in fs::read_dir(dir).ok()?.flatten() {
+        let path = entry.path();
+        if let Some(marker) = path::keep_marker(&path) {
+            return Some((path, marker));
+        }
+        if entry.file_type().is_ok_and(|kind| kind.is_dir())
+            && let Some(found) = find_protected(&path)
+        {
+            return Some(found);
+        }
+    }
+    None
+}
+
+/// Supprime un dossier et tout son contenu, comme [`fs::remove_dir_all`], mais
+/// élément par élément : après Ctrl+C, s'arrête avant l'élément suivant avec une
+/// erreur `Interrupted`. Les liens symboliques sont supprimés sans être suivis.
 ///
 /// # Arguments
-/// * `args` - Slice des arguments.
+/// * `dir` - Dossier à supprimer.
+/// * `removal` - Éléments déjà supprimés, et affichage de l'avancement (`--progress`).
+fn remove_tree(dir: &Path, removal: &mut Removal) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        interrupt::check()?;
+        let path = entry?.path();
+        if fs::symlink_metadata(&path)?.is_dir() {
+            remove_tree(&path, removal)?;
+        } else {
+            fs::remove_file(&path)?;
+            removal.file_removed();
+        }
+    }
+    fs::remove_dir(dir)?;
+    removal.dirs += 1;
+    Ok(())
+}
+
+/// Indique si un élément peut être supprimé au regard de `--older-than`.
 ///
-/// # Retour
-/// Tuple `(bool, Option<String>)` : (récursif, chemin_optionnel).
+/// Sans `--older-than`, tout élément peut l'être. Sinon, sa date de modification
+/// (`fs::metadata`) doit être plus ancienne que la durée demandée ; un élément trop
+/// récent, ou dont la date est illisible, est signalé et conservé.
+///
+/// # Arguments
+/// * `path_obj` - Chemin résolu.
+/// * `target_name` - Nom à afficher dans les messages.
+/// * `options` - Options de la commande.
+fn is_old_enough(path_obj: &Path, target_name: &str, options: &Options) -> bool {
+    let Some(min_age) = options.older_than else {
+        return true;
+    };
+
+    let age = fs::metadata(path_obj)
+        .and_then(|metadata| metadata.modified())
+        .map(|modified| SystemTime::now().duration_since(modified).unwrap_or_default());
+
+    match age {
+        Ok(age) if age >= min_age => true,
+        Ok(age) => {
+            println!("⏭️  '{}' ignoré : modifié il y a {}", target_name, format_age(age));
+            false
+        }
+        Err(e) => {
+            println!("⏭️  '{}' ignoré : date de modification illisible ({})", target_name, e);
+            false
+        }
+    }
+}
+
+/// Convertit une durée saisie (`30s`, `15m`, `24h`, `7d`, `2w`) en [`Duration`].
 ///
 /// # Exemple
 /// ```rust
-/// let (recursive, path) = parse_arguments(&["-r", "mon_dossier"]);
-/// assert_eq!(recursive, true);
-/// assert_eq!(path.unwrap(), "mon_dossier");
+/// assert_eq!(parse_duration("7d"), Ok(Duration::from_secs(7 * 86_400)));
+/// assert!(parse_duration("7").is_err());
 /// ```
-fn parse_arguments(args: &[&str]) -> (bool, Option<String>) {
-    let mut recursive = false;
-    let mut target: Option<String> = None;
+fn parse_duration(input: &str) -> Result<Duration, String> {
+    let invalid = || format!("Durée invalide : '{}' (ex : 30s, 15m, 24h, 7d, 2w)", input);
 
-    for arg in args {
-        if *arg == "-r" {
-            recursive = true;
-        } else if target.is_none() {
-            // Prendre le premier argument qui n'est pas -r comme chemin
-            target = Some(arg.to_string());
-        }
+    let Some((unit_index, _)) = input.char_indices().last() else {
+        return Err(invalid());
+    };
+    let (number, unit) = input.split_at(unit_index);
+    let seconds_per_unit = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        "w" => 7 * 86_400,
+        _ => return Err(invalid()),
+    };
+
+    let number: u64 = number.parse().map_err(|_| invalid())?;
+    number
+        .checked_mul(seconds_per_unit)
+        .map(Duration::from_secs)
+        .ok_or_else(invalid)
+}
+
+/// Formate un âge dans l'unité la plus grande possible (ex. `3 j`, `5 h`, `12 min`, `40 s`).
+fn format_age(age: Duration) -> String {
+    let seconds = age.as_secs();
+    match seconds {
+        s if s >= 86_400 => format!("{} j", s / 86_400),
+        s if s >= 3_600 => format!("{} h", s / 3_600),
+        s if s >= 60 => format!("{} min", s / 60),
+        s => format!("{} s", s),
     }
+}
+
+/// Calcule l'espace disque occupé par un fichier ou un dossier (récursivement), comme `du`.
+///
+/// Sous Unix, c'est la place réellement allouée qui compte (blocs de 512 octets,
+/// voir [`allocated_size`]), dossiers compris : un fichier creux compte moins que sa
+/// taille apparente. Un fichier déjà présent dans `measured` (même périphérique et
+/// même inode, c'est-à-dire un autre lien physique vers le même contenu) compte pour 0.
+///
+/// Les liens symboliques ne sont pas suivis : seule la taille du lien est comptée,
+/// puisque c'est le lien et non sa cible qui est supprimé.
+///
+/// # Arguments
+/// * `path` - Chemin à mesurer.
+/// * `measured` - Identifiants des fichiers déjà comptés, complété au passage.
+///
+/// # Retour
+/// Taille en octets (les entrées illisibles sont ignorées).
+fn disk_usage(path: &Path, measured: &mut HashSet<(u64, u64)>) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if let Some(id) = file_id(&metadata)
+        && !measured.insert(id)
+    {
+        return 0;
+    }
+
+    let own = allocated_size(&metadata);
+    if !metadata.is_dir() {
+        return own;
+    }
+
+    let children: u64 = fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| disk_usage(&entry.path(), measured)).sum())
+        .unwrap_or(0);
+    own + children
+}
 
-    (recursive, target)
+/// Place allouée sur le disque : nombre de blocs de 512 octets (`st_blocks`).
+#[cfg(unix)]
+fn allocated_size(metadata: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.blocks() * 512
 }
 
-/// Résout un chemin en gérant les cas spéciaux comme `.` (dossier courant).
+/// Place allouée sur le disque : hors Unix, la taille apparente du fichier.
+#[cfg(not(unix))]
+fn allocated_size(metadata: &fs::Metadata) -> u64 {
+    metadata.len()
+}
+
+/// Identifiant `(périphérique, inode)` d'un fichier, commun à tous ses liens physiques.
+#[cfg(unix)]
+fn file_id(metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+/// Identifiant d'un fichier : indisponible hors Unix, chaque nom est alors compté.
+#[cfg(not(unix))]
+fn file_id(_metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Parse les arguments pour extraire les options et le chemin cible.
 ///
 /// # Algorithme
-/// - Si le chemin est `.`, retourne le dossier courant.
-/// - Sinon, retourne le chemin tel quel.
+/// - Parcourt tous les arguments.
+/// - `-r` active le mode récursif, `-f` désactive la confirmation (`-rf` combine les deux).
+/// - `--older-than` prend la durée dans l'argument suivant (voir [`parse_duration`]).
+/// - Sinon, considère l'argument comme le chemin cible.
+/// - Seul le premier chemin trouvé est conservé.
 ///
 /// # Arguments
-/// * `path` - Chemin à résoudre.
+/// * `args` - Slice des arguments.
 ///
 /// # Retour
-/// `Option<String>` contenant le chemin résolu, ou `None` en cas d'erreur.
+/// Tuple `(Options, Option<String>)` : (options, chemin_optionnel),
+/// ou un message d'erreur si une option est mal utilisée.
 ///
 /// # Exemple
 /// ```rust
-/// let resolved = resolve_path(".").unwrap();
-/// // resolved contient le chemin absolu du dossier courant
+/// let (options, path) = parse_arguments(&["-r", "mon_dossier"]).unwrap();
+/// assert!(options.recursive);
+/// assert_eq!(path.unwrap(), "mon_dossier");
 /// ```
-fn resolve_path(path: &str) -> Option<String> {
-    if path == "." {
-        // Récupérer le dossier courant
-        env::current_dir()
-            .ok()
-            .map(|p| p.to_string_lossy().to_string())
-    } else {
-        Some(path.to_string())
+fn parse_arguments(args: &[&str]) -> Result<(Options, Option<String>), String> {
+    let mut options = Options::default();
+    let mut target: Option<String> = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match *arg {
+            "-r" => options.recursive = true,
+            "-f" | "--force" => options.force = true,
+            "--report-size" => options.report_size = true,
+            "--empty" => options.empty = true,
+            "--atomic" => options.atomic = true,
+            "--progress" => options.progress = true,
+            "--older-than" => {
+                // La durée est l'argument suivant
+                let Some(duration) = iter.next() else {
+                    return Err("L'option --older-than attend une durée (ex : 7d, 24h)".to_string());
+                };
+                options.older_than = Some(parse_duration(duration)?);
+            }
+            "-rf" | "-fr" => {
+                options.recursive = true;
+                options.force = true;
+            }
+            _ if target.is_none() => {
+                // Prendre le premier argument qui n'est pas une option comme chemin
+                target = Some(arg.to_string());
+            }
+            _ => {}
+        }
     }
+
+    Ok((options, target))
 }
 
 /// Affiche l'aide complète du programme `rm`.
@@ -343,6 +928,16 @@ fn display_help() {
     println!();
     println!("Options:");
     println!("  -r                       Supprime les dossiers et leur contenu de manière récursive");
+    println!("  -f, --force              Ne demande pas de confirmation pour un motif et supprime");
+    println!("                           aussi les fichiers protégés par un marqueur <nom>.keep");
+    println!("      --report-size        Affiche l'espace disque libéré à la fin");
+    println!("      --older-than DUREE   Ne supprime que les éléments modifiés il y a plus de DUREE");
+    println!("                           (s, m, h, d ou w, ex : 30d, 24h)");
+    println!("      --empty              Vide le dossier (fichiers cachés compris) sans le supprimer");
+    println!("      --atomic             Renomme d'abord l'élément en un nom caché du même dossier :");
+    println!("                           il disparaît d'un coup, puis est supprimé sous ce nom");
+    println!("      --progress           Avec -r, affiche le nombre de fichiers supprimés au fur et à");
+    println!("                           mesure (mis à jour sur place), puis le total");
     println!("      --help               Affiche cette aide et quitte");
     println!();
     println!("Exemples:");
@@ -350,8 +945,197 @@ fn display_help() {
     println!("  rm -r mon_dossier        Supprime le dossier 'mon_dossier' et tout son contenu");
     println!("  rm \"fichier avec espaces.txt\"  Supprime un fichier avec des espaces dans le nom");
     println!("  rm .                     Supprime le dossier courant (nécessite -r)");
+    println!("  rm *.tmp                 Affiche les fichiers .tmp puis demande confirmation");
+    println!("  rm -f --older-than 30d *.log  Supprime les journaux de plus de 30 jours");
+    println!("  rm --empty cache         Supprime le contenu du dossier 'cache' mais garde le dossier");
+    println!("  rm -r --atomic build     Fait disparaître 'build' immédiatement, puis le supprime");
+    println!("  rm -r --progress cache   Supprime 'cache' en affichant le nombre de fichiers supprimés");
+    println!("  rm -f notes.txt          Supprime 'notes.txt' même si 'notes.txt.keep' existe");
     println!();
     println!("Attention:");
     println!("  ⚠️  La suppression est définitive et irréversible !");
     println!("  Utilisez cette commande avec précaution.");
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdir::TestDir;
+
+    #[cfg(unix)]
+    #[test]
+    fn hard_links_are_measured_once() {
+        let dir = TestDir::new("rm-hard-links");
+        let file = dir.write("a/donnees.bin", vec![1u8; 64 * 1024]);
+        fs::hard_link(&file, dir.join("a/lien.bin")).unwrap();
+        fs::hard_link(&file, dir.join("autre.bin")).unwrap();
+
+        let mut measured = HashSet::new();
+        let file_size = disk_usage(&file, &mut measured);
+        assert!(file_size >= 64 * 1024);
+
+        let mut measured = HashSet::new();
+        let folder = disk_usage(&dir.join("a"), &mut measured);
+        let folder_itself = allocated_size(&fs::symlink_metadata(dir.join("a")).unwrap());
+        assert_eq!(folder, folder_itself + file_size);
+        // Le même contenu, déjà compté avec le dossier
+        assert_eq!(disk_usage(&dir.join("autre.bin"), &mut measured), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sparse_files_count_allocated_blocks() {
+        let dir = TestDir::new("rm-sparse");
+        let path = dir.join("creux.bin");
+        fs::File::create(&path).unwrap().set_len(64 * 1024 * 1024).unwrap();
+        assert!(disk_usage(&path, &mut HashSet::new()) < 64 * 1024 * 1024);
+    }
+
+    #[test]
+    fn literal_name_with_brackets_is_removed() {
+        let dir = TestDir::new("rm-literal");
+        let literal = dir.write("report[1].txt", "");
+        let matching = dir.write("report1.txt", "");
+
+        process_command(&["-f", literal.to_str().unwrap()]);
+        assert!(!literal.exists());
+        assert!(matching.exists());
+    }
+
+    #[test]
+    fn pattern_removes_matches_when_no_literal_file() {
+        let dir = TestDir::new("rm-pattern");
+        let first = dir.write("report1.txt", "");
+        let second = dir.write("report2.txt", "");
+        let other = dir.write("notes.txt", "");
+
+        process_command(&["-f", dir.join("report[0-9].txt").to_str().unwrap()]);
+        assert!(!first.exists());
+        assert!(!second.exists());
+        assert!(other.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn linked_directories_are_left_intact() {
+        let dir = TestDir::new("rm-linked-dir");
+        let precious = dir.write("precieux/donnees.txt", "à garder");
+        dir.mkdir("travail");
+        std::os::unix::fs::symlink(dir.join("precieux"), dir.join("travail/lien")).unwrap();
+        std::os::unix::fs::symlink(dir.join("precieux"), dir.join("lien_direct")).unwrap();
+
+        // Le lien donné directement : seul le lien disparaît
+        process_command(&["-r", "-f", dir.join("lien_direct").to_str().unwrap()]);
+        assert!(fs::symlink_metadata(dir.join("lien_direct")).is_err());
+        assert!(precious.exists());
+
+        // Un lien à l'intérieur du dossier supprimé n'est pas suivi non plus
+        process_command(&["-r", "-f", dir.join("travail").to_str().unwrap()]);
+        assert!(!dir.join("travail").exists());
+        assert_eq!(fs::read_to_string(&precious).unwrap(), "à garder");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn broken_links_can_be_removed() {
+        let dir = TestDir::new("rm-broken-link");
+        std::os::unix::fs::symlink(dir.join("absent"), dir.join("casse")).unwrap();
+
+        process_command(&["-f", dir.join("casse").to_str().unwrap()]);
+        assert!(fs::symlink_metadata(dir.join("casse")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn empty_keeps_the_directory_and_link_targets() {
+        let dir = TestDir::new("rm-empty");
+        let precious = dir.write("precieux/donnees.txt", "à garder");
+        dir.write("vider/a.txt", "a");
+        dir.write("vider/.cache", "");
+        dir.write("vider/sous/b.txt", "b");
+        std::os::unix::fs::symlink(dir.join("precieux"), dir.join("vider/lien")).unwrap();
+
+        process_command(&["--empty", "-f", dir.join("vider").to_str().unwrap()]);
+        assert!(dir.join("vider").is_dir());
+        assert_eq!(fs::read_dir(dir.join("vider")).unwrap().count(), 0);
+        assert_eq!(fs::read_to_string(&precious).unwrap(), "à garder");
+    }
+
+    #[test]
+    fn failed_removal_is_reported() {
+        let dir = TestDir::new("rm-failed");
+        dir.write("protege.txt", "");
+        dir.write("protege.txt.keep", "");
+        let folder = dir.mkdir("dossier");
+
+        let options = Options::default();
+        assert_eq!(remove_path(&dir.join("protege.txt"), "protege.txt", &options, &mut HashSet::new()), None);
+        assert_eq!(remove_path(&folder, "dossier", &options, &mut HashSet::new()), None);
+        assert!(folder.exists());
+        let options = Options { recursive: true, ..Default::default() };
+        assert_eq!(remove_path(&folder, "dossier", &options, &mut HashSet::new()), Some(0));
+    }
+
+    #[test]
+    fn hidden_entries_leave_their_name_until_restored() {
+        let dir = TestDir::new("rm-hide");
+        let folder = dir.write("cache/a.txt", "a").parent().unwrap().to_path_buf();
+        fs::create_dir(dir.join(&format!(".cache.rm-{}", process::id()))).unwrap();
+
+        let hidden = hide_for_removal(&folder).unwrap();
+        assert_eq!(hidden, dir.join(&format!(".cache.rm-{}-2", process::id())));
+        assert!(!folder.exists());
+        assert_eq!(fs::read_to_string(hidden.join("a.txt")).unwrap(), "a");
+
+        // Un élément entamé garde son nom temporaire
+        assert!(!restore_name(&hidden, &folder, 1));
+        assert!(!folder.exists());
+
+        assert!(restore_name(&hidden, &folder, 0));
+        assert!(!hidden.exists());
+        assert_eq!(fs::read_to_string(folder.join("a.txt")).unwrap(), "a");
+    }
+
+    #[test]
+    fn durations_need_a_number_and_a_unit() {
+        assert_eq!(parse_duration("7d"), Ok(Duration::from_secs(7 * 86_400)));
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("2w"), Ok(Duration::from_secs(14 * 86_400)));
+        for invalid in ["7", "", "d", "7x", "-1h", "7é", &format!("{}w", u64::MAX)] {
+            assert!(parse_duration(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn older_than_keeps_recent_files() {
+        let dir = TestDir::new("rm-older-than");
+        let old = dir.write("ancien.log", "");
+        let recent = dir.write("recent.log", "");
+        let old_time = SystemTime::now() - Duration::from_secs(3 * 86_400);
+        fs::File::options().write(true).open(&old).unwrap().set_modified(old_time).unwrap();
+
+        let options = Options { older_than: Some(Duration::from_secs(86_400)), ..Default::default() };
+        assert!(is_old_enough(&old, "ancien.log", &options));
+        assert!(!is_old_enough(&recent, "recent.log", &options));
+        assert!(!is_old_enough(&dir.join("absent"), "absent", &options));
+        assert!(is_old_enough(&recent, "recent.log", &Options::default()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn removal_counts_links_as_files_without_following_them() {
+        let dir = TestDir::new("rm-removal-counts");
+        dir.write("precieux/a.txt", "a");
+        dir.write("precieux/b.txt", "b");
+        dir.write("arbre/un.txt", "1");
+        dir.write("arbre/sous/deux.txt", "2");
+        std::os::unix::fs::symlink(dir.join("precieux"), dir.join("arbre/sous/lien")).unwrap();
+
+        let mut removal = Removal::new(true);
+        remove_tree(&dir.join("arbre"), &mut removal).unwrap();
+        // un.txt, deux.txt et le lien ; sous/ et arbre/
+        assert_eq!((removal.files, removal.dirs, removal.count()), (3, 2, 5));
+        assert!(!dir.join("arbre").exists());
+        assert_eq!(fs::read_dir(dir.join("precieux")).unwrap().count(), 2);
+    }
+}
@@ -0,0 +1,127 @@
+//! # Module `size`
+//!
+//! Ce module fournit l'affichage des tailles partagé par les commandes
+//! (ex. `1.2 Go` au lieu de `1288490188`), ainsi que la taille des tampons de
+//! lecture choisie avec `--buffer-size` ou la variable `UTILS_BUFFER_SIZE`.
+
+use std::env;
+
+/// Unités successives, chacune valant 1024 fois la précédente.
+const UNITS: [&str; 6] = ["o", "Ko", "Mo", "Go", "To", "Po"];
+
+/// Variable d'environnement donnant la taille des tampons de `cat` et `cp`, si
+/// `--buffer-size` n'est pas indiqué.
+pub const BUFFER_SIZE_VAR: &str = "UTILS_BUFFER_SIZE";
+
+/// Taille maximale acceptée pour un tampon de lecture (64 Mio).
+pub const MAX_BUFFER_SIZE: usize = 64 * 1024 * 1024;
+
+/// # Fonction : `human_readable`
+///
+/// Convertit une taille en octets en texte lisible, avec une décimale au-delà du kilo-octet.
+///
+/// ## Exemple :
+/// ```rust
+/// assert_eq!(size::human_readable(512), "512 o");
+/// assert_eq!(size::human_readable(1536), "1.5 Ko");
+/// assert_eq!(size::human_readable(1_288_490_188), "1.2 Go");
+/// ```
+pub fn human_readable(bytes: u64) -> String {
+    let mut value = bytes as f64;
+    let mut unit = 0;
+
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// # Fonction : `parse_buffer_size`
+///
+/// Lit une taille de tampon en octets, avec un suffixe `K` ou `M` facultatif
+/// (multiples de 1024). La taille doit être strictement positive et ne pas
+/// dépasser [`MAX_BUFFER_SIZE`].
+///
+/// ## Exemple :
+/// ```rust
+/// assert_eq!(size::parse_buffer_size("4096"), Ok(4096));
+/// assert_eq!(size::parse_buffer_size("1M"), Ok(1024 * 1024));
+/// assert!(size::parse_buffer_size("0").is_err());
+/// assert!(size::parse_buffer_size("1G").is_err());
+/// ```
+pub fn parse_buffer_size(text: &str) -> Result<usize, String> {
+    let text = text.trim();
+    let (digits, multiplier) = match text.char_indices().last() {
+        Some((index, 'k' | 'K')) => (&text[..index], 1024),
+        Some((index, 'm' | 'M')) => (&text[..index], 1024 * 1024),
+        _ => (text, 1),
+    };
+
+    let size = digits
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .filter(|&n| n > 0)
+        .ok_or_else(|| format!("taille de tampon invalide : '{}' (ex. 4096, 64K, 1M)", text))?;
+
+    if size > MAX_BUFFER_SIZE {
+        return Err(format!(
+            "taille de tampon trop grande : '{}' (maximum {})",
+            text,
+            human_readable(MAX_BUFFER_SIZE as u64)
+        ));
+    }
+    Ok(size)
+}
+
+/// # Fonction : `env_buffer_size`
+///
+/// Retourne la taille de tampon donnée par la variable [`BUFFER_SIZE_VAR`], ou
+/// `None` si elle n'est pas définie. Une valeur invalide est une erreur, pour ne
+/// pas l'ignorer sans le dire.
+///
+/// ## Exemple :
+/// ```rust
+/// // UTILS_BUFFER_SIZE=1M
+/// assert_eq!(size::env_buffer_size(), Ok(Some(1024 * 1024)));
+/// ```
+pub fn env_buffer_size() -> Result<Option<usize>, String> {
+    match env::var(BUFFER_SIZE_VAR) {
+        Ok(value) => parse_buffer_size(&value)
+            .map(Some)
+            .map_err(|e| format!("{} : {}", BUFFER_SIZE_VAR, e)),
+        Err(_) => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn human_readable_picks_the_unit() {
+        assert_eq!(human_readable(0), "0 o");
+        assert_eq!(human_readable(1023), "1023 o");
+        assert_eq!(human_readable(1024), "1.0 Ko");
+        assert_eq!(human_readable(1536), "1.5 Ko");
+        assert_eq!(human_readable(5 * 1024 * 1024), "5.0 Mo");
+        assert_eq!(human_readable(1_288_490_188), "1.2 Go");
+    }
+
+    #[test]
+    fn buffer_sizes_accept_k_and_m_suffixes() {
+        assert_eq!(parse_buffer_size("4096"), Ok(4096));
+        assert_eq!(parse_buffer_size(" 64k "), Ok(64 * 1024));
+        assert_eq!(parse_buffer_size("1M"), Ok(1024 * 1024));
+        assert_eq!(parse_buffer_size("64M"), Ok(MAX_BUFFER_SIZE));
+        for invalid in ["0", "", "K", "-1", "1G", "65M", "abc"] {
+            assert!(parse_buffer_size(invalid).is_err(), "{invalid}");
+        }
+    }
+}
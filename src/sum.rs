@@ -0,0 +1,267 @@
+//! # Module `sum`
+//!
+//! Ce module calcule l'empreinte SHA-256 du contenu d'un fichier, pour vérifier
+//! qu'il n'a pas changé (ex. manifeste de `ls --manifest`), et compare le contenu
+//! de deux fichiers (ex. `cp --verify`, `--skip-identical`).
+//!
+//! Le résultat est identique à celui de `sha256sum` : il peut donc être comparé
+//! avec les outils du système. L'algorithme est celui de la norme FIPS 180-4,
+//! écrit sans dépendance externe.
+
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+/// Taille des blocs lus dans le fichier (64 Kio).
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+/// Taille des blocs lus dans chaque fichier par [`same_contents`] (64 Kio).
+const COMPARE_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Valeurs initiales de l'état (parties fractionnaires des racines carrées des 8 premiers nombres premiers).
+const INITIAL_STATE: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Constantes de tour (parties fractionnaires des racines cubiques des 64 premiers nombres premiers).
+const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Calcul incrémental d'une empreinte SHA-256.
+///
+/// ## Exemple :
+/// ```rust
+/// let mut hasher = sum::Sha256::new();
+/// hasher.update(b"ab");
+/// hasher.update(b"c");
+/// assert_eq!(
+///     hasher.finish(),
+///     "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+/// );
+/// ```
+pub struct Sha256 {
+    /// État courant (8 mots de 32 bits).
+    state: [u32; 8],
+    /// Octets en attente d'un bloc complet de 64 octets.
+    pending: Vec<u8>,
+    /// Nombre total d'octets reçus.
+    length: u64,
+}
+
+impl Sha256 {
+    /// Commence une nouvelle empreinte.
+    pub fn new() -> Self {
+        Sha256 { state: INITIAL_STATE, pending: Vec::with_capacity(64), length: 0 }
+    }
+
+    /// Ajoute des octets au calcul.
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.length += data.len() as u64;
+
+        // Compléter le bloc commencé lors d'un appel précédent
+        if !self.pending.is_empty() {
+            let missing = (64 - self.pending.len()).min(data.len());
+            self.pending.extend_from_slice(&data[..missing]);
+            data = &data[missing..];
+            if self.pending.len() < 64 {
+                return;
+            }
+            let block: [u8; 64] = self.pending[..].try_into().unwrap();
+            self.compress(&block);
+            self.pending.clear();
+        }
+
+        let mut blocks = data.chunks_exact(64);
+        for block in &mut blocks {
+            self.compress(block.try_into().unwrap());
+        }
+        self.pending.extend_from_slice(blocks.remainder());
+    }
+
+    /// Termine le calcul et retourne l'empreinte en hexadécimal (64 caractères minuscules).
+    pub fn finish(mut self) -> String {
+        let bit_length = self.length.wrapping_mul(8);
+
+        // Bourrage : un bit à 1, des zéros, puis la longueur en bits sur 8 octets
+        let mut padding = vec![0x80];
+        let used = (self.length % 64) as usize;
+        let zeros = if used < 56 { 55 - used } else { 119 - used };
+        padding.extend(std::iter::repeat_n(0, zeros));
+        padding.extend_from_slice(&bit_length.to_be_bytes());
+        let length = self.length;
+        self.update(&padding);
+        self.length = length;
+
+        self.state.iter().map(|word| format!("{:08x}", word)).collect()
+    }
+
+    /// Traite un bloc de 64 octets.
+    fn compress(&mut self, block: &[u8; 64]) {
+        let mut schedule = [0u32; 64];
+        for (index, word) in block.chunks_exact(4).enumerate() {
+            schedule[index] = u32::from_be_bytes(word.try_into().unwrap());
+        }
+        for index in 16..64 {
+            let s0 = schedule[index - 15].rotate_right(7)
+                ^ schedule[index - 15].rotate_right(18)
+                ^ (schedule[index - 15] >> 3);
+            let s1 = schedule[index - 2].rotate_right(17)
+                ^ schedule[index - 2].rotate_right(19)
+                ^ (schedule[index - 2] >> 10);
+            schedule[index] = schedule[index - 16]
+                .wrapping_add(s0)
+                .wrapping_add(schedule[index - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+        for index in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let choice = (e & f) ^ (!e & g);
+            let temp1 = h
+                .wrapping_add(s1)
+                .wrapping_add(choice)
+                .wrapping_add(ROUND_CONSTANTS[index])
+                .wrapping_add(schedule[index]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add(majority);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+
+        for (word, value) in self.state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// # Fonction : `file_digest`
+///
+/// Calcule l'empreinte SHA-256 du contenu d'un fichier, lu par blocs.
+///
+/// ## Exemple :
+/// ```rust
+/// // Même résultat que `sha256sum notes.txt`
+/// let digest = sum::file_digest(Path::new("notes.txt"))?;
+/// ```
+pub fn file_digest(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; READ_BUFFER_SIZE];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hasher.finish())
+}
+
+/// # Fonction : `same_contents`
+///
+/// Compare le contenu de deux fichiers octet par octet.
+///
+/// ## Fonctionnement :
+/// 1. Compare d'abord les tailles, ce qui évite de relire des fichiers différents.
+/// 2. Lit les deux fichiers en parallèle par tranches de `COMPARE_BUFFER_SIZE` octets,
+///    sans jamais les charger entièrement, et s'arrête à la première différence.
+///
+/// ## Exemple :
+/// ```rust
+/// // Copie vérifiée : même contenu que l'original
+/// assert!(sum::same_contents(Path::new("notes.txt"), Path::new("copie.txt"))?);
+/// ```
+pub fn same_contents(first: &Path, second: &Path) -> io::Result<bool> {
+    if fs::metadata(first)?.len() != fs::metadata(second)?.len() {
+        return Ok(false);
+    }
+
+    let mut left = BufReader::with_capacity(COMPARE_BUFFER_SIZE, File::open(first)?);
+    let mut right = BufReader::with_capacity(COMPARE_BUFFER_SIZE, File::open(second)?);
+
+    loop {
+        let (length, equal) = {
+            let left_bytes = left.fill_buf()?;
+            let right_bytes = right.fill_buf()?;
+            if left_bytes.is_empty() || right_bytes.is_empty() {
+                return Ok(left_bytes.is_empty() && right_bytes.is_empty());
+            }
+            let length = left_bytes.len().min(right_bytes.len());
+            (length, left_bytes[..length] == right_bytes[..length])
+        };
+
+        if !equal {
+            return Ok(false);
+        }
+        left.consume(length);
+        right.consume(length);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdir::TestDir;
+
+    fn digest(data: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(data);
+        hasher.finish()
+    }
+
+    #[test]
+    fn known_vectors() {
+        assert_eq!(digest(b""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(digest(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(
+            digest(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+        assert_eq!(digest(&[b'a'; 1_000_000]), "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0");
+    }
+
+    #[test]
+    fn pieces_give_the_same_digest_as_a_whole() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        // Morceaux à cheval sur les blocs de 64 octets et sur le remplissage final
+        for size in [1, 7, 55, 56, 63, 64, 65, 999] {
+            let mut hasher = Sha256::new();
+            for piece in data.chunks(size) {
+                hasher.update(piece);
+            }
+            assert_eq!(hasher.finish(), digest(&data), "morceaux de {size} octets");
+        }
+    }
+
+    #[test]
+    fn file_digest_matches_sha256sum() {
+        let dir = TestDir::new("sum-file");
+        let file = dir.write("a.txt", "hello\n");
+        assert_eq!(file_digest(&file).unwrap(), "5891b5b522d5df086d0ff0b110fbd9d21bb4fc7163af34d08286a2e846f6be03");
+        assert!(file_digest(&dir.join("absent")).is_err());
+    }
+}
@@ -0,0 +1,272 @@
+//! # Module `syntax`
+//!
+//! Ce module colore un texte ligne par ligne selon son format (`cat --syntax`),
+//! avec des couleurs ANSI minimales et sans dépendance externe :
+//!
+//! - `diff` : lignes ajoutées (`+`) en vert, supprimées (`-`) en rouge, blocs (`@@`)
+//!   en cyan, en-têtes (`diff`, `index`, `+++`, `---`) en gras ;
+//! - `log` : lignes `ERROR` en rouge, `WARN` en jaune, `DEBUG` et `TRACE` en grisé,
+//!   et le mot `INFO` en vert ;
+//! - `json` : clés en bleu, chaînes en vert, nombres et `true`/`false`/`null` en
+//!   jaune, ponctuation grisée.
+//!
+//! Chaque format est un coloriage pur d'une ligne (`colorize_line`) : le résultat
+//! ne dépend que de la ligne, ce qui permet de colorer un flux sans le mémoriser.
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const BLUE: &str = "\x1b[34m";
+const CYAN: &str = "\x1b[36m";
+
+/// Formats reconnus par [`colorize`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Language {
+    Json,
+    Diff,
+    Log,
+}
+
+impl Language {
+    /// Retourne le format désigné par `name` (`json`, `diff` ou `log`, sans
+    /// tenir compte de la casse), ou `None` s'il est inconnu.
+    pub fn parse(name: &str) -> Option<Language> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Some(Language::Json),
+            "diff" | "patch" => Some(Language::Diff),
+            "log" => Some(Language::Log),
+            _ => None,
+        }
+    }
+}
+
+/// # Fonction : `colorize`
+///
+/// Colore chaque ligne de `content` selon `language`. Les fins de ligne sont
+/// conservées telles quelles.
+pub fn colorize(content: &str, language: Language) -> String {
+    let colorize_line = match language {
+        Language::Json => json::colorize_line,
+        Language::Diff => diff::colorize_line,
+        Language::Log => log::colorize_line,
+    };
+
+    content
+        .split_inclusive('\n')
+        .map(|line| {
+            let text = line.trim_end_matches(['\n', '\r']);
+            format!("{}{}", colorize_line(text), &line[text.len()..])
+        })
+        .collect()
+}
+
+/// Entoure `text` de la couleur `color` (rien pour une ligne vide).
+fn paint(color: &str, text: &str) -> String {
+    if text.is_empty() {
+        String::new()
+    } else {
+        format!("{}{}{}", color, text, RESET)
+    }
+}
+
+/// Coloriage des différences (`diff -u`, `git diff`).
+pub mod diff {
+    use super::{paint, BOLD, CYAN, GREEN, RED};
+
+    /// # Fonction : `colorize_line`
+    ///
+    /// Colore une ligne de diff selon son premier caractère. Les en-têtes `+++` et
+    /// `---` sont reconnus avant les lignes ajoutées ou supprimées.
+    ///
+    /// ## Exemple :
+    /// ```rust
+    /// assert_eq!(diff::colorize_line("+ajout"), "\x1b[32m+ajout\x1b[0m");
+    /// assert_eq!(diff::colorize_line("-retrait"), "\x1b[31m-retrait\x1b[0m");
+    /// assert_eq!(diff::colorize_line("@@ -1,2 +1,3 @@"), "\x1b[36m@@ -1,2 +1,3 @@\x1b[0m");
+    /// assert_eq!(diff::colorize_line("+++ b/main.rs"), "\x1b[1m+++ b/main.rs\x1b[0m");
+    /// assert_eq!(diff::colorize_line(" contexte"), " contexte");
+    /// ```
+    pub fn colorize_line(line: &str) -> String {
+        let color = if line.starts_with("+++ ")
+            || line.starts_with("--- ")
+            || line.starts_with("diff ")
+            || line.starts_with("index ")
+        {
+            BOLD
+        } else if line.starts_with("@@") {
+            CYAN
+        } else if line.starts_with('+') {
+            GREEN
+        } else if line.starts_with('-') {
+            RED
+        } else {
+            return line.to_string();
+        };
+        paint(color, line)
+    }
+}
+
+/// Coloriage des journaux selon leur niveau (`ERROR`, `WARN`, `INFO`...).
+pub mod log {
+    use super::{paint, DIM, GREEN, RED, YELLOW};
+
+    /// # Fonction : `colorize_line`
+    ///
+    /// Colore une ligne de journal d'après le premier mot de niveau qu'elle contient
+    /// (en majuscules, comme un mot entier : `ERRORS` ou `Error` ne comptent pas).
+    /// Les lignes d'erreur, d'avertissement et de débogage sont colorées en entier ;
+    /// pour `INFO` et `NOTICE`, seul le mot est coloré, pour ne pas noyer les autres.
+    ///
+    /// ## Exemple :
+    /// ```rust
+    /// assert_eq!(log::colorize_line("[ERROR] disque plein"), "\x1b[31m[ERROR] disque plein\x1b[0m");
+    /// assert_eq!(log::colorize_line("12:00 WARN lent"), "\x1b[33m12:00 WARN lent\x1b[0m");
+    /// assert_eq!(log::colorize_line("12:00 INFO prêt"), "12:00 \x1b[32mINFO\x1b[0m prêt");
+    /// assert_eq!(log::colorize_line("aucune erreur"), "aucune erreur");
+    /// ```
+    pub fn colorize_line(line: &str) -> String {
+        // Parcourt les mots (suites de lettres et chiffres), un espace final
+        // terminant le dernier
+        let mut start = 0;
+        for (index, c) in line.char_indices().chain([(line.len(), ' ')]) {
+            if c.is_ascii_alphanumeric() {
+                continue;
+            }
+            let word = &line[start..index];
+            start = index + c.len_utf8();
+
+            let Some((level, color)) = LEVELS.iter().find(|(name, _)| *name == word) else {
+                continue;
+            };
+            return if *level == "INFO" || *level == "NOTICE" {
+                format!("{}{}{}", &line[..index - word.len()], paint(color, word), &line[index..])
+            } else {
+                paint(color, line)
+            };
+        }
+        line.to_string()
+    }
+
+    /// Mots de niveau reconnus et leur couleur.
+    const LEVELS: [(&str, &str); 10] = [
+        ("FATAL", RED),
+        ("CRITICAL", RED),
+        ("ERROR", RED),
+        ("ERR", RED),
+        ("WARNING", YELLOW),
+        ("WARN", YELLOW),
+        ("INFO", GREEN),
+        ("NOTICE", GREEN),
+        ("DEBUG", DIM),
+        ("TRACE", DIM),
+    ];
+}
+
+/// Coloriage du JSON (clés, valeurs et ponctuation).
+pub mod json {
+    use super::{paint, BLUE, DIM, GREEN, YELLOW};
+
+    /// # Fonction : `colorize_line`
+    ///
+    /// Colore une ligne de JSON. Une chaîne suivie de `:` est une clé ; les autres
+    /// chaînes sont des valeurs. Une chaîne non fermée en fin de ligne (JSON invalide)
+    /// est colorée jusqu'au bout, sans erreur.
+    ///
+    /// ## Exemple :
+    /// ```rust
+    /// assert_eq!(
+    ///     json::colorize_line(r#"  "port": 8080,"#),
+    ///     "  \x1b[34m\"port\"\x1b[0m\x1b[2m:\x1b[0m \x1b[33m8080\x1b[0m\x1b[2m,\x1b[0m"
+    /// );
+    /// ```
+    pub fn colorize_line(line: &str) -> String {
+        let chars: Vec<char> = line.chars().collect();
+        let mut result = String::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            let c = chars[i];
+            match c {
+                '"' => {
+                    let start = i;
+                    i += 1;
+                    while i < chars.len() && chars[i] != '"' {
+                        // Un caractère échappé (\" ou \\) ne termine pas la chaîne
+                        i += if chars[i] == '\\' { 2 } else { 1 };
+                    }
+                    i = (i + 1).min(chars.len());
+                    let text: String = chars[start..i].iter().collect();
+                    let is_key = chars[i..].iter().find(|c| !c.is_whitespace()) == Some(&':');
+                    result.push_str(&paint(if is_key { BLUE } else { GREEN }, &text));
+                }
+                '{' | '}' | '[' | ']' | ':' | ',' => {
+                    result.push_str(&paint(DIM, &c.to_string()));
+                    i += 1;
+                }
+                c if c == '-' || c.is_ascii_alphanumeric() => {
+                    let start = i;
+                    while i < chars.len() && (chars[i].is_ascii_alphanumeric() || "+-.".contains(chars[i])) {
+                        i += 1;
+                    }
+                    let text: String = chars[start..i].iter().collect();
+                    result.push_str(&paint(YELLOW, &text));
+                }
+                c => {
+                    result.push(c);
+                    i += 1;
+                }
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn languages_are_parsed_without_case() {
+        assert_eq!(Language::parse("JSON"), Some(Language::Json));
+        assert_eq!(Language::parse("patch"), Some(Language::Diff));
+        assert_eq!(Language::parse("Log"), Some(Language::Log));
+        assert_eq!(Language::parse("yaml"), None);
+    }
+
+    #[test]
+    fn diff_lines_are_colored_by_their_first_character() {
+        assert_eq!(diff::colorize_line("+ajout"), format!("{GREEN}+ajout{RESET}"));
+        assert_eq!(diff::colorize_line("-retrait"), format!("{RED}-retrait{RESET}"));
+        assert_eq!(diff::colorize_line("@@ -1,2 +1,3 @@"), format!("{CYAN}@@ -1,2 +1,3 @@{RESET}"));
+        assert_eq!(diff::colorize_line("+++ b/main.rs"), format!("{BOLD}+++ b/main.rs{RESET}"));
+        assert_eq!(diff::colorize_line("--- a/main.rs"), format!("{BOLD}--- a/main.rs{RESET}"));
+        assert_eq!(diff::colorize_line(" contexte"), " contexte");
+    }
+
+    #[test]
+    fn log_levels_are_whole_uppercase_words() {
+        assert_eq!(log::colorize_line("[ERROR] disque plein"), format!("{RED}[ERROR] disque plein{RESET}"));
+        assert_eq!(log::colorize_line("12:00 WARN lent"), format!("{YELLOW}12:00 WARN lent{RESET}"));
+        assert_eq!(log::colorize_line("12:00 INFO prêt"), format!("12:00 {GREEN}INFO{RESET} prêt"));
+        assert_eq!(log::colorize_line("ERRORS Error error"), "ERRORS Error error");
+    }
+
+    #[test]
+    fn json_keys_and_values_differ() {
+        assert_eq!(
+            json::colorize_line(r#"  "port": 8080,"#),
+            format!("  {BLUE}\"port\"{RESET}{DIM}:{RESET} {YELLOW}8080{RESET}{DIM},{RESET}")
+        );
+        assert_eq!(json::colorize_line(r#""ouvert"#), format!("{GREEN}\"ouvert{RESET}"));
+    }
+
+    #[test]
+    fn colorize_keeps_line_endings() {
+        let colored = colorize("+a\r\n\n-b", Language::Diff);
+        assert_eq!(colored, format!("{GREEN}+a{RESET}\r\n\n{RED}-b{RESET}"));
+    }
+}
@@ -0,0 +1,286 @@
+//! # Module `tar`
+//!
+//! Ce module lit les fichiers contenus dans une archive `.tar` (non compressée),
+//! sans l'extraire, pour les traiter un par un (ex. `wc --archive`).
+//!
+//! Une archive tar est une suite de blocs de 512 octets : chaque membre commence
+//! par un bloc d'en-tête (nom, taille, type...), suivi de son contenu complété
+//! jusqu'au bloc suivant ; un bloc vide marque la fin. Sont pris en charge :
+//!
+//! - les formats ustar, GNU et pax (noms longs compris) ;
+//! - les tailles écrites en octal ou en base 256 (fichiers de plus de 8 Gio).
+//!
+//! Seuls les fichiers réguliers sont transmis : les dossiers, liens et fichiers
+//! spéciaux sont ignorés. L'archive est lue au fil de l'eau, sans être chargée.
+
+use std::io::{self, Read};
+
+/// Taille d'un bloc tar.
+const BLOCK_SIZE: usize = 512;
+
+/// # Fonction : `for_each_member`
+///
+/// Appelle `visit` pour chaque fichier régulier de l'archive, avec son nom (chemin
+/// dans l'archive) et un lecteur limité à son contenu. La partie du contenu que
+/// `visit` n'a pas lue est sautée.
+///
+/// Retourne une erreur `InvalidData` si un en-tête est invalide (somme de contrôle
+/// incorrecte : le fichier n'est sans doute pas une archive tar) ou si l'archive
+/// est tronquée.
+///
+/// ## Exemple :
+/// ```rust
+/// let archive = BufReader::new(File::open("sources.tar")?);
+/// tar::for_each_member(archive, |name, member| {
+///     let mut content = String::new();
+///     member.read_to_string(&mut content)?;
+///     println!("{} : {} lignes", name, content.lines().count());
+///     Ok(())
+/// })?;
+/// ```
+pub fn for_each_member<R, F>(mut reader: R, mut visit: F) -> io::Result<()>
+where
+    R: Read,
+    F: FnMut(&str, &mut dyn Read) -> io::Result<()>,
+{
+    // Nom long annoncé par l'en-tête précédent (GNU `L` ou pax `path=`)
+    let mut long_name: Option<String> = None;
+    let mut header = [0u8; BLOCK_SIZE];
+
+    loop {
+        if !read_block(&mut reader, &mut header)? || header.iter().all(|&byte| byte == 0) {
+            return Ok(());
+        }
+        check_header(&header)?;
+
+        let size = parse_size(&header[124..136])?;
+        let name = long_name.take().unwrap_or_else(|| header_name(&header));
+        let mut content = (&mut reader).take(size);
+
+        match header[156] {
+            // Fichier régulier (`7` : fichier contigu, traité comme un fichier normal)
+            b'0' | b'\0' | b'7' => visit(&name, &mut content)?,
+            // Nom long du membre suivant (GNU)
+            b'L' => long_name = Some(read_text(&mut content)?.trim_end_matches('\0').to_string()),
+            // Attributs étendus du membre suivant (pax)
+            b'x' => long_name = pax_path(&read_text(&mut content)?),
+            // Dossiers, liens, fichiers spéciaux, attributs globaux : ignorés
+            _ => {}
+        }
+
+        // Sauter le reste du contenu, puis le bourrage jusqu'au bloc suivant
+        let unread = content.limit();
+        let padding = (BLOCK_SIZE as u64 - size % BLOCK_SIZE as u64) % BLOCK_SIZE as u64;
+        let skipped = io::copy(&mut (&mut reader).take(unread + padding), &mut io::sink())?;
+        if skipped < unread {
+            return Err(truncated());
+        }
+    }
+}
+
+/// Lit un bloc entier ; retourne `false` à la fin du flux (archive sans bloc de fin).
+fn read_block(reader: &mut impl Read, block: &mut [u8; BLOCK_SIZE]) -> io::Result<bool> {
+    let mut filled = 0;
+    while filled < BLOCK_SIZE {
+        match reader.read(&mut block[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => return Err(truncated()),
+            Ok(read) => filled += read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(true)
+}
+
+/// Vérifie la somme de contrôle d'un en-tête : somme de ses octets, le champ
+/// de la somme lui-même (octets 148 à 155) comptant comme des espaces.
+fn check_header(header: &[u8; BLOCK_SIZE]) -> io::Result<()> {
+    let expected = parse_octal(&header[148..156]);
+    let actual: u64 = header
+        .iter()
+        .enumerate()
+        .map(|(index, &byte)| if (148..156).contains(&index) { b' ' as u64 } else { byte as u64 })
+        .sum();
+
+    if expected == Some(actual) {
+        Ok(())
+    } else {
+        Err(io::Error::new(io::ErrorKind::InvalidData, "en-tête tar invalide (somme de contrôle incorrecte)"))
+    }
+}
+
+/// Retourne le nom d'un membre : le champ `name`, précédé du champ `prefix` en ustar.
+fn header_name(header: &[u8; BLOCK_SIZE]) -> String {
+    let field = |bytes: &[u8]| {
+        let end = bytes.iter().position(|&byte| byte == 0).unwrap_or(bytes.len());
+        String::from_utf8_lossy(&bytes[..end]).into_owned()
+    };
+
+    let name = field(&header[..100]);
+    let prefix = if &header[257..262] == b"ustar" { field(&header[345..500]) } else { String::new() };
+    if prefix.is_empty() { name } else { format!("{}/{}", prefix, name) }
+}
+
+/// Lit la taille d'un membre, en octal ou en base 256 (premier octet ≥ 0x80).
+fn parse_size(field: &[u8]) -> io::Result<u64> {
+    if field[0] & 0x80 != 0 {
+        let value = field[1..].iter().fold(0u128, |value, &byte| (value << 8) | byte as u128);
+        return u64::try_from(value).map_err(|_| invalid_size());
+    }
+    parse_octal(field).ok_or_else(invalid_size)
+}
+
+/// Lit un nombre octal terminé par un espace ou un octet nul (vide : 0).
+fn parse_octal(field: &[u8]) -> Option<u64> {
+    let text = std::str::from_utf8(field).ok()?;
+    let digits = text.trim_matches(|c: char| c == '\0' || c == ' ');
+    if digits.is_empty() {
+        return Some(0);
+    }
+    u64::from_str_radix(digits, 8).ok()
+}
+
+/// Lit le contenu (court) d'un membre d'information : nom long ou attributs pax.
+fn read_text(content: &mut impl Read) -> io::Result<String> {
+    let mut bytes = Vec::new();
+    content.read_to_end(&mut bytes)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Cherche l'attribut `path` dans des enregistrements pax (`<longueur> path=<nom>\n`).
+fn pax_path(records: &str) -> Option<String> {
+    records
+        .lines()
+        .filter_map(|record| record.split_once(' ').map(|(_, field)| field))
+        .find_map(|field| field.strip_prefix("path="))
+        .map(|path| path.to_string())
+}
+
+fn truncated() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "archive tar tronquée")
+}
+
+fn invalid_size() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "taille invalide dans un en-tête tar")
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Construit un en-tête ustar (`prefix` vide : pas de préfixe) avec sa somme de contrôle.
+    fn header(name: &str, prefix: &str, size: usize, kind: u8) -> [u8; BLOCK_SIZE] {
+        let mut header = [0u8; BLOCK_SIZE];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        header[124..136].copy_from_slice(format!("{:011o}\0", size).as_bytes());
+        header[156] = kind;
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+
+        header[148..156].fill(b' ');
+        let sum: u32 = header.iter().map(|&byte| byte as u32).sum();
+        header[148..156].copy_from_slice(format!("{:06o}\0 ", sum).as_bytes());
+        header
+    }
+
+    /// Ajoute un membre (en-tête, contenu et bourrage) à une archive.
+    fn push(archive: &mut Vec<u8>, name: &str, kind: u8, content: &[u8]) {
+        archive.extend(header(name, "", content.len(), kind));
+        archive.extend(content);
+        archive.resize(archive.len().next_multiple_of(BLOCK_SIZE), 0);
+    }
+
+    /// Archive tar contenant les fichiers `(nom, contenu)`, terminée par deux blocs vides.
+    pub(crate) fn archive(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut archive = Vec::new();
+        for (name, content) in files {
+            push(&mut archive, name, b'0', content.as_bytes());
+        }
+        archive.extend([0u8; 2 * BLOCK_SIZE]);
+        archive
+    }
+
+    /// Noms et contenus des fichiers transmis par [`for_each_member`].
+    fn members(archive: &[u8]) -> io::Result<Vec<(String, String)>> {
+        let mut members = Vec::new();
+        for_each_member(archive, |name, content| {
+            let mut text = String::new();
+            content.read_to_string(&mut text)?;
+            members.push((name.to_string(), text));
+            Ok(())
+        })?;
+        Ok(members)
+    }
+
+    fn pairs(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+        expected.iter().map(|(name, content)| (name.to_string(), content.to_string())).collect()
+    }
+
+    #[test]
+    fn regular_files_are_visited_in_order() {
+        let mut data = Vec::new();
+        push(&mut data, "src/", b'5', b"");
+        push(&mut data, "src/main.rs", b'0', b"fn main() {}\n");
+        push(&mut data, "lien", b'2', b"");
+        push(&mut data, "vide.txt", b'0', b"");
+        push(&mut data, "grand.txt", b'0', &[b'x'; 1000]);
+
+        let expected = pairs(&[("src/main.rs", "fn main() {}\n"), ("vide.txt", ""), ("grand.txt", &"x".repeat(1000))]);
+        assert_eq!(members(&data).unwrap(), expected);
+        // Sans blocs de fin, l'archive se termine avec le flux
+        let mut data = Vec::new();
+        push(&mut data, "a.txt", b'0', b"a");
+        assert_eq!(members(&data).unwrap(), pairs(&[("a.txt", "a")]));
+    }
+
+    #[test]
+    fn unread_content_is_skipped() {
+        let data = archive(&[("a.txt", &"a".repeat(700)), ("b.txt", "b")]);
+        let mut names = Vec::new();
+        for_each_member(&data[..], |name, _| {
+            names.push(name.to_string());
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(names, ["a.txt", "b.txt"]);
+    }
+
+    #[test]
+    fn long_names_come_from_gnu_pax_or_ustar_prefix() {
+        let long = format!("{}/fichier.txt", "dossier".repeat(20));
+        let mut data = Vec::new();
+        push(&mut data, "././@LongLink", b'L', format!("{}\0", long).as_bytes());
+        push(&mut data, "tronqué", b'0', b"gnu");
+        push(&mut data, "PaxHeaders/x", b'x', format!("20 path={}\n", "pax/nom.txt").as_bytes());
+        push(&mut data, "nom.txt", b'0', b"pax");
+        data.extend(header("nom.txt", "ustar/prefixe", 0, b'0'));
+
+        let names: Vec<String> = members(&data).unwrap().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, [long, "pax/nom.txt".to_string(), "ustar/prefixe/nom.txt".to_string()]);
+    }
+
+    #[test]
+    fn sizes_can_be_octal_or_base_256() {
+        assert_eq!(parse_size(b"00000001750\0").unwrap(), 1000);
+        assert_eq!(parse_size(b"           \0").unwrap(), 0);
+        let mut huge = [0u8; 12];
+        huge[0] = 0x80;
+        huge[7] = 0x02; // 2 << 32 : 8 Gio
+        assert_eq!(parse_size(&huge).unwrap(), 8 << 30);
+        assert!(parse_size(b"0000000999\0\0").is_err());
+    }
+
+    #[test]
+    fn invalid_or_truncated_archives_are_errors() {
+        let error = members(b"ceci n'est pas une archive tar".repeat(20).as_slice()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        let mut data = archive(&[("a.txt", &"a".repeat(600))]);
+        data.truncate(BLOCK_SIZE + 100);
+        assert_eq!(members(&data).unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+        data.truncate(BLOCK_SIZE / 2);
+        assert_eq!(members(&data).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
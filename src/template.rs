@@ -0,0 +1,261 @@
+//! # Module `template`
+//!
+//! Ce module construit le nom de destination de chaque fichier à partir d'un
+//! modèle (`cp --as` et `mv --as`), par exemple `{name}-{date}.{ext}`.
+//!
+//! Emplacements reconnus :
+//!
+//! - `{name}` : nom du fichier source sans son extension (`rapport` pour `rapport.pdf`) ;
+//! - `{ext}` : extension du fichier source, sans le point (vide s'il n'en a pas) ;
+//! - `{date}` : date du jour, au format `AAAA-MM-JJ` (UTC).
+//!
+//! `{{` et `}}` produisent une accolade seule. Un emplacement inconnu ou une
+//! accolade non fermée est refusé dès la lecture du modèle, avant toute copie.
+//!
+//! [`Batch`] regroupe ce que `cp --as` et `mv --as` font de la même façon : vérifier
+//! le dossier de destination et ne jamais donner deux fois le même nom.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::path;
+
+/// Morceau d'un modèle : texte recopié tel quel ou emplacement à remplacer.
+#[derive(Clone, PartialEq, Debug)]
+enum Part {
+    Text(String),
+    Name,
+    Ext,
+    Date,
+}
+
+/// Modèle de nom de fichier déjà analysé (voir [`Template::parse`]).
+#[derive(Clone, PartialEq, Debug)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    /// # Fonction : `parse`
+    ///
+    /// Analyse un modèle. Retourne un message d'erreur (en anglais, comme ceux de
+    /// `cp` et `mv`) si un emplacement est inconnu, si une accolade n'est pas
+    /// fermée, ou si le modèle contient un séparateur de chemin : il ne doit
+    /// produire qu'un nom de fichier.
+    ///
+    /// ## Exemple :
+    /// ```rust
+    /// assert!(Template::parse("{name}-{date}.{ext}").is_ok());
+    /// assert_eq!(Template::parse("{nom}.txt").unwrap_err(), "unknown placeholder '{nom}' (expected {name}, {ext} or {date})");
+    /// assert!(Template::parse("{name").is_err());
+    /// ```
+    pub fn parse(template: &str) -> Result<Template, String> {
+        if template.is_empty() {
+            return Err("empty template".to_string());
+        }
+        if template.contains(['/', '\\']) {
+            return Err(format!("template '{}' must not contain a path separator", template));
+        }
+
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut placeholder = String::new();
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            closed = true;
+                            break;
+                        }
+                        placeholder.push(c);
+                    }
+                    if !closed {
+                        return Err(format!("unclosed '{{' in template '{}'", template));
+                    }
+
+                    let part = match placeholder.as_str() {
+                        "name" => Part::Name,
+                        "ext" => Part::Ext,
+                        "date" => Part::Date,
+                        _ => {
+                            return Err(format!(
+                                "unknown placeholder '{{{}}}' (expected {{name}}, {{ext}} or {{date}})",
+                                placeholder
+                            ));
+                        }
+                    };
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(part);
+                }
+                '}' => return Err(format!("unmatched '}}' in template '{}' (use '}}}}')", template)),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+
+        Ok(Template { parts })
+    }
+
+    /// # Fonction : `expand`
+    ///
+    /// Retourne le nom de destination de `source` : `{name}` et `{ext}` sont tirés
+    /// de son dernier composant, `{date}` est remplacé par `date` (calculée une seule
+    /// fois par commande, pour que tous les fichiers portent la même).
+    ///
+    /// Le nom est découpé comme [`Path::file_stem`] et [`Path::extension`] : seule la
+    /// dernière extension compte, et un fichier caché comme `.bashrc` n'en a pas.
+    ///
+    /// ## Exemple :
+    /// ```rust
+    /// let template = Template::parse("{name}-{date}.{ext}")?;
+    /// assert_eq!(template.expand(Path::new("logs/app.log"), "2024-05-01"), "app-2024-05-01.log");
+    /// assert_eq!(template.expand(Path::new("site.tar.gz"), "2024-05-01"), "site.tar-2024-05-01.gz");
+    ///
+    /// let template = Template::parse("{{{name}}}.{ext}")?;
+    /// assert_eq!(template.expand(Path::new("notes.txt"), "2024-05-01"), "{notes}.txt");
+    /// assert_eq!(template.expand(Path::new(".bashrc"), "2024-05-01"), "{.bashrc}.");
+    /// ```
+    pub fn expand(&self, source: &Path, date: &str) -> String {
+        let name = source.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+        let ext = source.extension().map(|s| s.to_string_lossy()).unwrap_or_default();
+
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => text.as_str(),
+                Part::Name => &name,
+                Part::Ext => &ext,
+                Part::Date => date,
+            })
+            .collect()
+    }
+}
+
+/// Noms de destination d'une commande `--as` : chaque source reçoit le chemin
+/// `dossier/nom`, où `nom` est donné par le modèle.
+///
+/// Les messages d'erreur sont en anglais et sans préfixe : l'appelant ajoute `cp:` ou `mv:`.
+pub struct Batch<'a> {
+    template: &'a Template,
+    directory: &'a str,
+    date: String,
+    /// Noms déjà produits pendant cette commande, pour qu'une source n'écrase pas une autre.
+    claimed: HashSet<String>,
+}
+
+impl<'a> Batch<'a> {
+    /// # Fonction : `new`
+    ///
+    /// Prépare les noms d'une commande vers `directory`, qui doit être un dossier
+    /// existant. `date` remplace `{date}` pour toutes les sources (calculée une seule
+    /// fois par l'appelant, pour que tous les fichiers portent la même).
+    pub fn new(template: &'a Template, directory: &'a str, date: String) -> Result<Batch<'a>, String> {
+        match path::resolve(directory) {
+            Ok(dir) if dir.is_dir() => {}
+            Ok(_) => return Err(format!("target '{}' is not a directory", directory)),
+            Err(e) => return Err(format!("cannot resolve path: {}", e)),
+        }
+
+        Ok(Batch { template, directory, date, claimed: HashSet::new() })
+    }
+
+    /// # Fonction : `destination`
+    ///
+    /// Retourne le chemin de destination de `source` (dont le nom est tiré de
+    /// `source_path`), ou le message expliquant pourquoi la source est ignorée : le
+    /// nom produit est vide, `.` ou `..`, ou a déjà été donné à une autre source.
+    ///
+    /// ## Exemple :
+    /// ```rust
+    /// let template = Template::parse("{name}.bak")?;
+    /// let mut batch = Batch::new(&template, "sauvegardes/", "2024-05-01".to_string())?;
+    /// assert_eq!(batch.destination("a.txt", Path::new("a.txt")), Ok("sauvegardes/a.bak".to_string()));
+    /// assert!(batch.destination("a.md", Path::new("a.md")).is_err());
+    /// ```
+    pub fn destination(&mut self, source: &str, source_path: &Path) -> Result<String, String> {
+        let name = self.template.expand(source_path, &self.date);
+        if name.is_empty() || name == "." || name == ".." {
+            return Err(format!("skipping '{}': template gives an invalid name '{}'", source, name));
+        }
+        if !self.claimed.insert(name.clone()) {
+            return Err(format!("skipping '{}': '{}' was already produced by another source", source, name));
+        }
+
+        Ok(format!("{}/{}", self.directory.trim_end_matches(['/', '\\']), name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdir::TestDir;
+
+    const DAY: &str = "2024-05-01";
+
+    fn expand(template: &str, source: &str) -> String {
+        Template::parse(template).unwrap().expand(Path::new(source), DAY)
+    }
+
+    #[test]
+    fn placeholders_are_expanded() {
+        assert_eq!(expand("{name}-{date}.{ext}", "logs/app.log"), "app-2024-05-01.log");
+        assert_eq!(expand("{date}_{name}", "site.tar.gz"), "2024-05-01_site.tar");
+        assert_eq!(expand("{ext}", "sans_extension"), "");
+        assert_eq!(expand("{name}.{ext}", ".bashrc"), ".bashrc.");
+        assert_eq!(expand("{{{name}}}", "notes.txt"), "{notes}");
+        assert_eq!(expand("fixe.txt", "notes.txt"), "fixe.txt");
+    }
+
+    #[test]
+    fn invalid_templates_are_rejected() {
+        for template in ["", "{nom}", "{name", "name}", "dossier/{name}", "a\\b"] {
+            assert!(Template::parse(template).is_err(), "{template}");
+        }
+    }
+
+    #[test]
+    fn batch_needs_an_existing_directory() {
+        let dir = TestDir::new("template-batch-dir");
+        let file = dir.write("fichier.txt", "");
+        let template = Template::parse("{name}").unwrap();
+
+        assert!(Batch::new(&template, dir.path().to_str().unwrap(), DAY.to_string()).is_ok());
+        let error = Batch::new(&template, file.to_str().unwrap(), DAY.to_string()).err().unwrap();
+        assert!(error.contains("is not a directory"), "{error}");
+        assert!(Batch::new(&template, dir.join("absent").to_str().unwrap(), DAY.to_string()).is_err());
+    }
+
+    #[test]
+    fn batch_never_gives_a_name_twice() {
+        let dir = TestDir::new("template-batch");
+        let directory = format!("{}/", dir.path().display());
+        let template = Template::parse("{name}-{date}").unwrap();
+        let mut batch = Batch::new(&template, &directory, DAY.to_string()).unwrap();
+
+        let first = batch.destination("a.txt", Path::new("a.txt")).unwrap();
+        assert_eq!(first, format!("{}/a-2024-05-01", dir.path().display()));
+        let error = batch.destination("src/a.md", Path::new("src/a.md")).unwrap_err();
+        assert!(error.contains("already produced"), "{error}");
+        assert!(batch.destination("b.txt", Path::new("b.txt")).is_ok());
+
+        let template = Template::parse("{ext}").unwrap();
+        let mut batch = Batch::new(&template, &directory, DAY.to_string()).unwrap();
+        assert!(batch.destination("sans_extension", Path::new("sans_extension")).unwrap_err().contains("invalid name"));
+    }
+}
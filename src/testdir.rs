@@ -0,0 +1,62 @@
+//! # Module `testdir`
+//!
+//! Dossiers temporaires pour les tests : chaque [`TestDir`] est créé vide sous
+//! le dossier temporaire du système, avec un nom unique, et supprimé avec tout
+//! son contenu à la fin du test.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Compteur qui rend uniques les dossiers d'un même processus (tests parallèles).
+static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+/// Dossier temporaire supprimé à la fin du test.
+pub struct TestDir {
+    path: PathBuf,
+}
+
+impl TestDir {
+    /// Crée un dossier vide dont le nom contient `label` (pour s'y retrouver
+    /// si un test est interrompu avant le nettoyage).
+    pub fn new(label: &str) -> TestDir {
+        let id = NEXT.fetch_add(1, Ordering::SeqCst);
+        let path = std::env::temp_dir().join(format!("utils-cli-{}-{}-{}", label, std::process::id(), id));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).expect("création du dossier de test");
+        TestDir { path }
+    }
+
+    /// Chemin du dossier.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Chemin d'une entrée du dossier.
+    pub fn join(&self, name: &str) -> PathBuf {
+        self.path.join(name)
+    }
+
+    /// Écrit un fichier (et ses dossiers parents) et retourne son chemin.
+    pub fn write(&self, name: &str, content: impl AsRef<[u8]>) -> PathBuf {
+        let path = self.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("création des dossiers parents");
+        }
+        fs::write(&path, content).expect("écriture du fichier de test");
+        path
+    }
+
+    /// Crée un sous-dossier (et ses parents) et retourne son chemin.
+    pub fn mkdir(&self, name: &str) -> PathBuf {
+        let path = self.join(name);
+        fs::create_dir_all(&path).expect("création du sous-dossier de test");
+        path
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
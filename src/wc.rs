@@ -1,19 +1,33 @@
-use std::fs::File;
-use std::io::{self, Write, BufReader, BufRead};
+//! Implémentation Rust de la commande `wc`.
+//!
+//! Ce module implémente la commande `wc` (word count) avec Rust. Elle permet de compter
+//! les lignes, mots, caractères et octets dans des fichiers ou des flux stdin.
+//!
+//! Le comptage lui-même est exposé par [`count_reader`], utilisable sur n'importe
+//! quelle source implémentant [`Read`] (fichier, tampon en mémoire, socket...).
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Write, BufReader, Read};
 use std::path::Path;
 
+use crate::{escape, file_list, glob, lines, menu, path, pipeline, tar};
+
 const VERSION: &str = "1.0.0";
+const DEFAULT_BUCKET_SIZE: usize = 10; // Largeur par défaut d'une tranche de l'histogramme
+const DEFAULT_TERMINAL_WIDTH: usize = 80; // Largeur utilisée si COLUMNS n'est pas défini
+const IGNORE_FILE: &str = ".wcignore"; // Motifs exclus d'un comptage récursif (-r)
+const TREEMAP_BAR_WIDTH: usize = 20; // Largeur de la barre d'un dossier à 100 % (--treemap)
 
-/// Implémentation Rust de la commande `wc`.
-///
-/// Ce module implémente la commande `wc` (word count) avec Rust. Elle permet de compter
-/// les lignes, mots, caractères et octets dans des fichiers ou des flux stdin.
+/// Abréviations courantes dont le point ne termine pas une phrase (`--prose`).
+const ABBREVIATIONS: [&str; 12] = ["etc", "ex", "cf", "env", "mr", "mrs", "ms", "dr", "mme", "mlle", "st", "vs"];
 
 /// Structure représentant les options de comptage pour la commande `wc`.
 ///
 /// Chaque champ correspond à une option possible de `wc`.
 #[derive(Default, Clone)]
-struct Options {
+pub struct Options {
     /// Affiche le nombre d'octets (`-c`).
     show_bytes: bool,
     /// Affiche le nombre de caractères (`-m`).
@@ -24,31 +38,86 @@ struct Options {
     show_words: bool,
     /// Affiche la longueur de la ligne la plus longue (`-L`).
     show_max_line_length: bool,
-    /// Fichier contenant une liste de fichiers séparés par `\0` (`--files0-from=F`).
-    files0_from: Option<String>,
+    /// Listes de fichiers à compter (`--files0-from F`, `--files-from F`).
+    lists: file_list::Sources,
+    /// Modèle d'affichage des compteurs (`--format="%l %w %c"`).
+    format: Option<String>,
+    /// Affiche l'histogramme des longueurs de ligne (`--histogram`).
+    show_histogram: bool,
+    /// Affiche la position des lignes non vides la plus longue et la plus courte (`--line-stats`).
+    show_line_stats: bool,
+    /// Regroupe les totaux par extension de fichier (`--by-extension`).
+    by_extension: bool,
+    /// Compte aussi les phrases et les paragraphes (`--prose`).
+    prose: bool,
+    /// Largeur d'une tranche de l'histogramme (`--bucket N`).
+    bucket_size: Option<usize>,
+    /// Caractère dont on compte les occurrences (`--count-char C`).
+    count_char: Option<char>,
+    /// Ligne séparant les enregistrements à compter (`--record-separator SEP`).
+    record_separator: Option<String>,
+    /// Ne lit que les `N` premiers octets de chaque fichier et extrapole (`--sample N`).
+    sample: Option<u64>,
+    /// Compte les fichiers des dossiers et de leurs sous-dossiers (`-r`).
+    recursive: bool,
+    /// Affiche un objet JSON par ligne au lieu des colonnes (`--ndjson`).
+    ndjson: bool,
+    /// Sous-chaîne ou motif (`*`, `?`, `[...]`) des mots à compter (`--token MOTIF`).
+    token: Option<String>,
+    /// Compare les mots au motif de `--token` sans tenir compte de la casse (`-i`).
+    ignore_case: bool,
+    /// Affiche la répartition du premier chiffre des nombres du texte (`--digits`).
+    digits: bool,
+    /// Affiche la part des lignes de chaque sous-dossier au lieu d'une ligne par fichier (`--treemap`).
+    treemap: bool,
+    /// Compare les compteurs de deux fichiers côte à côte, avec leur écart (`--compare`).
+    compare: bool,
+    /// Compte séparément chaque fichier contenu dans une archive `.tar` (`--archive`).
+    archive: bool,
 }
 
 /// Structure représentant les résultats du comptage.
 ///
 /// Stocke tous les compteurs pour un fichier ou un flux.
 #[derive(Default, Clone)]
-struct CountResult {
-    /// Nombre de lignes.
-    lines: usize,
+pub struct CountResult {
+    /// Nombre de lignes (nombre de `\n`).
+    pub lines: usize,
     /// Nombre de mots.
-    words: usize,
+    pub words: usize,
     /// Nombre de caractères.
-    chars: usize,
+    pub chars: usize,
     /// Nombre d'octets.
-    bytes: usize,
+    pub bytes: usize,
     /// Longueur maximale d'une ligne.
-    max_line_length: usize,
+    pub max_line_length: usize,
+    /// Nombre de lignes pour chaque longueur (en caractères), rempli avec `--histogram`.
+    pub line_lengths: BTreeMap<usize, usize>,
+    /// Nombre d'occurrences du caractère de `--count-char`.
+    pub char_occurrences: usize,
+    /// Plus longue ligne non vide : `(numéro de ligne, longueur)`, première en cas d'égalité.
+    pub longest_line: Option<(usize, usize)>,
+    /// Plus courte ligne non vide : `(numéro de ligne, longueur)`, première en cas d'égalité.
+    pub shortest_line: Option<(usize, usize)>,
+    /// Nombre de phrases, rempli avec `--prose` (voir [`count_sentences`]).
+    pub sentences: usize,
+    /// Nombre de paragraphes (blocs séparés par des lignes vides), rempli avec `--prose`.
+    pub paragraphs: usize,
+    /// Nombre d'enregistrements séparés par la ligne de `--record-separator`.
+    pub records: usize,
+    /// Nombre de mots correspondant au motif de `--token` (voir [`token_matches`]).
+    pub token_matches: usize,
+    /// Nombre de nombres commençant par chaque chiffre de 1 à 9, rempli avec `--digits`
+    /// (voir [`add_leading_digits`]).
+    pub leading_digits: [usize; 9],
+    /// Les compteurs sont extrapolés d'un échantillon (`--sample`) et non exacts.
+    pub estimated: bool,
 }
 
 /// Fonction principale du programme `wc`.
 ///
 /// Démarre un shell interactif permettant de saisir des commandes `wc`. Si l'utilisateur
-/// quitte l'utilitaire en tapant `quit`, il reviendra sur le menu principal.
+/// quitte l'utilitaire en tapant `menu` (ou `b`, `0`, `quit`), il reviendra sur le menu principal.
 ///
 /// # Exemple
 /// ```no_run
@@ -57,7 +126,7 @@ struct CountResult {
 pub fn wc() {
     loop {
         println!("\n=== Programme utilitaire wc ===");
-        println!("Entrez votre commande (ou 'quit' pour quitter) :");
+        println!("Entrez votre commande (ou 'menu' pour revenir au menu principal) :");
         print!("> ");
         io::stdout().flush().unwrap();
 
@@ -66,7 +135,7 @@ pub fn wc() {
         
         let input = input.trim();
         
-        if input == "quit" {
+        if input == "quit" || menu::is_back(input) {
             break;
         }
 
@@ -76,8 +145,8 @@ pub fn wc() {
 
         // Vérifier si la commande contient un pipe
         if input.contains('|') {
-            // Traiter la commande avec pipe (echo ... | wc ...)
-            match process_piped_command(input) {
+            // Enchaîner les commandes (echo ... | wc ..., cat ... | wc ...)
+            match pipeline::run_line(input) {
                 Ok(_) => {},
                 Err(e) => {
                     eprintln!("Erreur : {}", e);
@@ -100,7 +169,7 @@ pub fn wc() {
 
             // Traiter la commande
             let args: Vec<&str> = parts[1..].iter().map(|s| s.as_str()).collect();
-            match process_command(&args) {
+            match process_command(&args, None, &mut io::stdout()) {
                 Ok(_) => {},
                 Err(e) => {
                     eprintln!("Erreur : {}", e);
@@ -111,11 +180,30 @@ pub fn wc() {
     }
 }
 
+/// Exécute une commande `wc` en mode non interactif.
+///
+/// Les arguments sont ceux passés au programme après le nom de la commande
+/// (ex. `projet wc fichier.txt`). Comme coreutils, retourne le code de sortie 1 si
+/// un fichier n'a pas pu être compté, 0 sinon : c'est l'appelant qui quitte, ce qui
+/// permet à `--bench` de continuer ses mesures. Si le lecteur de la sortie s'arrête
+/// (`projet wc -l *.txt | head -1`), le programme se termine sans message.
+pub fn run(args: &[String]) -> i32 {
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    if let Err(e) = process_command(&args, None, &mut io::stdout()) {
+        pipeline::exit_on_broken_pipe(&e);
+        eprintln!("Erreur : {}", e);
+        return 1;
+    }
+    0
+}
+
 /// Parse une ligne de commande en respectant les guillemets et échappements.
 ///
 /// # Algorithme
 /// - Parcours caractère par caractère.
-/// - Bascule l'état `in_quotes` sur les guillemets.
+/// - Bascule l'état `in_quotes` sur les guillemets `"`.
+/// - Hors guillemets doubles, des apostrophes `'...'` regroupent un texte pris
+///   tel quel, comme dans un shell : `'\t'` donne les deux caractères `\` et `t`.
 /// - Si espace ou tabulation et hors guillemets, finalise le mot.
 /// - Gère les échappements `\`.
 /// - Ajoute le mot final à la liste.
@@ -130,6 +218,8 @@ pub fn wc() {
 /// ```rust
 /// let args = wc_rs::parse_command_line(r#"wc -l "fichier avec espaces.txt""#);
 /// assert_eq!(args, vec!["wc", "-l", "fichier avec espaces.txt"]);
+/// let args = wc_rs::parse_command_line(r"wc --count-char '\t' 'mes notes.tsv'");
+/// assert_eq!(args, vec!["wc", "--count-char", r"\t", "mes notes.tsv"]);
 /// ```
 fn parse_command_line(input: &str) -> Vec<String> {
     let mut result = Vec::new();
@@ -142,6 +232,10 @@ fn parse_command_line(input: &str) -> Vec<String> {
             '"' => {
                 in_quotes = !in_quotes;
             },
+            '\'' if !in_quotes => {
+                // Texte littéral jusqu'à l'apostrophe fermante (ou la fin de la ligne)
+                current.extend(chars.by_ref().take_while(|&c| c != '\''));
+            },
             ' ' | '\t' => {
                 if in_quotes {
                     current.push(ch);
@@ -175,193 +269,597 @@ fn parse_command_line(input: &str) -> Vec<String> {
     result
 }
 
-/// Interprète les séquences d'échappement dans une chaîne.
-///
-/// # Algorithme
-/// - Parcours de la chaîne caractère par caractère.
-/// - Si `\`, détermine la séquence (`n`, `t`, `r`, `xFF`, etc...).
-/// - Remplace par le caractère correspondant.
-///
-/// # Arguments
-/// * `input` - Chaîne avec séquences d'échappement.
-///
-/// # Retour
-/// Chaîne transformée.
+/// Commande `wc` utilisable dans un pipeline (`cat fichier | wc -l`).
 ///
-/// # Exemple
-/// ```rust
-/// let text = wc_rs::unescape("Hello\\nWorld");
-/// assert_eq!(text, "Hello\nWorld");
-/// ```
-fn unescape(input: &str) -> String {
-    let mut output = String::new();
-    let mut chars = input.chars().peekable();
+/// Sans fichier, compte le texte reçu de la commande précédente.
+pub struct WcCommand {
+    args: Vec<String>,
+}
 
-    while let Some(c) = chars.next() {
-        if c == '\\' {
-            match chars.peek() {
-                Some('n') => { output.push('\n'); chars.next(); }
-                Some('t') => { output.push('\t'); chars.next(); }
-                Some('r') => { output.push('\r'); chars.next(); }
-                Some('v') => { output.push('\u{000B}'); chars.next(); }
-                Some('a') => { output.push('\u{0007}'); chars.next(); }
-                Some('x') => {
-                    chars.next();
-                    let hex: String = chars.by_ref().take(2).collect();
-                    if let Ok(val) = u8::from_str_radix(&hex, 16) {
-                        output.push(val as char);
-                    }
-                }
-                Some('\\') => { output.push('\\'); chars.next(); }
-                _ => output.push(c),
-            }
-        } else {
-            output.push(c);
-        }
+impl WcCommand {
+    pub fn new(args: &[String]) -> Self {
+        WcCommand { args: args.to_vec() }
     }
+}
 
-    output
+impl pipeline::Command for WcCommand {
+    fn run(&self, input: Option<&[u8]>, _terminal: bool) -> io::Result<Vec<u8>> {
+        let args: Vec<&str> = self.args.iter().map(|s| s.as_str()).collect();
+        let mut output = Vec::new();
+        process_command(&args, input, &mut output)?;
+        Ok(output)
+    }
 }
 
-/// Traite une commande contenant un pipe (`echo ... | wc ...`).
+/// Traite une commande `wc` avec ses arguments.
 ///
 /// # Algorithme
-/// 1. Sépare par `|`.
-/// 2. Vérifie la partie echo.
-/// 3. Extrait le texte après echo.
-/// 4. Vérifie la partie wc.
-/// 5. Parse les options.
-/// 6. Applique le comptage sur le texte.
+/// - Gère `--help` et `--version`.
+/// - Parse les options et les fichiers.
+/// - Ajoute les fichiers listés par `--files0-from` / `--files-from`.
+/// - Avec `-r`, remplace chaque dossier par les fichiers qu'il contient (voir [`expand_directories`]).
+/// - Traite le(s) fichier(s), ou `input` (sortie de la commande précédente d'un pipeline).
+/// - Un fichier illisible (droits insuffisants, introuvable...) est signalé puis ignoré :
+///   les suivants sont comptés, et une erreur est retournée à la fin.
 ///
 /// # Arguments
-/// * `input` - Ligne de commande avec pipe.
+/// * `args` - Arguments de la commande.
+/// * `input` - Texte reçu d'un pipeline, ou `None`.
+/// * `out` - Destination de l'affichage (sortie standard ou tampon du pipeline).
 ///
 /// # Retour
 /// `io::Result<()>` indiquant succès ou erreur.
 ///
 /// # Exemple
 /// ```no_run
-/// process_piped_command(r#"echo "Hello World" | wc -w"#)?;
-/// // Affiche : 2
+/// process_command(&["-l", "fichier.txt"], None, &mut io::stdout())?;
+/// // Affiche : 42 fichier.txt
 /// ```
-fn process_piped_command(input: &str) -> io::Result<()> {
-    let pipe_parts: Vec<&str> = input.split('|').map(|s| s.trim()).collect();
-    
-    if pipe_parts.len() != 2 {
+fn process_command(args: &[&str], input: Option<&[u8]>, out: &mut impl Write) -> io::Result<()> {
+    // Gérer --help
+    if args.contains(&"--help") {
+        return display_help(out);
+    }
+
+    // Gérer --version
+    if args.contains(&"--version") {
+        display_version();
+        return Ok(());
+    }
+
+    // Parser les options et les fichiers
+    let (options, mut file_paths) = parse_arguments(args)?;
+
+    // Chaque ligne de --ndjson doit rester un objet JSON valide
+    if options.ndjson && (options.format.is_some() || options.show_histogram || options.show_line_stats || options.digits) {
         return Err(io::Error::new(
             io::ErrorKind::InvalidInput,
-            "Format invalide : utilisez 'echo [texte] | wc [options]'"
+            "L'option --ndjson ne peut pas être combinée avec --format, --histogram, --line-stats ou --digits"
         ));
     }
 
-    let echo_part = pipe_parts[0];
-    let wc_part = pipe_parts[1];
+    // --treemap répartit les lignes des dossiers parcourus par -r
+    if options.treemap && !options.recursive {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "L'option --treemap nécessite -r (--recursive)"
+        ));
+    }
+    if options.treemap && (options.ndjson || options.by_extension) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "L'option --treemap ne peut pas être combinée avec --ndjson ou --by-extension"
+        ));
+    }
 
-    // Parser la partie echo avec gestion des guillemets
-    let echo_parsed = parse_command_line(echo_part);
-    if echo_parsed.is_empty() || echo_parsed[0] != "echo" {
+    // --compare affiche deux fichiers côte à côte, sans ligne par fichier ni total
+    if options.compare
+        && (options.ndjson || options.by_extension || options.treemap || options.recursive || options.format.is_some())
+    {
         return Err(io::Error::new(
             io::ErrorKind::InvalidInput,
-            "La commande doit commencer par 'echo'"
+            "L'option --compare ne peut pas être combinée avec --ndjson, --by-extension, --treemap, -r ou --format"
         ));
     }
 
-    // Extraire le texte après echo (tout sauf le premier mot "echo")
-    let stdin_text = echo_parsed[1..].join(" ");
+    // --archive affiche une ligne par membre : les vues qui regroupent les fichiers
+    // liraient l'archive comme un fichier ordinaire
+    if options.archive && (options.compare || options.by_extension || options.treemap) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "L'option --archive ne peut pas être combinée avec --compare, --by-extension ou --treemap"
+        ));
+    }
 
-    // Parser la partie wc avec gestion des guillemets
-    let wc_parsed = parse_command_line(wc_part);
-    if wc_parsed.is_empty() || wc_parsed[0] != "wc" {
+    // -i ne s'applique qu'au motif de --token
+    if options.ignore_case && options.token.is_none() {
         return Err(io::Error::new(
             io::ErrorKind::InvalidInput,
-            "Après le pipe, la commande doit être 'wc [options]'"
+            "L'option -i (--ignore-case) nécessite --token"
         ));
     }
 
-    // Parser les options de wc (tout sauf le premier mot "wc")
-    let wc_args: Vec<&str> = wc_parsed[1..].iter().map(|s| s.as_str()).collect();
-    let (options, _) = parse_arguments(&wc_args)?;
+    // Ajouter les fichiers listés dans --files0-from (séparés par \0) et --files-from (un par ligne)
+    if !options.lists.is_empty() {
+        file_paths = file_list::collect(&file_paths, &options.lists, input)?
+            .iter()
+            .map(|file| file.to_string_lossy().into_owned())
+            .collect();
+    }
+    // Une liste lue sur stdin n'est pas un texte à compter
+    let input = if options.lists.reads_stdin() { None } else { input };
 
-    // Traiter le stdin avec les options
-    let text_unescape = unescape(&stdin_text);
-    process_stdin(&text_unescape, &options)?;
+    // Avec -r, compter les fichiers des dossiers plutôt que de les refuser
+    if options.recursive {
+        file_paths = expand_directories(&file_paths);
+    }
 
-    Ok(())
-}
+    // Sans fichier, compter le texte reçu du pipeline
+    if file_paths.is_empty()
+        && let Some(input) = input
+    {
+        return process_stdin(input, &options, out);
+    }
 
-/// Traite une commande `wc` avec ses arguments.
-///
-/// # Algorithme
-/// - Gère `--help` et `--version`.
-/// - Parse les options et les fichiers.
-/// - Traite le(s) fichier(s) ou stdin.
-///
-/// # Arguments
-/// * `args` - Arguments de la commande.
-///
-/// # Retour
-/// `io::Result<()>` indiquant succès ou erreur.
-///
-/// # Exemple
-/// ```no_run
-/// process_command(&["-l", "fichier.txt"])?;
-/// // Affiche : 42 fichier.txt
-/// ```
-fn process_command(args: &[&str]) -> io::Result<()> {
-    // Gérer --help
-    if args.contains(&"--help") {
-        display_help();
+    // Si aucun fichier spécifié, erreur
+    if file_paths.is_empty() {
+        writeln!(out, "Erreur : Aucun fichier spécifié")?;
+        writeln!(out, "Utilisez 'wc --help' pour plus d'informations")?;
         return Ok(());
     }
 
-    // Gérer --version
-    if args.contains(&"--version") {
-        display_version();
-        return Ok(());
+    // Comparer deux fichiers plutôt qu'afficher chaque fichier
+    if options.compare {
+        return compare_files(&file_paths, &options, out);
     }
 
-    // Parser les options et les fichiers
-    let (options, file_paths) = parse_arguments(args)?;
+    // Regrouper par extension plutôt qu'afficher chaque fichier
+    if options.by_extension {
+        return display_by_extension(&file_paths, &options, out);
+    }
 
-    // Si aucun fichier spécifié, erreur
-    if file_paths.is_empty() {
-        println!("Erreur : Aucun fichier spécifié");
-        println!("Utilisez 'wc --help' pour plus d'informations");
-        return Ok(());
+    // Répartir les lignes par dossier plutôt qu'afficher chaque fichier
+    if options.treemap {
+        return display_treemap(&file_paths, &options, out);
     }
 
-    // Traiter les fichiers
+    // Traiter les fichiers (une archive compte pour plusieurs fichiers)
     let mut total = CountResult::default();
-    let multiple_files = file_paths.len() > 1;
+    let multiple_files = file_paths.len() > 1 || options.archive;
+    let mut failures = 0;
 
     for path in &file_paths {
-        match process_file(path) {
+        if options.archive && is_tar_archive(path) {
+            if let Err(e) = process_archive(path, &options, &mut total, out) {
+                report_error(path, &e);
+                failures += 1;
+            }
+            continue;
+        }
+
+        match process_file(path, &options) {
             Ok(result) => {
-                display_result(&result, &options, Some(path));
+                display_file_result(&result, &options, path, out)?;
                 
                 // Accumuler pour le total
                 if multiple_files {
-                    total.lines += result.lines;
-                    total.words += result.words;
-                    total.chars += result.chars;
-                    total.bytes += result.bytes;
-                    total.max_line_length = total.max_line_length.max(result.max_line_length);
+                    accumulate(&mut total, &result);
                 }
             },
-            Err(e) => {
+            Err(e) if e.kind() == io::ErrorKind::IsADirectory => {
+                // Comme coreutils : message explicite puis ligne à zéro
                 eprintln!("wc: {}: {}", path, e);
+                display_result(&CountResult::default(), &options, Some(path), out)?;
+                failures += 1;
+            },
+            Err(e) => {
+                report_error(path, &e);
+                failures += 1;
             }
         }
     }
 
     // Afficher le total si plusieurs fichiers
     if multiple_files {
-        display_result(&total, &options, Some("total"));
+        display_result(&total, &options, Some("total"), out)?;
+        if options.digits {
+            display_digits(&total, out)?;
+        }
+    }
+
+    partial_failure(failures)
+}
+
+/// Affiche la ligne d'un fichier, suivie de l'histogramme (`--histogram`), des
+/// lignes extrêmes (`--line-stats`) et des premiers chiffres (`--digits`) demandés.
+fn display_file_result(result: &CountResult, options: &Options, name: &str, out: &mut impl Write) -> io::Result<()> {
+    display_result(result, options, Some(name), out)?;
+    if options.show_histogram {
+        display_histogram(result, options, out)?;
+    }
+    if options.show_line_stats {
+        display_line_stats(result, out)?;
+    }
+    if options.digits {
+        display_digits(result, out)?;
+    }
+    Ok(())
+}
+
+/// Indique si un chemin désigne une archive tar, d'après son extension (`.tar`).
+fn is_tar_archive(path: &str) -> bool {
+    Path::new(path).extension().is_some_and(|extension| extension.eq_ignore_ascii_case("tar"))
+}
+
+/// Compte séparément chaque fichier d'une archive tar (`--archive`).
+///
+/// # Algorithme
+/// - Lit l'archive au fil de l'eau avec [`tar::for_each_member`] : les dossiers,
+///   liens et fichiers spéciaux sont ignorés.
+/// - Compte chaque fichier avec [`count_reader`] et affiche sa ligne, nommée
+///   `archive.tar:chemin/dans/archive`, puis l'ajoute au total.
+///
+/// Une archive invalide ou tronquée est signalée après les membres déjà affichés.
+///
+/// # Exemple
+/// ```text
+/// wc -l --archive sources.tar
+///   12 sources.tar:src/main.rs
+///   40 sources.tar:src/lib.rs
+///   52 total
+/// ```
+fn process_archive(path: &str, options: &Options, total: &mut CountResult, out: &mut impl Write) -> io::Result<()> {
+    let archive = BufReader::new(File::open(path::resolve(path)?)?);
+
+    tar::for_each_member(archive, |name, member| {
+        let result = count_reader(member, options)?;
+        display_file_result(&result, options, &format!("{}:{}", path, name), out)?;
+        accumulate(total, &result);
+        Ok(())
+    })
+}
+
+/// Affiche l'erreur d'un fichier qui n'a pas pu être compté.
+///
+/// Un manque de droits est affiché comme coreutils (`wc: secret.txt: Permission denied`),
+/// sans le code d'erreur du système, pour être repéré facilement au milieu des résultats.
+fn report_error(path: &str, error: &io::Error) {
+    eprintln!("{}", error_message(path, error));
+}
+
+/// Message de [`report_error`] pour un fichier qui n'a pas pu être compté.
+fn error_message(path: &str, error: &io::Error) -> String {
+    if error.kind() == io::ErrorKind::PermissionDenied {
+        format!("wc: {}: Permission denied", path)
+    } else {
+        format!("wc: {}: {}", path, error)
+    }
+}
+
+/// Retourne une erreur récapitulative si des fichiers n'ont pas pu être comptés.
+fn partial_failure(failures: usize) -> io::Result<()> {
+    match failures {
+        0 => Ok(()),
+        1 => Err(io::Error::other("1 fichier n'a pas pu être compté")),
+        n => Err(io::Error::other(format!("{} fichiers n'ont pas pu être comptés", n))),
+    }
+}
+
+/// Affiche les totaux regroupés par extension (`--by-extension`).
+///
+/// # Algorithme
+/// - Compte chaque fichier et l'ajoute au groupe de son extension en minuscules
+///   (`.rs`, `.txt`...) ; un fichier sans extension va dans `(none)`.
+/// - Affiche une ligne par extension, dans l'ordre alphabétique, puis le total général.
+/// - Une erreur sur un fichier est affichée et le fichier est ignoré
+///   (voir [`report_error`]) ; une erreur est retournée à la fin.
+///
+/// # Arguments
+/// * `file_paths` - Fichiers à compter.
+/// * `options` - Options.
+/// * `out` - Destination de l'affichage.
+///
+/// # Exemple
+/// ```no_run
+/// display_by_extension(&["a.rs".into(), "b.RS".into(), "notes".into()], &options, &mut io::stdout())?;
+/// // Affiche (exemple) :
+/// //      42     120     900 .rs
+/// //       3      10      50 (none)
+/// //      45     130     950 total
+/// ```
+fn display_by_extension(file_paths: &[String], options: &Options, out: &mut impl Write) -> io::Result<()> {
+    let mut groups: BTreeMap<String, CountResult> = BTreeMap::new();
+    let mut total = CountResult::default();
+    let mut failures = 0;
+
+    for path in file_paths {
+        match process_file(path, options) {
+            Ok(result) => {
+                let extension = Path::new(path)
+                    .extension()
+                    .map(|ext| format!(".{}", ext.to_string_lossy().to_lowercase()))
+                    .unwrap_or_else(|| "(none)".to_string());
+                accumulate(groups.entry(extension).or_default(), &result);
+                accumulate(&mut total, &result);
+            }
+            Err(e) => {
+                report_error(path, &e);
+                failures += 1;
+            }
+        }
+    }
+
+    for (extension, result) in &groups {
+        display_result(result, options, Some(extension), out)?;
+    }
+    display_result(&total, options, Some("total"), out)?;
+    partial_failure(failures)
+}
+
+/// Compte deux fichiers et affiche leurs compteurs côte à côte (`--compare`).
+///
+/// # Algorithme
+/// - Vérifie qu'il y a exactement deux fichiers.
+/// - Compte chacun avec [`process_file`] ; un fichier illisible ou introuvable
+///   est signalé (voir [`report_error`]) et la comparaison est abandonnée.
+/// - Affiche le résultat avec [`display_comparison`].
+///
+/// # Exemple
+/// ```no_run
+/// compare_files(&["v1.txt".into(), "v2.txt".into()], &options, &mut io::stdout())?;
+/// ```
+fn compare_files(file_paths: &[String], options: &Options, out: &mut impl Write) -> io::Result<()> {
+    let [first, second] = file_paths else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("L'option --compare attend exactement deux fichiers ({} indiqué(s))", file_paths.len())
+        ));
+    };
+
+    let mut results = Vec::with_capacity(2);
+    for path in [first, second] {
+        match process_file(path, options) {
+            Ok(result) => results.push(result),
+            Err(e) => {
+                report_error(path, &e);
+                return Err(io::Error::other(format!("Impossible de comparer : '{}' n'a pas pu être compté", path)));
+            }
+        }
+    }
+
+    display_comparison((first, &results[0]), (second, &results[1]), options, out)
+}
+
+/// Affiche les compteurs demandés de deux fichiers et l'écart du second par rapport
+/// au premier (`--compare`), un compteur par ligne.
+///
+/// Les compteurs et leurs noms sont ceux de [`shown_counters`]. Des compteurs
+/// extrapolés avec `--sample` sont signalés par `(estimation)`.
+///
+/// # Exemple
+/// ```rust
+/// let old = CountResult { lines: 100, words: 700, bytes: 4000, ..Default::default() };
+/// let new = CountResult { lines: 120, words: 690, bytes: 4000, ..Default::default() };
+/// let mut out = Vec::new();
+/// display_comparison(("v1.txt", &old), ("v2.txt", &new), &options, &mut out)?;
+/// assert_eq!(String::from_utf8(out)?, "\
+/// v1.txt vs v2.txt
+///   lines: 100 vs 120 (+20)
+///   words: 700 vs 690 (-10)
+///   bytes: 4000 vs 4000 (=)
+/// ");
+/// ```
+fn display_comparison(
+    (first_name, first): (&str, &CountResult),
+    (second_name, second): (&str, &CountResult),
+    options: &Options,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let estimated = if first.estimated || second.estimated { " (estimation)" } else { "" };
+    writeln!(out, "{} vs {}{}", first_name, second_name, estimated)?;
+
+    let counters = shown_counters(first, options);
+    let label_width = counters.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    for ((name, before), (_, after)) in counters.into_iter().zip(shown_counters(second, options)) {
+        let delta = match after as i128 - before as i128 {
+            0 => "=".to_string(),
+            delta => format!("{:+}", delta),
+        };
+        writeln!(out, "  {:>width$}: {} vs {} ({})", name, before, after, delta, width = label_width)?;
+    }
+
+    Ok(())
+}
+
+/// Nombre de lignes d'un dossier et de ses sous-dossiers (`--treemap`).
+#[derive(Default)]
+struct TreemapNode {
+    /// Lignes de tous les fichiers du dossier, sous-dossiers compris.
+    lines: usize,
+    /// Sous-dossiers, par nom.
+    children: BTreeMap<String, TreemapNode>,
+}
+
+impl TreemapNode {
+    /// Ajoute les lignes d'un fichier à chacun des dossiers de son chemin.
+    ///
+    /// # Exemple
+    /// ```rust
+    /// let mut root = TreemapNode::default();
+    /// root.add("projet/src/main.rs", 30);
+    /// root.add("projet/src/cmd/ls.rs", 60);
+    /// root.add("projet/README.md", 10);
+    /// let projet = &root.children["projet"];
+    /// assert_eq!((root.lines, projet.lines), (100, 100));
+    /// assert_eq!((projet.children["src"].lines, projet.children["src"].children["cmd"].lines), (90, 60));
+    /// ```
+    fn add(&mut self, file_path: &str, lines: usize) {
+        self.lines += lines;
+        let mut node = self;
+        let mut components: Vec<&str> = file_path.split(['/', '\\']).filter(|c| !c.is_empty() && *c != ".").collect();
+        components.pop(); // Le nom du fichier lui-même
+        for component in components {
+            node = node.children.entry(component.to_string()).or_default();
+            node.lines += lines;
+        }
+    }
+}
+
+/// Affiche la part des lignes de chaque dossier et sous-dossier (`--treemap`).
+///
+/// # Algorithme
+/// - Compte chaque fichier et ajoute ses lignes à tous les dossiers de son chemin
+///   (voir [`TreemapNode::add`]), sans garder les résultats par fichier.
+/// - Affiche les dossiers en arbre indenté, les plus gros d'abord, avec leur
+///   pourcentage du total et une barre proportionnelle. La différence entre un
+///   dossier et la somme de ses sous-dossiers correspond à ses propres fichiers.
+/// - Une erreur sur un fichier est affichée et le fichier est ignoré
+///   (voir [`report_error`]) ; une erreur est retournée à la fin.
+///
+/// # Exemple
+/// ```no_run
+/// display_treemap(&expand_directories(&["projet".into()]), &options, &mut io::stdout())?;
+/// // Affiche (exemple) :
+/// //  100.0%      100  ████████████████████  projet/
+/// //   90.0%       90  ██████████████████      src/
+/// //   60.0%       60  ████████████              cmd/
+/// //  100.0%      100  total
+/// ```
+fn display_treemap(file_paths: &[String], options: &Options, out: &mut impl Write) -> io::Result<()> {
+    let mut root = TreemapNode::default();
+    let mut failures = 0;
+
+    for path in file_paths {
+        match process_file(path, options) {
+            Ok(result) => root.add(path, result.lines),
+            Err(e) => {
+                report_error(path, &e);
+                failures += 1;
+            }
+        }
+    }
+
+    write_treemap_children(&root, root.lines, 0, out)?;
+    writeln!(out, "{:>6.1}%  {:>7}  total", 100.0, root.lines)?;
+    partial_failure(failures)
+}
+
+/// Affiche les sous-dossiers de `node`, triés par nombre de lignes décroissant, puis les leurs.
+fn write_treemap_children(node: &TreemapNode, total: usize, depth: usize, out: &mut impl Write) -> io::Result<()> {
+    let mut children: Vec<(&String, &TreemapNode)> = node.children.iter().collect();
+    children.sort_by(|a, b| b.1.lines.cmp(&a.1.lines).then_with(|| a.0.cmp(b.0)));
+
+    for (name, child) in children {
+        let share = if total == 0 { 0.0 } else { child.lines as f64 / total as f64 };
+        let bar = "█".repeat((share * TREEMAP_BAR_WIDTH as f64).round() as usize);
+        writeln!(
+            out,
+            "{:>6.1}%  {:>7}  {:<width$}  {}{}/",
+            share * 100.0,
+            child.lines,
+            bar,
+            "  ".repeat(depth),
+            name,
+            width = TREEMAP_BAR_WIDTH
+        )?;
+        write_treemap_children(child, total, depth + 1, out)?;
     }
 
     Ok(())
 }
 
+/// Ajoute les compteurs de `result` à `total` (la longueur maximale est le maximum des deux).
+fn accumulate(total: &mut CountResult, result: &CountResult) {
+    total.lines += result.lines;
+    total.words += result.words;
+    total.chars += result.chars;
+    total.bytes += result.bytes;
+    total.max_line_length = total.max_line_length.max(result.max_line_length);
+    total.char_occurrences += result.char_occurrences;
+    total.sentences += result.sentences;
+    total.paragraphs += result.paragraphs;
+    total.records += result.records;
+    total.token_matches += result.token_matches;
+    for (total_count, count) in total.leading_digits.iter_mut().zip(result.leading_digits) {
+        *total_count += count;
+    }
+    total.estimated |= result.estimated;
+}
+
+/// Indique si un mot correspond au motif de `--token`.
+///
+/// Un motif contenant `*`, `?` ou `[...]` doit correspondre au mot entier
+/// ([`glob::matches`]) ; sinon, il suffit que le mot contienne le motif.
+/// Avec `ignore_case`, le mot est mis en minuscules (le motif l'est déjà).
+///
+/// # Exemple
+/// ```rust
+/// assert!(token_matches("todo", "TODO:", true));
+/// assert!(token_matches("err*", "error", false));
+/// assert!(!token_matches("err*", "stderr", false));
+/// ```
+fn token_matches(pattern: &str, word: &str, ignore_case: bool) -> bool {
+    let lowered;
+    let word = if ignore_case {
+        lowered = word.to_lowercase();
+        &lowered
+    } else {
+        word
+    };
+
+    if glob::is_pattern(pattern) {
+        glob::matches(pattern, word)
+    } else {
+        word.contains(pattern)
+    }
+}
+
+/// Compte les phrases d'une ligne (`--prose`).
+///
+/// # Heuristique
+/// - Une phrase se termine par `.`, `!` ou `?` suivi d'un blanc, d'un guillemet ou d'une
+///   parenthèse fermante, ou de la fin de la ligne.
+/// - Une suite de ponctuations (`...`, `?!`) ne compte qu'une fois.
+/// - Un point après une seule lettre (initiale : `J. Dupont`) ou après une abréviation
+///   courante (`etc.`, `ex.`, `M.`, `Dr.`...) ne termine pas la phrase.
+/// - Un point au milieu d'un mot (`3.14`, `fichier.txt`) est ignoré.
+///
+/// Une phrase qui s'étend sur plusieurs lignes est comptée sur la ligne où elle se termine.
+///
+/// # Exemple
+/// ```rust
+/// assert_eq!(count_sentences("Bonjour. Ça va ? Oui, etc. Voir J. Dupont..."), 3);
+/// ```
+fn count_sentences(line: &str) -> usize {
+    let chars: Vec<char> = line.chars().collect();
+    let mut sentences = 0;
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !matches!(c, '.' | '!' | '?') {
+            continue;
+        }
+
+        // Seule la dernière ponctuation d'une suite compte, et elle doit terminer un mot
+        let next = chars.get(i + 1).copied();
+        if next.is_some_and(|n| !n.is_whitespace() && !matches!(n, '"' | '\'' | ')' | ']' | '»')) {
+            continue;
+        }
+
+        if c == '.' {
+            let start = chars[..i].iter().rposition(|ch| ch.is_whitespace() || *ch == '(').map_or(0, |p| p + 1);
+            let word: String = chars[start..i].iter().collect::<String>().to_lowercase();
+            let is_initial = word.chars().count() == 1 && word.chars().all(char::is_alphabetic);
+            if is_initial || ABBREVIATIONS.contains(&word.trim_end_matches('.')) {
+                continue;
+            }
+        }
+
+        sentences += 1;
+    }
+
+    sentences
+}
+
 /// Parse les arguments pour extraire options et chemins de fichiers.
 ///
 /// # Algorithme
@@ -388,9 +886,88 @@ fn parse_arguments(args: &[&str]) -> io::Result<(Options, Vec<String>)> {
     while i < args.len() {
         let arg = args[i];
         
-        if arg.starts_with("--files0-from=") {
-            let file = arg.trim_start_matches("--files0-from=");
-            options.files0_from = Some(file.to_string());
+        // --files-from / --files0-from : la liste est la valeur après '=' ou l'argument suivant
+        let listed = file_list::parse_option(&mut options.lists, arg, || {
+            i += 1;
+            args.get(i).copied()
+        })
+        .map_err(|option| io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("L'option {} attend un fichier (ou - pour stdin)", option)
+        ))?;
+        if listed {
+            i += 1;
+            continue;
+        }
+
+        if let Some(template) = arg.strip_prefix("--format=") {
+            options.format = Some(template.to_string());
+        } else if arg == "--sample" {
+            // La taille de l'échantillon (en octets) est l'argument suivant
+            i += 1;
+            match args.get(i).and_then(|n| n.parse::<u64>().ok()) {
+                Some(n) if n > 0 => options.sample = Some(n),
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "L'option --sample attend un nombre d'octets strictement positif"
+                    ));
+                }
+            }
+        } else if arg == "--bucket" {
+            // La taille de tranche est l'argument suivant
+            i += 1;
+            match args.get(i).and_then(|n| n.parse::<usize>().ok()) {
+                Some(n) if n > 0 => options.bucket_size = Some(n),
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "L'option --bucket attend un nombre strictement positif"
+                    ));
+                }
+            }
+        } else if arg == "--count-char" {
+            // Le caractère est l'argument suivant (séquences comme \t acceptées)
+            i += 1;
+            let value = args.get(i).map(|c| escape::unescape(c)).unwrap_or_default();
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => options.count_char = Some(c),
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "L'option --count-char attend un seul caractère"
+                    ));
+                }
+            }
+        } else if arg == "--token" {
+            // Le motif est l'argument suivant
+            i += 1;
+            match args.get(i) {
+                Some(pattern) if !pattern.is_empty() && !pattern.contains(char::is_whitespace) => {
+                    options.token = Some(pattern.to_string());
+                }
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "L'option --token attend un motif sans espace (ex: TODO ou 'err*')"
+                    ));
+                }
+            }
+        } else if arg == "--record-separator" {
+            // La ligne séparatrice est l'argument suivant (séquences comme \t acceptées)
+            i += 1;
+            match args.get(i).map(|separator| escape::unescape(separator)) {
+                Some(separator) if !separator.is_empty() && !separator.contains('\n') => {
+                    options.record_separator = Some(separator);
+                }
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "L'option --record-separator attend une ligne séparatrice non vide (ex: ---)"
+                    ));
+                }
+            }
         } else if arg.starts_with('-') {
             parse_option(arg, &mut options)?;
         } else {
@@ -437,6 +1014,17 @@ fn parse_option(opt: &str, options: &mut Options) -> io::Result<()> {
         "-l" | "--lines" => options.show_lines = true,
         "-w" | "--words" => options.show_words = true,
         "-L" | "--max-line-length" => options.show_max_line_length = true,
+        "--histogram" => options.show_histogram = true,
+        "--line-stats" => options.show_line_stats = true,
+        "--by-extension" => options.by_extension = true,
+        "--prose" => options.prose = true,
+        "-r" | "--recursive" => options.recursive = true,
+        "--ndjson" => options.ndjson = true,
+        "-i" | "--ignore-case" => options.ignore_case = true,
+        "--digits" => options.digits = true,
+        "--treemap" => options.treemap = true,
+        "--compare" => options.compare = true,
+        "--archive" => options.archive = true,
         _ => {
             // Gérer les options combinées (ex: -lwc)
             if opt.starts_with('-') && opt.len() > 2 && !opt.starts_with("--") {
@@ -444,7 +1032,7 @@ fn parse_option(opt: &str, options: &mut Options) -> io::Result<()> {
                     let single_opt = format!("-{}", c);
                     parse_option(&single_opt, options)?;
                 }
-            } else if !opt.starts_with("--files0-from=") {
+            } else {
                 return Err(io::Error::new(
                     io::ErrorKind::InvalidInput,
                     format!("Option invalide : {}", opt)
@@ -455,158 +1043,356 @@ fn parse_option(opt: &str, options: &mut Options) -> io::Result<()> {
     Ok(())
 }
 
-/// Traite un fichier ligne par ligne.
+/// Remplace chaque dossier de la liste par les fichiers qu'il contient (`-r`).
 ///
 /// # Algorithme
-/// - Vérifie l'existence du fichier.
-/// - Lit le fichier ligne par ligne avec BufRead.
-/// - Compte les lignes, mots, caractères et octets au fur et à mesure.
-/// - Trouve la ligne la plus longue.
-/// - Retourne les résultats.
+/// - Un chemin qui n'est pas un dossier est gardé tel quel.
+/// - Pour un dossier, lit le fichier `.wcignore` placé à sa racine (s'il existe)
+///   avec [`glob::IgnoreList`], puis parcourt récursivement son contenu avec
+///   [`collect_files`] en sautant les entrées exclues.
 ///
 /// # Arguments
-/// * `file_path` - Chemin vers le fichier.
+/// * `file_paths` - Chemins saisis par l'utilisateur.
 ///
 /// # Retour
-/// `io::Result<CountResult>`.
-///
+/// Les chemins des fichiers à compter, préfixés par le dossier saisi (ex. `src/main.rs`).
+fn expand_directories(file_paths: &[String]) -> Vec<String> {
+    let mut expanded = Vec::new();
+
+    for file_path in file_paths {
+        let Ok(root) = path::resolve(file_path) else {
+            expanded.push(file_path.clone());
+            continue;
+        };
+        if !fs::symlink_metadata(&root).is_ok_and(|metadata| metadata.is_dir()) {
+            expanded.push(file_path.clone());
+            continue;
+        }
+
+        let ignore = fs::read_to_string(root.join(IGNORE_FILE))
+            .map(|content| glob::IgnoreList::parse(&content))
+            .unwrap_or_default();
+        let display_root = file_path.trim_end_matches(['/', '\\']);
+        collect_files(&root, display_root, "", &ignore, &mut expanded);
+    }
+
+    expanded
+}
+
+/// Ajoute à `files` les fichiers d'un dossier et de ses sous-dossiers, triés par nom.
+///
+/// Les liens symboliques vers des dossiers ne sont pas suivis (ce qui évite les boucles).
+/// Un dossier illisible est signalé sur stderr puis ignoré.
+///
+/// # Arguments
+/// * `dir` - Dossier à parcourir.
+/// * `display_root` - Dossier tel que saisi, utilisé pour afficher les chemins.
+/// * `relative` - Chemin de `dir` relatif à la racine (vide pour la racine).
+/// * `ignore` - Motifs de `.wcignore`, comparés aux chemins relatifs.
+/// * `files` - Liste complétée.
+fn collect_files(dir: &Path, display_root: &str, relative: &str, ignore: &glob::IgnoreList, files: &mut Vec<String>) {
+    let mut entries: Vec<fs::DirEntry> = match fs::read_dir(dir) {
+        Ok(entries) => entries.flatten().collect(),
+        Err(e) => {
+            eprintln!("wc: {}: {}", dir.display(), e);
+            return;
+        }
+    };
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let entry_relative = if relative.is_empty() { name } else { format!("{}/{}", relative, name) };
+        let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+
+        if ignore.is_ignored(&entry_relative, is_dir) {
+            continue;
+        }
+        if is_dir {
+            collect_files(&entry.path(), display_root, &entry_relative, ignore, files);
+        } else if !entry.path().is_dir() {
+            files.push(format!("{}/{}", display_root, entry_relative));
+        }
+    }
+}
+
+/// Traite un fichier avec [`count_reader`].
+///
+/// # Algorithme
+/// - Vérifie l'existence du fichier.
+/// - Refuse les dossiers avec une erreur `IsADirectory`.
+/// - Compte le contenu du fichier avec [`count_reader`].
+/// - Avec `--sample N`, si le fichier dépasse `N` octets, ne compte que ses `N` premiers
+///   octets et extrapole le résultat à sa taille réelle avec [`extrapolate`].
+///
+/// # Arguments
+/// * `file_path` - Chemin vers le fichier.
+/// * `options` - Options.
+///
+/// # Retour
+/// `io::Result<CountResult>`.
+///
 /// # Exemple
 /// ```no_run
-/// let result = process_file("fichier.txt")?;
+/// let result = process_file("fichier.txt", &Options::default())?;
 /// println!("Lignes: {}", result.lines);
 /// ```
-fn process_file(file_path: &str) -> io::Result<CountResult> {
+fn process_file(file_path: &str, options: &Options) -> io::Result<CountResult> {
+    // Résoudre le chemin (gérer ., .. et ~)
+    let resolved = path::resolve(file_path)?;
+
     // Vérifier si le fichier existe
-    if !Path::new(file_path).exists() {
+    if !resolved.exists() {
         return Err(io::Error::new(
             io::ErrorKind::NotFound,
-            format!("Aucun fichier ou dossier de ce type")
+            "Aucun fichier ou dossier de ce type"
         ));
     }
 
-    // Ouvrir le fichier
-    let file = File::open(file_path)?;
-    let reader = BufReader::new(file);
-    
-    let mut result = CountResult::default();
-    
-    // Traiter ligne par ligne
-    for line_result in reader.lines() {
-        let line = line_result?;
-        
-        // Compter les lignes
-        result.lines += 1;
-        
-        // Compter les mots dans la ligne
-        result.words += line.split_whitespace().count();
-        
-        // Compter les caractères dans la ligne (+ 1 pour le \n)
-        result.chars += line.chars().count() + 1;
-        
-        // Compter les octets dans la ligne (+ 1 pour le \n)
-        result.bytes += line.as_bytes().len() + 1;
-        
-        // Trouver la longueur maximale
-        let line_length = line.chars().count();
-        if line_length > result.max_line_length {
-            result.max_line_length = line_length;
+    // Un dossier ne peut pas être compté (File::open réussirait mais la lecture échouerait)
+    if resolved.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::IsADirectory,
+            "Est un dossier"
+        ));
+    }
+
+    let file = File::open(&resolved)?;
+    if let Some(sample) = options.sample {
+        let size = file.metadata()?.len();
+        if size > sample {
+            let result = count_reader(file.take(sample), options)?;
+            return Ok(extrapolate(&result, size));
         }
     }
 
-    Ok(result)
+    count_reader(file, options)
 }
 
-/// Traite le texte provenant de stdin.
+/// Extrapole les compteurs d'un échantillon (`--sample`) à la taille réelle du fichier.
 ///
 /// # Algorithme
-/// - Compte les statistiques du texte.
-/// - Affiche le résultat.
+/// - Multiplie lignes, mots, caractères et autres occurrences par le rapport
+///   `taille du fichier / octets lus`, en arrondissant.
+/// - Le nombre d'octets devient la taille exacte du fichier.
+/// - La longueur maximale, l'histogramme et les lignes extrêmes restent ceux de l'échantillon.
+/// - Marque le résultat comme estimé ([`CountResult::estimated`]).
 ///
 /// # Arguments
-/// * `text` - Texte à analyser.
-/// * `options` - Options.
+/// * `sample` - Compteurs de l'échantillon (`sample.bytes` octets lus).
+/// * `size` - Taille réelle du fichier, en octets.
 ///
-/// # Retour
-/// `io::Result<()>`.
-fn process_stdin(text: &str, options: &Options) -> io::Result<()> {
-    let result = count_content(text);
-    display_result(&result, options, None);
-    Ok(())
+/// # Exemple
+/// ```rust
+/// let sample = CountResult { lines: 10, words: 40, chars: 1000, bytes: 1000, ..Default::default() };
+/// let estimate = extrapolate(&sample, 5000);
+/// assert_eq!((estimate.lines, estimate.words, estimate.bytes), (50, 200, 5000));
+/// assert!(estimate.estimated);
+/// ```
+fn extrapolate(sample: &CountResult, size: u64) -> CountResult {
+    if sample.bytes == 0 {
+        return CountResult { estimated: true, ..sample.clone() };
+    }
+
+    let ratio = size as f64 / sample.bytes as f64;
+    let scale = |count: usize| (count as f64 * ratio).round() as usize;
+    CountResult {
+        lines: scale(sample.lines),
+        words: scale(sample.words),
+        chars: scale(sample.chars),
+        bytes: size as usize,
+        char_occurrences: scale(sample.char_occurrences),
+        sentences: scale(sample.sentences),
+        paragraphs: scale(sample.paragraphs),
+        records: scale(sample.records),
+        token_matches: scale(sample.token_matches),
+        estimated: true,
+        ..sample.clone()
+    }
 }
 
-/// Compte les lignes, mots, caractères et longueur maximale dans un contenu.
+/// Compte les lignes, mots, caractères et octets d'une source quelconque.
 ///
 /// # Algorithme
-/// 1. Compte les lignes en parcourant le texte.
-/// 2. Compte les mots en séparant par les espaces blancs.
-/// 3. Compte les caractères Unicode.
-/// 4. Trouve la ligne la plus longue.
+/// - Lit la source ligne par ligne avec [`lines::lines_with_terminators`].
+/// - Compte les octets exacts, terminateurs compris.
+/// - Décode chaque ligne en UTF-8 pour compter caractères et mots
+///   (une séquence invalide compte pour un caractère).
+/// - Compte une ligne par `\n`, comme `wc` : une dernière ligne sans
+///   terminateur est comptée dans les mots et caractères mais pas dans les lignes.
+/// - Trouve la ligne la plus longue (sans `\r\n`) et, avec `--histogram`,
+///   la distribution des longueurs.
+/// - Avec `--count-char`, compte les occurrences du caractère demandé.
+/// - Avec `--token`, compte les mots qui correspondent au motif (voir [`token_matches`]).
+/// - Avec `--digits`, relève le premier chiffre des nombres (voir [`add_leading_digits`]).
+/// - Avec `--record-separator`, compte les enregistrements : un enregistrement commence
+///   à la première ligne non blanche qui suit le début du texte ou une ligne égale au
+///   séparateur. Des séparateurs consécutifs ou en début et fin de texte ne créent donc
+///   pas d'enregistrement vide.
 ///
 /// # Arguments
-/// * `content` - Texte à analyser.
+/// * `reader` - Source à analyser.
+/// * `options` - Options.
 ///
 /// # Retour
-/// `CountResult` avec toutes les statistiques.
+/// `io::Result<CountResult>` avec toutes les statistiques.
 ///
 /// # Exemple
 /// ```rust
-/// use wc_rs::count_content;
-/// let result = count_content("Hello World\nBonjour");
-/// assert_eq!(result.lines, 2);
-/// assert_eq!(result.words, 3);
+/// let result = count_reader(Cursor::new(b"Hello World\nBonjour\n"), &Options::default())?;
+/// assert_eq!((result.lines, result.words, result.bytes), (2, 3, 20));
 /// ```
-fn count_content(content: &str) -> CountResult {
+pub fn count_reader<R: Read>(reader: R, options: &Options) -> io::Result<CountResult> {
     let mut result = CountResult::default();
+    let mut line_number = 0;
+    let mut in_paragraph = false;
+    let mut in_record = false;
+    // Avec -i, le motif est mis en minuscules une seule fois
+    let token_pattern = options.token.as_ref().map(|pattern| {
+        if options.ignore_case { pattern.to_lowercase() } else { pattern.clone() }
+    });
 
-    // Compter les lignes et trouver la ligne la plus longue
-    let lines: Vec<&str> = content.lines().collect();
-    result.lines = lines.len();
-    
-    // Si le contenu se termine par un retour à la ligne, on compte cette ligne vide
-    if content.ends_with('\n') && !content.is_empty() {
-        result.lines += 1;
-    }
+    for line in lines::lines_with_terminators(BufReader::new(reader)) {
+        let (bytes, terminator) = line?;
+        line_number += 1;
 
-    for line in &lines {
-        let line_length = line.chars().count();
-        if line_length > result.max_line_length {
-            result.max_line_length = line_length;
+        // Le terminateur (\n ou \r\n) ne contient que des caractères ASCII, sans mot
+        let text = String::from_utf8_lossy(&bytes);
+        let content: &str = &text;
+        result.bytes += bytes.len() + terminator.len();
+        result.chars += content.chars().count() + terminator.len();
+        for word in content.split_whitespace() {
+            result.words += 1;
+            if let Some(pattern) = &token_pattern
+                && token_matches(pattern, word, options.ignore_case)
+            {
+                result.token_matches += 1;
+            }
+        }
+        if !terminator.is_empty() {
+            result.lines += 1;
+        }
+
+        if let Some(wanted) = options.count_char {
+            let in_terminator = terminator.chars().filter(|&c| c == wanted).count();
+            result.char_occurrences += content.chars().filter(|&c| c == wanted).count() + in_terminator;
         }
-    }
 
-    // Compter les mots (séparés par des espaces blancs)
-    result.words = content.split_whitespace().count();
+        if options.digits {
+            add_leading_digits(content, &mut result.leading_digits);
+        }
 
-    // Compter les caractères Unicode
-    result.chars = content.chars().count();
+        // Trouver la longueur maximale
+        let line_length = content.chars().count();
+        result.max_line_length = result.max_line_length.max(line_length);
 
-    // Compter les octets
-    result.bytes = content.as_bytes().len();
+        // Distribution des longueurs pour l'histogramme
+        if options.show_histogram {
+            *result.line_lengths.entry(line_length).or_insert(0) += 1;
+        }
 
-    result
+        // Phrases et paragraphes : un paragraphe commence à chaque ligne non blanche
+        // qui suit une ligne blanche (ou le début du texte)
+        if options.prose {
+            result.sentences += count_sentences(content);
+            let is_blank = content.trim().is_empty();
+            if !is_blank && !in_paragraph {
+                result.paragraphs += 1;
+            }
+            in_paragraph = !is_blank;
+        }
+
+        // Enregistrements : la ligne séparatrice (sans sa fin de ligne) ferme le précédent
+        if let Some(separator) = &options.record_separator {
+            if content == separator {
+                in_record = false;
+            } else if !in_record && !content.trim().is_empty() {
+                result.records += 1;
+                in_record = true;
+            }
+        }
+
+        // Lignes non vides extrêmes : la comparaison stricte garde la première en cas d'égalité
+        if line_length > 0 {
+            if result.longest_line.is_none_or(|(_, longest)| line_length > longest) {
+                result.longest_line = Some((line_number, line_length));
+            }
+            if result.shortest_line.is_none_or(|(_, shortest)| line_length < shortest) {
+                result.shortest_line = Some((line_number, line_length));
+            }
+        }
+    }
+
+    Ok(result)
 }
 
+/// Traite le texte provenant de stdin.
+///
+/// # Algorithme
+/// - Compte les statistiques du texte avec [`count_reader`].
+/// - Affiche le résultat.
+///
+/// # Arguments
+/// * `input` - Texte à analyser (sortie de la commande précédente).
+/// * `options` - Options.
+/// * `out` - Destination de l'affichage.
+///
+/// # Retour
+/// `io::Result<()>`.
+fn process_stdin(input: &[u8], options: &Options, out: &mut impl Write) -> io::Result<()> {
+    let result = count_reader(input, options)?;
+    display_result(&result, options, None, out)?;
+    if options.show_histogram {
+        display_histogram(&result, options, out)?;
+    }
+    if options.show_line_stats {
+        display_line_stats(&result, out)?;
+    }
+    if options.digits {
+        display_digits(&result, out)?;
+    }
+    Ok(())
+}
 
 /// Affiche les résultats du comptage selon les options.
 ///
 /// # Algorithme
 /// - Affiche chaque compteur demandé dans l'ordre : lignes, mots, caractères/octets, longueur max.
-/// - Ajoute le nom du fichier si fourni.
+/// - Ajoute le nom du fichier si fourni, puis `(estimation)` si les compteurs sont extrapolés.
 ///
 /// # Arguments
 /// * `result` - Résultats du comptage.
 /// * `options` - Options indiquant quoi afficher.
 /// * `filename` - Nom du fichier optionnel.
+/// * `out` - Destination de l'affichage.
 ///
 /// # Exemple
 /// ```rust
 /// let result = CountResult { lines: 10, words: 50, chars: 200, bytes: 200, max_line_length: 80 };
-/// display_result(&result, &options, Some("fichier.txt"));
+/// display_result(&result, &options, Some("fichier.txt"), &mut io::stdout())?;
 /// // Affiche : 10 50 200 fichier.txt
 /// ```
-fn display_result(result: &CountResult, options: &Options, filename: Option<&str>) {
+fn display_result(result: &CountResult, options: &Options, filename: Option<&str>, out: &mut impl Write) -> io::Result<()> {
+    // Un objet JSON par ligne, écrit dès que le fichier est compté
+    if options.ndjson {
+        return writeln!(out, "{}", json_record(result, options, filename));
+    }
+
     let mut output = String::new();
 
+    // Un modèle --format remplace l'ordre des colonnes
+    if let Some(template) = &options.format {
+        output.push_str(&expand_format(template, result));
+        if let Some(name) = filename {
+            output.push(' ');
+            output.push_str(name);
+        }
+        if result.estimated {
+            output.push_str(" (estimation)");
+        }
+        return writeln!(out, "{}", output);
+    }
+
     if options.show_lines {
         output.push_str(&format!("{:7} ", result.lines));
     }
@@ -626,41 +1412,374 @@ fn display_result(result: &CountResult, options: &Options, filename: Option<&str
         output.push_str(&format!("{:7} ", result.max_line_length));
     }
 
+    if options.count_char.is_some() {
+        output.push_str(&format!("{:7} ", result.char_occurrences));
+    }
+
+    if options.prose {
+        output.push_str(&format!("{:7} {:7} ", result.sentences, result.paragraphs));
+    }
+
+    if options.record_separator.is_some() {
+        output.push_str(&format!("{:7} ", result.records));
+    }
+
+    if options.token.is_some() {
+        output.push_str(&format!("{:7} ", result.token_matches));
+    }
+
     if let Some(name) = filename {
         output.push_str(name);
     }
 
-    println!("{}", output.trim_end());
+    // Compteurs extrapolés avec --sample : le signaler sur la ligne
+    if result.estimated {
+        output.push_str(" (estimation)");
+    }
+
+    writeln!(out, "{}", output.trim_end())
+}
+
+/// Sérialise les compteurs d'un fichier en un objet JSON sur une seule ligne (`--ndjson`).
+///
+/// # Algorithme
+/// - `file` contient le nom du fichier (`"total"` pour le total, `null` pour un pipeline).
+/// - Seuls les compteurs demandés sont présents, sous les noms `lines`, `words`,
+///   `chars`, `bytes`, `max_line_length`, puis `char_occurrences`, `sentences`,
+///   `paragraphs`, `records` et `token_matches` avec les options correspondantes.
+/// - `estimated` vaut `true` pour des compteurs extrapolés avec `--sample`.
+///
+/// # Exemple
+/// ```rust
+/// let result = CountResult { lines: 2, words: 5, bytes: 24, ..Default::default() };
+/// assert_eq!(
+///     json_record(&result, &options, Some("a.txt")),
+///     r#"{"file":"a.txt","lines":2,"words":5,"bytes":24,"estimated":false}"#
+/// );
+/// ```
+fn json_record(result: &CountResult, options: &Options, filename: Option<&str>) -> String {
+    let file = filename.map(json_string).unwrap_or_else(|| "null".to_string());
+    let mut fields = vec![format!("\"file\":{}", file)];
+
+    for (name, value) in shown_counters(result, options) {
+        fields.push(format!("\"{}\":{}", name, value));
+    }
+    fields.push(format!("\"estimated\":{}", result.estimated));
+
+    format!("{{{}}}", fields.join(","))
+}
+
+/// Retourne les compteurs demandés par les options, avec leur nom (`lines`, `words`,
+/// `chars`, `bytes`, `max_line_length`, puis `char_occurrences`, `sentences`,
+/// `paragraphs`, `records` et `token_matches`), dans cet ordre.
+///
+/// Utilisé par `--ndjson` ([`json_record`]) et `--compare` ([`display_comparison`]).
+fn shown_counters(result: &CountResult, options: &Options) -> Vec<(&'static str, usize)> {
+    let counters = [
+        (options.show_lines, "lines", result.lines),
+        (options.show_words, "words", result.words),
+        (options.show_chars, "chars", result.chars),
+        (options.show_bytes, "bytes", result.bytes),
+        (options.show_max_line_length, "max_line_length", result.max_line_length),
+        (options.count_char.is_some(), "char_occurrences", result.char_occurrences),
+        (options.prose, "sentences", result.sentences),
+        (options.prose, "paragraphs", result.paragraphs),
+        (options.record_separator.is_some(), "records", result.records),
+        (options.token.is_some(), "token_matches", result.token_matches),
+    ];
+
+    counters
+        .into_iter()
+        .filter(|(shown, _, _)| *shown)
+        .map(|(_, name, value)| (name, value))
+        .collect()
+}
+
+/// Encadre un texte de guillemets en échappant `"`, `\` et les caractères de contrôle,
+/// pour l'écrire comme chaîne JSON.
+fn json_string(text: &str) -> String {
+    let mut output = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if c.is_control() => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
+
+/// Affiche le numéro et la longueur des lignes non vides la plus longue et la plus courte.
+///
+/// # Arguments
+/// * `result` - Résultats du comptage.
+/// * `out` - Destination de l'affichage.
+///
+/// # Exemple
+/// ```no_run
+/// display_line_stats(&result, &mut io::stdout())?;
+/// // Affiche :
+/// //   plus longue : ligne 12 (87 caractères)
+/// //   plus courte : ligne 3 (1 caractère)
+/// ```
+fn display_line_stats(result: &CountResult, out: &mut impl Write) -> io::Result<()> {
+    match (result.longest_line, result.shortest_line) {
+        (Some(longest), Some(shortest)) => {
+            writeln!(out, "  plus longue : {}", describe_line(longest))?;
+            writeln!(out, "  plus courte : {}", describe_line(shortest))
+        }
+        _ => writeln!(out, "  aucune ligne non vide"),
+    }
+}
+
+/// Relève le premier chiffre significatif de chaque nombre d'une ligne (`--digits`).
+///
+/// # Algorithme
+/// - Un nombre est une suite de chiffres et de points (`42`, `3.14`, `0.0071`) ;
+///   tout autre caractère le termine (`2026-10-16` contient donc trois nombres).
+/// - Son premier chiffre significatif est le premier chiffre non nul :
+///   `0.0071` compte pour 7. Un nombre nul (`0`, `0.00`) est ignoré.
+///
+/// # Arguments
+/// * `line` - Ligne à analyser.
+/// * `digits` - Compteurs des chiffres 1 à 9 (index 0 pour le chiffre 1).
+///
+/// # Exemple
+/// ```rust
+/// let mut digits = [0; 9];
+/// add_leading_digits("prix : 19.90 €, remise 0.5, total 0", &mut digits);
+/// assert_eq!(digits, [1, 0, 0, 0, 1, 0, 0, 0, 0]);
+/// ```
+fn add_leading_digits(line: &str, digits: &mut [usize; 9]) {
+    for number in line.split(|c: char| !c.is_ascii_digit() && c != '.') {
+        if let Some(first) = number.bytes().find(|b| (b'1'..=b'9').contains(b)) {
+            digits[(first - b'1') as usize] += 1;
+        }
+    }
+}
+
+/// Affiche la répartition des premiers chiffres (`--digits`), à comparer à la loi de Benford.
+///
+/// Pour chaque chiffre de 1 à 9 : le nombre de nombres qui commencent par ce chiffre,
+/// leur pourcentage et le pourcentage attendu par la loi de Benford (`log10(1 + 1/d)`),
+/// que suivent beaucoup de données réelles (montants, populations, mesures...).
+///
+/// # Exemple
+/// ```no_run
+/// display_digits(&result, &mut io::stdout())?;
+/// // Affiche (exemple) :
+/// //   chiffre  nombres       %  Benford
+/// //         1       31   31.0%    30.1%
+/// //         2       17   17.0%    17.6%
+/// //   ...
+/// ```
+fn display_digits(result: &CountResult, out: &mut impl Write) -> io::Result<()> {
+    let numbers: usize = result.leading_digits.iter().sum();
+    if numbers == 0 {
+        return writeln!(out, "  aucun nombre trouvé");
+    }
+
+    writeln!(out, "  chiffre  nombres       %  Benford")?;
+    for (index, count) in result.leading_digits.iter().enumerate() {
+        let digit = index + 1;
+        let percent = *count as f64 * 100.0 / numbers as f64;
+        let benford = (1.0 + 1.0 / digit as f64).log10() * 100.0;
+        writeln!(out, "  {:>7}  {:>7}  {:>5.1}%   {:>5.1}%", digit, count, percent, benford)?;
+    }
+
+    Ok(())
+}
+
+/// Formate `(numéro, longueur)` en `ligne N (L caractères)`.
+fn describe_line((number, length): (usize, usize)) -> String {
+    let plural = if length > 1 { "s" } else { "" };
+    format!("ligne {} ({} caractère{})", number, length, plural)
 }
 
-/// Affiche l'aide complète du programme `wc`.
+/// Affiche l'histogramme de la distribution des longueurs de ligne.
+///
+/// # Algorithme
+/// - Regroupe les longueurs par tranches de `bucket_size` caractères (0–9, 10–19, …).
+/// - Calcule l'échelle à partir de la tranche la plus remplie et de la largeur
+///   du terminal (variable `COLUMNS`, 80 par défaut).
+/// - Affiche une ligne par tranche non vide avec une barre de `#` (une seule
+///   très longue ligne ne produit donc pas des milliers de tranches vides).
+///
+/// # Arguments
+/// * `result` - Résultats du comptage.
+/// * `options` - Options (taille des tranches).
+/// * `out` - Destination de l'affichage.
+fn display_histogram(result: &CountResult, options: &Options, out: &mut impl Write) -> io::Result<()> {
+    let bucket_size = options.bucket_size.unwrap_or(DEFAULT_BUCKET_SIZE);
+    let buckets = bucket_line_lengths(&result.line_lengths, bucket_size);
+
+    let max_count = buckets.values().copied().max().unwrap_or(0);
+    if max_count == 0 {
+        return Ok(());
+    }
+
+    // Largeur disponible pour la barre, après l'intervalle et le compteur
+    let terminal_width = env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_TERMINAL_WIDTH);
+    let bar_width = terminal_width.saturating_sub(24).max(10);
+
+    for (index, count) in buckets {
+        let start = index * bucket_size;
+        let end = start + (bucket_size - 1);
+        let bar = (count * bar_width / max_count).max(1);
+        writeln!(out, "{:>6}-{:<6} {:>7} {}", start, end, count, "#".repeat(bar))?;
+    }
+
+    Ok(())
+}
+
+/// Regroupe une distribution de longueurs de ligne en tranches.
+///
+/// # Arguments
+/// * `line_lengths` - Nombre de lignes pour chaque longueur.
+/// * `bucket_size` - Largeur d'une tranche.
+///
+/// # Retour
+/// Nombre de lignes par numéro de tranche, pour les seules tranches non vides.
+///
+/// # Exemple
+/// ```rust
+/// let lengths = BTreeMap::from([(3, 2), (12, 1), (100_000, 1)]);
+/// assert_eq!(bucket_line_lengths(&lengths, 10), BTreeMap::from([(0, 2), (1, 1), (10_000, 1)]));
+/// ```
+fn bucket_line_lengths(line_lengths: &BTreeMap<usize, usize>, bucket_size: usize) -> BTreeMap<usize, usize> {
+    let mut buckets = BTreeMap::new();
+
+    for (length, count) in line_lengths {
+        *buckets.entry(length / bucket_size).or_insert(0) += count;
+    }
+
+    buckets
+}
+
+/// Remplace les spécificateurs d'un modèle `--format` par les compteurs.
+///
+/// # Algorithme
+/// - Parcourt le modèle caractère par caractère.
+/// - Remplace `%l` (lignes), `%w` (mots), `%c` (octets), `%m` (caractères),
+///   `%L` (longueur maximale), `%R` (enregistrements, avec `--record-separator`)
+///   et `%%` (un `%` littéral).
+/// - Recopie tel quel tout autre texte, y compris un spécificateur inconnu.
+///
+/// # Arguments
+/// * `template` - Modèle d'affichage.
+/// * `result` - Résultats du comptage.
+///
+/// # Retour
+/// Chaîne formatée.
+///
+/// # Exemple
+/// ```rust
+/// let result = CountResult { lines: 2, words: 3, chars: 19, bytes: 20, max_line_length: 11 };
+/// assert_eq!(expand_format("%l lignes, %w mots", &result), "2 lignes, 3 mots");
+/// ```
+fn expand_format(template: &str, result: &CountResult) -> String {
+    let mut output = String::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            output.push(c);
+            continue;
+        }
+
+        match chars.peek() {
+            Some('l') => output.push_str(&result.lines.to_string()),
+            Some('w') => output.push_str(&result.words.to_string()),
+            Some('c') => output.push_str(&result.bytes.to_string()),
+            Some('m') => output.push_str(&result.chars.to_string()),
+            Some('L') => output.push_str(&result.max_line_length.to_string()),
+            Some('R') => output.push_str(&result.records.to_string()),
+            Some('%') => output.push('%'),
+            _ => {
+                output.push(c);
+                continue;
+            }
+        }
+        chars.next();
+    }
+
+    output
+}
+
+/// Écrit l'aide complète du programme `wc` dans `out`.
 ///
 /// # Exemple
 /// ```no_run
-/// display_help();
+/// display_help(&mut io::stdout())?;
 /// ```
-fn display_help() {
-    println!("Usage: wc [OPTIONS] [FICHIER]...");
-    println!();
-    println!("Affiche le nombre de lignes, mots et octets pour chaque fichier.");
-    println!();
-    println!("Options:");
-    println!("  -c, --bytes              Affiche le nombre d'octets");
-    println!("  -m, --chars              Affiche le nombre de caractères (utile avec UTF-8)");
-    println!("  -l, --lines              Affiche le nombre de lignes");
-    println!("  -w, --words              Affiche le nombre de mots");
-    println!("  -L, --max-line-length    Affiche la longueur de la ligne la plus longue");
-    println!("      --help               Affiche cette aide et quitte");
-    println!("      --version            Affiche la version et quitte");
-    println!();
-    println!("Sans options, wc affiche par défaut : lignes, mots et octets.");
-    println!();
-    println!("Exemples:");
-    println!("  wc fichier.txt");
-    println!("  wc -l fichier.txt");
-    println!("  wc -lwc fichier1.txt fichier2.txt");
-    println!("  wc \"chemin/avec des espaces/fichier.txt\"");
-    println!("  echo \"Bonjour le monde\" | wc -w");
+fn display_help(out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "Usage: wc [OPTIONS] [FICHIER]...")?;
+    writeln!(out)?;
+    writeln!(out, "Affiche le nombre de lignes, mots et octets pour chaque fichier.")?;
+    writeln!(out)?;
+    writeln!(out, "Options:")?;
+    writeln!(out, "  -c, --bytes              Affiche le nombre d'octets")?;
+    writeln!(out, "  -m, --chars              Affiche le nombre de caractères (utile avec UTF-8)")?;
+    writeln!(out, "  -l, --lines              Affiche le nombre de lignes")?;
+    writeln!(out, "  -w, --words              Affiche le nombre de mots")?;
+    writeln!(out, "  -L, --max-line-length    Affiche la longueur de la ligne la plus longue")?;
+    writeln!(out, "      --count-char C       Affiche aussi le nombre d'occurrences du caractère C")?;
+    writeln!(out, "                           (séquences comme \\t acceptées)")?;
+    writeln!(out, "      --token MOTIF        Ajoute le nombre de mots contenant MOTIF, ou correspondant")?;
+    writeln!(out, "                           au motif s'il contient *, ? ou [...]")?;
+    writeln!(out, "  -i, --ignore-case        Ignore la casse pour --token")?;
+    writeln!(out, "      --files0-from F      Lit les fichiers à compter dans F (séparés par \\0, - pour stdin)")?;
+    writeln!(out, "      --files-from F       Lit les fichiers à compter dans F (un par ligne, - pour stdin)")?;
+    writeln!(out, "  -r, --recursive          Compte les fichiers des dossiers et sous-dossiers, sauf ceux")?;
+    writeln!(out, "                           exclus par le fichier .wcignore du dossier (motifs comme .gitignore)")?;
+    writeln!(out, "      --prose              Ajoute le nombre de phrases et de paragraphes (heuristique)")?;
+    writeln!(out, "      --sample N           Ne lit que les N premiers octets de chaque fichier et")?;
+    writeln!(out, "                           extrapole une estimation à partir de sa taille")?;
+    writeln!(out, "      --record-separator SEP")?;
+    writeln!(out, "                           Ajoute le nombre d'enregistrements séparés par la ligne SEP")?;
+    writeln!(out, "      --by-extension       Regroupe les totaux par extension de fichier")?;
+    writeln!(out, "      --compare            Compare deux fichiers : compteurs côte à côte et écart")?;
+    writeln!(out, "                           (ex: lines: 100 vs 120 (+20))")?;
+    writeln!(out, "      --treemap            Avec -r, affiche la part des lignes de chaque dossier et")?;
+    writeln!(out, "                           sous-dossier, en arbre indenté, les plus gros d'abord")?;
+    writeln!(out, "      --archive            Compte séparément chaque fichier d'une archive .tar")?;
+    writeln!(out, "                           (une ligne archive.tar:membre par fichier, puis le total)")?;
+    writeln!(out, "      --line-stats         Affiche la position des lignes non vides la plus longue et la plus courte")?;
+    writeln!(out, "      --histogram          Affiche l'histogramme des longueurs de ligne")?;
+    writeln!(out, "      --digits             Affiche la répartition du premier chiffre des nombres,")?;
+    writeln!(out, "                           comparée à la loi de Benford")?;
+    writeln!(out, "      --bucket N           Largeur des tranches de l'histogramme (10 par défaut)")?;
+    writeln!(out, "      --ndjson             Affiche un objet JSON par ligne et par fichier (puis le total)")?;
+    writeln!(out, "      --format=MODELE      Affiche les compteurs selon un modèle : %l (lignes), %w (mots),")?;
+    writeln!(out, "                           %c (octets), %m (caractères), %L (longueur max),")?;
+    writeln!(out, "                           %R (enregistrements), %% (%)")?;
+    writeln!(out, "      --help               Affiche cette aide et quitte")?;
+    writeln!(out, "      --version            Affiche la version et quitte")?;
+    writeln!(out)?;
+    writeln!(out, "Sans options, wc affiche par défaut : lignes, mots et octets.")?;
+    writeln!(out)?;
+    writeln!(out, "Exemples:")?;
+    writeln!(out, "  wc fichier.txt")?;
+    writeln!(out, "  wc -l fichier.txt")?;
+    writeln!(out, "  wc -lwc fichier1.txt fichier2.txt")?;
+    writeln!(out, "  wc --format=\"%l %w %c\" fichier.txt")?;
+    writeln!(out, "  wc --count-char '\\t' fichier.tsv")?;
+    writeln!(out, "  wc -w -i --token todo src/main.rs")?;
+    writeln!(out, "  wc -l --record-separator --- documents.yaml")?;
+    writeln!(out, "  wc -l --files-from liste.txt")?;
+    writeln!(out, "  wc -l -r src")?;
+    writeln!(out, "  wc -r --treemap projet")?;
+    writeln!(out, "  wc --compare v1.txt v2.txt")?;
+    writeln!(out, "  wc \"chemin/avec des espaces/fichier.txt\"")?;
+    writeln!(out, "  echo \"Bonjour le monde\" | wc -w")
 }
 
 /// Affiche la version du programme.
@@ -672,4 +1791,389 @@ fn display_help() {
 fn display_version() {
     println!("wc version {}", VERSION);
     println!("Implémentation Rust de la commande wc");
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdir::TestDir;
+
+    #[test]
+    fn command_line_handles_both_quote_styles() {
+        assert_eq!(parse_command_line(r#"wc -l "a b.txt""#), ["wc", "-l", "a b.txt"]);
+        assert_eq!(parse_command_line(r"wc --count-char '\t' fichier.tsv"), ["wc", "--count-char", r"\t", "fichier.tsv"]);
+        assert_eq!(parse_command_line(r#"wc 'l"apostrophe' "l'autre""#), ["wc", r#"l"apostrophe"#, "l'autre"]);
+        assert_eq!(parse_command_line("wc ''"), ["wc"]);
+    }
+
+    #[test]
+    fn count_reader_counts_any_source() {
+        let result = count_reader(std::io::Cursor::new(b"Hello World\nBonjour\n"), &Options::default()).unwrap();
+        assert_eq!((result.lines, result.words, result.chars, result.bytes), (2, 3, 20, 20));
+
+        // Une dernière ligne sans retour à la ligne n'est pas comptée comme ligne, comme wc
+        let result = count_reader(&b"un deux"[..], &Options::default()).unwrap();
+        assert_eq!((result.lines, result.words, result.max_line_length), (0, 2, 7));
+
+        let result = count_reader("été\r\n".as_bytes(), &Options::default()).unwrap();
+        assert_eq!((result.lines, result.chars, result.bytes, result.max_line_length), (1, 5, 7, 3));
+    }
+
+    #[test]
+    fn buckets_keep_only_non_empty_ranges() {
+        let lengths = BTreeMap::from([(0, 1), (3, 2), (9, 1), (12, 1), (1_000_000, 1)]);
+        let buckets = bucket_line_lengths(&lengths, 10);
+        assert_eq!(buckets, BTreeMap::from([(0, 4), (1, 1), (100_000, 1)]));
+    }
+
+    #[test]
+    fn histogram_lists_non_empty_ranges_only() {
+        let result = CountResult { line_lengths: BTreeMap::from([(3, 2), (12, 1), (95, 1)]), ..Default::default() };
+        let options = Options { bucket_size: Some(10), ..Default::default() };
+        let mut out = Vec::new();
+        display_histogram(&result, &options, &mut out).unwrap();
+
+        let output = String::from_utf8(out).unwrap();
+        let rows: Vec<(&str, &str)> = output
+            .lines()
+            .map(|line| (line[..13].trim(), line[13..22].trim()))
+            .collect();
+        assert_eq!(rows, [("0-9", "2"), ("10-19", "1"), ("90-99", "1")]);
+    }
+
+    /// Sortie dont le lecteur s'est arrêté, comme un tube fermé.
+    struct ClosedPipe;
+
+    impl Write for ClosedPipe {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn closed_output_is_reported_as_broken_pipe() {
+        let dir = TestDir::new("wc-pipe");
+        let file = dir.write("a.txt", "un deux\ntrois\n");
+        let file = file.to_str().unwrap();
+
+        for args in [&[file][..], &["-l", file], &["-w", file, file], &["--help"]] {
+            let error = process_command(args, None, &mut ClosedPipe).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::BrokenPipe, "{args:?}");
+        }
+    }
+
+    #[test]
+    fn comparison_shows_signed_deltas() {
+        let old = CountResult { lines: 100, words: 700, bytes: 4000, max_line_length: 80, ..Default::default() };
+        let new = CountResult { lines: 120, words: 690, bytes: 4000, max_line_length: 72, ..Default::default() };
+        let options = Options { show_lines: true, show_words: true, show_bytes: true, show_max_line_length: true, ..Default::default() };
+
+        let mut out = Vec::new();
+        display_comparison(("v1.txt", &old), ("v2.txt", &new), &options, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "v1.txt vs v2.txt\n\
+             \x20           lines: 100 vs 120 (+20)\n\
+             \x20           words: 700 vs 690 (-10)\n\
+             \x20           bytes: 4000 vs 4000 (=)\n\
+             \x20 max_line_length: 80 vs 72 (-8)\n"
+        );
+
+        let sampled = CountResult { estimated: true, ..new };
+        let mut out = Vec::new();
+        display_comparison(("v1.txt", &old), ("v2.txt", &sampled), &options, &mut out).unwrap();
+        assert!(String::from_utf8(out).unwrap().starts_with("v1.txt vs v2.txt (estimation)\n"));
+    }
+
+    #[test]
+    fn compare_counts_two_files() {
+        let dir = TestDir::new("wc-compare");
+        let first = dir.write("v1.txt", "a b\nc\n");
+        let second = dir.write("v2.txt", "a b c d\n");
+        let (first, second) = (first.to_str().unwrap(), second.to_str().unwrap());
+
+        let mut out = Vec::new();
+        process_command(&["-l", "--compare", first, second], None, &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), format!("{first} vs {second}\n  lines: 2 vs 1 (-1)\n"));
+
+        let error = process_command(&["--compare", first], None, &mut Vec::new()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert!(process_command(&["--compare", first, "absent.txt"], None, &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn archive_members_are_counted_separately() {
+        let dir = TestDir::new("wc-archive");
+        let archive = dir.write("sources.tar", crate::tar::tests::archive(&[("src/main.rs", "a\nb\n"), ("README", "c\n")]));
+        let archive = archive.to_str().unwrap();
+
+        let mut out = Vec::new();
+        process_command(&["-l", "--archive", archive], None, &mut out).unwrap();
+        let lines: Vec<Vec<String>> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| line.split_whitespace().map(String::from).collect())
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                vec!["2".to_string(), format!("{archive}:src/main.rs")],
+                vec!["1".to_string(), format!("{archive}:README")],
+                vec!["3".to_string(), "total".to_string()],
+            ]
+        );
+    }
+
+    /// Sortie de `wc` avec ces arguments, sur `input` comme dans un pipeline.
+    fn wc_of(args: &[&str], input: Option<&str>) -> io::Result<String> {
+        let mut out = Vec::new();
+        process_command(args, input.map(str::as_bytes), &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn format_fixes_the_column_order() {
+        let result = CountResult { lines: 2, words: 3, chars: 19, bytes: 20, max_line_length: 11, ..Default::default() };
+        assert_eq!(expand_format("%l %w %c", &result), "2 3 20");
+        assert_eq!(expand_format("%c,%m,%L,%l", &result), "20,19,11,2");
+        assert_eq!(expand_format("%l lignes (100%%) %x %", &result), "2 lignes (100%) %x %");
+
+        let input = Some("un deux\ntrois\n");
+        let expected = wc_of(&["--format=%l %w %c"], input).unwrap();
+        assert_eq!(expected, "2 3 14\n");
+        for args in [&["-w", "-l", "--format=%l %w %c"][..], &["--format=%l %w %c", "-c", "-w"], &["-L", "--format=%l %w %c"]] {
+            assert_eq!(wc_of(args, input).unwrap(), expected, "{args:?}");
+        }
+        // Sans modèle non plus, l'ordre des colonnes ne dépend pas de celui des options
+        assert_eq!(wc_of(&["-w", "-l"], input).unwrap(), wc_of(&["-l", "-w"], input).unwrap());
+    }
+
+    #[test]
+    fn directories_are_reported_and_counted_as_zero() {
+        let dir = TestDir::new("wc-directory");
+        let sub = dir.mkdir("dossier");
+        let file = dir.write("a.txt", "un deux\n");
+        let (sub, file) = (sub.to_str().unwrap(), file.to_str().unwrap());
+
+        let error = process_file(sub, &Options::default()).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::IsADirectory);
+
+        let mut out = Vec::new();
+        let error = process_command(&["-lw", sub, file], None, &mut out).unwrap_err();
+        assert_eq!(error.to_string(), "1 fichier n'a pas pu être compté");
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!("      0       0 {sub}\n      1       2 {file}\n      1       2 total\n")
+        );
+    }
+
+    #[test]
+    fn permission_denied_is_reported_plainly() {
+        let denied = io::Error::from_raw_os_error(13);
+        assert_eq!(denied.kind(), io::ErrorKind::PermissionDenied);
+        assert_eq!(error_message("secret.txt", &denied), "wc: secret.txt: Permission denied");
+        let missing = io::Error::new(io::ErrorKind::NotFound, "Aucun fichier ou dossier de ce type");
+        assert_eq!(error_message("absent.txt", &missing), "wc: absent.txt: Aucun fichier ou dossier de ce type");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unreadable_file_does_not_stop_the_others() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TestDir::new("wc-denied");
+        let secret = dir.write("secret.txt", "caché\n");
+        let file = dir.write("a.txt", "un deux\n");
+        fs::set_permissions(&secret, fs::Permissions::from_mode(0o000)).unwrap();
+        // root lit quand même le fichier : rien à vérifier dans ce cas
+        if File::open(&secret).is_ok() {
+            return;
+        }
+        let (secret, file) = (secret.to_str().unwrap(), file.to_str().unwrap());
+
+        let mut out = Vec::new();
+        let error = process_command(&["-l", secret, file], None, &mut out).unwrap_err();
+        assert_eq!(error.to_string(), "1 fichier n'a pas pu être compté");
+        assert_eq!(String::from_utf8(out).unwrap(), format!("      1 {file}\n      1 total\n"));
+    }
+
+    #[test]
+    fn line_stats_report_the_first_extreme_lines() {
+        let text = "moyenne\n\nla plus longue ici\nab\nxy\nla plus longue ici\n";
+        let options = Options { show_line_stats: true, ..Default::default() };
+        let result = count_reader(text.as_bytes(), &options).unwrap();
+        // Les lignes vides sont ignorées ; en cas d'égalité, la première l'emporte
+        assert_eq!(result.longest_line, Some((3, 18)));
+        assert_eq!(result.shortest_line, Some((4, 2)));
+
+        assert_eq!(
+            wc_of(&["-l", "--line-stats"], Some("é\nabc\n")).unwrap(),
+            "      2\n  plus longue : ligne 2 (3 caractères)\n  plus courte : ligne 1 (1 caractère)\n"
+        );
+        assert_eq!(wc_of(&["-l", "--line-stats"], Some("\n\n")).unwrap(), "      2\n  aucune ligne non vide\n");
+    }
+
+    #[test]
+    fn by_extension_totals_each_group() {
+        let dir = TestDir::new("wc-by-ext");
+        let files: Vec<String> = [("a.rs", "1\n2\n"), ("b.RS", "3\n"), ("notes.txt", "a\nb\nc\n"), ("Makefile", "x\n")]
+            .iter()
+            .map(|(name, content)| dir.write(name, content).display().to_string())
+            .collect();
+        let mut args = vec!["-l", "--by-extension"];
+        args.extend(files.iter().map(String::as_str));
+
+        assert_eq!(
+            wc_of(&args, None).unwrap(),
+            "      1 (none)\n      3 .rs\n      3 .txt\n      7 total\n"
+        );
+    }
+
+    #[test]
+    fn prose_counts_sentences_and_paragraphs() {
+        assert_eq!(count_sentences("Bonjour. Ça va ? Oui, etc. Voir J. Dupont..."), 3);
+        assert_eq!(count_sentences("Pi vaut 3.14 environ. Voir fichier.txt !"), 2);
+        assert_eq!(count_sentences("« Vraiment ?! » (Oui.)"), 2);
+        assert_eq!(count_sentences("sans ponctuation finale"), 0);
+
+        let text = "Premier paragraphe. Deux phrases !\n\n\nSecond paragraphe\nsur deux lignes.\n   \nFin?\n";
+        let options = Options { prose: true, ..Default::default() };
+        let result = count_reader(text.as_bytes(), &options).unwrap();
+        assert_eq!((result.sentences, result.paragraphs), (4, 3));
+        assert_eq!(wc_of(&["-l", "--prose"], Some(text)).unwrap(), "      7       4       3\n");
+    }
+
+    #[test]
+    fn records_are_delimited_by_the_separator_line() {
+        let records = |text: &str, separator: &str| {
+            let options = Options { record_separator: Some(separator.to_string()), ..Default::default() };
+            count_reader(text.as_bytes(), &options).unwrap().records
+        };
+        assert_eq!(records("a: 1\nb: 2\n---\na: 3\n---\na: 4\nb: 5\n", "---"), 3);
+        // Séparateurs en tête, en fin ou répétés : pas d'enregistrement vide
+        assert_eq!(records("---\na\n---\n---\n\n---\nb\n---\n", "---"), 2);
+        // Seule une ligne égale au séparateur compte
+        assert_eq!(records("a\n--- \nb\n----\nc\r\n---\r\nd", "---"), 2);
+        assert_eq!(records("", "---"), 0);
+
+        let text = "a\n---\nb\n";
+        assert_eq!(wc_of(&["-l", "--record-separator", "---"], Some(text)).unwrap(), "      3       2\n");
+        assert_eq!(wc_of(&["--format=%R", "--record-separator", "---"], Some(text)).unwrap(), "2\n");
+    }
+
+    #[test]
+    fn sample_estimates_scale_with_the_file_size() {
+        let sample = CountResult { lines: 10, words: 40, chars: 1000, bytes: 1000, max_line_length: 99, ..Default::default() };
+        let estimate = extrapolate(&sample, 5000);
+        assert_eq!((estimate.lines, estimate.words, estimate.chars, estimate.bytes), (50, 200, 5000, 5000));
+        assert_eq!(estimate.max_line_length, 99);
+        assert!(estimate.estimated);
+        assert!(extrapolate(&CountResult::default(), 5000).estimated);
+
+        // Un fichier régulier : l'estimation retombe sur le compte exact
+        let dir = TestDir::new("wc-sample");
+        let file = dir.write("gros.txt", "un deux trois\n".repeat(1000));
+        let file = file.to_str().unwrap();
+        let options = Options { sample: Some(1400), ..Default::default() };
+        let result = process_file(file, &options).ok().unwrap();
+        assert_eq!((result.lines, result.words, result.bytes), (1000, 3000, 14_000));
+        assert!(result.estimated);
+
+        assert_eq!(wc_of(&["-l", "--sample", "1400", file], None).unwrap(), format!("   1000 {file} (estimation)\n"));
+        // Un fichier plus petit que l'échantillon est compté exactement
+        assert_eq!(wc_of(&["-l", "--sample", "20000", file], None).unwrap(), format!("   1000 {file}\n"));
+    }
+
+    #[test]
+    fn ndjson_writes_one_object_per_file_then_the_total() {
+        let dir = TestDir::new("wc-ndjson");
+        let first = dir.write("a.txt", "un deux\n");
+        let second = dir.write("guillemet\".txt", "trois\n");
+        let (first, second) = (first.to_str().unwrap(), second.to_str().unwrap());
+
+        let output = wc_of(&["-lw", "--ndjson", first, second], None).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                format!(r#"{{"file":"{first}","lines":1,"words":2,"estimated":false}}"#),
+                format!(r#"{{"file":{},"lines":1,"words":1,"estimated":false}}"#, json_string(second)),
+                r#"{"file":"total","lines":2,"words":3,"estimated":false}"#.to_string(),
+            ]
+        );
+        assert!(json_string(second).ends_with(r#"guillemet\".txt""#));
+
+        assert_eq!(wc_of(&["-c", "--ndjson"], Some("abc")).unwrap(), "{\"file\":null,\"bytes\":3,\"estimated\":false}\n");
+        assert_eq!(json_string("a\tb\u{1}"), r#""a\tb\u0001""#);
+    }
+
+    #[test]
+    fn tokens_match_a_substring_or_a_pattern() {
+        assert!(token_matches("ERR", "ERROR:", false));
+        assert!(!token_matches("ERR", "error:", false));
+        assert!(token_matches("err", "error:", true));
+        assert!(token_matches("ERROR*", "ERROR:", false));
+        assert!(!token_matches("ERROR*", "[ERROR]", false));
+
+        let line = "ERROR: disque plein\nINFO ok [ERROR] error ERROR\n";
+        assert_eq!(wc_of(&["-l", "--token", "ERROR*"], Some(line)).unwrap(), "      2       2\n");
+        assert_eq!(wc_of(&["-l", "--token", "ERROR"], Some(line)).unwrap(), "      2       3\n");
+        assert_eq!(wc_of(&["-l", "-i", "--token", "ERROR"], Some(line)).unwrap(), "      2       4\n");
+        assert!(wc_of(&["-i"], Some(line)).is_err());
+    }
+
+    #[test]
+    fn digits_tally_the_first_significant_digit() {
+        let mut digits = [0; 9];
+        add_leading_digits("prix : 19.90 €, remise 0.5, total 0", &mut digits);
+        assert_eq!(digits, [1, 0, 0, 0, 1, 0, 0, 0, 0]);
+
+        let numbers = "1 12 150\n2 27\n3\n0.0071 9 -4\n2026-10-16\n";
+        let options = Options { digits: true, ..Default::default() };
+        let result = count_reader(numbers.as_bytes(), &options).unwrap();
+        // 1, 12, 150, 10 (mois), 16 ; 2, 27, 2026 ; 3 ; 4 ; 7 ; 9
+        assert_eq!(result.leading_digits, [5, 3, 1, 1, 0, 0, 1, 0, 1]);
+
+        let output = wc_of(&["-l", "--digits"], Some("1 2 10\n")).unwrap();
+        let rows: Vec<&str> = output.lines().collect();
+        assert_eq!(rows[..3], ["      1", "  chiffre  nombres       %  Benford", "        1        2   66.7%    30.1%"]);
+        assert_eq!(rows.len(), 11);
+        assert_eq!(wc_of(&["-l", "--digits"], Some("aucun\n")).unwrap(), "      1\n  aucun nombre trouvé\n");
+    }
+
+    #[test]
+    fn treemap_shares_lines_by_directory() {
+        let mut root = TreemapNode::default();
+        root.add("./projet/src/main.rs", 30);
+        root.add("projet\\src\\cmd\\ls.rs", 60);
+        root.add("projet/README.md", 10);
+        let projet = &root.children["projet"];
+        assert_eq!((root.lines, projet.lines, projet.children["src"].lines), (100, 100, 90));
+        assert_eq!(projet.children["src"].children["cmd"].lines, 60);
+
+        let dir = TestDir::new("wc_treemap");
+        dir.mkdir("src/cmd");
+        dir.mkdir("docs");
+        dir.write("README.md", "a\n");
+        dir.write("docs/guide.md", "a\nb\n");
+        dir.write("src/main.rs", "a\nb\nc\n");
+        dir.write("src/cmd/ls.rs", "a\nb\nc\nd\n");
+        let root = dir.path().to_str().unwrap();
+        let output = wc_of(&["-r", "--treemap", root], None).unwrap();
+        let rows: Vec<&str> = output.lines().collect();
+        let name = dir.path().file_name().unwrap().to_str().unwrap();
+        assert_eq!(rows.len(), 4 + root.matches('/').count());
+        let tail = &rows[rows.len() - 4..];
+        assert_eq!(tail[0], format!("  70.0%        7  {:<20}  {}src/", "█".repeat(14), "  ".repeat(rows.len() - 4)));
+        assert!(tail[1].starts_with("  40.0%        4  ████████ ") && tail[1].ends_with("  cmd/"));
+        assert!(tail[2].starts_with("  20.0%        2  ████ ") && tail[2].ends_with("docs/"));
+        assert_eq!(tail[3], " 100.0%       10  total");
+        assert!(rows[rows.len() - 5].ends_with(&format!("{name}/")));
+
+        assert!(wc_of(&["--treemap", root], None).is_err());
+    }
+}
@@ -0,0 +1,164 @@
+//! # Module `width`
+//!
+//! Ce module calcule la largeur d'un texte dans un terminal, en colonnes, et coupe
+//! les textes trop longs (ex. `ls --max-name-width`).
+//!
+//! Un caractère n'occupe pas toujours une colonne :
+//!
+//! - les idéogrammes, le hangûl, les caractères pleine chasse et la plupart des
+//!   emoji en occupent deux ;
+//! - les accents combinants, les caractères de largeur nulle et les caractères
+//!   de contrôle n'en occupent aucune.
+//!
+//! Les plages retenues sont les principales de la norme Unicode (UAX #11), sans
+//! dépendance externe ; un caractère rare peut donc être mal estimé.
+
+/// Caractère ajouté à la fin d'un texte coupé par [`truncate`].
+pub const ELLIPSIS: char = '…';
+
+/// Plages de caractères de largeur nulle (accents combinants, séparateurs invisibles,
+/// sélecteurs de variante).
+const ZERO_WIDTH: [(u32, u32); 8] = [
+    (0x0300, 0x036F),
+    (0x1AB0, 0x1AFF),
+    (0x1DC0, 0x1DFF),
+    (0x200B, 0x200F),
+    (0x2060, 0x2064),
+    (0x20D0, 0x20FF),
+    (0xFE00, 0xFE0F),
+    (0xFE20, 0xFE2F),
+];
+
+/// Plages de caractères larges (deux colonnes).
+const WIDE: [(u32, u32); 15] = [
+    (0x1100, 0x115F),
+    (0x2E80, 0x303E),
+    (0x3041, 0x33FF),
+    (0x3400, 0x4DBF),
+    (0x4E00, 0x9FFF),
+    (0xA000, 0xA4CF),
+    (0xAC00, 0xD7A3),
+    (0xF900, 0xFAFF),
+    (0xFE30, 0xFE4F),
+    (0xFF00, 0xFF60),
+    (0xFFE0, 0xFFE6),
+    (0x1F300, 0x1F64F),
+    (0x1F680, 0x1F6FF),
+    (0x1F900, 0x1F9FF),
+    (0x20000, 0x3FFFD),
+];
+
+/// # Fonction : `char_width`
+///
+/// Retourne le nombre de colonnes occupées par un caractère (0, 1 ou 2).
+///
+/// ## Exemple :
+/// ```rust
+/// assert_eq!(width::char_width('a'), 1);
+/// assert_eq!(width::char_width('漢'), 2);
+/// assert_eq!(width::char_width('\u{301}'), 0); // accent aigu combinant
+/// ```
+pub fn char_width(c: char) -> usize {
+    let code = c as u32;
+    let in_ranges = |ranges: &[(u32, u32)]| ranges.iter().any(|&(start, end)| (start..=end).contains(&code));
+
+    if c.is_control() || in_ranges(&ZERO_WIDTH) {
+        0
+    } else if in_ranges(&WIDE) {
+        2
+    } else {
+        1
+    }
+}
+
+/// # Fonction : `text_width`
+///
+/// Retourne le nombre de colonnes occupées par un texte (somme de [`char_width`]).
+///
+/// ## Exemple :
+/// ```rust
+/// assert_eq!(width::text_width("café"), 4);
+/// assert_eq!(width::text_width("日本語.txt"), 10);
+/// ```
+pub fn text_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+/// # Fonction : `truncate`
+///
+/// Coupe un texte plus large que `max_width` colonnes et termine par [`ELLIPSIS`],
+/// de sorte que le résultat n'occupe jamais plus de `max_width` colonnes. Un texte
+/// assez court est retourné tel quel. Un caractère large qui ne tient plus
+/// entièrement avant `…` est retiré (le résultat peut alors faire une colonne de moins).
+///
+/// ## Exemple :
+/// ```rust
+/// assert_eq!(width::truncate("notes.txt", 12), "notes.txt");
+/// assert_eq!(width::truncate("rapport_annuel_final.pdf", 10), "rapport_a…");
+/// assert_eq!(width::truncate("日本語のファイル.txt", 6), "日本…");
+/// ```
+pub fn truncate(text: &str, max_width: usize) -> String {
+    if text_width(text) <= max_width {
+        return text.to_string();
+    }
+
+    let available = max_width.saturating_sub(char_width(ELLIPSIS));
+    let mut truncated = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let width = char_width(c);
+        if used + width > available {
+            break;
+        }
+        truncated.push(c);
+        used += width;
+    }
+    truncated.push(ELLIPSIS);
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn characters_take_zero_one_or_two_columns() {
+        assert_eq!(char_width('a'), 1);
+        assert_eq!(char_width('é'), 1);
+        assert_eq!(char_width('漢'), 2);
+        assert_eq!(char_width('한'), 2);
+        assert_eq!(char_width('Ａ'), 2); // pleine chasse
+        assert_eq!(char_width('🚀'), 2);
+        assert_eq!(char_width('\u{301}'), 0);
+        assert_eq!(char_width('\u{200B}'), 0);
+        assert_eq!(char_width('\t'), 0);
+        assert_eq!(text_width("e\u{301}cole"), 5);
+        assert_eq!(text_width("日本語.txt"), 10);
+    }
+
+    #[test]
+    fn short_names_are_kept() {
+        assert_eq!(truncate("notes.txt", 12), "notes.txt");
+        assert_eq!(truncate("notes.txt", 9), "notes.txt");
+        assert_eq!(truncate("日本語", 6), "日本語");
+        assert_eq!(truncate("", 1), "");
+    }
+
+    #[test]
+    fn long_names_end_with_an_ellipsis() {
+        assert_eq!(truncate("rapport_annuel_final.pdf", 10), "rapport_a…");
+        assert_eq!(truncate("notes.txt", 8), "notes.t…");
+        assert_eq!(truncate("abc", 1), "…");
+        assert_eq!(text_width(&truncate("rapport_annuel_final.pdf", 10)), 10);
+    }
+
+    #[test]
+    fn wide_characters_are_never_cut_in_half() {
+        assert_eq!(truncate("日本語のファイル.txt", 6), "日本…");
+        assert_eq!(truncate("日本語のファイル.txt", 5), "日本…");
+        assert_eq!(truncate("日本語のファイル.txt", 4), "日…");
+        for max_width in 1..20 {
+            assert!(text_width(&truncate("日本語のファイル.txt", max_width)) <= max_width, "{max_width}");
+        }
+    }
+}
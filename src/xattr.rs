@@ -0,0 +1,239 @@
+//! # Module `xattr`
+//!
+//! Ce module copie ou supprime les attributs étendus (*extended attributes*) d'un fichier,
+//! pour les options `--xattrs` et `--no-xattrs` de `cp` et `mv`.
+//!
+//! Il utilise les appels système `listxattr`, `getxattr`, `setxattr` et `removexattr`
+//! de Linux. Sur les autres plateformes, les fonctions retournent une erreur
+//! [`io::ErrorKind::Unsupported`] : l'appelant affiche un avertissement et continue.
+//!
+//! Un attribut refusé (par exemple `EPERM` sur un attribut `security.*` ou `trusted.*`)
+//! n'arrête pas le traitement des autres : l'erreur retournée nomme ceux qui ont échoué.
+
+use std::io;
+use std::path::Path;
+
+/// # Fonction : `copy`
+///
+/// Recopie tous les attributs étendus de `source` sur `destination`
+/// (les attributs déjà présents sur la destination sont remplacés).
+#[cfg(target_os = "linux")]
+pub fn copy(source: &Path, destination: &Path) -> io::Result<()> {
+    let source_path = sys::c_path(source)?;
+    let destination_path = sys::c_path(destination)?;
+
+    let failures = sys::list(&source_path)?
+        .into_iter()
+        .filter_map(|name| {
+            let result = sys::get(&source_path, &name).and_then(|value| sys::set(&destination_path, &name, &value));
+            result.err().map(|e| (name, e))
+        })
+        .collect();
+    report("copy", failures)
+}
+
+/// # Fonction : `strip`
+///
+/// Supprime tous les attributs étendus de `path`.
+#[cfg(target_os = "linux")]
+pub fn strip(path: &Path) -> io::Result<()> {
+    let c_path = sys::c_path(path)?;
+
+    let failures = sys::list(&c_path)?
+        .into_iter()
+        .filter_map(|name| sys::remove(&c_path, &name).err().map(|e| (name, e)))
+        .collect();
+    report("remove", failures)
+}
+
+/// # Fonction : `report`
+///
+/// Regroupe les attributs qui n'ont pas pu être traités en une seule erreur, du type
+/// de la première, qui les nomme tous.
+///
+/// ## Exemple :
+/// ```text
+/// cannot remove 'security.selinux': Operation not permitted (os error 1)
+/// cannot copy 'user.a', 'user.b': No space left on device (os error 28)
+/// ```
+#[cfg(target_os = "linux")]
+fn report(action: &str, failures: Vec<(std::ffi::CString, io::Error)>) -> io::Result<()> {
+    let Some((_, first)) = failures.first() else {
+        return Ok(());
+    };
+
+    let names: Vec<String> = failures
+        .iter()
+        .map(|(name, _)| format!("'{}'", name.to_string_lossy()))
+        .collect();
+    Err(io::Error::new(first.kind(), format!("cannot {action} {}: {first}", names.join(", "))))
+}
+
+/// # Fonction : `copy`
+///
+/// Les attributs étendus ne sont pris en charge que sous Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn copy(_source: &Path, _destination: &Path) -> io::Result<()> {
+    Err(unsupported())
+}
+
+/// # Fonction : `strip`
+///
+/// Les attributs étendus ne sont pris en charge que sous Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn strip(_path: &Path) -> io::Result<()> {
+    Err(unsupported())
+}
+
+/// # Fonction : `set`
+///
+/// Pose un attribut étendu, pour préparer les tests de `cp`, `mv` et de ce module.
+/// Retourne `false` si le système de fichiers ne prend pas en charge les attributs
+/// (le test concerné s'arrête alors sans rien vérifier).
+#[cfg(all(test, target_os = "linux"))]
+pub(crate) fn set(path: &Path, name: &str, value: &[u8]) -> bool {
+    let name = std::ffi::CString::new(name).unwrap();
+    match sys::set(&sys::c_path(path).unwrap(), &name, value) {
+        Ok(()) => true,
+        Err(e) if e.raw_os_error() == Some(95) => false, // EOPNOTSUPP
+        Err(e) => panic!("setxattr : {e}"),
+    }
+}
+
+/// # Fonction : `user_attributes`
+///
+/// Retourne les attributs `user.*` de `path` et leur valeur, triés par nom (tests).
+#[cfg(all(test, target_os = "linux"))]
+pub(crate) fn user_attributes(path: &Path) -> Vec<(String, Vec<u8>)> {
+    let c_path = sys::c_path(path).unwrap();
+    let mut attributes: Vec<_> = sys::list(&c_path)
+        .unwrap()
+        .into_iter()
+        .filter(|name| name.to_bytes().starts_with(b"user."))
+        .map(|name| (name.to_string_lossy().into_owned(), sys::get(&c_path, &name).unwrap()))
+        .collect();
+    attributes.sort();
+    attributes
+}
+
+/// Erreur retournée hors Linux.
+#[cfg(not(target_os = "linux"))]
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "extended attributes are not supported on this platform",
+    )
+}
+
+/// Appels système Linux, encapsulés dans des fonctions sûres.
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::ffi::{CStr, CString, c_char, c_int, c_void};
+    use std::io;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    unsafe extern "C" {
+        fn listxattr(path: *const c_char, list: *mut c_char, size: usize) -> isize;
+        fn getxattr(path: *const c_char, name: *const c_char, value: *mut c_void, size: usize) -> isize;
+        fn setxattr(path: *const c_char, name: *const c_char, value: *const c_void, size: usize, flags: c_int) -> c_int;
+        fn removexattr(path: *const c_char, name: *const c_char) -> c_int;
+    }
+
+    /// Convertit un chemin en chaîne C.
+    pub fn c_path(path: &Path) -> io::Result<CString> {
+        Ok(CString::new(path.as_os_str().as_bytes())?)
+    }
+
+    /// Retourne le nom des attributs étendus du fichier.
+    pub fn list(path: &CStr) -> io::Result<Vec<CString>> {
+        // SAFETY : un tampon nul de taille 0 demande seulement la taille nécessaire.
+        let size = check(unsafe { listxattr(path.as_ptr(), std::ptr::null_mut(), 0) })?;
+        let mut buffer = vec![0u8; size];
+        // SAFETY : le tampon fait exactement `buffer.len()` octets.
+        let size = check(unsafe { listxattr(path.as_ptr(), buffer.as_mut_ptr().cast(), buffer.len()) })?;
+        buffer.truncate(size);
+
+        // Les noms sont séparés (et terminés) par un octet nul
+        Ok(buffer
+            .split(|&b| b == 0)
+            .filter(|name| !name.is_empty())
+            .filter_map(|name| CString::new(name).ok())
+            .collect())
+    }
+
+    /// Retourne la valeur d'un attribut étendu.
+    pub fn get(path: &CStr, name: &CStr) -> io::Result<Vec<u8>> {
+        // SAFETY : un tampon nul de taille 0 demande seulement la taille nécessaire.
+        let size = check(unsafe { getxattr(path.as_ptr(), name.as_ptr(), std::ptr::null_mut(), 0) })?;
+        let mut value = vec![0u8; size];
+        // SAFETY : le tampon fait exactement `value.len()` octets.
+        let size = check(unsafe { getxattr(path.as_ptr(), name.as_ptr(), value.as_mut_ptr().cast(), value.len()) })?;
+        value.truncate(size);
+        Ok(value)
+    }
+
+    /// Crée ou remplace un attribut étendu.
+    pub fn set(path: &CStr, name: &CStr, value: &[u8]) -> io::Result<()> {
+        // SAFETY : les chaînes sont terminées par un octet nul et `value` fait `value.len()` octets.
+        let status = unsafe { setxattr(path.as_ptr(), name.as_ptr(), value.as_ptr().cast(), value.len(), 0) };
+        check(status as isize).map(|_| ())
+    }
+
+    /// Supprime un attribut étendu.
+    pub fn remove(path: &CStr, name: &CStr) -> io::Result<()> {
+        // SAFETY : les chaînes sont terminées par un octet nul.
+        let status = unsafe { removexattr(path.as_ptr(), name.as_ptr()) };
+        check(status as isize).map(|_| ())
+    }
+
+    /// Convertit le retour d'un appel système (-1 en cas d'erreur) en `io::Result`.
+    fn check(status: isize) -> io::Result<usize> {
+        if status < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(status as usize)
+        }
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::testdir::TestDir;
+    use std::ffi::CString;
+
+    #[test]
+    fn copy_then_strip_round_trip() {
+        let dir = TestDir::new("xattr");
+        let source = dir.write("source.txt", "s");
+        let destination = dir.write("destination.txt", "d");
+        if !set(&source, "user.auteur", b"moi") {
+            return;
+        }
+        set(&source, "user.vide", b"");
+
+        copy(&source, &destination).unwrap();
+        assert_eq!(
+            user_attributes(&destination),
+            [("user.auteur".to_string(), b"moi".to_vec()), ("user.vide".to_string(), Vec::new())]
+        );
+
+        strip(&destination).unwrap();
+        assert!(user_attributes(&destination).is_empty());
+        assert_eq!(user_attributes(&source).len(), 2);
+    }
+
+    #[test]
+    fn report_names_every_failed_attribute() {
+        assert!(report("remove", Vec::new()).is_ok());
+
+        let failures = vec![
+            (CString::new("security.a").unwrap(), io::Error::from_raw_os_error(1)),
+            (CString::new("trusted.b").unwrap(), io::Error::from_raw_os_error(1)),
+        ];
+        let error = report("remove", failures).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+        assert!(error.to_string().starts_with("cannot remove 'security.a', 'trusted.b': "));
+    }
+}